@@ -1,39 +1,135 @@
 use std::{
     fmt::{Debug, Display},
+    fs,
     io::Write,
     process,
     str::FromStr,
 };
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use colored::*;
 use rush_lib::error::runtime::ErrorContext;
 use rush_lib::{
-    Binary, RushError, Runtime
+    Binary, InstSet, Object, RushError, Runtime, TaggedFile
 };
-use rush_utils::RushConfig;
+use rush_utils::{Endianness, RushConfig};
 use text_io::try_read;
 use rush_lib::runtime::system_clock::get_curr_time_as_millis;
 
 #[derive(Parser, Debug)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Opts {
-    /// Emulation config with binaries and memory mapping
+    /// Emulation config with binaries and memory mapping,
+    /// or an assembly file (`.s`/`.asm`) or ELF executable to run with spim's memory layout
     #[arg()]
-    config: Option<String>
+    config: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Assemble source files (and link them with any objects) into an executable,
+    /// or with `-c` a single source file into a relocatable object
+    Asm {
+        /// Only assemble, writing a relocatable object
+        #[arg(short = 'c')]
+        object: bool,
+
+        /// Where to write the executable or object
+        #[arg(short)]
+        output: String,
+
+        /// Assembly files, or objects to link with them
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// Link relocatable objects into an executable
+    Link {
+        /// Where to write the executable
+        #[arg(short)]
+        output: String,
+
+        /// Objects to link, in order
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
 }
 
 fn main() {
     let args: Opts = Opts::parse();
 
+    if let Some(command) = args.command {
+        let iset = rush_instructions::inst_set();
+
+        match command {
+            Command::Asm { object: true, output, files } => {
+                if files.len() != 1 {
+                    fail("`-c` takes exactly one file");
+                }
+
+                write_file(&output, &assemble(&files[0], &iset).to_elf());
+            }
+            Command::Asm { object: false, output, files } | Command::Link { output, files } => {
+                let objects = files.iter()
+                    .map(|file| match rush_lib::is_assembly(file) {
+                        true  => assemble(file, &iset),
+                        false => read_object(file),
+                    })
+                    .collect();
+
+                let binary = match rush_lib::link(&iset, objects, &RushConfig::spim(output.clone())) {
+                    Ok(binary) => binary,
+                    Err(err) => fail(err),
+                };
+
+                write_file(&output, &binary.to_elf());
+            }
+        }
+
+        return;
+    }
+
     if args.config.is_none() {
         // launch() returns !
         rush_interactive::launch();
     }
 
-    let config = RushConfig::new(&args.config.unwrap());
+    let path = args.config.unwrap();
+    let config = if rush_lib::is_assembly(&path) || rush_lib::is_elf(&path) {
+        RushConfig::spim(path)
+    } else {
+        match RushConfig::new(&path) {
+            Ok(config) => config,
+            Err(err) => {
+                eprintln!("{}{} {}", "error".bright_red().bold(), ":".bold(), err);
+                process::exit(1);
+            }
+        }
+    };
+
+    let iset = rush_instructions::inst_set();
 
-    let binary = Binary::new(&config);
+    let binary = if rush_lib::is_assembly(&config.executable) {
+        compile(&config, &iset)
+    } else {
+        match Binary::new(&config) {
+            Ok(binary) => binary,
+            Err(err) => {
+                eprintln!("{}{} {}", "error".bright_red().bold(), ":".bold(), err);
+                process::exit(1);
+            }
+        }
+    };
 
-    let mut runtime = Runtime::new(&binary, config);
+    let mut runtime = match Runtime::new(&binary, config) {
+        Ok(runtime) => runtime,
+        Err(err) => {
+            eprintln!("{}{} {}", "error".bright_red().bold(), ":".bold(), err);
+            process::exit(1);
+        }
+    };
 
     runtime.system_clock.start_time = get_curr_time_as_millis();
     loop {
@@ -90,7 +186,8 @@ fn main() {
                             Sbrk(_args, new_runtime) => {
                                 runtime = new_runtime;
                             }
-                            Exit(_new_runtime) => {
+                            Exit(mut new_runtime) => {
+                                show_summary(&mut new_runtime);
                                 process::exit(0);
                             }
                             PrintChar(args, new_runtime) => {
@@ -119,7 +216,8 @@ fn main() {
                                 println!("close");
                                 process::exit(1);
                             }
-                            ExitStatus(args, _new_runtime) => {
+                            ExitStatus(args, mut new_runtime) => {
+                                show_summary(&mut new_runtime);
                                 process::exit(args.exit_code);
                             }
                             Breakpoint(new_runtime) => {
@@ -132,28 +230,119 @@ fn main() {
                     }
                 }
             }
+            Err((_, RushError::Parser(_) | RushError::Compiler(_))) => unreachable!(),
             Err((old_runtime, RushError::Runtime(err))) => {
                 runtime = old_runtime;
-                runtime.system_clock.stop_time = get_curr_time_as_millis();
-
-                let delta_time_sec = (runtime.system_clock.stop_time - runtime.system_clock.start_time) / 1000 + 1;
-                let ips = runtime.system_clock.steps / delta_time_sec;
-                println!("Emulation running on {} sec., IPS = {}", delta_time_sec, ips);
+                show_summary(&mut runtime);
+                eprintln!();
 
-                println!();
+                show_load_hazard(&iset, &binary, &mut runtime);
                 err.show_error(
                     ErrorContext::Binary,
+                    &iset,
+                    &binary,
                     &runtime,
                 );
 
                 process::exit(1);
             }
         }
+        show_load_hazard(&iset, &binary, &mut runtime);
         runtime.system_clock.steps += 1;
-        runtime.system_clock.update(1)
     }
 }
 
+/// Shows how long the program took, and how the caches did, once it's exited or failed. They go to stderr,
+/// after a blank line, so as not to be taken for the program's output.
+fn show_summary(runtime: &mut Runtime) {
+    runtime.system_clock.stop_time = get_curr_time_as_millis();
+
+    eprintln!();
+    eprintln!("{}", runtime.system_clock.summary());
+    if let Some(cache_summary) = runtime.cache_summary() {
+        eprintln!("{}", cache_summary);
+    }
+}
+
+fn show_load_hazard(iset: &InstSet, binary: &Binary, runtime: &mut Runtime) {
+    if let Some(hazard) = runtime.state_mut().take_load_hazard() {
+        hazard.show_warning(iset, binary, runtime);
+    }
+}
+
+fn compile(config: &RushConfig, iset: &InstSet) -> Binary {
+    let source = match fs::read_to_string(&config.executable) {
+        Ok(source) => source,
+        Err(err) => {
+            eprintln!("{}{} failed to read file `{}`: {}", "error".bright_red().bold(), ":".bold(), config.executable, err);
+            process::exit(1);
+        }
+    };
+
+    let files = vec![TaggedFile::new(Some(config.executable.as_str()), &source)];
+
+    match rush_lib::compile(iset, files.clone(), config) {
+        Ok(binary) => binary,
+        Err(err) => {
+            match err {
+                RushError::Parser(err) => err.show_error(rush_lib::source_of(&files, &err.file_tag())),
+                RushError::Compiler(err) => err.show_error(rush_lib::source_of(&files, &err.file_tag())),
+                RushError::Runtime(_) => unreachable!(),
+            }
+
+            process::exit(1);
+        }
+    }
+}
+
+fn assemble(path: &str, iset: &InstSet) -> Object {
+    let source = match fs::read_to_string(path) {
+        Ok(source) => source,
+        Err(err) => fail(format!("failed to read file `{}`: {}", path, err)),
+    };
+
+    let file = TaggedFile::new(Some(path), &source);
+
+    // objects are little-endian, as spim is
+    match rush_lib::compile_object(iset, file.clone(), Endianness::Little) {
+        Ok(object) => object,
+        Err(err) => {
+            let files = vec![file];
+
+            match err {
+                RushError::Parser(err) => err.show_error(rush_lib::source_of(&files, &err.file_tag())),
+                RushError::Compiler(err) => err.show_error(rush_lib::source_of(&files, &err.file_tag())),
+                RushError::Runtime(_) => unreachable!(),
+            }
+
+            process::exit(1);
+        }
+    }
+}
+
+fn read_object(path: &str) -> Object {
+    let bytes = match fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) => fail(format!("failed to read file `{}`: {}", path, err)),
+    };
+
+    match Object::from_elf(path, &bytes) {
+        Ok(object) => object,
+        Err(err) => fail(format!("`{}`: {}", path, err)),
+    }
+}
+
+fn write_file(path: &str, bytes: &[u8]) {
+    if let Err(err) = fs::write(path, bytes) {
+        fail(format!("failed to write file `{}`: {}", path, err));
+    }
+}
+
+fn fail(message: impl Display) -> ! {
+    eprintln!("{}{} {}", "error".bright_red().bold(), ":".bold(), message);
+    process::exit(1);
+}
+
 fn get_input<T>(name: &str, line: bool) -> T
     where
         T: FromStr + Display,
@@ -245,6 +434,7 @@ fn get_input_int(name: &str) -> Option<i32> {
     }
 }
 
+#[allow(clippy::never_loop)]
 fn read_string(_max_len: u32) -> String {
     loop {
         let input: String = get_input("string", true);
@@ -60,7 +60,14 @@ pub enum ArgumentType {
     Rd,
     Rs,
     Rt,
+    Fd,
+    Fs,
+    Ft,
+    Cd,
     Shamt,
+    ExtSize,
+    InsSize,
+    Hint,
     I16,
     U16,
     J,
@@ -119,6 +126,7 @@ impl From<RuntimeYaml> for RuntimeSignature {
             },
             InstructionType::I => RuntimeSignature::I {
                 opcode: x.opcode.expect("I-type requires opcode"),
+                rs: x.rs,
                 rt: x.rt,
             },
             InstructionType::J => RuntimeSignature::J {
@@ -140,7 +148,14 @@ impl From<ArgumentType> for rush_lib::ArgumentType {
             ArgumentType::Rd => rush_lib::ArgumentType::Rd,
             ArgumentType::Rs => rush_lib::ArgumentType::Rs,
             ArgumentType::Rt => rush_lib::ArgumentType::Rt,
+            ArgumentType::Fd => rush_lib::ArgumentType::Fd,
+            ArgumentType::Fs => rush_lib::ArgumentType::Fs,
+            ArgumentType::Ft => rush_lib::ArgumentType::Ft,
+            ArgumentType::Cd => rush_lib::ArgumentType::Cd,
             ArgumentType::Shamt => rush_lib::ArgumentType::Shamt,
+            ArgumentType::ExtSize => rush_lib::ArgumentType::ExtSize,
+            ArgumentType::InsSize => rush_lib::ArgumentType::InsSize,
+            ArgumentType::Hint => rush_lib::ArgumentType::Hint,
             ArgumentType::I16 => rush_lib::ArgumentType::I16,
             ArgumentType::U16 => rush_lib::ArgumentType::U16,
             ArgumentType::J => rush_lib::ArgumentType::J,
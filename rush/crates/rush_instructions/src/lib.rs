@@ -1,23 +1,40 @@
 //! # rush_instructions
 //!
-//! This crate, on it's surface, seems incredibly useless.
-//! It only exports a single function, `inst_set`, which
-//! only calls a single macro, the `instruction_set!` macro.
+//! This crate holds the MIPS instruction set that the rest
+//! of rush is built around. It only exports a single function,
+//! `inst_set`, which parses `mips.yaml` (at the root of the
+//! workspace) into an `InstSet`.
 //!
-//! The `instruction_set!` macro is used to parse the MIPS
-//! instruction set and generate a Rust struct that can be
-//! used to decode the instructions.
-//!
-//! This macro, however, is *rather slow* and usually won't
-//! need to be recompiled often. On my Ryzen 7 5800X, it
-//! takes ~25 seconds to run the macro, and this computation
-//! doesn't seem possible to parallelize as rustc seems to
-//! only allocate a single thread for a proc-macro computaion.
-//!
-//! By putting this macro invocation in a separate crate,
-//! incremental compilation allows us to only compile this crate
-//! once, and then only recompile it if the instruction set
-//! changes (although this would have to be a manual `clean`).
+//! The YAML is embedded into the binary and parsed at runtime,
+//! so changes to the instruction set only require rebuilding
+//! this crate. Pseudo-instructions are described in the `meta`
+//! format, whose `derives` are expanded into plain `base`
+//! pseudo-instructions as they are loaded.
 
 pub mod base;
 pub mod meta;
+
+use rush_lib::inst::InstSet;
+use serde::{Deserialize, Serialize};
+
+const MIPS_YAML: &str = include_str!("../../../mips.yaml");
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct YamlFile {
+    instructions: Vec<base::InstructionYaml>,
+    #[serde(default)]
+    pseudoinstructions: Vec<meta::PseudoInstructionYaml>,
+}
+
+pub fn inst_set() -> InstSet {
+    let yaml: YamlFile = serde_yaml::from_str(MIPS_YAML).expect("mips.yaml should be valid");
+
+    InstSet::new(
+        yaml.instructions.into_iter().map(Into::into).collect(),
+        yaml.pseudoinstructions
+            .into_iter()
+            .flat_map(meta::PseudoInstructionYaml::derive)
+            .map(Into::into)
+            .collect(),
+    )
+}
@@ -80,7 +80,14 @@ pub enum ArgumentType {
     Rd,
     Rs,
     Rt,
+    Fd,
+    Fs,
+    Ft,
+    Cd,
     Shamt,
+    ExtSize,
+    InsSize,
+    Hint,
     I16,
     U16,
     J,
@@ -111,7 +118,14 @@ impl Display for ArgumentType {
             ArgumentType::Rd => write!(f, "Rd"),
             ArgumentType::Rs => write!(f, "Rs"),
             ArgumentType::Rt => write!(f, "Rt"),
+            ArgumentType::Fd => write!(f, "Fd"),
+            ArgumentType::Fs => write!(f, "Fs"),
+            ArgumentType::Ft => write!(f, "Ft"),
+            ArgumentType::Cd => write!(f, "Cd"),
             ArgumentType::Shamt => write!(f, "Shamt"),
+            ArgumentType::ExtSize => write!(f, "ExtSize"),
+            ArgumentType::InsSize => write!(f, "InsSize"),
+            ArgumentType::Hint => write!(f, "Hint"),
             ArgumentType::I16 => write!(f, "I16"),
             ArgumentType::U16 => write!(f, "U16"),
             ArgumentType::J => write!(f, "J"),
@@ -144,7 +158,14 @@ impl From<ArgumentType> for super::base::ArgumentType {
             ArgumentType::Rd => super::base::ArgumentType::Rd,
             ArgumentType::Rs => super::base::ArgumentType::Rs,
             ArgumentType::Rt => super::base::ArgumentType::Rt,
+            ArgumentType::Fd => super::base::ArgumentType::Fd,
+            ArgumentType::Fs => super::base::ArgumentType::Fs,
+            ArgumentType::Ft => super::base::ArgumentType::Ft,
+            ArgumentType::Cd => super::base::ArgumentType::Cd,
             ArgumentType::Shamt => super::base::ArgumentType::Shamt,
+            ArgumentType::ExtSize => super::base::ArgumentType::ExtSize,
+            ArgumentType::InsSize => super::base::ArgumentType::InsSize,
+            ArgumentType::Hint => super::base::ArgumentType::Hint,
             ArgumentType::I16 => super::base::ArgumentType::I16,
             ArgumentType::U16 => super::base::ArgumentType::U16,
             ArgumentType::J => super::base::ArgumentType::J,
@@ -180,3 +201,167 @@ impl From<ReadsRegisterType> for super::base::ReadsRegisterType {
         }
     }
 }
+
+impl From<CompileYaml> for super::base::CompileYaml {
+    fn from(x: CompileYaml) -> super::base::CompileYaml {
+        super::base::CompileYaml {
+            format: x.format.into_iter().map(Into::into).collect(),
+            relative_label: x.relative_label,
+        }
+    }
+}
+
+impl From<Imm2RegImmType> for ArgumentType {
+    fn from(x: Imm2RegImmType) -> ArgumentType {
+        match x {
+            Imm2RegImmType::I16 => ArgumentType::I16,
+            Imm2RegImmType::U16 => ArgumentType::U16,
+            Imm2RegImmType::I32 => ArgumentType::I32,
+            Imm2RegImmType::U32 => ArgumentType::U32,
+        }
+    }
+}
+
+impl PseudoInstructionYaml {
+    /// The pseudo-instruction itself (unless it is `only_derive`),
+    /// followed by every variant generated by its `derives`.
+    pub fn derive(self) -> Vec<super::base::PseudoInstructionYaml> {
+        let derived: Vec<_> = self
+            .derives
+            .iter()
+            .flat_map(|derive| derive.apply(&self))
+            .flat_map(PseudoInstructionYaml::derive)
+            .collect();
+
+        if self.only_derive {
+            return derived;
+        }
+
+        let mut pseudos = vec![super::base::PseudoInstructionYaml {
+            name: self.name,
+            desc_short: self.desc_short,
+            desc_long: self.desc_long,
+            compile: self.compile.into(),
+            expand: self.expand.into_iter().map(Into::into).collect(),
+        }];
+        pseudos.extend(derived);
+
+        pseudos
+    }
+
+    fn with(&self, format: Vec<ArgumentType>, expand: Vec<InstructionExpansionYaml>, derives: &[DeriveStatementYaml]) -> Self {
+        Self {
+            name: self.name.clone(),
+            desc_short: self.desc_short.clone(),
+            desc_long: self.desc_long.clone(),
+            compile: CompileYaml {
+                format,
+                relative_label: self.compile.relative_label,
+            },
+            expand,
+            only_derive: false,
+            derives: derives.to_vec(),
+        }
+    }
+}
+
+impl DeriveStatementYaml {
+    fn apply(&self, pseudo: &PseudoInstructionYaml) -> Vec<PseudoInstructionYaml> {
+        match self {
+            DeriveStatementYaml::Imm2Reg {
+                register,
+                imm_types,
+                sign_extend,
+                derives,
+                imm_register,
+            } => {
+                let imm_register = imm_register.as_deref().unwrap_or("$at");
+                let is_register = |arg: &ArgumentType| arg.to_string().eq_ignore_ascii_case(register);
+
+                if !pseudo.compile.format.iter().any(is_register) {
+                    panic!("{} has no {} to derive an immediate for", pseudo.name, register);
+                }
+
+                imm_types
+                    .iter()
+                    .cloned()
+                    .map(ArgumentType::from)
+                    .map(|imm_type| {
+                        let load = match (imm_type, sign_extend) {
+                            (ArgumentType::I16, false) => InstructionExpansionYaml {
+                                inst: "ORI".to_string(),
+                                data: vec![imm_register.to_string(), "$zero".to_string(), "$I16uLo".to_string()],
+                            },
+                            _ => InstructionExpansionYaml {
+                                inst: "LI".to_string(),
+                                data: vec![imm_register.to_string(), format!("${}", imm_type)],
+                            },
+                        };
+
+                        let format = pseudo
+                            .compile
+                            .format
+                            .iter()
+                            .map(|arg| if is_register(arg) { imm_type } else { *arg })
+                            .collect();
+                        let expand = std::iter::once(load)
+                            .chain(pseudo.expand.iter().map(|expand| expand.replace(register, imm_register)))
+                            .collect();
+
+                        pseudo.with(format, expand, derives)
+                    })
+                    .collect()
+            }
+            DeriveStatementYaml::DefaultValue {
+                value,
+                default,
+                derives,
+            } => {
+                let format = pseudo.compile.format.iter().copied().filter(|arg| arg != value).collect();
+                let expand = pseudo
+                    .expand
+                    .iter()
+                    .map(|expand| expand.replace(&value.to_string(), default))
+                    .collect();
+
+                vec![pseudo.with(format, expand, derives)]
+            }
+        }
+    }
+}
+
+impl InstructionExpansionYaml {
+    /// Replaces the variable `$var` (but not e.g. `$varuHi`) with `with`.
+    fn replace(&self, var: &str, with: &str) -> Self {
+        let replace = |data: &String| {
+            let mut result = String::new();
+            let mut rest = data.as_str();
+
+            while let Some(start) = rest.find('$') {
+                result.push_str(&rest[..start]);
+
+                let word_len = rest[start + 1..]
+                    .find(|c: char| !c.is_ascii_alphanumeric())
+                    .unwrap_or(rest.len() - start - 1);
+                let word = &rest[start + 1..start + 1 + word_len];
+
+                if word.eq_ignore_ascii_case(var) {
+                    result.push_str(with);
+                } else {
+                    result.push('$');
+                    result.push_str(word);
+                }
+
+                rest = &rest[start + 1 + word_len..];
+            }
+
+            result.push_str(rest);
+            result
+        };
+
+        Self {
+            inst: self.inst.clone(),
+            data: self.data.iter().map(replace).collect(),
+        }
+    }
+}
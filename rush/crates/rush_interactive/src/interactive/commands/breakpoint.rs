@@ -2,6 +2,9 @@ use crate::interactive::{error::CommandError, prompt};
 
 use super::*;
 use colored::*;
+use rush_lib::{compile::Breakpoint, Binary};
+use rush_parser::{MpArgument, MpImmediate, MpNumber};
+use std::iter::successors;
 
 
 enum EnableOp {
@@ -18,7 +21,10 @@ enum InsertOp {
 }
 
 enum RushArgType {
-    LineNumber
+    Immediate,
+    Label,
+    Id,
+    LineNumber,
 }
 
 pub(crate) fn breakpoint_command() -> Command {
@@ -219,76 +225,76 @@ fn breakpoint_insert(
         ));
     }
 
-    let (addr, _arg_type) = parse_breakpoint_arg(state, &args[0])?;
+    let (addr, arg_type) = parse_breakpoint_arg(state, &args[0])?;
 
     if addr % 4 != 0 {
         prompt::error_nl(format!("address 0x{:08x} should be word-aligned", addr));
         return Ok("".into());
     }
 
-    let _binary = state.binary.as_mut().ok_or(CommandError::MustLoadFile)?;
-
-    // let id;
-    // let action = if op == InsertOp::Delete {
-    //     if let Some(bp) = binary.breakpoints.remove(&addr) {
-    //         id = bp.id;
-    //         "removed"
-    //     } else {
-    //         prompt::error_nl(format!(
-    //             "breakpoint at {} doesn't exist",
-    //             match arg_type {
-    //                 RushArgType::LineNumber => args[0].as_str().into(),
-    //                 RushArgType::Immediate => args[0].white(),
-    //                 RushArgType::Label => args[0].yellow().bold(),
-    //                 RushArgType::Id => args[0].blue(),
-    //             }
-    //         ));
-    //         return Ok("".into());
-    //     }
-    // } else if !binary.breakpoints.contains_key(&addr) {
-    //     id = Binary::generate_id(&binary.breakpoints);
-    //     let mut bp = Breakpoint::new(id);
-    //     if op == InsertOp::Temporary {
-    //         bp.commands.push(format!("breakpoint remove !{id}"))
-    //     }
-    //     binary.breakpoints.insert(addr, bp);
-    //
-    //     "inserted"
-    // } else {
-    //     prompt::error_nl(format!(
-    //         "breakpoint at {} already exists",
-    //         match arg_type {
-    //             RushArgType::LineNumber => args[0].as_str().into(),
-    //             RushArgType::Immediate => args[0].white(),
-    //             RushArgType::Label => args[0].yellow().bold(),
-    //             RushArgType::Id => args[0].blue(),
-    //         }
-    //     ));
-    //     return Ok("".into());
-    // };
-    //
-    // let label = match arg_type {
-    //     RushArgType::Immediate => None,
-    //     RushArgType::Label => Some(&args[0]),
-    //     RushArgType::Id | RushArgType::LineNumber => Some(&args[0]),
-    // };
-    //
-    // if let Some(label) = label {
-    //     prompt::success_nl(format!(
-    //         "breakpoint {} {} at {} (0x{:08x})",
-    //         format!("!{}", id).blue(),
-    //         action,
-    //         label.yellow().bold(),
-    //         addr
-    //     ));
-    // } else {
-    //     prompt::success_nl(format!(
-    //         "breakpoint {} {} at 0x{:08x}",
-    //         format!("!{}", id).blue(),
-    //         action,
-    //         addr
-    //     ));
-    // }
+    let binary = state.binary.as_mut().ok_or(CommandError::MustLoadFile)?;
+
+    let id;
+    let action = if op == InsertOp::Delete {
+        if let Some(bp) = binary.breakpoints.remove(&addr) {
+            id = bp.id;
+            "removed"
+        } else {
+            prompt::error_nl(format!(
+                "breakpoint at {} doesn't exist",
+                match arg_type {
+                    RushArgType::LineNumber => args[0].as_str().into(),
+                    RushArgType::Immediate => args[0].white(),
+                    RushArgType::Label => args[0].yellow().bold(),
+                    RushArgType::Id => args[0].blue(),
+                }
+            ));
+            return Ok("".into());
+        }
+    } else if !binary.breakpoints.contains_key(&addr) {
+        id = Binary::generate_id(&binary.breakpoints);
+        let mut bp = Breakpoint::new(id);
+        if op == InsertOp::Temporary {
+            bp.commands.push(format!("breakpoint remove !{id}"))
+        }
+        binary.breakpoints.insert(addr, bp);
+
+        "inserted"
+    } else {
+        prompt::error_nl(format!(
+            "breakpoint at {} already exists",
+            match arg_type {
+                RushArgType::LineNumber => args[0].as_str().into(),
+                RushArgType::Immediate => args[0].white(),
+                RushArgType::Label => args[0].yellow().bold(),
+                RushArgType::Id => args[0].blue(),
+            }
+        ));
+        return Ok("".into());
+    };
+
+    let label = match arg_type {
+        RushArgType::Immediate => None,
+        RushArgType::Label => Some(&args[0]),
+        RushArgType::Id | RushArgType::LineNumber => Some(&args[0]),
+    };
+
+    if let Some(label) = label {
+        prompt::success_nl(format!(
+            "breakpoint {} {} at {} (0x{:08x})",
+            format!("!{}", id).blue(),
+            action,
+            label.yellow().bold(),
+            addr
+        ));
+    } else {
+        prompt::success_nl(format!(
+            "breakpoint {} {} at 0x{:08x}",
+            format!("!{}", id).blue(),
+            action,
+            addr
+        ));
+    }
 
     Ok("".into())
 }
@@ -309,82 +315,82 @@ fn breakpoint_list(state: &InteractiveState, label: &str, _args: &[String]) -> R
         );
     }
 
-    let _binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
-
-    // if binary.breakpoints.is_empty() {
-    //     prompt::error_nl("no breakpoints set");
-    //     return Ok("".into());
-    // }
-
-    // let mut breakpoints = binary
-    //     .breakpoints
-    //     .iter()
-    //     .map(|x| {
-    //         let (&addr, bp) = x;
-    //         let id = bp.id;
-    //         (
-    //             (
-    //                 id,
-    //                 // https://github.com/rust-lang/rust/issues/70887 is stabilised
-    //                 successors(Some(id), |&id| (id >= 10).then_some(id / 10)).count(),
-    //             ),
-    //             addr,
-    //             binary
-    //                 .labels
-    //                 .iter()
-    //                 .find(|(_, &val)| val == addr)
-    //                 .map(|(name, _)| name),
-    //             bp,
-    //         )
-    //     })
-    //     .collect::<Vec<_>>();
-    //
-    // breakpoints.sort_by_key(|(_, addr, _, _)| *addr);
-    //
-    // let max_id_len = breakpoints
-    //     .iter()
-    //     .map(|&(id, _, _, _)| id.1)
-    //     .max()
-    //     .unwrap_or(0);
-    //
-    // println!("\n{}", "[breakpoints]".green().bold());
-    // for (id, addr, text, bp) in breakpoints {
-    //     let disabled = match bp.enabled {
-    //         true => "",
-    //         false => " (disabled)",
-    //     };
-    //
-    //     let ignored = match bp.ignore_count {
-    //         0 => "".to_string(),
-    //         i => format!(" (ignored for the next {} hits)", i.to_string().bold()),
-    //     };
-    //
-    //     match text {
-    //         Some(name) => {
-    //             println!(
-    //                 "{}{}: {}{:08x} ({}){}{}",
-    //                 " ".repeat(max_id_len - id.1),
-    //                 id.0.to_string().blue(),
-    //                 "0x".magenta(),
-    //                 addr,
-    //                 name.yellow().bold(),
-    //                 disabled.bright_black(),
-    //                 ignored
-    //             );
-    //         }
-    //         None => {
-    //             println!(
-    //                 "{}{}: {}{:08x}{}{}",
-    //                 " ".repeat(max_id_len - id.1),
-    //                 id.0.to_string().blue(),
-    //                 "0x".magenta(),
-    //                 addr,
-    //                 disabled.bright_black(),
-    //                 ignored
-    //             );
-    //         }
-    //     }
-    // }
+    let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+
+    if binary.breakpoints.is_empty() {
+        prompt::error_nl("no breakpoints set");
+        return Ok("".into());
+    }
+
+    let mut breakpoints = binary
+        .breakpoints
+        .iter()
+        .map(|x| {
+            let (&addr, bp) = x;
+            let id = bp.id;
+            (
+                (
+                    id,
+                    // https://github.com/rust-lang/rust/issues/70887 is stabilised
+                    successors(Some(id), |&id| (id >= 10).then_some(id / 10)).count(),
+                ),
+                addr,
+                binary
+                    .labels
+                    .iter()
+                    .find(|(_, &val)| val == addr)
+                    .map(|(name, _)| name),
+                bp,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    breakpoints.sort_by_key(|(_, addr, _, _)| *addr);
+
+    let max_id_len = breakpoints
+        .iter()
+        .map(|&(id, _, _, _)| id.1)
+        .max()
+        .unwrap_or(0);
+
+    println!("\n{}", "[breakpoints]".green().bold());
+    for (id, addr, text, bp) in breakpoints {
+        let disabled = match bp.enabled {
+            true => "",
+            false => " (disabled)",
+        };
+
+        let ignored = match bp.ignore_count {
+            0 => "".to_string(),
+            i => format!(" (ignored for the next {} hits)", i.to_string().bold()),
+        };
+
+        match text {
+            Some(name) => {
+                println!(
+                    "{}{}: {}{:08x} ({}){}{}",
+                    " ".repeat(max_id_len - id.1),
+                    id.0.to_string().blue(),
+                    "0x".magenta(),
+                    addr,
+                    name.yellow().bold(),
+                    disabled.bright_black(),
+                    ignored
+                );
+            }
+            None => {
+                println!(
+                    "{}{}: {}{:08x}{}{}",
+                    " ".repeat(max_id_len - id.1),
+                    id.0.to_string().blue(),
+                    "0x".magenta(),
+                    addr,
+                    disabled.bright_black(),
+                    ignored
+                );
+            }
+        }
+    }
     println!();
 
     Ok("".into())
@@ -434,71 +440,71 @@ fn breakpoint_toggle(
         ));
     }
 
-    let (addr, _arg_type) = parse_breakpoint_arg(state, &args[0])?;
+    let (addr, arg_type) = parse_breakpoint_arg(state, &args[0])?;
 
     if addr % 4 != 0 {
         prompt::error_nl(format!("address 0x{:08x} should be word-aligned", addr));
         return Ok("".into());
     }
 
-    let _binary = state.binary.as_mut().ok_or(CommandError::MustLoadFile)?;
-
-    // let id;
-    // if let Some(br) = binary.breakpoints.get_mut(&addr) {
-    //     id = br.id;
-    //     br.enabled = match op {
-    //         EnableOp::Enable => true,
-    //         EnableOp::Disable => false,
-    //         EnableOp::Toggle => !br.enabled,
-    //     }
-    // } else {
-    //     prompt::error_nl(format!(
-    //         "breakpoint at {} doesn't exist",
-    //         match arg_type {
-    //             RushArgType::LineNumber => args[0].as_str().into(),
-    //             RushArgType::Immediate => args[0].white(),
-    //             RushArgType::Label => args[0].yellow().bold(),
-    //             RushArgType::Id => args[0].blue(),
-    //         }
-    //     ));
-    //     return Ok("".into());
-    // }
-    //
-    // // already ruled out possibility of entry not existing
-    // let action = match binary.breakpoints.get(&addr).unwrap().enabled {
-    //     true => "enabled",
-    //     false => "disabled",
-    // };
-
-    // let label = match arg_type {
-    //     RushArgType::Immediate => None,
-    //     RushArgType::Label => Some(&args[0]),
-    //     RushArgType::Id | RushArgType::LineNumber => {
-    //         let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
-    //         binary
-    //             .labels
-    //             .iter()
-    //             .find(|(_, &_addr)| _addr == addr)
-    //             .map(|(name, _)| name)
-    //     }
-    // };
-    //
-    // if let Some(label) = label {
-    //     prompt::success_nl(format!(
-    //         "breakpoint {} {} at {} (0x{:08x})",
-    //         format!("!{}", id).blue(),
-    //         action,
-    //         label.yellow().bold(),
-    //         addr
-    //     ));
-    // } else {
-    //     prompt::success_nl(format!(
-    //         "breakpoint {} {} at 0x{:08x}",
-    //         format!("!{}", id).blue(),
-    //         action,
-    //         addr
-    //     ));
-    // }
+    let binary = state.binary.as_mut().ok_or(CommandError::MustLoadFile)?;
+
+    let id;
+    if let Some(br) = binary.breakpoints.get_mut(&addr) {
+        id = br.id;
+        br.enabled = match op {
+            EnableOp::Enable => true,
+            EnableOp::Disable => false,
+            EnableOp::Toggle => !br.enabled,
+        }
+    } else {
+        prompt::error_nl(format!(
+            "breakpoint at {} doesn't exist",
+            match arg_type {
+                RushArgType::LineNumber => args[0].as_str().into(),
+                RushArgType::Immediate => args[0].white(),
+                RushArgType::Label => args[0].yellow().bold(),
+                RushArgType::Id => args[0].blue(),
+            }
+        ));
+        return Ok("".into());
+    }
+
+    // already ruled out possibility of entry not existing
+    let action = match binary.breakpoints.get(&addr).unwrap().enabled {
+        true => "enabled",
+        false => "disabled",
+    };
+
+    let label = match arg_type {
+        RushArgType::Immediate => None,
+        RushArgType::Label => Some(&args[0]),
+        RushArgType::Id | RushArgType::LineNumber => {
+            let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+            binary
+                .labels
+                .iter()
+                .find(|(_, &_addr)| _addr == addr)
+                .map(|(name, _)| name)
+        }
+    };
+
+    if let Some(label) = label {
+        prompt::success_nl(format!(
+            "breakpoint {} {} at {} (0x{:08x})",
+            format!("!{}", id).blue(),
+            action,
+            label.yellow().bold(),
+            addr
+        ));
+    } else {
+        prompt::success_nl(format!(
+            "breakpoint {} {} at 0x{:08x}",
+            format!("!{}", id).blue(),
+            action,
+            addr
+        ));
+    }
 
     Ok("".into())
 }
@@ -540,7 +546,7 @@ fn breakpoint_ignore(
         ));
     }
 
-    let (addr, _arg_type) = parse_breakpoint_arg(state, &args[0])?;
+    let (addr, arg_type) = parse_breakpoint_arg(state, &args[0])?;
 
     if addr % 4 != 0 {
         prompt::error_nl(format!("address 0x{:08x} should be word-aligned", addr));
@@ -558,7 +564,7 @@ fn breakpoint_ignore(
         ));
     }
 
-    let _ignore_count: u32 = args[0].parse().map_err(|_| {
+    let ignore_count: u32 = args[0].parse().map_err(|_| {
         generate_err(
             CommandError::BadArgument {
                 arg: "<ignore count>".into(),
@@ -568,26 +574,26 @@ fn breakpoint_ignore(
         )
     })?;
 
-    let _binary = state.binary.as_mut().ok_or(CommandError::MustLoadFile)?;
-
-    // if let Some(br) = binary.breakpoints.get_mut(&addr) {
-    //     br.ignore_count = ignore_count;
-    //     prompt::success_nl(format!(
-    //         "skipping breakpoint {} {} times",
-    //         format!("!{}", br.id).blue(),
-    //         ignore_count.to_string().yellow()
-    //     ));
-    // } else {
-    //     prompt::error_nl(format!(
-    //         "breakpoint at {} doesn't exist",
-    //         match arg_type {
-    //             RushArgType::LineNumber => args[0].as_str().into(),
-    //             RushArgType::Immediate => args[0].white(),
-    //             RushArgType::Label => args[0].yellow().bold(),
-    //             RushArgType::Id => args[0].blue(),
-    //         }
-    //     ));
-    // }
+    let binary = state.binary.as_mut().ok_or(CommandError::MustLoadFile)?;
+
+    if let Some(br) = binary.breakpoints.get_mut(&addr) {
+        br.ignore_count = ignore_count;
+        prompt::success_nl(format!(
+            "skipping breakpoint {} {} times",
+            format!("!{}", br.id).blue(),
+            ignore_count.to_string().yellow()
+        ));
+    } else {
+        prompt::error_nl(format!(
+            "breakpoint at {} doesn't exist",
+            match arg_type {
+                RushArgType::LineNumber => args[0].as_str().into(),
+                RushArgType::Immediate => args[0].white(),
+                RushArgType::Label => args[0].yellow().bold(),
+                RushArgType::Id => args[0].blue(),
+            }
+        ));
+    }
 
     Ok("".into())
 }
@@ -633,7 +639,7 @@ fn generate_err(error: CommandError, command_name: impl Into<String>) -> Command
 }
 
 fn parse_breakpoint_arg(state: &InteractiveState, arg: &String) -> Result<(u32, RushArgType), CommandError> {
-    let _get_error = |expected: &str| {
+    let get_error = |expected: &str| {
         generate_err(
             CommandError::BadArgument {
                 arg: expected.magenta().to_string(),
@@ -643,77 +649,84 @@ fn parse_breakpoint_arg(state: &InteractiveState, arg: &String) -> Result<(u32,
         )
     };
 
-    let _binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
-
-    // if let Some(id) = arg.strip_prefix('!') {
-    //     let id: u32 = id.parse().map_err(|_| get_error("<id>"))?;
-    //     let addr = binary
-    //         .breakpoints
-    //         .iter()
-    //         .find(|bp| bp.1.id == id)
-    //         .ok_or(CommandError::InvalidBpId {
-    //             arg: arg.to_string(),
-    //         })?
-    //         .0;
-    //
-    //     return Ok((*addr, RushArgType::Id));
-    // }
-    //
-    // if arg.contains(':') {
-    //     // parts contains at least 2 elements
-    //     let mut parts = arg.split(':');
-    //     let mut file = parts.next().unwrap();
-    //     if file.is_empty() {
-    //         let mut filenames = binary
-    //             .line_numbers
-    //             .values()
-    //             .map(|(filename, _)| filename)
-    //             .filter(|filename| filename.as_ref() != "kernel");
-    //
-    //         file = filenames.next().unwrap();
-    //         if !filenames.all(|f| f.as_ref() == file) {
-    //             return Err(CommandError::MustSpecifyFile);
-    //         }
-    //     }
-    //
-    //     let line_number: u32 = parts
-    //         .next()
-    //         .unwrap()
-    //         .parse()
-    //         .map_err(|_| get_error("<line number>"))?;
-    //     let mut lines = binary
-    //         .line_numbers
-    //         .iter()
-    //         .filter(|(_, (filename, _))| filename.as_ref() == file)
-    //         .collect::<Vec<_>>();
-    //     lines.sort_unstable_by(|a, b| a.1.1.cmp(&b.1.1));
-    //
-    //     // use first line after the specified line that contains an instruction
-    //     let addr = lines
-    //         .iter()
-    //         .find(|(_, &(_, _line_number))| _line_number >= line_number)
-    //         .ok_or(CommandError::LineDoesNotExist { line_number })?
-    //         .0;
-    //
-    //     return Ok((*addr, RushArgType::LineNumber));
-    // }
-    //
-    // let arg = rush_parser::parse_argument(arg, 4)
-    //     .map_err(|_| get_error("<addr>"))?;
-    //
-    // if let MpArgument::Number(MpNumber::Immediate(ref imm)) = arg {
-    //     Ok(match imm {
-    //         MpImmediate::I16(imm) => (*imm as u32, RushArgType::Immediate),
-    //         MpImmediate::U16(imm) => (*imm as u32, RushArgType::Immediate),
-    //         MpImmediate::I32(imm) => (*imm as u32, RushArgType::Immediate),
-    //         MpImmediate::U32(imm) => (*imm, RushArgType::Immediate),
-    //         MpImmediate::LabelReference(label) => (
-    //             4,
-    //             RushArgType::Label,
-    //         ),
-    //     })
-    // } else {
-    //     Err(get_error("<addr>"))
-    // }
-    Ok((4, RushArgType::LineNumber))
+    let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+
+    if let Some(id) = arg.strip_prefix('!') {
+        let id: u32 = id.parse().map_err(|_| get_error("<id>"))?;
+        let addr = binary
+            .breakpoints
+            .iter()
+            .find(|bp| bp.1.id == id)
+            .ok_or(CommandError::InvalidBpId {
+                arg: arg.to_string(),
+            })?
+            .0;
+
+        return Ok((*addr, RushArgType::Id));
+    }
+
+    if arg.contains(':') {
+        // parts contains at least 2 elements
+        let mut parts = arg.split(':');
+        let mut file = parts.next().unwrap();
+        if file.is_empty() {
+            let mut filenames = binary
+                .line_numbers
+                .values()
+                .flatten()
+                .map(|(filename, _)| filename)
+                .filter(|filename| filename.as_ref() != "kernel");
+
+            file = filenames.next().ok_or(CommandError::LineDoesNotExist { line_number: 0 })?;
+            if !filenames.all(|f| f.as_ref() == file) {
+                return Err(CommandError::MustSpecifyFile);
+            }
+        }
+
+        let line_number: u32 = parts
+            .next()
+            .unwrap()
+            .parse()
+            .map_err(|_| get_error("<line number>"))?;
+
+        // `prog.s:7` should match `/home/me/prog.s` from the debug info
+        let suffix = format!("/{}", file);
+        let mut lines = binary
+            .line_numbers
+            .iter()
+            .filter_map(|(addr, row)| row.as_ref().map(|row| (addr, row)))
+            .filter(|(_, (filename, _))| filename.as_ref() == file || filename.ends_with(&suffix))
+            .collect::<Vec<_>>();
+        lines.sort_unstable_by_key(|&(&addr, &(_, line))| (line, addr));
+
+        // use first line after the specified line that contains an instruction
+        let addr = lines
+            .iter()
+            .find(|(_, &(_, _line_number))| _line_number >= line_number)
+            .ok_or(CommandError::LineDoesNotExist { line_number })?
+            .0;
+
+        return Ok((*addr, RushArgType::LineNumber));
+    }
+
+    let arg = rush_parser::parse_argument(arg, 4)
+        .map_err(|_| get_error("<addr>"))?;
+
+    if let MpArgument::Number(MpNumber::Immediate(ref imm)) = arg {
+        Ok(match imm {
+            MpImmediate::I16(imm) => (*imm as u32, RushArgType::Immediate),
+            MpImmediate::U16(imm) => (*imm as u32, RushArgType::Immediate),
+            MpImmediate::I32(imm) => (*imm as u32, RushArgType::Immediate),
+            MpImmediate::U32(imm) => (*imm, RushArgType::Immediate),
+            MpImmediate::LabelReference(label) => (
+                *binary
+                    .labels
+                    .get(label)
+                    .ok_or(CommandError::UnknownLabel { label: label.clone() })?,
+                RushArgType::Label,
+            ),
+        })
+    } else {
+        Err(get_error("<addr>"))
+    }
 }
@@ -1,10 +1,11 @@
 use super::*;
-use crate::interactive::{commands::util::expect_u32, error::CommandError};
+use crate::interactive::{
+    commands::util::{self, expect_u32},
+    error::CommandError,
+};
+use rush_lib::decompile;
 use colored::*;
-use rush_lib::KTEXT_BOT;
-use rush_lib::TEXT_BOT;
 
-#[allow(unreachable_code)]
 pub(crate) fn context_command() -> Command {
     command(
         "context",
@@ -42,18 +43,14 @@ pub(crate) fn context_command() -> Command {
             for i in (-n)..=n {
                 let addr = {
                     let addr = base_addr.wrapping_add((i * 4) as u32);
-                    if addr < TEXT_BOT {
-                        continue;
-                    }
-
-                    if addr < KTEXT_BOT && addr >= (TEXT_BOT + binary.text.len() as u32) {
+                    if !binary.is_text(addr) {
                         continue;
                     }
 
                     addr
                 };
 
-                let _inst = {
+                let inst = {
                     if let Ok(inst) = runtime.state().read_mem_word(addr) {
                         inst
                     } else {
@@ -61,8 +58,8 @@ pub(crate) fn context_command() -> Command {
                     }
                 };
 
-                // let parts = decompile::decompile_inst_into_parts(binary, &state.iset, inst, addr);
-                // util::print_inst_parts(binary, &Ok(parts), Some(program), i == 0);
+                let parts = decompile::decompile_inst_into_parts(binary, &state.iset, inst, addr);
+                util::print_inst_parts(binary, &parts, i == 0);
             }
 
             println!();
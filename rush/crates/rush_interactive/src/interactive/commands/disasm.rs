@@ -0,0 +1,67 @@
+use super::*;
+use crate::interactive::{
+    commands::{
+        examine::parse_arg,
+        util::{self, expect_u32},
+    },
+    error::CommandError,
+};
+use colored::*;
+use rush_lib::decompile;
+
+pub(crate) fn disasm_command() -> Command {
+    command(
+        "disasm",
+        vec!["d", "dis"],
+        vec!["addr"],
+        vec!["n"],
+        vec![],
+        &format!(
+            "disassembles 10 (or {}) instructions starting at {}",
+            "[n]".magenta(),
+            "<addr>".magenta(),
+        ),
+        |_, state, label, args| {
+            if label == "__help__" {
+                return Ok(format!(
+                    "Disassembles 10 (or {0}) instructions, starting at {1}.\n\
+                     {1} may be: a register name (`$ra`, `ra`), a register number (`$31`, 31),\n\
+                \x20            a decimal address (`4194304`), a hex address (`{2}400000`),\n\
+                \x20            or a label (`{3}`).\n\
+                     The instruction about to be executed is marked with {4}.",
+                    "[n]".magenta(),
+                    "<addr>".magenta(),
+                    "0x".yellow(),
+                    "main".yellow().bold(),
+                    "->".bright_green().bold(),
+                ));
+            }
+
+            let f: Option<&dyn Fn(i32) -> String> = None;
+
+            let n = match args.get(1) {
+                Some(arg) => expect_u32(label, &"[n]".bright_magenta(), arg, f),
+                None => Ok(10),
+            }?;
+
+            let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+            let runtime = state.runtime.as_ref().unwrap();
+
+            let base_addr = parse_arg(state, &args[0])? & !0b11;
+            for i in 0..n {
+                let addr = base_addr.wrapping_add(i.wrapping_mul(4));
+
+                let inst = match runtime.state().read_mem_word_unchecked(addr) {
+                    Some(inst) => inst,
+                    None => break,
+                };
+
+                let parts = decompile::decompile_inst_into_parts(binary, &state.iset, inst, addr);
+                util::print_inst_parts(binary, &parts, addr == runtime.state().pc());
+            }
+
+            println!();
+            Ok("".into())
+        },
+    )
+}
@@ -0,0 +1,101 @@
+use crate::interactive::{error::CommandError, prompt};
+
+use super::*;
+use colored::*;
+
+pub(crate) fn display_command() -> Command {
+    let subcommands = vec![
+        command(
+            "save",
+            vec!["s"],
+            vec!["file"],
+            vec![],
+            vec![],
+            "",
+            |_, state, label, args| display_save(state, label, args),
+        ),
+    ];
+
+    command(
+        "display",
+        vec!["dp"],
+        vec!["subcommand"],
+        vec![],
+        subcommands,
+        &format!(
+            "work with the framebuffer ({} to list subcommands)",
+            "help display".bold()
+        ),
+        |cmd, state, label, args| {
+            if label == "__help__" && args.is_empty() {
+                return Ok(get_long_help());
+            }
+
+            let cmd = cmd
+                .subcommands
+                .iter()
+                .find(|c| c.name == args[0] || c.aliases.contains(&args[0]));
+            match cmd {
+                None if label == "__help__" => Ok(get_long_help()),
+                Some(cmd) => cmd.exec(state, label, &args[1..]),
+                None => Err(CommandError::BadArgument {
+                    arg: "<subcommand>".magenta().to_string(),
+                    instead: args[0].clone(),
+                }),
+            }
+        },
+    )
+}
+
+fn get_long_help() -> String {
+    format!(
+        "Works with the framebuffer device. Available {3}s are:\n\n\
+         {0} {1} {4} : save what the framebuffer is showing to an image\n\n\
+         {2} {3} will provide more information about the specified subcommand.
+        ",
+        "display".yellow().bold(),
+        "save".purple(),
+        "help display".bold(),
+        "<subcommand>".purple(),
+        "<file>".purple(),
+    )
+}
+
+fn display_save(state: &mut InteractiveState, label: &str, args: &[String]) -> CommandResult<String> {
+    if label == "__help__" {
+        return Ok(format!(
+            "Saves what the framebuffer is showing to {}, as a PNG if it ends in {},\n\
+             and as a PPM otherwise (see `{}` in the config).",
+            "<file>".purple(),
+            ".png".bold(),
+            "framebuffer".bold(),
+        ));
+    }
+
+    state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+    let runtime = state.runtime.as_ref().unwrap();
+
+    let Some(path) = args.first() else {
+        return Err(CommandError::MissingArguments {
+            args: vec!["file".to_string()],
+            instead: args.to_vec(),
+        });
+    };
+
+    let Some(frame) = runtime.state().frame() else {
+        prompt::error_nl("there's no framebuffer to save");
+        return Ok("".into());
+    };
+
+    match frame.save(path) {
+        Ok(()) => prompt::success_nl(format!(
+            "saved the {}x{} frame to {}",
+            frame.width,
+            frame.height,
+            path.bold(),
+        )),
+        Err(err) => prompt::error_nl(format!("couldn't save to {}: {}", path, err)),
+    }
+
+    Ok("".into())
+}
@@ -31,6 +31,8 @@ pub(crate) fn examine_command() -> Command {
                     \x20             or a label (`{4}`).\n\
                          If {7} is provided, then label names will be included in the output.\n\
                          Unprintable bytes are displayed as {5}, and uninitialized bytes are displayed as {6}.\n\
+                         Bytes are displayed in address order, so multi-byte values appear in the program's\n\
+                         byte order (see `endianness` in the config).\n\
                         ",
                         "<section>".magenta(),
                         "<length>".magenta(),
@@ -44,10 +46,10 @@ pub(crate) fn examine_command() -> Command {
                 );
             }
 
-            let _binary = inter_state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+            let binary = inter_state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
 
             let mut segment = if let Some(segment) =
-                args.get(0).and_then(|segment| match segment.as_ref() {
+                args.first().and_then(|segment| match segment.as_ref() {
                     ".data" => Some(Segment::Data),
                     ".text" => Some(Segment::Text),
                     ".stack" => Some(Segment::Stack),
@@ -59,7 +61,7 @@ pub(crate) fn examine_command() -> Command {
                 Segment::Data
             };
 
-            let dump_len = if let Some(len) = args.get(0).and_then(|num| num.parse::<usize>().ok())
+            let dump_len = if let Some(len) = args.first().and_then(|num| num.parse::<usize>().ok())
             {
                 args = &args[1..];
                 len
@@ -67,7 +69,7 @@ pub(crate) fn examine_command() -> Command {
                 128
             };
 
-            let mut base_addr = if let Some(base) = args.get(0).map(|arg| parse_arg(inter_state, arg)) {
+            let mut base_addr = if let Some(base) = args.first().map(|arg| parse_arg(inter_state, arg)) {
                 if base.is_ok() {
                     args = &args[1..];
                 }
@@ -77,8 +79,8 @@ pub(crate) fn examine_command() -> Command {
             };
 
             let hide_labels = args
-                .get(0)
-                .map_or(false, |a| a == &String::from("-nolabels"));
+                .first()
+                .is_some_and(|a| a == &String::from("-nolabels"));
             if hide_labels && base_addr.is_err() {
                 // if -labels was provided, ensure base_addr is valid
                 base_addr = Ok(segment.get_lower_bound());
@@ -103,9 +105,24 @@ pub(crate) fn examine_command() -> Command {
             }
             let offset: usize = row_size * 5 / 2;
 
+            // the first byte may be in the middle of a label, say where
+            if !hide_labels {
+                if let Some((label, label_offset)) = binary.nearest_label(base_addr as u32) {
+                    if label_offset != 0 {
+                        println!(
+                            "{}{:08x} <{}+{}>:",
+                            "0x".yellow(),
+                            base_addr,
+                            label.bold().yellow(),
+                            label_offset,
+                        );
+                    }
+                }
+            }
+
             for nth in 0..rows {
-                let label_strs: Vec<WrappedString> = Vec::new();
-                let arrow_tips = WrappedString::new();
+                let mut label_strs: Vec<WrappedString> = Vec::new();
+                let mut arrow_tips = WrappedString::new();
                 let mut byte_repr = String::with_capacity(row_size * 3);
                 let mut printable_repr = String::with_capacity(row_size);
 
@@ -136,27 +153,27 @@ pub(crate) fn examine_command() -> Command {
                         .read_mem_byte_uninit_unchecked(address as u32)
                         .unwrap();
 
-                    // if !hide_labels {
-                    //     if let Some((label, addr)) = binary
-                    //         .labels
-                    //         .iter()
-                    //         .find(|(_, &addr)| addr == address as u32)
-                    //     {
-                    //         let offset = byte_repr.len();
-                    //         label_strs.push(arrow_tips.clone());
-                    //         label_strs.last_mut().unwrap().pad_insert(
-                    //             offset,
-                    //             format!(
-                    //                 "{}: {}{}",
-                    //                 label.bold().yellow(),
-                    //                 "0x".yellow(),
-                    //                 format!("{addr:08x}").purple()
-                    //             )
-                    //             .as_ref(),
-                    //         );
-                    //         arrow_tips.pad_insert(offset, "|");
-                    //     }
-                    // }
+                    if !hide_labels {
+                        if let Some((label, addr)) = binary
+                            .labels
+                            .iter()
+                            .find(|(_, &addr)| addr == address as u32)
+                        {
+                            let offset = byte_repr.len();
+                            label_strs.push(arrow_tips.clone());
+                            label_strs.last_mut().unwrap().pad_insert(
+                                offset,
+                                format!(
+                                    "{}: {}{}",
+                                    label.bold().yellow(),
+                                    "0x".yellow(),
+                                    format!("{addr:08x}").purple()
+                                )
+                                .as_ref(),
+                            );
+                            arrow_tips.pad_insert(offset, "|");
+                        }
+                    }
 
                     byte_repr.push_str(render_data(byte).as_ref());
                     printable_repr.push_str(
@@ -213,6 +230,16 @@ impl WrappedString {
     fn new() -> Self {
         Self(String::new())
     }
+
+    /// Pads with spaces up to `index`, then appends `string`.
+    /// `index` is in terms of the uncoloured text already in the string.
+    fn pad_insert(&mut self, index: usize, string: &str) {
+        if self.0.len() < index {
+            self.0.push_str(&" ".repeat(index - self.0.len()));
+        }
+
+        self.0.push_str(string);
+    }
 }
 
 impl Display for WrappedString {
@@ -247,7 +274,7 @@ fn generate_err(error: CommandError, command_name: impl Into<String>) -> Command
     }
 }
 
-fn parse_arg(state: &InteractiveState, arg: &String) -> Result<u32, CommandError> {
+pub(super) fn parse_arg(state: &InteractiveState, arg: &String) -> Result<u32, CommandError> {
     let get_error = |expected: &str| {
         generate_err(
             CommandError::BadArgument {
@@ -259,6 +286,7 @@ fn parse_arg(state: &InteractiveState, arg: &String) -> Result<u32, CommandError
     };
 
     if let Ok(register) = Register::from_str(arg.strip_prefix('$').unwrap_or(arg)) {
+        // addresses are the low word of a register, even on a 64-bit processor
         return Ok(state
             .runtime
             .as_ref()
@@ -269,7 +297,7 @@ fn parse_arg(state: &InteractiveState, arg: &String) -> Result<u32, CommandError
             as u32);
     }
 
-    let _binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+    let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
     let arg = rush_parser::parse_argument(arg, 4)
         .map_err(|_| get_error("<addr>"))?;
 
@@ -279,7 +307,10 @@ fn parse_arg(state: &InteractiveState, arg: &String) -> Result<u32, CommandError
             MpImmediate::U16(imm) => *imm as u32,
             MpImmediate::I32(imm) => *imm as u32,
             MpImmediate::U32(imm) => *imm,
-            MpImmediate::LabelReference(_label) => 4
+            MpImmediate::LabelReference(label) => *binary
+                .labels
+                .get(label)
+                .ok_or(CommandError::UnknownLabel { label: label.clone() })?,
         })
     } else {
         Err(get_error("<addr>"))
@@ -0,0 +1,164 @@
+use crate::interactive::{error::CommandError, prompt};
+
+use super::*;
+use colored::*;
+
+pub(crate) fn info_command() -> Command {
+    let subcommands = vec![
+        command(
+            "cache",
+            vec!["caches"],
+            vec![],
+            vec![],
+            vec![],
+            "",
+            |_, state, label, args| info_cache(state, label, args),
+        ),
+        command(
+            "tlb",
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+            "",
+            |_, state, label, args| info_tlb(state, label, args),
+        ),
+    ];
+
+    command(
+        "info",
+        vec!["i"],
+        vec!["subcommand"],
+        vec![],
+        subcommands,
+        &format!(
+            "show the state of the simulated hardware ({} to list subcommands)",
+            "help info".bold()
+        ),
+        |cmd, state, label, args| {
+            if label == "__help__" && args.is_empty() {
+                return Ok(get_long_help());
+            }
+
+            let cmd = cmd
+                .subcommands
+                .iter()
+                .find(|c| c.name == args[0] || c.aliases.contains(&args[0]));
+            match cmd {
+                None if label == "__help__" => Ok(get_long_help()),
+                Some(cmd) => cmd.exec(state, label, &args[1..]),
+                None => Err(CommandError::BadArgument {
+                    arg: "<subcommand>".magenta().to_string(),
+                    instead: args[0].clone(),
+                }),
+            }
+        },
+    )
+}
+
+fn get_long_help() -> String {
+    format!(
+        "Shows the state of the simulated hardware. Available {3}s are:\n\n\
+         {0} {1} : hit and miss statistics for the I- and D-caches\n\
+         {0} {4}   : the valid entries of the TLB\n\n\
+         {2} {3} will provide more information about the specified subcommand.
+        ",
+        "info".yellow().bold(),
+        "cache".purple(),
+        "help info".bold(),
+        "<subcommand>".purple(),
+        "tlb".purple(),
+    )
+}
+
+fn info_cache(state: &mut InteractiveState, label: &str, _args: &[String]) -> CommandResult<String> {
+    if label == "__help__" {
+        return Ok(format!(
+            "Prints the size and layout of the simulated caches, and how many reads and\n\
+             writes have hit or missed them (see `{}` in the config).",
+            "cache".bold(),
+        ));
+    }
+
+    state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+    let runtime = state.runtime.as_ref().unwrap();
+
+    match runtime.cache_summary() {
+        Some(summary) => println!("{}\n", summary),
+        None => prompt::error_nl("no caches are being simulated"),
+    }
+
+    Ok("".into())
+}
+
+fn info_tlb(state: &mut InteractiveState, label: &str, _args: &[String]) -> CommandResult<String> {
+    if label == "__help__" {
+        return Ok(format!(
+            "Prints the TLB entries that map at least one valid page: the virtual pair of pages\n\
+             (VPN2), their ASID (or {}lobal), their size, and the physical address,\n\
+             cache coherency attribute (C), {}irty and {}alid bits of each (see `{}` in the config).",
+            "G".bold(),
+            "D".bold(),
+            "V".bold(),
+            "mmu".bold(),
+        ));
+    }
+
+    state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+    let runtime = state.runtime.as_ref().unwrap();
+
+    let Some(tlb) = runtime.state().cp0().tlb() else {
+        prompt::error_nl("there's no TLB without an MMU");
+        return Ok("".into());
+    };
+
+    println!(
+        "{} entries ({} wired), current ASID {}",
+        tlb.entries().len(),
+        tlb.wired(),
+        format!("{:#04x}", tlb.asid()).bold(),
+    );
+
+    let describe_page = |entry_lo: u32| {
+        format!(
+            "{:#010x} C{} {} {}",
+            (entry_lo >> 6) << 12,
+            (entry_lo >> 3) & 0b111,
+            if entry_lo & 0b100 != 0 { "D" } else { "-" },
+            if entry_lo & 0b010 != 0 { "V" } else { "-" },
+        )
+    };
+
+    let mut invalid = 0;
+    for (index, entry) in tlb.entries().iter().enumerate() {
+        if entry.entry_lo.iter().all(|entry_lo| entry_lo & 0b010 == 0) {
+            invalid += 1;
+            continue;
+        }
+
+        let page_size = match entry.page_size() {
+            size if size >= 1 << 20 => format!("{}M", size >> 20),
+            size => format!("{}K", size >> 10),
+        };
+
+        println!(
+            "  {}  {}  {}  {:>4}  even {}  odd {}",
+            format!("{:>2}", index).blue(),
+            format!("{:#010x}", entry.vpn2()).yellow(),
+            match entry.global {
+                true => "  G ".bold().to_string(),
+                false => format!("{:#04x}", entry.asid()),
+            },
+            page_size,
+            describe_page(entry.entry_lo[0]),
+            describe_page(entry.entry_lo[1]),
+        );
+    }
+
+    if invalid > 0 {
+        println!("  ({} invalid entries)", invalid);
+    }
+    println!();
+
+    Ok("".into())
+}
@@ -1,23 +1,24 @@
-use crate::interactive::prompt;
+use crate::interactive::{error::CommandError, prompt};
 
 use super::*;
 use colored::*;
-use rush_lib::{Binary, Runtime};
+use rush_lib::{Binary, InstSet, RushError, Runtime, TaggedFile};
 use rush_utils::RushConfig;
 
 pub(crate) fn load_command() -> Command {
     command(
         "load",
         vec!["l"],
-        vec!["config"],
+        vec!["file"],
         vec![],
         vec![],
-        "load a config file to run",
+        "load a config or assembly file to run",
         |_, inter_state, label, args| {
             if label == "__help__" {
                 return Ok(
                     format!(
                         "Loads a config file to run, overwriting whatever is currently loaded.\n\
+                         An assembly file (`.s`/`.asm`) or ELF executable can be loaded directly, using spim's memory layout.\n\
                          This command must be run prior to many others, such as `{}`, `{}`, `{}`, ...",
                         "run".bold(),
                         "step".bold(),
@@ -26,13 +27,27 @@ pub(crate) fn load_command() -> Command {
                 );
             }
 
-            let config_file = &args[0];
+            let path = &args[0];
 
-            inter_state.config = Some(RushConfig::new(config_file));
+            let config = if rush_lib::is_assembly(path) || rush_lib::is_elf(path) {
+                RushConfig::spim(path.clone())
+            } else {
+                RushConfig::new(path).map_err(|error| CommandError::CannotLoadConfig { error })?
+            };
 
-            inter_state.binary = Some(Binary::new(&inter_state.config.clone().unwrap()));
+            let binary = if rush_lib::is_assembly(&config.executable) {
+                compile(&config, &inter_state.iset)?
+            } else {
+                Binary::new(&config).map_err(|error| CommandError::CannotLoadBinary { error })?
+            };
 
-            inter_state.runtime = Some(Runtime::new(&inter_state.binary.clone().unwrap(), inter_state.config.clone().unwrap()));
+            let runtime = Runtime::new(&binary, config.clone()).map_err(|error| CommandError::CannotSetUpDevice { error })?;
+
+            inter_state.config = Some(config);
+
+            inter_state.binary = Some(binary);
+
+            inter_state.runtime = Some(runtime);
 
             inter_state.exited = false;
 
@@ -42,3 +57,27 @@ pub(crate) fn load_command() -> Command {
         },
     )
 }
+
+fn compile(config: &RushConfig, iset: &InstSet) -> Result<Binary, CommandError> {
+    let source = std::fs::read_to_string(&config.executable).map_err(|err| {
+        CommandError::CannotReadFile {
+            path: config.executable.clone(),
+            os_error: err.to_string(),
+        }
+    })?;
+
+    let files = vec![TaggedFile::new(Some(config.executable.as_str()), &source)];
+
+    rush_lib::compile(iset, files.clone(), config).map_err(|rush_error| {
+        let file_tag = match &rush_error {
+            RushError::Parser(error) => error.file_tag(),
+            RushError::Compiler(error) => error.file_tag(),
+            RushError::Runtime(_) => "".into(),
+        };
+
+        CommandError::CannotCompile {
+            source: rush_lib::source_of(&files, &file_tag),
+            rush_error,
+        }
+    })
+}
@@ -1,8 +1,11 @@
 mod breakpoint;
 mod context;
+mod disasm;
+mod display;
 mod examine;
 mod exit;
 mod help;
+mod info;
 mod load;
 mod print;
 mod reset;
@@ -12,9 +15,12 @@ pub(crate) mod util;
 
 pub(crate) use breakpoint::breakpoint_command;
 pub(crate) use context::context_command;
+pub(crate) use disasm::disasm_command;
+pub(crate) use display::display_command;
 pub(crate) use examine::examine_command;
 pub(crate) use exit::exit_command;
 pub(crate) use help::help_command;
+pub(crate) use info::info_command;
 pub(crate) use load::load_command;
 pub(crate) use print::print_command;
 pub(crate) use reset::reset_command;
@@ -29,6 +35,7 @@ pub(crate) enum Arguments {
         required: Vec<String>,
         optional: Vec<String>,
     },
+    #[allow(dead_code)]
     VarArgs {
         required: Vec<String>,
         format: String,
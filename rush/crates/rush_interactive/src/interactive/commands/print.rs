@@ -4,7 +4,7 @@ use crate::interactive::{error::CommandError, prompt};
 
 use super::*;
 use colored::*;
-use rush_lib::Register;
+use rush_lib::{compile::elf, Register, Runtime, Safe};
 use rush_parser::*;
 
 #[allow(clippy::format_in_format_args)]
@@ -26,9 +26,9 @@ pub(crate) fn print_command() -> Command {
                     \x20- a {5} {1}: `{2}{6}`, `{2}{7}`, `{2}{8}`,\n\
                     \x20- an {9}: decimal (`4194304`), hex (`{10}400000`), labelled (`{11}`),\n\
                     \x20- {12}: `{2}{13}` - prints all currently initialised registers.\n\
-                         {14} can optionally be specified (default: `{15}`) to specify how the value\n\
-                    \x20 should be printed. Options: `{16}`, `{17}`, `{15}`, `{18}{16}`, `{18}{17}`,\n\
-                    \x20                             `{18}{15}` / `{19}{18}`, `{20}`, `{21}`.",
+                         {14} can optionally be specified (default: `{15}`, or `{22}` for `mips64`) to specify\n\
+                    \x20 how the value should be printed. Options: `{16}`, `{17}`, `{15}`, `{22}`, `{18}{16}`,\n\
+                    \x20                             `{18}{17}`, `{18}{15}` / `{19}{18}`, `{18}{22}`, `{20}`, `{21}`.",
                         "<item>".magenta(),
                         "register".yellow().bold(),
                         "$".yellow(),
@@ -51,6 +51,7 @@ pub(crate) fn print_command() -> Command {
                         "he".bold(),
                         format!("{}{}", "c".yellow().bold(), "har".bold()),
                         format!("{}{}", "s".yellow().bold(), "tring".bold()),
+                        format!("{}{}", "d".yellow().bold(), "word".bold()),
                     ),
                 );
             }
@@ -66,10 +67,22 @@ pub(crate) fn print_command() -> Command {
             let arg = rush_parser::parse_argument(&args[0], 4)
                 .map_err(|_| get_error())?;
 
-            let print_type = &*args.get(1).cloned().unwrap_or_else(|| "word".to_string());
+            let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+            let runtime = state.runtime.as_ref().unwrap();
+
+            // registers are only worth printing whole if they can hold more than a word
+            let default_type = match runtime.state().mips64() {
+                true => "dword",
+                false => "word",
+            };
+            let print_type = &*args
+                .get(1)
+                .cloned()
+                .unwrap_or_else(|| default_type.to_string());
             match print_type {
-                "byte" | "half" | "word" | "xbyte" | "xhalf" | "xword" | "hex" | "char"
-                | "string" | "b" | "h" | "w" | "xb" | "xh" | "xw" | "x" | "c" | "s" => {}
+                "byte" | "half" | "word" | "dword" | "xbyte" | "xhalf" | "xword" | "xdword"
+                | "hex" | "char" | "string" | "b" | "h" | "w" | "d" | "xb" | "xh" | "xw" | "xd"
+                | "x" | "c" | "s" => {}
                 other => {
                     return Err(CommandError::BadArgument {
                         arg: "[format]".magenta().to_string(),
@@ -78,8 +91,6 @@ pub(crate) fn print_command() -> Command {
                 }
             }
 
-            let runtime = state.runtime.as_ref().unwrap();
-
             match arg {
                 MpArgument::Register(MpRegister::Normal(ident)) => {
                     match print_type {
@@ -101,7 +112,7 @@ pub(crate) fn print_command() -> Command {
                     if matches!(ident, MpRegisterIdentifier::Named(ref name) if name == "all") {
                         for register in &Register::all() {
                             if let Ok(val) =
-                                runtime.state().read_register(register.to_u32())
+                                runtime.state().read_register64(register.to_u32())
                             {
                                 let out = format_simple_print(val, print_type);
                                 println!(
@@ -113,7 +124,7 @@ pub(crate) fn print_command() -> Command {
                             }
                         }
 
-                        if let Ok(val) = runtime.state().read_lo() {
+                        if let Ok(val) = runtime.state().read_lo64() {
                             println!(
                                 " {:4} = {}",
                                 "lo".bold(),
@@ -121,7 +132,7 @@ pub(crate) fn print_command() -> Command {
                             );
                         }
 
-                        if let Ok(val) = runtime.state().read_hi() {
+                        if let Ok(val) = runtime.state().read_hi64() {
                             println!(
                                 " {:4} = {}",
                                 "hi".bold(),
@@ -132,7 +143,7 @@ pub(crate) fn print_command() -> Command {
                         println!(
                             " {:4} = {}",
                             "pc".bold(),
-                            format_simple_print(runtime.state().pc() as i32, print_type)
+                            format_simple_print(runtime.state().pc() as i32 as i64, print_type)
                         );
                     } else {
                         let (val, reg_name) = {
@@ -141,11 +152,15 @@ pub(crate) fn print_command() -> Command {
                                     let name = name.to_ascii_lowercase();
 
                                     if name == "pc" {
-                                        Ok((Ok(runtime.state().pc() as i32), "pc"))
+                                        Ok((Ok(runtime.state().pc() as i32 as i64), "pc".to_string()))
                                     } else if name == "hi" {
-                                        Ok((runtime.state().read_hi(), "hi"))
+                                        Ok((runtime.state().read_hi64(), "hi".to_string()))
                                     } else if name == "lo" {
-                                        Ok((runtime.state().read_lo(), "lo"))
+                                        Ok((runtime.state().read_lo64(), "lo".to_string()))
+                                    } else if let Some(reg_num) = fpu_register_number(&name) {
+                                        let val = runtime.state().read_fpu_register(reg_num);
+
+                                        Ok((val.map(|val| val as i32 as i64), name))
                                     } else {
                                         Register::from_str(&name)
                                             .map(|reg| {
@@ -153,8 +168,8 @@ pub(crate) fn print_command() -> Command {
                                                     runtime
                                                         
                                                         .state()
-                                                        .read_register(reg.to_u32()),
-                                                    reg.to_lower_str(),
+                                                        .read_register64(reg.to_u32()),
+                                                    reg.to_lower_str().to_string(),
                                                 )
                                             })
                                             .map_err(|_| CommandError::UnknownRegister {
@@ -169,8 +184,8 @@ pub(crate) fn print_command() -> Command {
                                                 runtime
                                                     
                                                     .state()
-                                                    .read_register(reg.to_u32()),
-                                                reg.to_lower_str(),
+                                                    .read_register64(reg.to_u32()),
+                                                reg.to_lower_str().to_string(),
                                             )
                                         })
                                         .map_err(|_| CommandError::UnknownRegister {
@@ -210,79 +225,50 @@ pub(crate) fn print_command() -> Command {
                             MpImmediate::U16(imm) => imm as u32,
                             MpImmediate::I32(imm) => imm as u32,
                             MpImmediate::U32(imm) => imm,
-                            MpImmediate::LabelReference(_label) => 4
+                            MpImmediate::LabelReference(label) => *binary
+                                .labels
+                                .get(&label)
+                                .ok_or(CommandError::UnknownLabel { label })?,
                         };
 
-                    let map_err = |_err| CommandError::UninitialisedPrint { addr: imm };
+                    let endianness = runtime.state().endianness();
+                    let byte = || -> CommandResult<u8> { Ok(peek_bytes(runtime, imm, 1)?[0]) };
+                    let half = || -> CommandResult<u16> {
+                        let bytes = peek_bytes(runtime, imm, 2)?;
+                        Ok(elf::u16_from_bytes(endianness, [bytes[0], bytes[1]]))
+                    };
+                    let word = || -> CommandResult<u32> {
+                        let bytes = peek_bytes(runtime, imm, 4)?;
+                        Ok(elf::u32_from_bytes(endianness, [bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    };
+                    let dword = || -> CommandResult<u64> {
+                        let bytes = peek_bytes(runtime, imm, 8)?;
+                        Ok(elf::u64_from_bytes(endianness, bytes.try_into().unwrap()))
+                    };
 
                     let value = match print_type {
-                        "byte" | "b" => format!(
-                            "{}",
-                            runtime
-                                
-                                .state()
-                                .read_mem_byte(imm)
-                                .map_err(map_err)?
-                        ),
-                        "half" | "h" => format!(
-                            "{}",
-                            runtime
-                                
-                                .state()
-                                .read_mem_half(imm)
-                                .map_err(map_err)?
-                        ),
-                        "word" | "w" => format!(
-                            "{}",
-                            runtime
-                                
-                                .state()
-                                .read_mem_word(imm)
-                                .map_err(map_err)?
-                        ),
-                        "xbyte" | "xb" => format!("0x{:02x}", {
-                            runtime
-                                
-                                .state()
-                                .read_mem_byte(imm)
-                                .map_err(map_err)?
-                        }),
-                        "xhalf" | "xh" => format!("0x{:04x}", {
-                            runtime
-                                
-                                .state()
-                                .read_mem_half(imm)
-                                .map_err(map_err)?
-                        }),
-                        "xword" | "xw" | "hex" | "x" => format!("0x{:08x}", {
-                            runtime
-                                
-                                .state()
-                                .read_mem_word(imm)
-                                .map_err(map_err)?
-                        }),
-                        "char" | "c" => format!(
-                            "\'{}\'",
-                            ascii::escape_default(
-                                runtime
-                                    
-                                    .state()
-                                    .read_mem_byte(imm)
-                                    .map_err(map_err)?
-                            )
-                        ),
+                        "byte" | "b" => format!("{}", byte()?),
+                        "half" | "h" => format!("{}", half()?),
+                        "word" | "w" => format!("{}", word()?),
+                        "xbyte" | "xb" => format!("0x{:02x}", byte()?),
+                        "xhalf" | "xh" => format!("0x{:04x}", half()?),
+                        "xword" | "xw" | "hex" | "x" => format!("0x{:08x}", word()?),
+                        "dword" | "d" => format!("{}", dword()? as i64),
+                        "xdword" | "xd" => format!("0x{:016x}", dword()?),
+                        "char" | "c" => format!("\'{}\'", ascii::escape_default(byte()?)),
                         "string" | "s" => {
                             let mut text = String::new();
 
                             let mut addr = imm;
                             loop {
-                                let chr = match runtime.state().read_mem_byte(addr) {
-                                    Ok(byte) => byte,
-                                    Err(_) => {
+                                let chr = match peek_bytes(runtime, addr, 1) {
+                                    Ok(bytes) => bytes[0],
+                                    Err(CommandError::UninitialisedPrint { .. }) => {
                                         return Err(CommandError::UnterminatedString {
                                             good_parts: text,
                                         });
                                     }
+                                    Err(err) => return Err(err),
                                 };
 
                                 if chr == 0 {
@@ -308,14 +294,47 @@ pub(crate) fn print_command() -> Command {
     )
 }
 
-fn format_simple_print(val: i32, print_type: &str) -> String {
+/// `f0` to `f31`, the floating point registers.
+fn fpu_register_number(name: &str) -> Option<u32> {
+    name.strip_prefix('f')?
+        .parse()
+        .ok()
+        .filter(|&num| num < 32)
+}
+
+/// Reads `len` bytes at `addr` for `print`, which (like `examine`) looks at memory directly,
+/// and so can see the kernel from user mode. Device registers are never read, as reading one
+/// can change it (e.g. by taking a byte from a FIFO).
+fn peek_bytes(runtime: &Runtime, addr: u32, len: u32) -> CommandResult<Vec<u8>> {
+    let state = runtime.state();
+
+    (0..len)
+        .map(|i| {
+            let addr = addr.wrapping_add(i);
+
+            if state.cp0().translate_unchecked(addr).and_then(|addr| state.device_at(addr)).is_some() {
+                return Err(CommandError::DevicePrint { addr });
+            }
+
+            match state.read_mem_byte_uninit_unchecked(addr) {
+                Ok(Safe::Valid(byte)) => Ok(byte),
+                _ => Err(CommandError::UninitialisedPrint { addr }),
+            }
+        })
+        .collect()
+}
+
+/// `val` is a whole (64-bit) register, which for 32-bit values is sign-extended.
+fn format_simple_print(val: i64, print_type: &str) -> String {
     match print_type {
         "byte" | "b" => format!("{}", val & 0xFF),
         "half" | "h" => format!("{}", val & 0xFFFF),
-        "word" | "w" => format!("{}", val),
+        "word" | "w" => format!("{}", val as i32),
+        "dword" | "d" => format!("{}", val),
         "xbyte" | "xb" => format!("0x{:02x}", (val as u32) & 0xFF),
         "xhalf" | "xh" => format!("0x{:04x}", (val as u32) & 0xFFFF),
         "xword" | "xw" | "hex" | "x" => format!("0x{:08x}", val as u32),
+        "xdword" | "xd" => format!("0x{:016x}", val as u64),
         "char" | "c" => format!("\'{}\'", ascii::escape_default((val & 0xFF) as u8)),
         _ => unreachable!(),
     }
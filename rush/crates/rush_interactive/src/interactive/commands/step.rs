@@ -1,7 +1,7 @@
 use std::sync::atomic::Ordering;
 use std::vec;
 
-use crate::interactive::error::CommandError;
+use crate::interactive::{error::CommandError, prompt};
 
 use super::Command;
 use super::*;
@@ -13,14 +13,22 @@ pub(crate) fn step_command() -> Command {
         vec!["s"],
         vec![],
         vec!["times"],
-        vec![],
+        vec![command(
+            "line",
+            vec!["l"],
+            vec![],
+            vec![],
+            vec![],
+            "step forwards until the source line changes",
+            |_, state, label, args| step_line(state, label, args),
+        )],
         "step forwards or execute a subcommand",
         |cmd, state, label, args| {
             if label == "__help__" && args.is_empty() {
                 return Ok(get_long_help());
             }
 
-            let cmd = args.get(0).and_then(|arg| {
+            let cmd = args.first().and_then(|arg| {
                 cmd.subcommands
                     .iter()
                     .find(|c| &c.name == arg || c.aliases.contains(arg))
@@ -39,6 +47,7 @@ fn get_long_help() -> String {
          \n\
          {3} {4}    : steps backwards instead of forwards\n\
          {3} {5} : steps forwards until the next syscall\n\
+         {3} {8}    : steps forwards until the source line changes\n\
          \n\
          {6} {7} will provide more information about the specified subcommand.\n\
          \n\
@@ -54,6 +63,7 @@ fn get_long_help() -> String {
         "syscall".purple(),
         "help step".white().bold(),
         "[subcommand]".magenta().bold(),
+        "line".purple(),
     )
 }
 
@@ -77,11 +87,28 @@ fn step_forward(state: &mut InteractiveState, label: &str, args: &[String]) -> R
     }
 
     state.interrupted.store(false, Ordering::SeqCst);
+    state.with_keyboard(|state| step_times(state, times))
+}
+
+fn step_times(state: &mut InteractiveState, times: u32) -> Result<String, CommandError> {
+    let mut shown_line = None;
     for _ in 0..times {
+        let binary = state.binary.as_ref().unwrap();
         let runtime = state.runtime.as_ref().unwrap();
 
+        // only show the source line when we move onto a new one
+        let source_line = binary
+            .source_line(runtime.state().pc())
+            .map(|(file, line)| (file.to_string(), line));
+        if source_line.is_some() && source_line != shown_line {
+            util::print_source_line(binary, runtime.state().pc());
+        }
+        shown_line = source_line;
+
         if let Ok(inst) = runtime.current_inst() {
             util::print_inst(
+                binary,
+                &state.iset,
                 inst,
                 runtime.state().pc()
             );
@@ -96,3 +123,71 @@ fn step_forward(state: &mut InteractiveState, label: &str, args: &[String]) -> R
 
     Ok("".into())
 }
+
+fn step_line(state: &mut InteractiveState, label: &str, _args: &[String]) -> Result<String, CommandError> {
+    if label == "__help__" {
+        return Ok(
+            format!(
+                "Steps forwards until execution reaches a different source line,\n\
+                 then prints the new line and the instruction about to be executed.\n\
+                 Instructions without line information (e.g. library code) are stepped over.\n\
+                 This requires the loaded binary to have been compiled with debug info (`{}`).",
+                "-g".bold(),
+            ),
+        );
+    }
+
+    let binary = state.binary.as_ref().ok_or(CommandError::MustLoadFile)?;
+
+    if state.exited {
+        return Err(CommandError::ProgramExited);
+    }
+
+    if binary.line_numbers.is_empty() {
+        prompt::error_nl("the loaded binary has no line number information");
+        return Ok("".into());
+    }
+
+    let start_line = binary
+        .source_line(state.runtime.as_ref().unwrap().state().pc())
+        .map(|(file, line)| (file.to_string(), line));
+
+    state.interrupted.store(false, Ordering::SeqCst);
+    if !state.with_keyboard(|state| step_to_new_line(state, start_line))? {
+        return Ok("".into());
+    }
+
+    let binary = state.binary.as_ref().unwrap();
+    let runtime = state.runtime.as_ref().unwrap();
+
+    util::print_source_line(binary, runtime.state().pc());
+    if let Ok(inst) = runtime.current_inst() {
+        util::print_inst(binary, &state.iset, inst, runtime.state().pc());
+    }
+
+    Ok("".into())
+}
+
+/// Steps until the source line changes from `start_line`, returning whether it did
+/// (rather than the program stopping first, on exit, a breakpoint or an interrupt).
+fn step_to_new_line(state: &mut InteractiveState, start_line: Option<(String, u32)>) -> Result<bool, CommandError> {
+    loop {
+        if state.step(false)? || state.interrupted.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
+
+        let binary = state.binary.as_ref().unwrap();
+        let pc = state.runtime.as_ref().unwrap().state().pc();
+
+        match binary.source_line(pc) {
+            Some((file, line)) if start_line.as_ref() != Some(&(file.to_string(), line)) => break,
+            _ => {}
+        }
+
+        if state.check_breakpoint() {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
@@ -2,6 +2,11 @@ use crate::interactive::{
     error::{CommandError, CommandResult},
 };
 use colored::*;
+use rush_lib::{
+    compile::elf,
+    decompile::{self, Decompiled},
+    Binary, InstSet,
+};
 
 pub(crate) fn expect_u32<F>(
     command: &str,
@@ -35,9 +40,51 @@ pub(crate) fn expect_u32<F>(
 }
 
 pub(crate) fn print_inst(
+    binary: &Binary,
+    iset: &InstSet,
     inst: u32,
     addr: u32,
 ) {
-    let bytes = inst.to_be_bytes();
-    println!("{:#X}: {:02X} {:02X} {:02X} {:02X}", addr, bytes[0], bytes[1], bytes[2], bytes[3]);
-}
\ No newline at end of file
+    let parts = decompile::decompile_inst_into_parts(binary, iset, inst, addr);
+    println!("{}", inst_line(binary, &parts));
+}
+
+/// Like `print_inst`, with a marker in front if `highlight` (e.g. for the current instruction).
+pub(crate) fn print_inst_parts(binary: &Binary, parts: &Decompiled, highlight: bool) {
+    let marker = match highlight {
+        true => "->".bright_green().bold(),
+        false => "  ".normal(),
+    };
+
+    println!("{} {}", marker, inst_line(binary, parts));
+}
+
+fn inst_line(binary: &Binary, parts: &Decompiled) -> String {
+    let label = match binary.symbolize(parts.addr) {
+        Some(label) => format!(" <{}>", label.yellow().bold()),
+        None => String::new(),
+    };
+
+    // as the instruction is laid out in memory
+    let bytes = elf::u32_bytes(binary.endian, parts.opcode);
+    format!(
+        "{:#X}{}: {:02X} {:02X} {:02X} {:02X}  {}",
+        parts.addr, label, bytes[0], bytes[1], bytes[2], bytes[3], parts,
+    )
+}
+
+/// Prints `file:line` for the source line that `addr` belongs to,
+/// along with the text of that line if the source file can be read.
+pub(crate) fn print_source_line(binary: &Binary, addr: u32) {
+    let (file, line) = match binary.source_line(addr) {
+        Some(source_line) => source_line,
+        None => return,
+    };
+
+    let text = std::fs::read_to_string(file)
+        .ok()
+        .and_then(|contents| contents.lines().nth(line as usize - 1).map(str::to_string))
+        .unwrap_or_default();
+
+    println!("{}{}{}  {}", file.bold(), ":".bold(), line.to_string().yellow(), text.trim_end());
+}
@@ -1,5 +1,8 @@
-use rush_lib::{RushError, Register};
+use std::rc::Rc;
+
+use rush_lib::{error::loader::Error as LoaderError, RushError, Register};
 use rush_parser::ErrorLocation;
+use rush_utils::ConfigError;
 
 pub type CommandResult<T> = Result<T, CommandError>;
 
@@ -32,6 +35,19 @@ pub enum CommandError {
         path: String,
         os_error: String,
     },
+    CannotLoadConfig {
+        error: ConfigError,
+    },
+    CannotLoadBinary {
+        error: LoaderError,
+    },
+    CannotSetUpDevice {
+        error: LoaderError,
+    },
+    CannotCompile {
+        source: Rc<str>,
+        rush_error: RushError,
+    },
     CannotParseLine {
         line: String,
         error: ErrorLocation,
@@ -55,6 +71,9 @@ pub enum CommandError {
     UninitialisedPrint {
         addr: u32,
     },
+    DevicePrint {
+        addr: u32,
+    },
     UnterminatedString {
         good_parts: String,
     },
@@ -17,7 +17,7 @@ use rush_lib::error::runtime::{Error, ErrorContext, InvalidSyscallReason, Runtim
 use rush_lib::runtime::{SYS13_OPEN, SYS14_READ, SYS15_WRITE, SYS16_CLOSE};
 use rush_lib::{
     runtime::SteppedRuntime,
-    Binary, RushError, Runtime,
+    Binary, InstSet, RushError, Runtime,
 };
 
 use colored::*;
@@ -26,6 +26,7 @@ use rustyline::{
     config::Configurer, error::ReadlineError, At, Cmd, Editor, KeyCode, KeyEvent, Modifiers,
     Movement, Word,
 };
+use rush_lib::runtime::device::KeyboardMode;
 use rush_lib::runtime::system_clock::get_curr_time_as_millis;
 
 use rush_utils::RushConfig;
@@ -35,12 +36,14 @@ use self::error::{CommandError, CommandResult};
 pub(crate) struct InteractiveState {
     pub(crate) config: Option<RushConfig>,
     pub(crate) commands: Vec<Command>,
+    pub(crate) iset: InstSet,
     pub(crate) binary: Option<Binary>,
     pub(crate) runtime: Option<Runtime>,
     pub(crate) exited: bool,
     pub(crate) prev_command: Option<String>,
     pub(crate) confirm_exit: bool,
     pub(crate) interrupted: Arc<AtomicBool>,
+    keyboard: Option<KeyboardMode>,
 }
 
 impl InteractiveState {
@@ -55,16 +58,21 @@ impl InteractiveState {
                 commands::breakpoint_command(),
                 commands::context_command(),
                 commands::examine_command(),
+                commands::disasm_command(),
                 commands::print_command(),
+                commands::info_command(),
+                commands::display_command(),
                 commands::help_command(),
                 commands::exit_command(),
             ],
+            iset: rush_instructions::inst_set(),
             binary: None,
             runtime: None,
             exited: false,
             prev_command: None,
             confirm_exit: false,
             interrupted: Arc::new(AtomicBool::new(false)),
+            keyboard: None,
         }
     }
 
@@ -184,6 +192,20 @@ impl InteractiveState {
             CommandError::CannotReadFile { path, os_error } => {
                 prompt::error(format!("failed to read file `{}`: {}", path, os_error));
             }
+            CommandError::CannotLoadConfig { error } => {
+                prompt::error(error);
+            }
+            CommandError::CannotLoadBinary { error } => {
+                prompt::error(format!("failed to load binary: {}", error));
+            }
+            CommandError::CannotSetUpDevice { error } => {
+                prompt::error(error);
+            }
+            CommandError::CannotCompile { source, rush_error } => match rush_error {
+                RushError::Parser(error) => error.show_error(source),
+                RushError::Compiler(error) => error.show_error(source),
+                RushError::Runtime(_) => self.rush_error(rush_error, ErrorContext::Interactive, None),
+            },
             CommandError::CannotParseLine { line: _, error: _ } => {
                 prompt::error("failed to parse");
 
@@ -250,6 +272,10 @@ impl InteractiveState {
             CommandError::UninitialisedPrint { addr } => {
                 prompt::error(format!("memory at address 0x{:08x} is uninitialized", addr));
             }
+            CommandError::DevicePrint { addr } => {
+                prompt::error(format!("address 0x{:08x} is a device register", addr));
+                prompt::tip("reading a device register can change it, so the debugger never does");
+            }
             CommandError::UnterminatedString { good_parts } => {
                 prompt::error(format!("unterminated string: \"{}\"", good_parts.red()));
                 prompt::tip(format!(
@@ -273,8 +299,12 @@ impl InteractiveState {
     ) {
 
         match error {
+            RushError::Parser(error) => prompt::error(error.error().message()),
+            RushError::Compiler(error) => prompt::error(error.error().message()),
             RushError::Runtime(error) => error.show_error(
                 context,
+                &self.iset,
+                self.binary.as_ref().unwrap(),
                 self.runtime.as_ref().unwrap(),
             ),
         }
@@ -314,19 +344,19 @@ impl InteractiveState {
                         runtime_handler::sys4_print_string(verbose, &args.value);
                     }
                     ReadInt(guard) => {
-                        let value = runtime_handler::sys5_read_int(verbose);
+                        let value = self.read_input(|| runtime_handler::sys5_read_int(verbose));
                         self.runtime = Some(guard(value));
                     }
                     ReadFloat(guard) => {
-                        let value = runtime_handler::sys6_read_float(verbose);
+                        let value = self.read_input(|| runtime_handler::sys6_read_float(verbose));
                         self.runtime = Some(guard(value));
                     }
                     ReadDouble(guard) => {
-                        let value = runtime_handler::sys7_read_double(verbose);
+                        let value = self.read_input(|| runtime_handler::sys7_read_double(verbose));
                         self.runtime = Some(guard(value));
                     }
                     ReadString(args, guard) => {
-                        let value = runtime_handler::sys8_read_string(verbose, args.max_len);
+                        let value = self.read_input(|| runtime_handler::sys8_read_string(verbose, args.max_len));
                         self.runtime = Some(guard(value));
                     }
                     Sbrk(args, new_runtime) => {
@@ -344,7 +374,7 @@ impl InteractiveState {
                         runtime_handler::sys11_print_char(verbose, args.value);
                     }
                     ReadChar(guard) => {
-                        let value = runtime_handler::sys12_read_char(verbose);
+                        let value = self.read_input(|| runtime_handler::sys12_read_char(verbose));
                         self.runtime = Some(guard(value));
                     }
                     Open(_args, guard) => {
@@ -432,25 +462,28 @@ impl InteractiveState {
                 self.runtime = Some(new_runtime);
 
                 self.runtime.as_mut().unwrap().system_clock.stop_time = get_curr_time_as_millis();
-
-                let delta_time_sec = (self.runtime.as_mut().unwrap().system_clock.stop_time - self.runtime.as_mut().unwrap().system_clock.start_time) / 1000 + 1;
-                let ips = self.runtime.as_mut().unwrap().system_clock.steps / delta_time_sec;
-                println!("Emulation running on {} sec., IPS = {}", delta_time_sec, ips);
+                eprintln!("{}", self.runtime.as_ref().unwrap().system_clock.summary());
+                if let Some(cache_summary) = self.runtime.as_ref().unwrap().cache_summary() {
+                    eprintln!("{}", cache_summary);
+                }
 
                 return Err(CommandError::RuntimeError { rush_error: err });
             }
         };
 
-        Ok(if self.exited {
-            true
-        } else {
-            false
-        })
+        Ok(self.exited || breakpoint || trapped)
     }
 
     pub(crate) fn step(&mut self, verbose: bool) -> CommandResult<bool> {
         let runtime = take(self.runtime.as_mut().unwrap());
-        self.eval_stepped_runtime(verbose, runtime.step())
+        let result = self.eval_stepped_runtime(verbose, runtime.step());
+
+        let runtime = self.runtime.as_mut().unwrap();
+        if let Some(hazard) = runtime.state_mut().take_load_hazard() {
+            prompt::warning(hazard.message(&self.iset, self.binary.as_ref().unwrap(), runtime));
+        }
+
+        result
     }
 
     pub(crate) fn run(&mut self) -> CommandResult<String> {
@@ -460,15 +493,88 @@ impl InteractiveState {
 
         self.interrupted.store(false, Ordering::SeqCst);
         self.runtime.as_mut().unwrap().system_clock.start_time = get_curr_time_as_millis();
-        while !self.interrupted.load(Ordering::SeqCst) {
-            if self.step(false)? {
-                break;
+
+        self.with_keyboard(|state| {
+            let mut first = true;
+            while !state.interrupted.load(Ordering::SeqCst) {
+                // don't immediately stop again on the breakpoint we're resuming from
+                if !first && state.check_breakpoint() {
+                    break;
+                }
+                first = false;
+
+                if state.step(false)? {
+                    break;
+                }
+                state.runtime.as_mut().unwrap().system_clock.steps += 1;
+            }
+
+            Ok("".into())
+        })
+    }
+
+    /// Runs `exec` (which steps the program) with keys going to the program's keyboard or UART
+    /// as they're typed, if it has one, and gives the terminal back to the prompt afterwards.
+    pub(crate) fn with_keyboard<T>(&mut self, exec: impl FnOnce(&mut Self) -> T) -> T {
+        self.keyboard = self.runtime.as_ref().unwrap().state().uses_terminal().then(KeyboardMode::enter);
+        let result = exec(self);
+        self.keyboard = None;
+
+        result
+    }
+
+    /// Reads a syscall's input as a line, echoed, as at the prompt,
+    /// leaving the keyboard's mode while it does (see `with_keyboard`).
+    fn read_input<T>(&mut self, read: impl FnOnce() -> T) -> T {
+        let keyboard = self.keyboard.take().is_some();
+        let value = read();
+        if keyboard {
+            self.keyboard = Some(KeyboardMode::enter());
+        }
+
+        value
+    }
+
+    /// Returns whether execution should stop at the current pc due to a breakpoint,
+    /// running the breakpoint's commands if so.
+    pub(crate) fn check_breakpoint(&mut self) -> bool {
+        let pc = self.runtime.as_ref().unwrap().state().pc();
+        let binary = self.binary.as_mut().unwrap();
+
+        let (id, commands) = match binary.breakpoints.get_mut(&pc) {
+            Some(bp) if bp.enabled && bp.ignore_count > 0 => {
+                bp.ignore_count -= 1;
+                return false;
             }
-            self.runtime.as_mut().unwrap().system_clock.steps += 1;
-            self.runtime.as_mut().unwrap().system_clock.update(1)
+            Some(bp) if bp.enabled => (bp.id, bp.commands.clone()),
+            _ => return false,
+        };
+
+        // a breakpoint in a delay slot is reported at its branch, as the two execute together
+        let delay_slot = self.runtime.as_ref().unwrap().state().delay_slot();
+        let addr = delay_slot.map_or(pc, |delay_slot| delay_slot.branch_addr);
+
+        let label = match binary.symbolize(addr) {
+            Some(label) => format!(" ({})", label.yellow().bold()),
+            None => String::new(),
+        };
+
+        println!(
+            "{} {} {} {}{:08x}{}",
+            "stopped at breakpoint".bold(),
+            format!("!{}", id).blue(),
+            if delay_slot.is_some() { "in the delay slot of" } else { "at" },
+            "0x".yellow(),
+            addr,
+            label,
+        );
+
+        for command in commands {
+            println!("{}{}", self.prompt(), command);
+            self.do_exec(&command);
         }
 
-        Ok("".into())
+        true
     }
 
     pub(crate) fn reset(&mut self) -> CommandResult<()> {
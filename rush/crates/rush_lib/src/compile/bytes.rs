@@ -1,3 +1,4 @@
+#[allow(dead_code)]
 pub trait ToBytes {
     fn to_bytes(&self) -> Vec<u8>;
 }
@@ -0,0 +1,197 @@
+use std::{cell::RefCell, collections::HashMap};
+
+use linked_hash_map::LinkedHashMap;
+use rush_parser::{MpConstValue, MpConstValueLoc};
+
+use super::object::{Reference, RelocationType};
+use crate::error::{compiler, InternalError, RushInternalResult};
+
+/// Resolves constants (`NAME = expr`) and labels to their values.
+///
+/// During layout the label addresses aren't known yet, so `labels` is `None`
+/// and every label reference evaluates to zero. Anything whose *size* depends
+/// on a value (`.space`, `.align`, repeat counts) should only use constants.
+///
+/// When assembling an object, labels that aren't defined are left for the linker:
+/// they evaluate to zero too, and every use of a label's address is noted as a `Reference`.
+pub(super) struct Resolver<'a> {
+    constants: &'a HashMap<String, MpConstValueLoc>,
+    labels: Option<&'a LinkedHashMap<String, u32>>,
+    references: Option<RefCell<Vec<Reference>>>,
+}
+
+impl<'a> Resolver<'a> {
+    pub(super) fn new(
+        constants: &'a HashMap<String, MpConstValueLoc>,
+        labels: Option<&'a LinkedHashMap<String, u32>>,
+    ) -> Self {
+        Self {
+            constants,
+            labels,
+            references: None,
+        }
+    }
+
+    pub(super) fn relocatable(
+        constants: &'a HashMap<String, MpConstValueLoc>,
+        labels: &'a LinkedHashMap<String, u32>,
+    ) -> Self {
+        Self {
+            constants,
+            labels: Some(labels),
+            references: Some(RefCell::new(vec![])),
+        }
+    }
+
+    pub(super) fn is_constant(&self, name: &str) -> bool {
+        self.constants.contains_key(name)
+    }
+
+    /// The address of a label, or zero if labels haven't been laid out yet.
+    pub(super) fn label(&self, name: &str) -> RushInternalResult<u32> {
+        let labels = match self.labels {
+            Some(labels) => labels,
+            None => return Ok(0),
+        };
+
+        match labels.get(name) {
+            Some(&addr) => Ok(addr),
+            None if self.references.is_some() => Ok(0),
+            None => Err(InternalError::Compiler(compiler::Error::UnresolvedLabel {
+                label: name.to_string(),
+                similar: similar_names(name, labels.keys()),
+            })),
+        }
+    }
+
+    /// Whether `name` is a label that will only be defined once this object is linked.
+    pub(super) fn is_external(&self, name: &str) -> bool {
+        match (self.labels, &self.references) {
+            (Some(labels), Some(_)) => !labels.contains_key(name) && !self.is_constant(name),
+            _ => false,
+        }
+    }
+
+    /// Notes that the value at `addr` uses the address of `label`, if assembling an object.
+    pub(super) fn relocate(&self, addr: u32, kind: RelocationType, label: String) {
+        if let Some(references) = &self.references {
+            references.borrow_mut().push(Reference { addr, kind, label });
+        }
+    }
+
+    /// The references noted since this was last called.
+    pub(super) fn take_references(&self) -> Vec<Reference> {
+        self.references
+            .as_ref()
+            .map(|references| references.take())
+            .unwrap_or_default()
+    }
+
+    /// The label `value` is relative to, if it refers to exactly one.
+    /// The difference of two labels doesn't depend on where they end up, so needs no relocation.
+    pub(super) fn label_reference(&self, value: &MpConstValueLoc) -> Option<String> {
+        let mut labels = vec![];
+        self.collect_labels(value, &mut labels);
+
+        match labels.len() {
+            1 => labels.pop(),
+            _ => None,
+        }
+    }
+
+    fn collect_labels(&self, value: &MpConstValueLoc, labels: &mut Vec<String>) {
+        match &value.0 {
+            MpConstValue::Value(_) => {}
+            MpConstValue::Const(name) if !self.is_constant(name) => labels.push(name.to_string()),
+            MpConstValue::Const(_) => {}
+            MpConstValue::Minus(x) | MpConstValue::Neg(x) => self.collect_labels(x, labels),
+            MpConstValue::Mult(x, y)
+            | MpConstValue::Sum(x, y)
+            | MpConstValue::Sub(x, y)
+            | MpConstValue::Div(x, y)
+            | MpConstValue::Mod(x, y)
+            | MpConstValue::And(x, y)
+            | MpConstValue::Or(x, y)
+            | MpConstValue::Xor(x, y)
+            | MpConstValue::Shl(x, y)
+            | MpConstValue::Shr(x, y) => {
+                self.collect_labels(x, labels);
+                self.collect_labels(y, labels);
+            }
+        }
+    }
+
+    /// Resolves a bare name, preferring constants over labels.
+    pub(super) fn name(&self, name: &str) -> RushInternalResult<i64> {
+        match self.constants.get_key_value(name) {
+            Some((name, value)) => self.evaluate_inner(value, &mut vec![name.as_str()]),
+            None => self.label(name).map(|addr| addr as i64),
+        }
+    }
+
+    pub(super) fn evaluate(&self, value: &MpConstValueLoc) -> RushInternalResult<i64> {
+        self.evaluate_inner(value, &mut vec![])
+    }
+
+    fn evaluate_inner<'v>(
+        &'v self,
+        value: &'v MpConstValueLoc,
+        visiting: &mut Vec<&'v str>,
+    ) -> RushInternalResult<i64> {
+        let mut eval = |value: &'v MpConstValueLoc| self.evaluate_inner(value, visiting);
+
+        Ok(match &value.0 {
+            MpConstValue::Value(value) => *value as i64,
+            MpConstValue::Const(name) => {
+                return match self.constants.get(name.as_str()) {
+                    // a constant defined in terms of itself can never be resolved
+                    Some(_) if visiting.contains(&name.as_str()) => {
+                        Err(InternalError::Compiler(compiler::Error::UnresolvedConstant {
+                            label: name.to_string(),
+                        }))
+                    }
+                    Some(value) => {
+                        visiting.push(name);
+                        let result = self.evaluate_inner(value, visiting);
+                        visiting.pop();
+
+                        result
+                    }
+                    None => self.label(name).map(|addr| addr as i64),
+                };
+            }
+            MpConstValue::Minus(x) => eval(x)?.wrapping_neg(),
+            MpConstValue::Mult(x, y) => eval(x)?.wrapping_mul(eval(y)?),
+            MpConstValue::Sum(x, y) => eval(x)?.wrapping_add(eval(y)?),
+            MpConstValue::Sub(x, y) => eval(x)?.wrapping_sub(eval(y)?),
+            MpConstValue::Div(x, y) => {
+                let (x, y) = (eval(x)?, eval(y)?);
+
+                x.checked_div(y)
+                    .ok_or(InternalError::Compiler(compiler::Error::DivisionByZero))?
+            }
+            MpConstValue::Mod(x, y) => {
+                let (x, y) = (eval(x)?, eval(y)?);
+
+                x.checked_rem(y)
+                    .ok_or(InternalError::Compiler(compiler::Error::DivisionByZero))?
+            }
+            MpConstValue::And(x, y) => eval(x)? & eval(y)?,
+            MpConstValue::Or(x, y) => eval(x)? | eval(y)?,
+            MpConstValue::Xor(x, y) => eval(x)? ^ eval(y)?,
+            MpConstValue::Neg(x) => !eval(x)?,
+            MpConstValue::Shl(x, y) => eval(x)?.wrapping_shl(eval(y)? as u32),
+            MpConstValue::Shr(x, y) => eval(x)?.wrapping_shr(eval(y)? as u32),
+        })
+    }
+}
+
+/// Names that are a small number of edits away from `name`, for "did you mean" tips.
+pub(super) fn similar_names<'a>(name: &str, names: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let max_distance = (name.len() / 3).max(1);
+
+    names
+        .filter(|other| strsim::levenshtein(name, other) <= max_distance)
+        .cloned()
+        .collect()
+}
@@ -0,0 +1,187 @@
+use rush_parser::{MpConstValueLoc, MpDirective};
+
+use rush_utils::Endianness;
+
+use super::{constants::Resolver, elf, object::RelocationType};
+use crate::{
+    error::{
+        compiler::{self, DirectiveType},
+        InternalError, RushInternalResult,
+    },
+    util::Safe,
+};
+
+/// The alignment spim automatically applies before a directive's data.
+pub(super) fn alignment(directive: &MpDirective) -> u32 {
+    match directive {
+        MpDirective::Half(_) => 2,
+        MpDirective::Word(_) | MpDirective::Float(_) => 4,
+        MpDirective::Double(_) => 8,
+        _ => 1,
+    }
+}
+
+/// Whether the directive emits data, and so is only allowed in the data segment.
+pub(super) fn is_data(directive: &MpDirective) -> bool {
+    !matches!(
+        directive,
+        MpDirective::Text
+            | MpDirective::Data
+            | MpDirective::KText(_)
+            | MpDirective::KData(_)
+            | MpDirective::Align(_)
+            | MpDirective::Globl(_)
+    )
+}
+
+/// The number of bytes a directive takes up when placed at `addr`,
+/// without materialising them (`.space` can be very large).
+pub(super) fn directive_size(
+    directive: &MpDirective,
+    addr: u32,
+    resolver: &Resolver,
+) -> RushInternalResult<u32> {
+    match directive {
+        MpDirective::Space(value) => {
+            fits(resolver.evaluate(value)?, DirectiveType::Space, 0, u32::MAX as i64).map(|size| size as u32)
+        }
+        // the byte order doesn't change the size
+        _ => directive_bytes(directive, addr, Endianness::Little, resolver).map(|bytes| bytes.len() as u32),
+    }
+}
+
+/// The bytes a directive assembles to when placed at `addr`.
+///
+/// Multi-byte values are stored in the `endian` byte order, as the runtime loads them.
+pub(super) fn directive_bytes(
+    directive: &MpDirective,
+    addr: u32,
+    endian: Endianness,
+    resolver: &Resolver,
+) -> RushInternalResult<Vec<Safe<u8>>> {
+    let valid = |bytes: &[u8]| bytes.iter().copied().map(Safe::Valid).collect::<Vec<_>>();
+
+    Ok(match directive {
+        MpDirective::Text
+        | MpDirective::Data
+        | MpDirective::KText(_)
+        | MpDirective::KData(_)
+        | MpDirective::Globl(_) => vec![],
+        MpDirective::Ascii(string) => valid(string.as_bytes()),
+        MpDirective::Asciiz(string) => {
+            let mut bytes = valid(string.as_bytes());
+            bytes.push(Safe::Valid(0));
+
+            bytes
+        }
+        MpDirective::Byte(values) => {
+            repeat(values, resolver, |value| {
+                let value = fits(value, DirectiveType::Byte, i8::MIN as i64, u8::MAX as i64)?;
+
+                Ok(valid(&[value as u8]))
+            })?
+        }
+        MpDirective::Half(values) => {
+            repeat(values, resolver, |value| {
+                let value = fits(value, DirectiveType::Half, i16::MIN as i64, u16::MAX as i64)?;
+
+                Ok(valid(&elf::u16_bytes(endian, value as u16)))
+            })?
+        }
+        MpDirective::Word(values) => {
+            let mut bytes = vec![];
+            for (value, count) in values {
+                let label = resolver.label_reference(value);
+                let word = fits(resolver.evaluate(value)?, DirectiveType::Word, i32::MIN as i64, u32::MAX as i64)?;
+
+                for _ in 0..count_of(count, resolver)? {
+                    // e.g. a jump table, which needs to be fixed up once it's linked
+                    if let Some(label) = &label {
+                        let word_addr = addr.wrapping_add(bytes.len() as u32);
+                        resolver.relocate(word_addr, RelocationType::Mips32, label.clone());
+                    }
+
+                    bytes.extend(valid(&elf::u32_bytes(endian, word as u32)));
+                }
+            }
+
+            bytes
+        }
+        MpDirective::Float(values) => {
+            let mut bytes = vec![];
+            for (value, count) in values {
+                for _ in 0..count_of(count, resolver)? {
+                    bytes.extend(valid(&elf::u32_bytes(endian, value.to_bits())));
+                }
+            }
+
+            bytes
+        }
+        MpDirective::Double(values) => {
+            let mut bytes = vec![];
+            for (value, count) in values {
+                for _ in 0..count_of(count, resolver)? {
+                    bytes.extend(valid(&elf::u64_bytes(endian, value.to_bits())));
+                }
+            }
+
+            bytes
+        }
+        MpDirective::Align(value) => {
+            let power = fits(resolver.evaluate(value)?, DirectiveType::Align, 0, 31)?;
+            let align = 1u32 << power;
+
+            vec![Safe::Uninitialised; (align_up(addr, align) - addr) as usize]
+        }
+        MpDirective::Space(value) => {
+            let size = fits(resolver.evaluate(value)?, DirectiveType::Space, 0, u32::MAX as i64)?;
+
+            vec![Safe::Uninitialised; size as usize]
+        }
+    })
+}
+
+pub(super) fn align_up(addr: u32, align: u32) -> u32 {
+    addr.wrapping_add(align - 1) & !(align - 1)
+}
+
+fn repeat(
+    values: &[(MpConstValueLoc, Option<MpConstValueLoc>)],
+    resolver: &Resolver,
+    mut to_bytes: impl FnMut(i64) -> RushInternalResult<Vec<Safe<u8>>>,
+) -> RushInternalResult<Vec<Safe<u8>>> {
+    let mut bytes = vec![];
+
+    for (value, count) in values {
+        let value_bytes = to_bytes(resolver.evaluate(value)?)?;
+
+        for _ in 0..count_of(count, resolver)? {
+            bytes.extend_from_slice(&value_bytes);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// The repeat count of a `value:count` entry, defaulting to one.
+fn count_of(count: &Option<MpConstValueLoc>, resolver: &Resolver) -> RushInternalResult<u32> {
+    match count {
+        Some(count) => {
+            fits(resolver.evaluate(count)?, DirectiveType::Space, 0, u32::MAX as i64).map(|count| count as u32)
+        }
+        None => Ok(1),
+    }
+}
+
+fn fits(value: i64, directive_type: DirectiveType, range_low: i64, range_high: i64) -> RushInternalResult<i64> {
+    if (range_low..=range_high).contains(&value) {
+        Ok(value)
+    } else {
+        Err(InternalError::Compiler(compiler::Error::ConstantValueDoesNotFit {
+            directive_type,
+            value,
+            range_low,
+            range_high,
+        }))
+    }
+}
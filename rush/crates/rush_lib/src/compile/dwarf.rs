@@ -0,0 +1,442 @@
+use std::{collections::BTreeMap, rc::Rc};
+
+use rush_utils::Endianness;
+
+use super::elf::ElfFile;
+
+const DW_LNS_COPY: u8 = 1;
+const DW_LNS_ADVANCE_PC: u8 = 2;
+const DW_LNS_ADVANCE_LINE: u8 = 3;
+const DW_LNS_SET_FILE: u8 = 4;
+const DW_LNS_CONST_ADD_PC: u8 = 8;
+const DW_LNS_FIXED_ADVANCE_PC: u8 = 9;
+
+const DW_LNE_END_SEQUENCE: u8 = 1;
+const DW_LNE_SET_ADDRESS: u8 = 2;
+const DW_LNE_DEFINE_FILE: u8 = 3;
+
+const DW_LNCT_PATH: u64 = 1;
+const DW_LNCT_DIRECTORY_INDEX: u64 = 2;
+
+const DW_FORM_BLOCK: u64 = 0x09;
+const DW_FORM_DATA1: u64 = 0x0b;
+const DW_FORM_DATA2: u64 = 0x05;
+const DW_FORM_DATA4: u64 = 0x06;
+const DW_FORM_DATA8: u64 = 0x07;
+const DW_FORM_DATA16: u64 = 0x1e;
+const DW_FORM_LINE_STRP: u64 = 0x1f;
+const DW_FORM_SDATA: u64 = 0x0d;
+const DW_FORM_STRING: u64 = 0x08;
+const DW_FORM_STRP: u64 = 0x0e;
+const DW_FORM_UDATA: u64 = 0x0f;
+
+/// Maps the address of every row in the line table to its `(file, line)`,
+/// and the end of every sequence to `None`, as the code after it has no line information.
+pub type LineNumbers = BTreeMap<u32, Option<(Rc<str>, u32)>>;
+
+/// Decodes the line-number programs in `.debug_line` (DWARF versions 2 to 5).
+///
+/// Debug info is a nicety rather than a requirement for running a binary,
+/// so a malformed unit just stops decoding, keeping whatever was read before it.
+pub fn line_numbers(elf: &ElfFile) -> LineNumbers {
+    let mut line_numbers = LineNumbers::new();
+
+    let section = |name: &str| {
+        elf.section(name)
+            .and_then(|section| elf.section_data(section).ok())
+            .unwrap_or_default()
+    };
+
+    let debug_line = section(".debug_line");
+    let sections = Sections {
+        debug_str: section(".debug_str"),
+        debug_line_str: section(".debug_line_str"),
+    };
+
+    let mut reader = Reader::new(debug_line, elf.endian());
+    while !reader.is_empty() {
+        if parse_unit(&mut reader, &sections, &mut line_numbers).is_none() {
+            break;
+        }
+    }
+
+    line_numbers
+}
+
+struct Sections<'a> {
+    debug_str: &'a [u8],
+    debug_line_str: &'a [u8],
+}
+
+struct LineState {
+    address: u32,
+    file: u64,
+    line: u32,
+}
+
+fn parse_unit(reader: &mut Reader, sections: &Sections, line_numbers: &mut LineNumbers) -> Option<()> {
+    let (unit_length, offset_size) = match reader.u32()? {
+        0xFFFF_FFFF => (reader.u64()? as usize, 8),
+        unit_length => (unit_length as usize, 4),
+    };
+
+    let mut unit = Reader::new(reader.bytes(unit_length)?, reader.endian);
+    unit.offset_size = offset_size;
+
+    let version = unit.u16()?;
+    if !(2..=5).contains(&version) {
+        return Some(());
+    }
+
+    if version >= 5 {
+        let _address_size = unit.u8()?;
+        let _segment_selector_size = unit.u8()?;
+    }
+
+    let header_length = unit.offset()? as usize;
+    let program = unit.pos.checked_add(header_length)?;
+
+    let minimum_instruction_length = unit.u8()? as u32;
+    if version >= 4 {
+        let _maximum_operations_per_instruction = unit.u8()?;
+    }
+    let _default_is_stmt = unit.u8()?;
+    let line_base = unit.u8()? as i8 as i32;
+    let line_range = unit.u8()?;
+    let opcode_base = unit.u8()?;
+    let standard_opcode_lengths = unit.bytes(opcode_base.saturating_sub(1) as usize)?.to_vec();
+
+    if line_range == 0 {
+        return None;
+    }
+
+    let (directories, mut files) = if version >= 5 {
+        let directories = parse_entries(&mut unit, sections)?
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+
+        let files = parse_entries(&mut unit, sections)?
+            .into_iter()
+            .map(|(path, dir)| join(directories.get(dir as usize), &path))
+            .collect::<Vec<_>>();
+
+        (directories, files)
+    } else {
+        let mut directories = vec![];
+        loop {
+            let directory = unit.str()?;
+            if directory.is_empty() {
+                break;
+            }
+
+            directories.push(directory);
+        }
+
+        // file 0 doesn't exist before DWARF 5, pad so indices line up
+        let mut files: Vec<Rc<str>> = vec![Rc::from("")];
+        loop {
+            let name = unit.str()?;
+            if name.is_empty() {
+                break;
+            }
+
+            let dir = unit.uleb()?;
+            let _mtime = unit.uleb()?;
+            let _length = unit.uleb()?;
+
+            files.push(join(dir.checked_sub(1).and_then(|dir| directories.get(dir as usize)), &name));
+        }
+
+        (directories, files)
+    };
+
+    unit.pos = program;
+
+    let initial_state = || LineState {
+        address: 0,
+        file: 1,
+        line: 1,
+    };
+
+    let mut state = initial_state();
+    while !unit.is_empty() {
+        let opcode = unit.u8()?;
+
+        if opcode >= opcode_base {
+            let adjusted = opcode - opcode_base;
+            state.address = state
+                .address
+                .wrapping_add((adjusted / line_range) as u32 * minimum_instruction_length);
+            state.line = u32::try_from(state.line as i64 + line_base as i64 + (adjusted % line_range) as i64).ok()?;
+
+            emit_row(&state, &files, line_numbers);
+            continue;
+        }
+
+        match opcode {
+            0 => {
+                let len = unit.uleb()? as usize;
+                let mut extended = Reader::new(unit.bytes(len)?, unit.endian);
+
+                match extended.u8()? {
+                    DW_LNE_END_SEQUENCE => {
+                        // (a sequence that starts where this one ends keeps its first row)
+                        line_numbers.entry(state.address).or_insert(None);
+                        state = initial_state();
+                    }
+                    DW_LNE_SET_ADDRESS => {
+                        state.address = match len - 1 {
+                            8 => extended.u64()? as u32,
+                            _ => extended.u32()?,
+                        }
+                    }
+                    DW_LNE_DEFINE_FILE => {
+                        let name = extended.str()?;
+                        let dir = extended.uleb()?;
+
+                        files.push(join(dir.checked_sub(1).and_then(|dir| directories.get(dir as usize)), &name));
+                    }
+                    _ => {}
+                }
+            }
+            DW_LNS_COPY => emit_row(&state, &files, line_numbers),
+            DW_LNS_ADVANCE_PC => {
+                state.address = state
+                    .address
+                    .wrapping_add(u32::try_from(unit.uleb()?).ok()?.checked_mul(minimum_instruction_length)?);
+            }
+            DW_LNS_ADVANCE_LINE => state.line = u32::try_from((state.line as i64).checked_add(unit.sleb()?)?).ok()?,
+            DW_LNS_SET_FILE => state.file = unit.uleb()?,
+            DW_LNS_CONST_ADD_PC => {
+                let adjusted = 255 - opcode_base;
+                state.address = state
+                    .address
+                    .wrapping_add((adjusted / line_range) as u32 * minimum_instruction_length);
+            }
+            DW_LNS_FIXED_ADVANCE_PC => state.address = state.address.wrapping_add(unit.u16()? as u32),
+            _ => {
+                // includes opcodes we don't care about (e.g. set_column),
+                // all of which only take ULEB128 operands
+                let operands = standard_opcode_lengths.get(opcode as usize - 1).copied().unwrap_or(0);
+                for _ in 0..operands {
+                    unit.uleb()?;
+                }
+            }
+        }
+    }
+
+    Some(())
+}
+
+fn emit_row(state: &LineState, files: &[Rc<str>], line_numbers: &mut LineNumbers) {
+    if state.line == 0 {
+        return;
+    }
+
+    if let Some(file) = files.get(state.file as usize) {
+        line_numbers.insert(state.address, Some((file.clone(), state.line)));
+    }
+}
+
+/// Parses a DWARF 5 directory/file name table, yielding `(path, directory index)` pairs.
+fn parse_entries(unit: &mut Reader, sections: &Sections) -> Option<Vec<(String, u64)>> {
+    let format_count = unit.u8()?;
+
+    let mut formats = vec![];
+    for _ in 0..format_count {
+        formats.push((unit.uleb()?, unit.uleb()?));
+    }
+
+    // a count larger than the whole unit is garbage (and would take forever to loop over)
+    let count = unit.uleb()?;
+    if count > unit.bytes.len() as u64 {
+        return None;
+    }
+
+    let mut entries = vec![];
+    for _ in 0..count {
+        let mut path = String::new();
+        let mut dir = 0;
+
+        for &(content_type, form) in &formats {
+            match (content_type, form) {
+                (DW_LNCT_PATH, DW_FORM_STRING) => path = unit.str()?,
+                (DW_LNCT_PATH, DW_FORM_LINE_STRP) => path = read_str(sections.debug_line_str, unit.offset()?),
+                (DW_LNCT_PATH, DW_FORM_STRP) => path = read_str(sections.debug_str, unit.offset()?),
+                (DW_LNCT_DIRECTORY_INDEX, _) => dir = unit.form_uint(form)?,
+                _ => unit.skip_form(form)?,
+            }
+        }
+
+        entries.push((path, dir));
+    }
+
+    Some(entries)
+}
+
+fn join(directory: Option<&String>, name: &str) -> Rc<str> {
+    match directory {
+        Some(directory) if !directory.is_empty() && !name.starts_with('/') => {
+            Rc::from(format!("{}/{}", directory.trim_end_matches('/'), name))
+        }
+        _ => Rc::from(name),
+    }
+}
+
+fn read_str(section: &[u8], offset: u64) -> String {
+    let bytes = section.get(offset as usize..).unwrap_or_default();
+    let len = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    endian: Endianness,
+    offset_size: u8,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], endian: Endianness) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            endian,
+            offset_size: 4,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.pos >= self.bytes.len()
+    }
+
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.bytes(2)?.try_into().ok()?;
+
+        Some(match self.endian {
+            Endianness::Big => u16::from_be_bytes(bytes),
+            Endianness::Little => u16::from_le_bytes(bytes),
+        })
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.bytes(4)?.try_into().ok()?;
+
+        Some(match self.endian {
+            Endianness::Big => u32::from_be_bytes(bytes),
+            Endianness::Little => u32::from_le_bytes(bytes),
+        })
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let bytes = self.bytes(8)?.try_into().ok()?;
+
+        Some(match self.endian {
+            Endianness::Big => u64::from_be_bytes(bytes),
+            Endianness::Little => u64::from_le_bytes(bytes),
+        })
+    }
+
+    fn offset(&mut self) -> Option<u64> {
+        match self.offset_size {
+            8 => self.u64(),
+            _ => self.u32().map(u64::from),
+        }
+    }
+
+    fn uleb(&mut self) -> Option<u64> {
+        let mut result = 0u64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7F) as u64) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                return Some(result);
+            }
+        }
+    }
+
+    fn sleb(&mut self) -> Option<i64> {
+        let mut result = 0i64;
+        let mut shift = 0;
+
+        loop {
+            let byte = self.u8()?;
+            if shift < 64 {
+                result |= ((byte & 0x7F) as i64) << shift;
+            }
+            shift += 7;
+
+            if byte & 0x80 == 0 {
+                if shift < 64 && byte & 0x40 != 0 {
+                    result |= -1 << shift;
+                }
+
+                return Some(result);
+            }
+        }
+    }
+
+    fn str(&mut self) -> Option<String> {
+        let rest = self.bytes.get(self.pos..)?;
+        let len = rest.iter().position(|&byte| byte == 0)?;
+        self.pos += len + 1;
+
+        Some(String::from_utf8_lossy(&rest[..len]).into_owned())
+    }
+
+    fn form_uint(&mut self, form: u64) -> Option<u64> {
+        match form {
+            DW_FORM_DATA1 => self.u8().map(u64::from),
+            DW_FORM_DATA2 => self.u16().map(u64::from),
+            DW_FORM_DATA4 => self.u32().map(u64::from),
+            DW_FORM_DATA8 => self.u64(),
+            DW_FORM_UDATA => self.uleb(),
+            _ => None,
+        }
+    }
+
+    fn skip_form(&mut self, form: u64) -> Option<()> {
+        match form {
+            DW_FORM_STRING => {
+                self.str()?;
+            }
+            DW_FORM_LINE_STRP | DW_FORM_STRP => {
+                self.offset()?;
+            }
+            DW_FORM_DATA16 => {
+                self.bytes(16)?;
+            }
+            DW_FORM_BLOCK => {
+                let len = self.uleb()? as usize;
+                self.bytes(len)?;
+            }
+            DW_FORM_SDATA => {
+                self.sleb()?;
+            }
+            _ => {
+                self.form_uint(form)?;
+            }
+        }
+
+        Some(())
+    }
+}
@@ -0,0 +1,574 @@
+use rush_utils::Endianness;
+
+use crate::error::loader::Error;
+
+pub const ELF_MAGIC: [u8; 4] = [0x7F, b'E', b'L', b'F'];
+
+const EI_CLASS: usize = 4;
+const EI_DATA: usize = 5;
+const ELFCLASS32: u8 = 1;
+const ELFDATA2LSB: u8 = 1;
+const ELFDATA2MSB: u8 = 2;
+
+const EV_CURRENT: u8 = 1;
+
+pub const ET_REL: u16 = 1;
+pub const ET_EXEC: u16 = 2;
+pub const EM_MIPS: u16 = 8;
+
+pub const PT_LOAD: u32 = 1;
+pub const PF_X: u32 = 1;
+pub const PF_W: u32 = 2;
+pub const PF_R: u32 = 4;
+
+pub const SHT_PROGBITS: u32 = 1;
+pub const SHT_SYMTAB: u32 = 2;
+pub const SHT_STRTAB: u32 = 3;
+pub const SHT_REL: u32 = 9;
+pub const SHT_DYNSYM: u32 = 11;
+
+pub const SHF_WRITE: u32 = 1;
+pub const SHF_ALLOC: u32 = 2;
+pub const SHF_EXECINSTR: u32 = 4;
+
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_ABS: u16 = 0xFFF1;
+
+pub const STB_LOCAL: u8 = 0;
+pub const STB_GLOBAL: u8 = 1;
+pub const STT_NOTYPE: u8 = 0;
+pub const STT_OBJECT: u8 = 1;
+pub const STT_FUNC: u8 = 2;
+pub const STT_SECTION: u8 = 3;
+
+pub const R_MIPS_32: u8 = 2;
+pub const R_MIPS_26: u8 = 4;
+pub const R_MIPS_HI16: u8 = 5;
+pub const R_MIPS_LO16: u8 = 6;
+
+const EHDR_SIZE: usize = 52;
+const PHDR_SIZE: usize = 32;
+const SHDR_SIZE: usize = 40;
+const SYM_SIZE: usize = 16;
+const REL_SIZE: usize = 8;
+
+#[derive(Debug, Default, Clone)]
+pub struct ElfHeader {
+    pub elf_type: u16,
+    pub machine: u16,
+    pub entry: u32,
+    pub phoff: u32,
+    pub shoff: u32,
+    pub flags: u32,
+    pub phentsize: u16,
+    pub phnum: u16,
+    pub shentsize: u16,
+    pub shnum: u16,
+    pub shstrndx: u16,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProgramHeader {
+    pub p_type: u32,
+    pub offset: u32,
+    pub vaddr: u32,
+    pub paddr: u32,
+    pub filesz: u32,
+    pub memsz: u32,
+    pub flags: u32,
+    pub align: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SectionHeader {
+    pub name: String,
+    pub sh_type: u32,
+    pub flags: u32,
+    pub addr: u32,
+    pub offset: u32,
+    pub size: u32,
+    pub link: u32,
+    pub info: u32,
+    pub addralign: u32,
+    pub entsize: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub name: String,
+    pub value: u32,
+    pub size: u32,
+    pub bind: u8,
+    pub sym_type: u8,
+    pub shndx: u16,
+}
+
+/// An entry of a `SHT_REL` section. The addend is whatever is already at `offset`.
+#[derive(Debug, Clone)]
+pub struct Rel {
+    pub offset: u32,
+    pub symbol: u32,
+    pub rel_type: u8,
+}
+
+/// A parsed view over the bytes of an ELF32 file.
+///
+/// Only the parts of the format rush actually needs are decoded,
+/// everything else is left in `bytes` to be read on demand.
+pub struct ElfFile<'a> {
+    bytes: &'a [u8],
+    endian: Endianness,
+    header: ElfHeader,
+    program_headers: Vec<ProgramHeader>,
+    section_headers: Vec<SectionHeader>,
+}
+
+pub fn is_elf(bytes: &[u8]) -> bool {
+    bytes.starts_with(&ELF_MAGIC)
+}
+
+impl<'a> ElfFile<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        if !is_elf(bytes) || bytes.len() < EHDR_SIZE || bytes[EI_CLASS] != ELFCLASS32 {
+            return Err(Error::NotElf32);
+        }
+
+        let endian = match bytes[EI_DATA] {
+            ELFDATA2LSB => Endianness::Little,
+            ELFDATA2MSB => Endianness::Big,
+            _ => return Err(Error::NotElf32),
+        };
+
+        let mut elf = Self {
+            bytes,
+            endian,
+            header: ElfHeader::default(),
+            program_headers: vec![],
+            section_headers: vec![],
+        };
+
+        elf.header = ElfHeader {
+            elf_type: elf.read_u16(16, "e_type")?,
+            machine: elf.read_u16(18, "e_machine")?,
+            entry: elf.read_u32(24, "e_entry")?,
+            phoff: elf.read_u32(28, "e_phoff")?,
+            shoff: elf.read_u32(32, "e_shoff")?,
+            flags: elf.read_u32(36, "e_flags")?,
+            phentsize: elf.read_u16(42, "e_phentsize")?,
+            phnum: elf.read_u16(44, "e_phnum")?,
+            shentsize: elf.read_u16(46, "e_shentsize")?,
+            shnum: elf.read_u16(48, "e_shnum")?,
+            shstrndx: elf.read_u16(50, "e_shstrndx")?,
+        };
+
+        if elf.header.machine != EM_MIPS {
+            return Err(Error::UnsupportedMachine {
+                machine: elf.header.machine,
+            });
+        }
+
+        let phentsize = (elf.header.phentsize as usize).max(PHDR_SIZE);
+        for i in 0..elf.header.phnum as usize {
+            let base = elf.header.phoff as usize + i * phentsize;
+
+            let program_header = ProgramHeader {
+                p_type: elf.read_u32(base, "program header")?,
+                offset: elf.read_u32(base + 4, "program header")?,
+                vaddr: elf.read_u32(base + 8, "program header")?,
+                paddr: elf.read_u32(base + 12, "program header")?,
+                filesz: elf.read_u32(base + 16, "program header")?,
+                memsz: elf.read_u32(base + 20, "program header")?,
+                flags: elf.read_u32(base + 24, "program header")?,
+                align: elf.read_u32(base + 28, "program header")?,
+            };
+
+            elf.program_headers.push(program_header);
+        }
+
+        let shentsize = (elf.header.shentsize as usize).max(SHDR_SIZE);
+        let mut name_offsets = vec![];
+        for i in 0..elf.header.shnum as usize {
+            let base = elf.header.shoff as usize + i * shentsize;
+
+            name_offsets.push(elf.read_u32(base, "section header")?);
+            let section_header = SectionHeader {
+                name: String::new(),
+                sh_type: elf.read_u32(base + 4, "section header")?,
+                flags: elf.read_u32(base + 8, "section header")?,
+                addr: elf.read_u32(base + 12, "section header")?,
+                offset: elf.read_u32(base + 16, "section header")?,
+                size: elf.read_u32(base + 20, "section header")?,
+                link: elf.read_u32(base + 24, "section header")?,
+                info: elf.read_u32(base + 28, "section header")?,
+                addralign: elf.read_u32(base + 32, "section header")?,
+                entsize: elf.read_u32(base + 36, "section header")?,
+            };
+
+            elf.section_headers.push(section_header);
+        }
+
+        // section names can only be resolved once `.shstrtab` has been read
+        if let Some(shstrtab) = elf.section_headers.get(elf.header.shstrndx as usize) {
+            let shstrtab = elf.section_data(shstrtab)?;
+
+            for (section_header, name_offset) in elf.section_headers.iter_mut().zip(name_offsets) {
+                section_header.name = read_str(shstrtab, name_offset as usize);
+            }
+        }
+
+        Ok(elf)
+    }
+
+    pub fn endian(&self) -> Endianness {
+        self.endian
+    }
+
+    pub fn header(&self) -> &ElfHeader {
+        &self.header
+    }
+
+    pub fn program_headers(&self) -> &[ProgramHeader] {
+        &self.program_headers
+    }
+
+    pub fn section_headers(&self) -> &[SectionHeader] {
+        &self.section_headers
+    }
+
+    pub fn section(&self, name: &str) -> Option<&SectionHeader> {
+        self.section_headers.iter().find(|section| section.name == name)
+    }
+
+    pub fn bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// The file contents backing a segment, i.e. `p_filesz` bytes from `p_offset`.
+    pub fn segment_data(&self, program_header: &ProgramHeader) -> Result<&'a [u8], Error> {
+        self.slice(
+            program_header.offset as usize,
+            program_header.filesz as usize,
+            "segment data",
+        )
+    }
+
+    pub fn section_data(&self, section_header: &SectionHeader) -> Result<&'a [u8], Error> {
+        self.slice(
+            section_header.offset as usize,
+            section_header.size as usize,
+            "section data",
+        )
+    }
+
+    /// All symbols from `.symtab` and `.dynsym`, in file order.
+    pub fn symbols(&self) -> Result<Vec<Symbol>, Error> {
+        let mut symbols = vec![];
+
+        for section_header in &self.section_headers {
+            if section_header.sh_type != SHT_SYMTAB && section_header.sh_type != SHT_DYNSYM {
+                continue;
+            }
+
+            let strtab = match self.section_headers.get(section_header.link as usize) {
+                Some(strtab) => self.section_data(strtab)?,
+                None => &[],
+            };
+
+            let entsize = (section_header.entsize as usize).max(SYM_SIZE);
+            let count = section_header.size as usize / entsize;
+
+            // entry 0 is always the reserved null symbol
+            for i in 1..count {
+                let base = section_header.offset as usize + i * entsize;
+                let info = self.slice(base + 12, 1, "symbol")?[0];
+
+                symbols.push(Symbol {
+                    name: read_str(strtab, self.read_u32(base, "symbol")? as usize),
+                    value: self.read_u32(base + 4, "symbol")?,
+                    size: self.read_u32(base + 8, "symbol")?,
+                    bind: info >> 4,
+                    sym_type: info & 0xF,
+                    shndx: self.read_u16(base + 14, "symbol")?,
+                });
+            }
+        }
+
+        Ok(symbols)
+    }
+
+    /// The entries of a `SHT_REL` section. Symbol indices count the null symbol,
+    /// so are one more than the index into `symbols`.
+    pub fn relocations(&self, section_header: &SectionHeader) -> Result<Vec<Rel>, Error> {
+        let entsize = (section_header.entsize as usize).max(REL_SIZE);
+        let count = section_header.size as usize / entsize;
+
+        (0..count)
+            .map(|i| {
+                let base = section_header.offset as usize + i * entsize;
+                let info = self.read_u32(base + 4, "relocation")?;
+
+                Ok(Rel {
+                    offset: self.read_u32(base, "relocation")?,
+                    symbol: info >> 8,
+                    rel_type: info as u8,
+                })
+            })
+            .collect()
+    }
+
+    pub fn slice(&self, offset: usize, len: usize, what: &'static str) -> Result<&'a [u8], Error> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.bytes.get(offset..end))
+            .ok_or(Error::Truncated { what })
+    }
+
+    pub fn read_u16(&self, offset: usize, what: &'static str) -> Result<u16, Error> {
+        let bytes: [u8; 2] = self.slice(offset, 2, what)?.try_into().unwrap();
+
+        Ok(match self.endian {
+            Endianness::Big => u16::from_be_bytes(bytes),
+            Endianness::Little => u16::from_le_bytes(bytes),
+        })
+    }
+
+    pub fn read_u32(&self, offset: usize, what: &'static str) -> Result<u32, Error> {
+        let bytes: [u8; 4] = self.slice(offset, 4, what)?.try_into().unwrap();
+
+        Ok(match self.endian {
+            Endianness::Big => u32::from_be_bytes(bytes),
+            Endianness::Little => u32::from_le_bytes(bytes),
+        })
+    }
+}
+
+/// Reads a NUL-terminated string out of a string table,
+/// yielding an empty string if the offset is out of bounds.
+fn read_str(strtab: &[u8], offset: usize) -> String {
+    let bytes = strtab.get(offset..).unwrap_or_default();
+    let len = bytes.iter().position(|&byte| byte == 0).unwrap_or(bytes.len());
+
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// A section to be written out by `write`.
+#[derive(Debug, Clone, Default)]
+pub struct OutputSection {
+    pub name: String,
+    pub sh_type: u32,
+    pub flags: u32,
+    pub addr: u32,
+    pub link: u32,
+    pub info: u32,
+    pub addralign: u32,
+    pub entsize: u32,
+    pub data: Vec<u8>,
+}
+
+/// Builds up a string table, where strings are referred to by their offset.
+pub struct StringTable {
+    bytes: Vec<u8>,
+}
+
+impl StringTable {
+    pub fn new() -> Self {
+        // offset 0 is always the empty string
+        Self { bytes: vec![0] }
+    }
+
+    pub fn add(&mut self, string: &str) -> u32 {
+        let offset = self.bytes.len() as u32;
+        self.bytes.extend_from_slice(string.as_bytes());
+        self.bytes.push(0);
+
+        offset
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl Default for StringTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encodes a `.symtab` entry.
+pub fn symbol_entry(endian: Endianness, name: u32, value: u32, bind: u8, sym_type: u8, shndx: u16) -> Vec<u8> {
+    let mut entry = vec![];
+    entry.extend(u32_bytes(endian, name));
+    entry.extend(u32_bytes(endian, value));
+    entry.extend(u32_bytes(endian, 0));
+    entry.push(bind << 4 | sym_type);
+    entry.push(0);
+    entry.extend(u16_bytes(endian, shndx));
+
+    entry
+}
+
+/// Encodes a `SHT_REL` entry.
+pub fn rel_entry(endian: Endianness, offset: u32, symbol: u32, rel_type: u8) -> Vec<u8> {
+    let mut entry = vec![];
+    entry.extend(u32_bytes(endian, offset));
+    entry.extend(u32_bytes(endian, symbol << 8 | rel_type as u32));
+
+    entry
+}
+
+/// Writes an ELF32 file made up of `sections`, which are numbered from 1
+/// (after the null section) for the purposes of `link` and `shndx`.
+/// `.shstrtab` is added after them.
+///
+/// Executables get a `PT_LOAD` segment for every `SHF_ALLOC` section.
+pub fn write(endian: Endianness, elf_type: u16, entry: u32, sections: &[OutputSection]) -> Vec<u8> {
+    let mut shstrtab = StringTable::new();
+    let names: Vec<u32> = sections.iter().map(|section| shstrtab.add(&section.name)).collect();
+    let shstrtab_name = shstrtab.add(".shstrtab");
+
+    let is_loaded = |section: &OutputSection| elf_type == ET_EXEC && section.flags & SHF_ALLOC != 0;
+    let phnum = sections.iter().filter(|section| is_loaded(section)).count();
+
+    let phoff = EHDR_SIZE;
+    let mut offset = phoff + phnum * PHDR_SIZE;
+
+    let mut offsets = vec![];
+    for section in sections {
+        let align = section.addralign.max(1) as usize;
+        offset = offset.div_ceil(align) * align;
+        offsets.push(offset);
+        offset += section.data.len();
+    }
+    let shstrtab = shstrtab.into_bytes();
+    let shstrtab_offset = offset;
+    offset += shstrtab.len();
+
+    let shoff = offset.div_ceil(4) * 4;
+    let shnum = sections.len() + 2;
+
+    let mut bytes = Vec::with_capacity(shoff + shnum * SHDR_SIZE);
+
+    let ei_data = match endian {
+        Endianness::Big => ELFDATA2MSB,
+        Endianness::Little => ELFDATA2LSB,
+    };
+    bytes.extend(ELF_MAGIC);
+    bytes.extend([ELFCLASS32, ei_data, EV_CURRENT]);
+    bytes.resize(16, 0);
+    bytes.extend(u16_bytes(endian, elf_type));
+    bytes.extend(u16_bytes(endian, EM_MIPS));
+    bytes.extend(u32_bytes(endian, EV_CURRENT as u32));
+    bytes.extend(u32_bytes(endian, entry));
+    bytes.extend(u32_bytes(endian, if phnum == 0 { 0 } else { phoff as u32 }));
+    bytes.extend(u32_bytes(endian, shoff as u32));
+    bytes.extend(u32_bytes(endian, 0));
+    bytes.extend(u16_bytes(endian, EHDR_SIZE as u16));
+    bytes.extend(u16_bytes(endian, PHDR_SIZE as u16));
+    bytes.extend(u16_bytes(endian, phnum as u16));
+    bytes.extend(u16_bytes(endian, SHDR_SIZE as u16));
+    bytes.extend(u16_bytes(endian, shnum as u16));
+    bytes.extend(u16_bytes(endian, shnum as u16 - 1));
+
+    for (section, &offset) in sections.iter().zip(&offsets) {
+        if !is_loaded(section) {
+            continue;
+        }
+
+        let mut flags = PF_R;
+        if section.flags & SHF_WRITE != 0 {
+            flags |= PF_W;
+        }
+        if section.flags & SHF_EXECINSTR != 0 {
+            flags |= PF_X;
+        }
+
+        bytes.extend(u32_bytes(endian, PT_LOAD));
+        bytes.extend(u32_bytes(endian, offset as u32));
+        bytes.extend(u32_bytes(endian, section.addr));
+        bytes.extend(u32_bytes(endian, section.addr));
+        bytes.extend(u32_bytes(endian, section.data.len() as u32));
+        bytes.extend(u32_bytes(endian, section.data.len() as u32));
+        bytes.extend(u32_bytes(endian, flags));
+        bytes.extend(u32_bytes(endian, section.addralign.max(1)));
+    }
+
+    for (section, &offset) in sections.iter().zip(&offsets) {
+        bytes.resize(offset, 0);
+        bytes.extend(&section.data);
+    }
+    bytes.extend(&shstrtab);
+    bytes.resize(shoff, 0);
+
+    let mut section_header = |name: u32, section: &OutputSection, offset: usize| {
+        bytes.extend(u32_bytes(endian, name));
+        bytes.extend(u32_bytes(endian, section.sh_type));
+        bytes.extend(u32_bytes(endian, section.flags));
+        bytes.extend(u32_bytes(endian, section.addr));
+        bytes.extend(u32_bytes(endian, offset as u32));
+        bytes.extend(u32_bytes(endian, section.data.len() as u32));
+        bytes.extend(u32_bytes(endian, section.link));
+        bytes.extend(u32_bytes(endian, section.info));
+        bytes.extend(u32_bytes(endian, section.addralign));
+        bytes.extend(u32_bytes(endian, section.entsize));
+    };
+
+    section_header(0, &OutputSection::default(), 0);
+    for ((section, &name), &offset) in sections.iter().zip(&names).zip(&offsets) {
+        section_header(name, section, offset);
+    }
+    section_header(
+        shstrtab_name,
+        &OutputSection {
+            sh_type: SHT_STRTAB,
+            addralign: 1,
+            data: shstrtab,
+            ..Default::default()
+        },
+        shstrtab_offset,
+    );
+
+    bytes
+}
+
+pub fn u16_bytes(endian: Endianness, value: u16) -> [u8; 2] {
+    match endian {
+        Endianness::Big => value.to_be_bytes(),
+        Endianness::Little => value.to_le_bytes(),
+    }
+}
+
+pub fn u32_bytes(endian: Endianness, value: u32) -> [u8; 4] {
+    match endian {
+        Endianness::Big => value.to_be_bytes(),
+        Endianness::Little => value.to_le_bytes(),
+    }
+}
+
+pub fn u64_bytes(endian: Endianness, value: u64) -> [u8; 8] {
+    match endian {
+        Endianness::Big => value.to_be_bytes(),
+        Endianness::Little => value.to_le_bytes(),
+    }
+}
+
+pub fn u16_from_bytes(endian: Endianness, bytes: [u8; 2]) -> u16 {
+    match endian {
+        Endianness::Big => u16::from_be_bytes(bytes),
+        Endianness::Little => u16::from_le_bytes(bytes),
+    }
+}
+
+pub fn u32_from_bytes(endian: Endianness, bytes: [u8; 4]) -> u32 {
+    match endian {
+        Endianness::Big => u32::from_be_bytes(bytes),
+        Endianness::Little => u32::from_le_bytes(bytes),
+    }
+}
+
+pub fn u64_from_bytes(endian: Endianness, bytes: [u8; 8]) -> u64 {
+    match endian {
+        Endianness::Big => u64::from_be_bytes(bytes),
+        Endianness::Little => u64::from_le_bytes(bytes),
+    }
+}
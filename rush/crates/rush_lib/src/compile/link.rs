@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use linked_hash_map::LinkedHashMap;
+use rush_utils::{Endianness, RushConfig};
+
+use super::{
+    data::align_up,
+    elf,
+    object::{Object, RelocationTarget, RelocationType, Section},
+    Binary, BinarySegment, TEXT_BOT,
+};
+use crate::{error::linker::Error, util::Safe};
+
+const ORI: u32 = 0x0D;
+
+/// Links objects into a `Binary`, laid out like `compile`'s: the text of every object
+/// (in order) from `memory.text.start` (or `TEXT_BOT`), and their data from `memory.data_bot`.
+///
+/// Local symbols can only be used by the object that defines them,
+/// while global symbols have to be defined by exactly one object.
+/// Every object has to be in the same byte order (see `endianness`).
+pub fn link(objects: &[Object], config: &RushConfig) -> Result<Binary, Error> {
+    let endian = endianness(objects, config);
+    if let Some(object) = objects.iter().find(|object| object.endian != endian) {
+        return Err(Error::WrongEndianness {
+            object: object.name.clone(),
+            expected: endian,
+        });
+    }
+
+    let text_bot = match config.memory.text.start {
+        0 => TEXT_BOT,
+        start => start,
+    };
+    let data_bot = config.memory.data_bot;
+
+    let mut bases = vec![];
+    let mut text_addr = text_bot;
+    let mut data_addr = data_bot;
+    for object in objects {
+        text_addr = align_up(text_addr, 4);
+        data_addr = align_up(data_addr, 8);
+        bases.push((text_addr, data_addr));
+
+        text_addr = text_addr.wrapping_add(object.text.len() as u32);
+        data_addr = data_addr.wrapping_add(object.data.len() as u32);
+    }
+
+    let data_size = data_addr.wrapping_sub(data_bot);
+    if data_size > config.memory.heap_bot.saturating_sub(data_bot) || data_addr < data_bot {
+        return Err(Error::TooMuchData { data_size });
+    }
+
+    let base = |object: usize, section: Section| match section {
+        Section::Text => bases[object].0,
+        Section::Data => bases[object].1,
+    };
+
+    let mut globals: HashMap<&str, (u32, usize)> = HashMap::new();
+    let mut labels = LinkedHashMap::new();
+    for (i, object) in objects.iter().enumerate() {
+        for symbol in &object.symbols {
+            let section = match symbol.section {
+                Some(section) if symbol.global => section,
+                _ => continue,
+            };
+
+            if let Some(&(_, first)) = globals.get(symbol.name.as_str()) {
+                return Err(Error::RedefinedSymbol {
+                    symbol: symbol.name.clone(),
+                    first: objects[first].name.clone(),
+                    second: object.name.clone(),
+                });
+            }
+
+            let addr = base(i, section).wrapping_add(symbol.value);
+            globals.insert(&symbol.name, (addr, i));
+            labels.insert(symbol.name.clone(), addr);
+        }
+    }
+
+    // locals are only kept for debugging, so if two objects use the same name it doesn't matter which wins
+    for (i, object) in objects.iter().enumerate() {
+        for symbol in object.symbols.iter().filter(|symbol| !symbol.global) {
+            if let Some(section) = symbol.section {
+                labels
+                    .entry(symbol.name.clone())
+                    .or_insert(base(i, section).wrapping_add(symbol.value));
+            }
+        }
+    }
+
+    let mut text = vec![0; text_addr.wrapping_sub(text_bot) as usize];
+    let mut data = vec![0; data_size as usize];
+    for (i, object) in objects.iter().enumerate() {
+        let (text_base, data_base) = bases[i];
+
+        let text_offset = text_base.wrapping_sub(text_bot) as usize;
+        text[text_offset..text_offset + object.text.len()].copy_from_slice(&object.text);
+
+        let data_offset = data_base.wrapping_sub(data_bot) as usize;
+        data[data_offset..data_offset + object.data.len()].copy_from_slice(&object.data);
+    }
+
+    for (i, object) in objects.iter().enumerate() {
+        for (n, relocation) in object.relocations.iter().enumerate() {
+            let target = match &relocation.target {
+                RelocationTarget::Section(section, offset) => base(i, *section).wrapping_add(*offset),
+                RelocationTarget::Symbol(name) => match object.defined_symbol(name) {
+                    Some(symbol) => base(i, symbol.section.unwrap()).wrapping_add(symbol.value),
+                    None => globals
+                        .get(name.as_str())
+                        .map(|&(addr, _)| addr)
+                        .ok_or_else(|| Error::UndefinedSymbol {
+                            symbol: name.clone(),
+                            object: object.name.clone(),
+                        })?,
+                },
+            };
+
+            let section = object.section(relocation.section);
+            let original = read_word(section, endian, relocation.offset);
+            let addr = base(i, relocation.section).wrapping_add(relocation.offset);
+
+            let word = match relocation.kind {
+                RelocationType::Mips32 => original.wrapping_add(target),
+                RelocationType::Mips26 => {
+                    let dest = target.wrapping_add((original & 0x03FF_FFFF) << 2);
+
+                    // a jump can only change the lower 28 bits of the pc
+                    if (dest ^ addr.wrapping_add(4)) & 0xF000_0000 != 0 {
+                        return Err(Error::JumpOutOfRange {
+                            addr,
+                            target: dest,
+                            object: object.name.clone(),
+                        });
+                    }
+
+                    original & 0xFC00_0000 | (dest >> 2) & 0x03FF_FFFF
+                }
+                RelocationType::Hi16 => {
+                    // adding the target to the lower half may carry into the upper half,
+                    // so the lower half has to be found (it's the next LO16 against the same target)
+                    let lo = object.relocations[n + 1..]
+                        .iter()
+                        .find(|lo| {
+                            lo.kind == RelocationType::Lo16
+                                && lo.section == relocation.section
+                                && lo.target == relocation.target
+                        })
+                        .map(|lo| read_word(section, endian, lo.offset));
+
+                    let hi = (original & 0xFFFF) << 16;
+                    let (value, carry) = match lo {
+                        // `la` and `li` pair `lui` with `ori`, whose immediate isn't sign-extended
+                        Some(lo) if lo >> 26 == ORI => (target.wrapping_add(hi | lo & 0xFFFF), 0),
+                        Some(lo) => (target.wrapping_add(hi).wrapping_add(lo as i16 as u32), 0x8000),
+                        None => (target.wrapping_add(hi), 0x8000),
+                    };
+
+                    original & 0xFFFF_0000 | value.wrapping_add(carry) >> 16
+                }
+                RelocationType::Lo16 => original & 0xFFFF_0000 | original.wrapping_add(target) & 0xFFFF,
+            };
+
+            let (image, image_base) = match relocation.section {
+                Section::Text => (&mut text, text_bot),
+                Section::Data => (&mut data, data_bot),
+            };
+            write_word(image, endian, addr.wrapping_sub(image_base), word);
+        }
+    }
+
+    let valid = |bytes: Vec<u8>| bytes.into_iter().map(Safe::Valid).collect();
+
+    let mut segments = vec![BinarySegment {
+        addr: text_bot,
+        bytes: valid(text),
+        executable: true,
+    }];
+    if !data.is_empty() {
+        segments.push(BinarySegment {
+            addr: data_bot,
+            bytes: valid(data),
+            executable: false,
+        });
+    }
+
+    Ok(Binary {
+        segments,
+        entry: labels.get("__start").copied().unwrap_or(text_bot),
+        endian,
+        globals: globals.into_keys().map(str::to_string).collect(),
+        labels,
+        ..Default::default()
+    })
+}
+
+/// The byte order of the linked program: the config's if it has one,
+/// and otherwise that of the first object (or little-endian, as spim is).
+pub fn endianness(objects: &[Object], config: &RushConfig) -> Endianness {
+    config
+        .endianness
+        .or_else(|| objects.first().map(|object| object.endian))
+        .unwrap_or(Endianness::Little)
+}
+
+fn read_word(bytes: &[u8], endian: Endianness, offset: u32) -> u32 {
+    let offset = offset as usize;
+    let word: [u8; 4] = bytes
+        .get(offset..offset + 4)
+        .and_then(|word| word.try_into().ok())
+        .unwrap_or_default();
+
+    elf::u32_from_bytes(endian, word)
+}
+
+fn write_word(bytes: &mut [u8], endian: Endianness, offset: u32, word: u32) {
+    let word = elf::u32_bytes(endian, word);
+
+    let offset = offset as usize;
+    if let Some(bytes) = bytes.get_mut(offset..offset + 4) {
+        bytes.copy_from_slice(&word);
+    }
+}
@@ -1,14 +1,37 @@
 use crate::{
+    error::{
+        compiler, loader::Error as LoaderError, InternalError, RushResult, ToRushResult,
+    },
+    inst::InstSet,
     util::Safe,
 };
+use linked_hash_map::LinkedHashMap;
+use rush_parser::{MpAttributedItem, MpDirective, MpItem, MpProgram};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::{collections::{HashMap, HashSet}, fs, rc::Rc};
 
 mod bytes;
 
 mod breakpoints;
+pub use breakpoints::{Breakpoint, Point};
 
-use rush_utils::RushConfig;
+mod constants;
+mod data;
+pub mod dwarf;
+pub mod elf;
+pub mod link;
+pub mod object;
+mod pseudo;
+mod symbols;
+mod text;
+
+use constants::Resolver;
+pub use object::Object;
+use object::Reference;
+
+use dwarf::LineNumbers;
+use elf::{ElfFile, OutputSection, StringTable};
+use rush_utils::{Endianness, RushConfig};
 
 pub const TEXT_BOT: u32 = 0x00400000;
 pub const TEXT_TOP: u32 = 0x0040C158;
@@ -19,27 +42,724 @@ pub const HEAP_BOT: u32 = 0x10040000;
 pub const STACK_BOT: u32 = 0x7FFF0000;
 pub const STACK_PTR: u32 = 0x7FFFFFF0;
 pub const STACK_TOP: u32 = 0x7FFFFFFF;
+
 pub const KTEXT_BOT: u32 = 0x80000000;
 pub const KDATA_BOT: u32 = 0x90000000;
-pub const PRINTF_ADDR: u32 = 0x0040035C;
+
+pub const TAB_SIZE: u32 = 8;
+
+/// The most .bss (`p_memsz` past `p_filesz`) an ELF segment may ask for, as it's allocated up front.
+pub const MAX_ZERO_FILL: u32 = 256 << 20;
+
+/// The file tag of the startup code that is assembled ahead of every program.
+pub const KERNEL_TAG: &str = "kernel";
+
+/// Like spim, programs are entered through `__start`, which calls `main` and then exits.
+pub const KERNEL: &str = "\
+        .text
+        .globl  __start
+__start:
+        jal     main
+        nop
+        ori     $v0, $zero, 10      # exit
+        syscall
+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Segment {
+    Text,
+    Data,
+    KText,
+    KData,
+}
+
+impl Segment {
+    fn is_text(self) -> bool {
+        matches!(self, Segment::Text | Segment::KText)
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BinarySegment {
+    pub addr: u32,
+    pub bytes: Vec<Safe<u8>>,
+    pub executable: bool,
+}
+
+impl BinarySegment {
+    pub fn end(&self) -> u32 {
+        self.addr.wrapping_add(self.bytes.len() as u32)
+    }
+
+    pub fn contains(&self, addr: u32) -> bool {
+        (self.addr..self.end()).contains(&addr)
+    }
+}
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Binary {
-    pub text: Vec<Safe<u8>>,
-    pub data: Vec<Safe<u8>>,
+    pub segments: Vec<BinarySegment>,
+    pub entry: u32,
+    pub endian: Endianness,
+    pub labels: LinkedHashMap<String, u32>,
+    /// The labels that were declared `.globl`, which `to_elf` writes as global symbols.
+    pub globals: HashSet<String>,
+    pub breakpoints: HashMap<u32, Breakpoint>,
+    pub line_numbers: LineNumbers,
 }
 
 impl Binary {
-    pub fn new(rush_config: &RushConfig) -> Self {
-        let binary_content: Vec<Safe<u8>> = fs::read(
-            rush_config.executable.clone()
-        ).unwrap().clone().into_iter().map(Safe::Valid).collect();
-        let text_size = rush_config.memory.text.end - rush_config.memory.text.start;
-        let segments = binary_content.split_at(text_size as usize);
-        Self {
-            text: segments.0.to_vec(),
-            data: segments.1.to_vec()
+    pub fn new(rush_config: &RushConfig) -> Result<Self, LoaderError> {
+        let binary_content = fs::read(&rush_config.executable).map_err(|err| {
+            LoaderError::CannotReadFile {
+                path: rush_config.executable.clone(),
+                os_error: err.to_string(),
+            }
+        })?;
+
+        let mut binary = if elf::is_elf(&binary_content) {
+            let binary = Self::from_elf(&binary_content)?;
+
+            match rush_config.endianness {
+                Some(expected) if expected != binary.endian => {
+                    return Err(LoaderError::WrongEndianness { expected, found: binary.endian });
+                }
+                _ => binary,
+            }
+        } else {
+            Self::from_flat(rush_config, binary_content)?
+        };
+
+        if let Some(symbol_map) = &rush_config.symbol_map {
+            let contents = fs::read_to_string(symbol_map).map_err(|err| {
+                LoaderError::CannotReadFile {
+                    path: symbol_map.clone(),
+                    os_error: err.to_string(),
+                }
+            })?;
+
+            binary.labels.extend(symbols::from_map(symbol_map, &contents)?);
+        }
+
+        Ok(binary)
+    }
 
+    /// Loads an ELF32 executable, placing every `PT_LOAD` segment at its
+    /// virtual address. The part of a segment past `p_filesz` (i.e. `.bss`)
+    /// is zero-filled up to `p_memsz`.
+    pub fn from_elf(bytes: &[u8]) -> Result<Self, LoaderError> {
+        let elf = ElfFile::parse(bytes)?;
+
+        if elf.header().elf_type != elf::ET_EXEC {
+            return Err(LoaderError::UnsupportedType {
+                elf_type: elf.header().elf_type,
+            });
         }
+
+        let mut segments = vec![];
+        for program_header in elf.program_headers() {
+            if program_header.p_type != elf::PT_LOAD || program_header.memsz == 0 {
+                continue;
+            }
+
+            // (p_memsz is only trusted as far as it could be real: no smaller than p_filesz,
+            // within the address space, and with no more than `MAX_ZERO_FILL` bytes of .bss)
+            let fits = program_header.vaddr.checked_add(program_header.memsz - 1).is_some();
+            if program_header.memsz < program_header.filesz || !fits
+                || program_header.memsz - program_header.filesz > MAX_ZERO_FILL {
+                return Err(LoaderError::BadSegmentSize {
+                    vaddr: program_header.vaddr,
+                    filesz: program_header.filesz,
+                    memsz: program_header.memsz,
+                });
+            }
+
+            let mut bytes: Vec<Safe<u8>> = elf
+                .segment_data(program_header)?
+                .iter()
+                .copied()
+                .map(Safe::Valid)
+                .collect();
+            bytes.resize(program_header.memsz as usize, Safe::Valid(0));
+
+            segments.push(BinarySegment {
+                addr: program_header.vaddr,
+                bytes,
+                executable: program_header.flags & elf::PF_X != 0,
+            });
+        }
+
+        if segments.is_empty() {
+            return Err(LoaderError::NoLoadableSegments);
+        }
+
+        Ok(Self {
+            segments,
+            entry: elf.header().entry,
+            endian: elf.endian(),
+            labels: symbols::from_elf(&elf)?,
+            line_numbers: dwarf::line_numbers(&elf),
+            ..Default::default()
+        })
     }
+
+    /// Writes the binary as an ELF32 executable (which `from_elf` can load),
+    /// with a `PT_LOAD` segment per segment and every label in `.symtab` (local unless it was declared `.globl`).
+    pub fn to_elf(&self) -> Vec<u8> {
+        let mut sections: Vec<OutputSection> = self
+            .segments
+            .iter()
+            .map(|segment| OutputSection {
+                name: match segment.executable {
+                    true => ".text",
+                    false => ".data",
+                }
+                .to_string(),
+                sh_type: elf::SHT_PROGBITS,
+                flags: match segment.executable {
+                    true => elf::SHF_ALLOC | elf::SHF_EXECINSTR,
+                    false => elf::SHF_ALLOC | elf::SHF_WRITE,
+                },
+                addr: segment.addr,
+                addralign: 4,
+                data: segment
+                    .bytes
+                    .iter()
+                    .map(|byte| byte.as_option().copied().unwrap_or(0))
+                    .collect(),
+                ..Default::default()
+            })
+            .collect();
+
+        // locals must come before globals
+        let (locals, globals): (Vec<_>, Vec<_>) = self.labels.iter().partition(|(label, _)| !self.globals.contains(*label));
+        let first_global = 1 + locals.len() as u32;
+
+        let mut strtab = StringTable::new();
+        let mut symtab = elf::symbol_entry(self.endian, 0, 0, 0, 0, elf::SHN_UNDEF);
+        for (label, &addr) in locals.into_iter().chain(globals) {
+            let shndx = self
+                .segments
+                .iter()
+                .position(|segment| segment.contains(addr))
+                .map_or(elf::SHN_ABS, |index| index as u16 + 1);
+
+            let bind = match self.globals.contains(label) {
+                true => elf::STB_GLOBAL,
+                false => elf::STB_LOCAL,
+            };
+
+            symtab.extend(elf::symbol_entry(
+                self.endian,
+                strtab.add(label),
+                addr,
+                bind,
+                elf::STT_NOTYPE,
+                shndx,
+            ));
+        }
+
+        let strtab_index = sections.len() as u32 + 2;
+        sections.push(OutputSection {
+            name: ".symtab".to_string(),
+            sh_type: elf::SHT_SYMTAB,
+            link: strtab_index,
+            info: first_global,
+            addralign: 4,
+            entsize: 16,
+            data: symtab,
+            ..Default::default()
+        });
+        sections.push(OutputSection {
+            name: ".strtab".to_string(),
+            sh_type: elf::SHT_STRTAB,
+            addralign: 1,
+            data: strtab.into_bytes(),
+            ..Default::default()
+        });
+
+        elf::write(self.endian, elf::ET_EXEC, self.entry, &sections)
+    }
+
+    /// Loads a raw `objcopy -O binary` image, which is split into text and data
+    /// according to `memory.text` in the config. A flat image doesn't say what byte order it's in,
+    /// so it's taken to be little-endian, as spim is, unless the config's `endianness` says otherwise.
+    pub fn from_flat(rush_config: &RushConfig, binary_content: Vec<u8>) -> Result<Self, LoaderError> {
+        let memory = &rush_config.memory;
+        if memory.text.end <= memory.text.start {
+            return Err(LoaderError::MissingTextRange);
+        }
+
+        let entry = rush_config.start_addr.ok_or(LoaderError::MissingStartAddr)?;
+
+        let binary_content: Vec<Safe<u8>> = binary_content.into_iter().map(Safe::Valid).collect();
+        let text_size = ((memory.text.end - memory.text.start) as usize).min(binary_content.len());
+        let (text, data) = binary_content.split_at(text_size);
+
+        Ok(Self {
+            segments: vec![
+                BinarySegment {
+                    addr: memory.text.start,
+                    bytes: text.to_vec(),
+                    executable: true,
+                },
+                BinarySegment {
+                    addr: memory.data_bot,
+                    bytes: data.to_vec(),
+                    executable: false,
+                },
+            ],
+            entry,
+            endian: rush_config.endianness.unwrap_or(Endianness::Little),
+            ..Default::default()
+        })
+    }
+
+    /// The lowest and highest (exclusive) addresses covered by executable segments,
+    /// not counting the kernel's.
+    pub fn text_bounds(&self) -> Option<(u32, u32)> {
+        let text = self
+            .segments
+            .iter()
+            .filter(|segment| segment.executable && segment.addr < KTEXT_BOT);
+
+        let start = text.clone().map(|segment| segment.addr).min()?;
+        let end = text.map(|segment| segment.end()).max()?;
+
+        Some((start, end))
+    }
+
+    /// Finds the closest label at or before `addr`, returning it along with the offset from it.
+    ///
+    /// Labels are only considered if they lie in the same segment as `addr`,
+    /// so that e.g. a heap address isn't described relative to the last label in `.data`.
+    pub fn nearest_label(&self, addr: u32) -> Option<(&str, u32)> {
+        let segment = self.segments.iter().find(|segment| segment.contains(addr));
+
+        self.labels
+            .iter()
+            .filter(|&(_, &label_addr)| match segment {
+                Some(segment) => segment.contains(label_addr) && label_addr <= addr,
+                None => label_addr == addr,
+            })
+            .max_by_key(|&(_, &label_addr)| label_addr)
+            .map(|(label, &label_addr)| (label.as_str(), addr - label_addr))
+    }
+
+    /// Renders `addr` as `label` or `label+offset`, if there's a label nearby.
+    pub fn symbolize(&self, addr: u32) -> Option<String> {
+        self.nearest_label(addr).map(|(label, offset)| match offset {
+            0 => label.to_string(),
+            offset => format!("{}+{}", label, offset),
+        })
+    }
+
+    /// The source file and line that the instruction at `addr` was generated from.
+    pub fn source_line(&self, addr: u32) -> Option<(&str, u32)> {
+        if !self.is_text(addr) {
+            return None;
+        }
+
+        self.line_numbers
+            .range(..=addr)
+            .next_back()
+            .and_then(|(_, row)| row.as_ref())
+            .map(|(file, line)| (file.as_ref(), *line))
+    }
+
+    pub fn generate_id<P: Point>(points: &HashMap<u32, P>) -> u32 {
+        points
+            .values()
+            .map(|point| point.get_id() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn is_text(&self, addr: u32) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| segment.executable && segment.contains(addr))
+    }
+}
+
+/// Assembles a parsed program into a `Binary`, laid out like spim's:
+/// text from `memory.text.start` (or `TEXT_BOT`) and data from `memory.data_bot`.
+pub fn compile(program: &MpProgram, config: &RushConfig, iset: &InstSet) -> RushResult<Binary> {
+    let text_bot = match config.memory.text.start {
+        0 => TEXT_BOT,
+        start => start,
+    };
+    let data_bot = config.memory.data_bot;
+    let data_limit = config.memory.heap_bot.saturating_sub(data_bot);
+
+    let endian = config.endianness.unwrap_or(Endianness::Little);
+    let assembled = assemble(program, iset, endian, text_bot, data_bot, data_limit, false)?;
+
+    let mut segments = vec![BinarySegment {
+        addr: text_bot,
+        bytes: assembled.text,
+        executable: true,
+    }];
+    if !assembled.data.is_empty() {
+        segments.push(BinarySegment {
+            addr: data_bot,
+            bytes: assembled.data,
+            executable: false,
+        });
+    }
+    if !assembled.ktext.is_empty() {
+        segments.push(BinarySegment {
+            addr: KTEXT_BOT,
+            bytes: assembled.ktext,
+            executable: true,
+        });
+    }
+    if !assembled.kdata.is_empty() {
+        segments.push(BinarySegment {
+            addr: KDATA_BOT,
+            bytes: assembled.kdata,
+            executable: false,
+        });
+    }
+
+    Ok(Binary {
+        segments,
+        entry: assembled.labels.get("__start").copied().unwrap_or(text_bot),
+        endian,
+        labels: assembled.labels,
+        globals: assembled.globals.into_iter().collect(),
+        line_numbers: assembled.line_numbers,
+        ..Default::default()
+    })
+}
+
+/// Assembles a single file into an `Object`, to be linked with others by `link::link`.
+///
+/// Labels that aren't defined in the file are assumed to be defined in another.
+pub fn compile_object(name: &str, program: &MpProgram, iset: &InstSet, endian: Endianness) -> RushResult<Object> {
+    let assembled = assemble(program, iset, endian, 0, 0, u32::MAX, true)?;
+
+    Ok(Object::new(name, endian, assembled))
+}
+
+/// The result of `assemble`, before it's made into a `Binary` or an `Object`.
+struct Assembled {
+    text: Vec<Safe<u8>>,
+    data: Vec<Safe<u8>>,
+    ktext: Vec<Safe<u8>>,
+    kdata: Vec<Safe<u8>>,
+    labels: LinkedHashMap<String, u32>,
+    label_segments: HashMap<String, Segment>,
+    globals: Vec<String>,
+    relocations: Vec<(Segment, Reference)>,
+    line_numbers: LineNumbers,
+}
+
+/// Lays out labels in a first pass, then encodes everything in a second.
+///
+/// If `relocatable`, labels that aren't defined are left for the linker,
+/// and every use of a label's address is noted in `relocations`.
+/// The kernel segments (`.ktext` and `.kdata`) always start at `KTEXT_BOT` and `KDATA_BOT`,
+/// so they can't be relocated.
+fn assemble(
+    program: &MpProgram,
+    iset: &InstSet,
+    endian: Endianness,
+    text_bot: u32,
+    data_bot: u32,
+    data_limit: u32,
+    relocatable: bool,
+) -> RushResult<Assembled> {
+    let mut constants = HashMap::new();
+    for item in program.items() {
+        if let MpItem::Constant(constant) = item.item() {
+            if constants
+                .insert(constant.label().to_string(), constant.value().clone())
+                .is_some()
+            {
+                return locate(
+                    Err(compiler::Error::RedefinedConstant {
+                        label: constant.label().to_string(),
+                    }),
+                    item,
+                    constant.col(),
+                    constant.col_end(),
+                );
+            }
+        }
+    }
+
+    // pass 1: work out where everything goes
+    let layout_resolver = Resolver::new(&constants, None);
+
+    let mut labels = LinkedHashMap::new();
+    let mut label_segments = HashMap::new();
+    let mut globals = vec![];
+    let mut placements: Vec<(&MpAttributedItem, Segment, u32)> = vec![];
+    let mut pending_labels: Vec<(&MpAttributedItem, String, u32, u32)> = vec![];
+
+    let mut segment = Segment::Text;
+    let mut file_tag = None;
+    // the next address in each segment, indexed by `Segment`
+    let mut addrs = [text_bot, data_bot, KTEXT_BOT, KDATA_BOT];
+
+    for item in program.items() {
+        let mut define = |pending, segment, addr| {
+            define_labels(&mut labels, &mut label_segments, pending, segment, addr)
+        };
+
+        // every file starts off in the text segment
+        if item.file_tag() != file_tag {
+            file_tag = item.file_tag();
+            if !segment.is_text() {
+                define(std::mem::take(&mut pending_labels), segment, addrs[segment as usize])?;
+            }
+            segment = Segment::Text;
+        }
+
+        match item.item() {
+            MpItem::Constant(_) => {}
+            MpItem::Label(label) => {
+                let pending = (item, label.label(), label.col(), label.col_end());
+
+                match segment.is_text() {
+                    true => define(vec![pending], segment, addrs[segment as usize])?,
+                    // data labels belong to whatever the next directive aligns to
+                    false => pending_labels.push(pending),
+                }
+            }
+            MpItem::Directive((directive, position)) => {
+                let locate_directive =
+                    |error: InternalError| locate_internal(error, item, position.col(), position.col_end());
+
+                let (new_segment, start) = match directive {
+                    MpDirective::Text => (Some(Segment::Text), None),
+                    MpDirective::Data => (Some(Segment::Data), None),
+                    MpDirective::KText(start) => (Some(Segment::KText), start.as_ref()),
+                    MpDirective::KData(start) => (Some(Segment::KData), start.as_ref()),
+                    _ => (None, None),
+                };
+
+                if let Some(new_segment) = new_segment {
+                    if matches!(new_segment, Segment::KText | Segment::KData) && relocatable {
+                        return Err(locate_directive(InternalError::Compiler(
+                            compiler::Error::KernelSegmentInObject {
+                                directive_type: directive.clone(),
+                            },
+                        )));
+                    }
+
+                    if new_segment != segment && !segment.is_text() {
+                        define(std::mem::take(&mut pending_labels), segment, addrs[segment as usize])?;
+                    }
+                    segment = new_segment;
+
+                    if let Some(start) = start {
+                        let (low, high) = match segment {
+                            Segment::KText => (KTEXT_BOT, KDATA_BOT - 1),
+                            _ => (KDATA_BOT, u32::MAX),
+                        };
+
+                        let addr = layout_resolver.evaluate(start).map_err(locate_directive)?;
+                        if !(low as i64..=high as i64).contains(&addr) || (segment.is_text() && addr % 4 != 0) {
+                            return Err(locate_directive(InternalError::Compiler(
+                                compiler::Error::SegmentAddressOutOfRange {
+                                    directive_type: directive.clone(),
+                                    addr,
+                                },
+                            )));
+                        }
+
+                        define(std::mem::take(&mut pending_labels), segment, addrs[segment as usize])?;
+                        addrs[segment as usize] = addr as u32;
+                    }
+
+                    continue;
+                }
+
+                match directive {
+                    MpDirective::Globl(label) => globals.push(label.to_string()),
+                    _ if segment.is_text() => {
+                        if data::is_data(directive) {
+                            return Err(locate_directive(InternalError::Compiler(
+                                compiler::Error::DataInTextSegment {
+                                    directive_type: directive.clone(),
+                                },
+                            )));
+                        }
+                    }
+                    _ => {
+                        let data_addr = &mut addrs[segment as usize];
+
+                        *data_addr = data::align_up(*data_addr, data::alignment(directive));
+                        if !matches!(directive, MpDirective::Align(_)) {
+                            define(std::mem::take(&mut pending_labels), segment, *data_addr)?;
+                        }
+
+                        placements.push((item, segment, *data_addr));
+                        let size = data::directive_size(directive, *data_addr, &layout_resolver)
+                            .map_err(locate_directive)?;
+                        *data_addr = data_addr.wrapping_add(size);
+
+                        let data_size = data_addr.wrapping_sub(data_bot);
+                        if segment == Segment::Data && (data_size > data_limit || *data_addr < data_bot) {
+                            return Err(locate_directive(InternalError::Compiler(
+                                compiler::Error::TooMuchData { data_size },
+                            )));
+                        }
+                    }
+                }
+            }
+            MpItem::Instruction(inst) => {
+                if !segment.is_text() {
+                    return locate(
+                        Err(compiler::Error::InstructionInDataSegment),
+                        item,
+                        inst.col(),
+                        inst.col_end(),
+                    );
+                }
+
+                let length = text::instruction_length(iset, inst, &layout_resolver);
+                let length = locate_result(length, item, inst.col(), inst.col_end())?;
+
+                placements.push((item, segment, addrs[segment as usize]));
+                addrs[segment as usize] = addrs[segment as usize].wrapping_add(length * 4);
+            }
+        }
+    }
+
+    define_labels(
+        &mut labels,
+        &mut label_segments,
+        std::mem::take(&mut pending_labels),
+        segment,
+        addrs[segment as usize],
+    )?;
+
+    // pass 2: encode everything now that the labels are known
+    let resolver = match relocatable {
+        true => Resolver::relocatable(&constants, &labels),
+        false => Resolver::new(&constants, Some(&labels)),
+    };
+
+    let bots = [text_bot, data_bot, KTEXT_BOT, KDATA_BOT];
+    let mut contents: [Vec<Safe<u8>>; 4] = Default::default();
+    let mut relocations = vec![];
+    let mut line_numbers = LineNumbers::new();
+
+    for &(item, segment, addr) in &placements {
+        let offset = (addr - bots[segment as usize]) as usize;
+
+        match item.item() {
+            MpItem::Instruction(inst) => {
+                let words = text::compile_instruction(iset, inst, addr, &resolver);
+                let words = locate_result(words, item, inst.col(), inst.col_end())?;
+
+                let bytes = words.iter().flat_map(|&word| elf::u32_bytes(endian, word)).map(Safe::Valid);
+                place(&mut contents[segment as usize], offset, bytes);
+
+                // (the kernel's code has no lines of its own, so it isn't credited to the line before it)
+                let file_tag = item.file_tag().filter(|tag| tag.as_ref() != KERNEL_TAG);
+                line_numbers.insert(addr, file_tag.map(|file_tag| (file_tag, item.line_number())));
+
+                relocations.extend(resolver.take_references().into_iter().map(|reference| (segment, reference)));
+            }
+            MpItem::Directive((directive, position)) => {
+                let bytes = data::directive_bytes(directive, addr, endian, &resolver);
+                let bytes = locate_result(bytes, item, position.col(), position.col_end())?;
+
+                place(&mut contents[segment as usize], offset, bytes);
+
+                relocations.extend(resolver.take_references().into_iter().map(|reference| (segment, reference)));
+            }
+            _ => {}
+        }
+    }
+
+    let [text, data, ktext, kdata] = contents;
+
+    Ok(Assembled {
+        text,
+        data,
+        ktext,
+        kdata,
+        labels,
+        label_segments,
+        globals,
+        relocations,
+        line_numbers,
+    })
+}
+
+/// Writes `bytes` into a segment's contents at `offset`, growing it as needed.
+/// Gaps (e.g. before a `.ktext 0x80000180`) are left uninitialised.
+fn place(contents: &mut Vec<Safe<u8>>, offset: usize, bytes: impl IntoIterator<Item = Safe<u8>>) {
+    if contents.len() < offset {
+        contents.resize(offset, Safe::Uninitialised);
+    }
+
+    for (i, byte) in bytes.into_iter().enumerate() {
+        if offset + i >= contents.len() {
+            contents.resize(offset + i + 1, Safe::Uninitialised);
+        }
+
+        contents[offset + i] = byte;
+    }
+}
+
+fn define_labels(
+    labels: &mut LinkedHashMap<String, u32>,
+    label_segments: &mut HashMap<String, Segment>,
+    pending: Vec<(&MpAttributedItem, String, u32, u32)>,
+    segment: Segment,
+    addr: u32,
+) -> RushResult<()> {
+    for (item, label, col, col_end) in pending {
+        if labels.contains_key(&label) {
+            return locate(Err(compiler::Error::RedefinedLabel { label }), item, col, col_end);
+        }
+
+        label_segments.insert(label.clone(), segment);
+        labels.insert(label, addr);
+    }
+
+    Ok(())
+}
+
+fn locate<T>(
+    result: Result<T, compiler::Error>,
+    item: &MpAttributedItem,
+    col: u32,
+    col_end: u32,
+) -> RushResult<T> {
+    locate_result(result.map_err(InternalError::Compiler), item, col, col_end)
+}
+
+fn locate_result<T>(
+    result: Result<T, InternalError>,
+    item: &MpAttributedItem,
+    col: u32,
+    col_end: u32,
+) -> RushResult<T> {
+    result.into_compiler_rush_result(
+        item.file_tag().unwrap_or_else(|| Rc::from("")),
+        item.line_number(),
+        col,
+        col_end,
+    )
+}
+
+fn locate_internal(
+    error: InternalError,
+    item: &MpAttributedItem,
+    col: u32,
+    col_end: u32,
+) -> crate::RushError {
+    error.into_compiler_rush_error(
+        item.file_tag().unwrap_or_else(|| Rc::from("")),
+        item.line_number(),
+        col,
+        col_end,
+    )
 }
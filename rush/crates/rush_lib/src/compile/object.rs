@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+
+use rush_utils::Endianness;
+
+use super::{
+    elf::{self, ElfFile, OutputSection, StringTable},
+    Assembled, Segment,
+};
+use crate::{error::loader::Error as LoaderError, util::Safe};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Section {
+    Text,
+    Data,
+}
+
+impl Section {
+    fn name(self) -> &'static str {
+        match self {
+            Section::Text => ".text",
+            Section::Data => ".data",
+        }
+    }
+
+    /// The index of the section in objects written by `Object::to_elf`.
+    fn shndx(self) -> u16 {
+        match self {
+            Section::Text => 1,
+            Section::Data => 2,
+        }
+    }
+}
+
+impl From<Segment> for Section {
+    fn from(segment: Segment) -> Self {
+        match segment {
+            Segment::Text => Section::Text,
+            Segment::Data => Section::Data,
+            // `assemble` rejects these when assembling an object
+            Segment::KText | Segment::KData => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationType {
+    /// A whole word, e.g. `.word label`.
+    Mips32,
+    /// The target of a `j` or `jal`.
+    Mips26,
+    /// The upper half of an address, loaded by a `lui`.
+    Hi16,
+    /// The lower half of an address, in the instruction after the `lui`.
+    Lo16,
+}
+
+impl RelocationType {
+    fn to_elf(self) -> u8 {
+        match self {
+            RelocationType::Mips32 => elf::R_MIPS_32,
+            RelocationType::Mips26 => elf::R_MIPS_26,
+            RelocationType::Hi16 => elf::R_MIPS_HI16,
+            RelocationType::Lo16 => elf::R_MIPS_LO16,
+        }
+    }
+
+    fn from_elf(rel_type: u8) -> Option<Self> {
+        match rel_type {
+            elf::R_MIPS_32 => Some(RelocationType::Mips32),
+            elf::R_MIPS_26 => Some(RelocationType::Mips26),
+            elf::R_MIPS_HI16 => Some(RelocationType::Hi16),
+            elf::R_MIPS_LO16 => Some(RelocationType::Lo16),
+            _ => None,
+        }
+    }
+}
+
+/// What a relocated value is relative to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelocationTarget {
+    /// One of the object's own sections, from this far in (which is only ever
+    /// more than 0 for `.bss` from another assembler, as it's read onto the end of `.data`).
+    Section(Section, u32),
+    /// A symbol, which may be defined in another object.
+    Symbol(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation {
+    pub section: Section,
+    pub offset: u32,
+    pub kind: RelocationType,
+    pub target: RelocationTarget,
+}
+
+/// A use of a label's address, noted while assembling (see `Resolver::relocate`).
+pub(super) struct Reference {
+    pub(super) addr: u32,
+    pub(super) kind: RelocationType,
+    pub(super) label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectSymbol {
+    pub name: String,
+    /// `None` if the symbol is only referenced here, and defined elsewhere.
+    pub section: Option<Section>,
+    pub value: u32,
+    pub global: bool,
+}
+
+/// A single assembled file, whose sections both start at zero, waiting to be linked.
+///
+/// The sections hold exactly what will end up in memory, in the object's byte order.
+/// Wherever an address is needed, the section holds its offset from the relocation's
+/// target, to be added to once that's been placed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object {
+    pub name: String,
+    pub endian: Endianness,
+    pub text: Vec<u8>,
+    pub data: Vec<u8>,
+    pub symbols: Vec<ObjectSymbol>,
+    pub relocations: Vec<Relocation>,
+}
+
+impl Object {
+    pub(super) fn new(name: &str, endian: Endianness, assembled: Assembled) -> Self {
+        let mut symbols: Vec<ObjectSymbol> = assembled
+            .labels
+            .iter()
+            .map(|(label, &value)| ObjectSymbol {
+                name: label.to_string(),
+                section: assembled.label_segments.get(label).map(|&segment| segment.into()),
+                value,
+                global: assembled.globals.contains(label),
+            })
+            .collect();
+
+        let mut relocations = vec![];
+        for (segment, reference) in assembled.relocations {
+            let target = match assembled.label_segments.get(&reference.label) {
+                Some(&segment) => RelocationTarget::Section(segment.into(), 0),
+                None => RelocationTarget::Symbol(reference.label),
+            };
+
+            relocations.push(Relocation {
+                section: segment.into(),
+                offset: reference.addr,
+                kind: reference.kind,
+                target,
+            });
+        }
+
+        // anything referenced (or declared `.globl`) but not defined comes from another object
+        let external = relocations
+            .iter()
+            .filter_map(|relocation| match &relocation.target {
+                RelocationTarget::Symbol(name) => Some(name),
+                RelocationTarget::Section(..) => None,
+            })
+            .chain(&assembled.globals)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        for name in external {
+            if !symbols.iter().any(|symbol| symbol.name == name) {
+                symbols.push(ObjectSymbol {
+                    name,
+                    section: None,
+                    value: 0,
+                    global: true,
+                });
+            }
+        }
+
+        Self {
+            name: name.to_string(),
+            endian,
+            text: initialised(&assembled.text),
+            data: initialised(&assembled.data),
+            symbols,
+            relocations,
+        }
+    }
+
+    pub fn section(&self, section: Section) -> &[u8] {
+        match section {
+            Section::Text => &self.text,
+            Section::Data => &self.data,
+        }
+    }
+
+    /// The symbol called `name`, if this object defines it.
+    pub fn defined_symbol(&self, name: &str) -> Option<&ObjectSymbol> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name == name && symbol.section.is_some())
+    }
+
+    /// Writes the object as an ELF32 relocatable (`ET_REL`).
+    pub fn to_elf(&self) -> Vec<u8> {
+        const SYMTAB: u32 = 5;
+
+        let mut strtab = StringTable::new();
+        let mut symtab = elf::symbol_entry(self.endian, 0, 0, 0, 0, elf::SHN_UNDEF);
+        let mut indices = HashMap::new();
+
+        for section in [Section::Text, Section::Data] {
+            symtab.extend(elf::symbol_entry(self.endian, 0, 0, elf::STB_LOCAL, elf::STT_SECTION, section.shndx()));
+        }
+
+        // locals must come before globals
+        let (locals, globals): (Vec<_>, Vec<_>) = self.symbols.iter().partition(|symbol| !symbol.global);
+        let first_global = 3 + locals.len() as u32;
+
+        for (i, symbol) in locals.iter().chain(&globals).enumerate() {
+            let bind = match symbol.global {
+                true => elf::STB_GLOBAL,
+                false => elf::STB_LOCAL,
+            };
+            let shndx = symbol.section.map_or(elf::SHN_UNDEF, Section::shndx);
+
+            indices.insert(symbol.name.as_str(), 3 + i as u32);
+            symtab.extend(elf::symbol_entry(
+                self.endian,
+                strtab.add(&symbol.name),
+                symbol.value,
+                bind,
+                elf::STT_NOTYPE,
+                shndx,
+            ));
+        }
+
+        let rel = |section: Section| {
+            self.relocations
+                .iter()
+                .filter(|relocation| relocation.section == section)
+                .flat_map(|relocation| {
+                    let symbol = match &relocation.target {
+                        // the section symbols come straight after the null symbol, in section order
+                        RelocationTarget::Section(section, _) => section.shndx() as u32,
+                        RelocationTarget::Symbol(name) => indices[name.as_str()],
+                    };
+
+                    elf::rel_entry(self.endian, relocation.offset, symbol, relocation.kind.to_elf())
+                })
+                .collect()
+        };
+
+        let sections = [
+            OutputSection {
+                name: Section::Text.name().to_string(),
+                sh_type: elf::SHT_PROGBITS,
+                flags: elf::SHF_ALLOC | elf::SHF_EXECINSTR,
+                addralign: 4,
+                data: self.text.clone(),
+                ..Default::default()
+            },
+            OutputSection {
+                name: Section::Data.name().to_string(),
+                sh_type: elf::SHT_PROGBITS,
+                flags: elf::SHF_ALLOC | elf::SHF_WRITE,
+                addralign: 8,
+                data: self.data.clone(),
+                ..Default::default()
+            },
+            OutputSection {
+                name: ".rel.text".to_string(),
+                sh_type: elf::SHT_REL,
+                link: SYMTAB,
+                info: Section::Text.shndx() as u32,
+                addralign: 4,
+                entsize: 8,
+                data: rel(Section::Text),
+                ..Default::default()
+            },
+            OutputSection {
+                name: ".rel.data".to_string(),
+                sh_type: elf::SHT_REL,
+                link: SYMTAB,
+                info: Section::Data.shndx() as u32,
+                addralign: 4,
+                entsize: 8,
+                data: rel(Section::Data),
+                ..Default::default()
+            },
+            OutputSection {
+                name: ".symtab".to_string(),
+                sh_type: elf::SHT_SYMTAB,
+                link: SYMTAB + 1,
+                info: first_global,
+                addralign: 4,
+                entsize: 16,
+                data: symtab,
+                ..Default::default()
+            },
+            OutputSection {
+                name: ".strtab".to_string(),
+                sh_type: elf::SHT_STRTAB,
+                addralign: 1,
+                data: strtab.into_bytes(),
+                ..Default::default()
+            },
+        ];
+
+        elf::write(self.endian, elf::ET_REL, 0, &sections)
+    }
+
+    /// Reads an ELF32 relocatable, keeping only `.text`, `.data` and their relocations.
+    /// `.bss` (which rush doesn't emit, but other assemblers do) is zero-filled onto the end of `.data`.
+    pub fn from_elf(name: &str, bytes: &[u8]) -> Result<Self, LoaderError> {
+        let elf = ElfFile::parse(bytes)?;
+
+        if elf.header().elf_type != elf::ET_REL {
+            return Err(LoaderError::NotRelocatable {
+                elf_type: elf.header().elf_type,
+            });
+        }
+
+        let section_data = |name: &str| match elf.section(name) {
+            Some(section) => elf.section_data(section).map(<[u8]>::to_vec),
+            None => Ok(vec![]),
+        };
+
+        let mut data = section_data(".data")?;
+        let bss_offset = match elf.section(".bss") {
+            Some(bss) => {
+                let bss_offset = (data.len() as u32).next_multiple_of(bss.addralign.max(1));
+                let bss_end = bss_offset.checked_add(bss.size).ok_or(LoaderError::Truncated { what: ".bss" })?;
+                data.resize(bss_end as usize, 0);
+
+                bss_offset
+            }
+            None => 0,
+        };
+
+        // where in the object's sections a section index starts
+        let section_of = |shndx: u16| match elf.section_headers().get(shndx as usize) {
+            Some(section) if section.name == ".text" => Some((Section::Text, 0)),
+            Some(section) if section.name == ".data" => Some((Section::Data, 0)),
+            Some(section) if section.name == ".bss" => Some((Section::Data, bss_offset)),
+            _ => None,
+        };
+
+        let elf_symbols = elf.symbols()?;
+        let symbols = elf_symbols
+            .iter()
+            .filter(|symbol| symbol.sym_type != elf::STT_SECTION && !symbol.name.is_empty())
+            .map(|symbol| {
+                let section = section_of(symbol.shndx);
+
+                ObjectSymbol {
+                    name: symbol.name.clone(),
+                    section: section.map(|(section, _)| section),
+                    value: symbol.value.wrapping_add(section.map_or(0, |(_, offset)| offset)),
+                    global: symbol.bind != elf::STB_LOCAL,
+                }
+            })
+            .collect();
+
+        let mut relocations = vec![];
+        for section in [Section::Text, Section::Data] {
+            let rel_section = match elf.section(&format!(".rel{}", section.name())) {
+                Some(rel_section) => rel_section,
+                None => continue,
+            };
+
+            for rel in elf.relocations(rel_section)? {
+                let kind = RelocationType::from_elf(rel.rel_type).ok_or(LoaderError::UnsupportedRelocation {
+                    rel_type: rel.rel_type,
+                })?;
+
+                let symbol = rel
+                    .symbol
+                    .checked_sub(1)
+                    .and_then(|index| elf_symbols.get(index as usize))
+                    .ok_or(LoaderError::Truncated { what: "relocation symbol" })?;
+
+                let target = match symbol.sym_type {
+                    elf::STT_SECTION => {
+                        let (section, offset) = section_of(symbol.shndx)
+                            .ok_or(LoaderError::Truncated { what: "relocation section" })?;
+
+                        RelocationTarget::Section(section, offset)
+                    }
+                    _ => RelocationTarget::Symbol(symbol.name.clone()),
+                };
+
+                relocations.push(Relocation {
+                    section,
+                    offset: rel.offset,
+                    kind,
+                    target,
+                });
+            }
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            endian: elf.endian(),
+            text: section_data(".text")?,
+            data,
+            symbols,
+            relocations,
+        })
+    }
+}
+
+/// Uninitialised bytes (e.g. from `.space`) are written out as zero.
+fn initialised(bytes: &[Safe<u8>]) -> Vec<u8> {
+    bytes
+        .iter()
+        .map(|byte| byte.as_option().copied().unwrap_or(0))
+        .collect()
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use rush_parser::{MpArgument, MpInstruction, MpRegister};
+
+use super::{
+    constants::Resolver,
+    object::RelocationType,
+    text::{label_reference, number, offset_register, Mismatch},
+    TAB_SIZE,
+};
+use crate::inst::{instruction::PseudoVariable, ArgumentType, PseudoSignature};
+
+/// What a `PseudoVariable` is replaced with: the argument as written,
+/// its value if it is a number (for the `Hi`/`Lo` suffixes),
+/// and the label that value is relative to, if any.
+struct Binding {
+    text: String,
+    value: Option<i64>,
+    label: Option<String>,
+}
+
+impl Binding {
+    fn new(text: String, value: Option<i64>, label: Option<String>) -> Self {
+        Self { text, value, label }
+    }
+}
+
+/// An instruction a pseudo-instruction expands to, along with the relocation
+/// it needs if it holds half of a label's address (e.g. `lui $at, $U32uHi`).
+pub(super) type Expanded = (MpInstruction, Option<(RelocationType, String)>);
+
+/// Expands a pseudo-instruction, whose arguments are known to fit `signature`,
+/// into the instructions it stands for.
+///
+/// The variables in each `PseudoExpand` are substituted textually and the result
+/// is parsed again, so expansions can use any syntax an instruction could.
+pub(super) fn expand(
+    signature: &PseudoSignature,
+    inst: &MpInstruction,
+    resolver: &Resolver,
+) -> Result<Vec<Expanded>, Mismatch> {
+    let bindings = bind(signature.compile_sig().format(), inst.arguments(), resolver)?;
+
+    signature
+        .expansion()
+        .iter()
+        .map(|expand| {
+            let mut relocation = None;
+            let data: Vec<String> = expand
+                .data()
+                .iter()
+                .map(|data| substitute(data, &bindings, &mut relocation))
+                .collect();
+
+            let text = match data.is_empty() {
+                true => expand.inst().to_string(),
+                false => format!("{} {}", expand.inst(), data.join(", ")),
+            };
+
+            rush_parser::parse_instruction(text, TAB_SIZE)
+                .map(|inst| (inst, relocation))
+                .map_err(|_| Mismatch::Format)
+        })
+        .collect()
+}
+
+fn bind(
+    format: &[ArgumentType],
+    arguments: &[(MpArgument, u32, u32)],
+    resolver: &Resolver,
+) -> Result<HashMap<PseudoVariable, Binding>, Mismatch> {
+    let mut bindings = HashMap::new();
+
+    for (arg_type, (arg, _, _)) in format.iter().zip(arguments) {
+        let text = arg.to_string();
+        let label = label_reference(arg, resolver);
+
+        let (var, value) = match arg_type {
+            ArgumentType::Rd => (PseudoVariable::Rd, None),
+            ArgumentType::Rs => (PseudoVariable::Rs, None),
+            ArgumentType::Rt => (PseudoVariable::Rt, None),
+            ArgumentType::Fd => (PseudoVariable::Fd, None),
+            ArgumentType::Fs => (PseudoVariable::Fs, None),
+            ArgumentType::Ft => (PseudoVariable::Ft, None),
+            ArgumentType::Cd => (PseudoVariable::Cd, None),
+            ArgumentType::F32 => (PseudoVariable::F32, None),
+            ArgumentType::F64 => (PseudoVariable::F64, None),
+            ArgumentType::Shamt => (PseudoVariable::Shamt, Some(number(arg, resolver)?)),
+            ArgumentType::ExtSize | ArgumentType::InsSize => (PseudoVariable::Size, Some(number(arg, resolver)?)),
+            ArgumentType::Hint => (PseudoVariable::Hint, Some(number(arg, resolver)?)),
+            ArgumentType::I16 => (PseudoVariable::I16, Some(number(arg, resolver)?)),
+            ArgumentType::U16 => (PseudoVariable::U16, Some(number(arg, resolver)?)),
+            ArgumentType::J => (PseudoVariable::J, Some(number(arg, resolver)?)),
+            ArgumentType::I32 => (PseudoVariable::I32, Some(number(arg, resolver)?)),
+            ArgumentType::U32 => (PseudoVariable::U32, Some(number(arg, resolver)?)),
+            ArgumentType::OffRs | ArgumentType::OffRt | ArgumentType::Off32Rs | ArgumentType::Off32Rt => {
+                bind_offset(&mut bindings, arg_type, arg, resolver)?;
+                continue;
+            }
+        };
+
+        bindings.insert(var, Binding::new(text, value, label));
+    }
+
+    Ok(bindings)
+}
+
+/// Binds the offset, the base register (`$Rs` or `$Rt`), and for 16-bit offsets
+/// the whole argument (`$OffRs` or `$OffRt`).
+fn bind_offset(
+    bindings: &mut HashMap<PseudoVariable, Binding>,
+    arg_type: &ArgumentType,
+    arg: &MpArgument,
+    resolver: &Resolver,
+) -> Result<(), Mismatch> {
+    let (base_var, whole_var, offset_var) = match arg_type {
+        ArgumentType::OffRs => (PseudoVariable::Rs, Some(PseudoVariable::OffRs), PseudoVariable::Off),
+        ArgumentType::OffRt => (PseudoVariable::Rt, Some(PseudoVariable::OffRt), PseudoVariable::Off),
+        ArgumentType::Off32Rs => (PseudoVariable::Rs, None, PseudoVariable::Off32),
+        _ => (PseudoVariable::Rt, None, PseudoVariable::Off32),
+    };
+
+    // a bare number (e.g. `lw $t0, label`) is an offset from $zero
+    let (offset_text, base_text, value) = match arg {
+        MpArgument::Register(MpRegister::Offset(imm, ident)) => {
+            (imm.to_string(), format!("${}", ident), offset_register(arg, resolver)?.0)
+        }
+        MpArgument::Register(MpRegister::BinaryOpOffset(imm1, op, imm2, ident)) => (
+            format!("{} {} {}", imm1, op, imm2),
+            format!("${}", ident),
+            offset_register(arg, resolver)?.0,
+        ),
+        _ => (arg.to_string(), "$zero".to_string(), number(arg, resolver)?),
+    };
+
+    let label = label_reference(arg, resolver);
+
+    bindings.insert(base_var, Binding::new(base_text, None, None));
+    bindings.insert(offset_var, Binding::new(offset_text, Some(value), label.clone()));
+    if let Some(whole_var) = whole_var {
+        bindings.insert(whole_var, Binding::new(arg.to_string(), Some(value), label));
+    }
+
+    Ok(())
+}
+
+/// Replaces every `$variable` in `data` with its binding.
+/// Anything else starting with a `$` (e.g. `$at`) is a register, and is left alone.
+///
+/// Taking half of a label's address turns it into a plain number,
+/// so the relocation that stands in for the label is noted in `relocation`.
+fn substitute(
+    data: &str,
+    bindings: &HashMap<PseudoVariable, Binding>,
+    relocation: &mut Option<(RelocationType, String)>,
+) -> String {
+    let mut result = String::new();
+    let mut rest = data;
+
+    while let Some(start) = rest.find('$') {
+        result.push_str(&rest[..start]);
+
+        let word_len = rest[start + 1..]
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(rest.len() - start - 1);
+        let word = &rest[start + 1..start + 1 + word_len];
+
+        match lookup(word, bindings, relocation) {
+            Some(replacement) => result.push_str(&replacement),
+            None => {
+                result.push('$');
+                result.push_str(word);
+            }
+        }
+
+        rest = &rest[start + 1 + word_len..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+fn lookup(
+    word: &str,
+    bindings: &HashMap<PseudoVariable, Binding>,
+    relocation: &mut Option<(RelocationType, String)>,
+) -> Option<String> {
+    let lower = word.to_ascii_lowercase();
+
+    if let Some(binding) = PseudoVariable::from_name(&lower).and_then(|var| bindings.get(&var)) {
+        return Some(binding.text.clone());
+    }
+
+    let (name, suffix) = lower.split_at(lower.len().checked_sub(3)?);
+    let binding = PseudoVariable::from_name(name).and_then(|var| bindings.get(&var))?;
+    let value = binding.value?;
+
+    let (half, kind) = match suffix {
+        // for `lui` followed by `ori`
+        "uhi" => ((value >> 16) & 0xFFFF, RelocationType::Hi16),
+        "ulo" => (value & 0xFFFF, RelocationType::Lo16),
+        // for `lui` followed by a sign-extended immediate (`addiu`, or a load/store offset)
+        "shi" => (((value + 0x8000) >> 16) & 0xFFFF, RelocationType::Hi16),
+        "slo" => (value as i16 as i64, RelocationType::Lo16),
+        _ => return None,
+    };
+
+    if let Some(label) = &binding.label {
+        *relocation = Some((kind, label.clone()));
+    }
+
+    Some(half.to_string())
+}
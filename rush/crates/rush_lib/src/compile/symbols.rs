@@ -0,0 +1,79 @@
+use linked_hash_map::LinkedHashMap;
+
+use super::elf::{self, ElfFile};
+use crate::error::loader::Error;
+
+/// Collects the defined function/object symbols of an ELF file, ordered by address.
+///
+/// Global symbols take priority over local symbols that share their name.
+pub fn from_elf(elf: &ElfFile) -> Result<LinkedHashMap<String, u32>, Error> {
+    let mut symbols = elf
+        .symbols()?
+        .into_iter()
+        .filter(|symbol| symbol.shndx != elf::SHN_UNDEF)
+        .filter(|symbol| {
+            matches!(
+                symbol.sym_type,
+                elf::STT_NOTYPE | elf::STT_OBJECT | elf::STT_FUNC
+            )
+        })
+        .filter(|symbol| !symbol.name.is_empty() && !symbol.name.starts_with('$'))
+        .collect::<Vec<_>>();
+
+    symbols.sort_by_key(|symbol| symbol.value);
+
+    let mut labels = LinkedHashMap::new();
+    for symbol in symbols.iter().filter(|symbol| symbol.bind != elf::STB_LOCAL) {
+        labels.insert(symbol.name.clone(), symbol.value);
+    }
+
+    for symbol in symbols.iter().filter(|symbol| symbol.bind == elf::STB_LOCAL) {
+        labels.entry(symbol.name.clone()).or_insert(symbol.value);
+    }
+
+    Ok(sorted(labels))
+}
+
+/// Parses the output of `nm`, i.e. lines of the form `00400018 T main`,
+/// optionally with a size column (`nm -S`). Undefined symbols are skipped.
+pub fn from_map(path: &str, contents: &str) -> Result<LinkedHashMap<String, u32>, Error> {
+    let mut labels = LinkedHashMap::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let parts = line.split_whitespace().collect::<Vec<_>>();
+
+        let (addr, kind, name) = match parts[..] {
+            [] => continue,
+            [_, _] => continue,
+            [addr, kind, name] | [addr, _, kind, name] => (addr, kind, name),
+            _ => {
+                return Err(Error::MalformedSymbolMap {
+                    path: path.to_string(),
+                    line: line_number as u32 + 1,
+                })
+            }
+        };
+
+        if matches!(kind, "U" | "u" | "w" | "v") {
+            continue;
+        }
+
+        let addr = u32::from_str_radix(addr.trim_start_matches("0x"), 16).map_err(|_| {
+            Error::MalformedSymbolMap {
+                path: path.to_string(),
+                line: line_number as u32 + 1,
+            }
+        })?;
+
+        labels.insert(name.to_string(), addr);
+    }
+
+    Ok(sorted(labels))
+}
+
+fn sorted(labels: LinkedHashMap<String, u32>) -> LinkedHashMap<String, u32> {
+    let mut labels = labels.into_iter().collect::<Vec<_>>();
+    labels.sort_by_key(|&(_, addr)| addr);
+
+    labels.into_iter().collect()
+}
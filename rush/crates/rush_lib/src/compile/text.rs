@@ -0,0 +1,473 @@
+use std::str::FromStr;
+
+use rush_parser::{
+    MpArgument, MpImmediate, MpImmediateBinaryOp, MpInstruction, MpNumber, MpOffsetOperator,
+    MpRegister, MpRegisterIdentifier,
+};
+
+use super::{
+    constants::{similar_names, Resolver},
+    object::RelocationType,
+    pseudo,
+};
+use crate::{
+    error::{compiler, InternalError, RushInternalResult},
+    inst::{ArgumentType, CompileSignature, InstSet, InstSignature, RuntimeSignature, SignatureRef},
+    Register,
+};
+
+/// The fields of an instruction word, as filled in from its arguments.
+#[derive(Default)]
+struct Fields {
+    rs: Option<u32>,
+    rt: Option<u32>,
+    rd: Option<u32>,
+    shamt: Option<u32>,
+    imm: Option<u32>,
+    target: Option<u32>,
+}
+
+/// Why an instruction's arguments couldn't be used with a particular signature.
+pub(super) enum Mismatch {
+    /// The arguments are the wrong shape, another signature may still fit.
+    Format,
+    /// The arguments fit, but resolving them failed (e.g. an unknown label).
+    Error(InternalError),
+}
+
+impl From<InternalError> for Mismatch {
+    fn from(error: InternalError) -> Self {
+        Self::Error(error)
+    }
+}
+
+/// Picks the signature matching `inst` and encodes it as it would be at `addr`.
+/// Pseudo-instructions are expanded, and each instruction they expand to encoded in turn.
+pub(super) fn compile_instruction(
+    iset: &InstSet,
+    inst: &MpInstruction,
+    addr: u32,
+    resolver: &Resolver,
+) -> RushInternalResult<Vec<u32>> {
+    let words = match select(iset, inst, resolver)? {
+        SignatureRef::Native(signature) => encode(signature, inst, addr, resolver).map(|word| vec![word]),
+        SignatureRef::Pseudo(signature) => pseudo::expand(signature, inst, resolver).and_then(|expansion| {
+            let mut words = vec![];
+            for (expanded, relocation) in expansion {
+                let addr = addr.wrapping_add(words.len() as u32 * 4);
+                if let Some((kind, label)) = relocation {
+                    resolver.relocate(addr, kind, label);
+                }
+
+                words.extend(compile_instruction(iset, &expanded, addr, resolver)?);
+            }
+
+            Ok(words)
+        }),
+    };
+
+    words.map_err(|mismatch| into_error(mismatch, iset, inst))
+}
+
+/// The number of instruction words `inst` will be assembled into.
+///
+/// Labels aren't known yet when this is called, so which signature is picked
+/// must never depend on a label's address (see `fits`).
+pub(super) fn instruction_length(
+    iset: &InstSet,
+    inst: &MpInstruction,
+    resolver: &Resolver,
+) -> RushInternalResult<u32> {
+    match select(iset, inst, resolver)? {
+        SignatureRef::Native(_) => Ok(1),
+        SignatureRef::Pseudo(signature) => pseudo::expand(signature, inst, resolver)
+            .map_err(|mismatch| into_error(mismatch, iset, inst))?
+            .iter()
+            .map(|(expanded, _)| instruction_length(iset, expanded, resolver))
+            .sum(),
+    }
+}
+
+/// The first signature (native, then pseudo) whose format fits the arguments of `inst`.
+fn select<'a>(
+    iset: &'a InstSet,
+    inst: &MpInstruction,
+    resolver: &Resolver,
+) -> RushInternalResult<SignatureRef<'a>> {
+    let signatures = find_signatures(iset, inst)?;
+
+    let mut first_error = None;
+    for signature in signatures {
+        match fits(signature.compile_sig(), inst, resolver) {
+            Ok(()) => return Ok(signature),
+            Err(Mismatch::Format) => {}
+            Err(Mismatch::Error(error)) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    Err(first_error.unwrap_or_else(|| bad_format(iset, inst)))
+}
+
+fn into_error(mismatch: Mismatch, iset: &InstSet, inst: &MpInstruction) -> InternalError {
+    match mismatch {
+        Mismatch::Format => bad_format(iset, inst),
+        Mismatch::Error(error) => error,
+    }
+}
+
+fn bad_format(iset: &InstSet, inst: &MpInstruction) -> InternalError {
+    InternalError::Compiler(compiler::Error::InstructionBadFormat {
+        inst_ast: inst.clone(),
+        correct_formats: iset
+            .find_by_name(inst.name())
+            .iter()
+            .map(SignatureRef::to_owned_sig)
+            .collect(),
+    })
+}
+
+fn find_signatures<'a>(
+    iset: &'a InstSet,
+    inst: &MpInstruction,
+) -> RushInternalResult<Vec<SignatureRef<'a>>> {
+    let signatures = iset.find_by_name(inst.name());
+    if !signatures.is_empty() {
+        return Ok(signatures);
+    }
+
+    let names: Vec<String> = iset
+        .native_set()
+        .iter()
+        .map(|sig| sig.name().to_string())
+        .chain(iset.pseudo_set().iter().map(|sig| sig.name().to_string()))
+        .collect();
+    let mut similar = similar_names(&inst.name().to_ascii_lowercase(), names.iter());
+    similar.dedup();
+
+    if similar.is_empty() {
+        return Err(InternalError::Compiler(compiler::Error::UnknownInstruction {
+            inst_ast: inst.clone(),
+        }));
+    }
+
+    Err(InternalError::Compiler(compiler::Error::InstructionSimName {
+        inst_ast: inst.clone(),
+        similar_instns: similar
+            .iter()
+            .flat_map(|name| iset.find_by_name(name))
+            .map(|sig| sig.to_owned_sig())
+            .collect(),
+    }))
+}
+
+/// Whether the arguments of `inst` are the right shape and size for `compile_sig`.
+///
+/// Labels count as 32-bit values, so they never fit a 16-bit immediate
+/// (except as the target of a relative branch).
+fn fits(compile_sig: &CompileSignature, inst: &MpInstruction, resolver: &Resolver) -> Result<(), Mismatch> {
+    let format = compile_sig.format();
+    if format.len() != inst.arguments().len() {
+        return Err(Mismatch::Format);
+    }
+
+    // the bit field of `ext`/`ins` must end within the word
+    let mut pos = 0;
+
+    for (arg_type, (arg, _, _)) in format.iter().zip(inst.arguments()) {
+        match arg_type {
+            ArgumentType::Rd | ArgumentType::Rs | ArgumentType::Rt => {
+                register(arg)?;
+            }
+            ArgumentType::Fd | ArgumentType::Fs | ArgumentType::Ft => {
+                fpu_register(arg)?;
+            }
+            ArgumentType::Cd => {
+                cp0_register(arg)?;
+            }
+            ArgumentType::Shamt => {
+                pos = constant(arg, resolver)?;
+                in_range(pos, 0, 31)?;
+            }
+            ArgumentType::ExtSize | ArgumentType::InsSize => in_range(constant(arg, resolver)?, 1, 32 - pos)?,
+            ArgumentType::Hint => in_range(constant(arg, resolver)?, 0, 31)?,
+            ArgumentType::I16 if compile_sig.relative_label() => {
+                number(arg, resolver)?;
+            }
+            ArgumentType::I16 => in_range(constant(arg, resolver)?, i16::MIN as i64, i16::MAX as i64)?,
+            ArgumentType::U16 => in_range(constant(arg, resolver)?, 0, u16::MAX as i64)?,
+            ArgumentType::J => {
+                number(arg, resolver)?;
+            }
+            ArgumentType::I32 => in_range(number(arg, resolver)?, i32::MIN as i64, i32::MAX as i64)?,
+            ArgumentType::U32 => in_range(number(arg, resolver)?, 0, u32::MAX as i64)?,
+            ArgumentType::OffRs | ArgumentType::OffRt => {
+                if references_label(arg, resolver) {
+                    return Err(Mismatch::Format);
+                }
+
+                let (offset, _) = offset_register(arg, resolver)?;
+                in_range(offset, i16::MIN as i64, i16::MAX as i64)?;
+            }
+            ArgumentType::Off32Rs | ArgumentType::Off32Rt => {
+                let offset = match arg {
+                    MpArgument::Number(_) => number(arg, resolver)?,
+                    _ => offset_register(arg, resolver)?.0,
+                };
+                in_range(offset, i32::MIN as i64, u32::MAX as i64)?;
+            }
+            // there are no floating point immediates
+            ArgumentType::F32 | ArgumentType::F64 => return Err(Mismatch::Format),
+        }
+    }
+
+    Ok(())
+}
+
+fn in_range(value: i64, low: i64, high: i64) -> Result<(), Mismatch> {
+    match (low..=high).contains(&value) {
+        true => Ok(()),
+        false => Err(Mismatch::Format),
+    }
+}
+
+/// Encodes a native instruction, whose arguments are known to fit its signature.
+fn encode(
+    signature: &InstSignature,
+    inst: &MpInstruction,
+    addr: u32,
+    resolver: &Resolver,
+) -> Result<u32, Mismatch> {
+    let format = signature.compile_sig().format();
+    let relative_label = signature.compile_sig().relative_label();
+
+    let mut fields = Fields::default();
+    for (arg_type, (arg, _, _)) in format.iter().zip(inst.arguments()) {
+        match arg_type {
+            ArgumentType::Rd => fields.rd = Some(register(arg)?),
+            ArgumentType::Rs => fields.rs = Some(register(arg)?),
+            ArgumentType::Rt => fields.rt = Some(register(arg)?),
+            // coprocessor 1 puts its registers where the R-type fields would be
+            ArgumentType::Fd => fields.shamt = Some(fpu_register(arg)?),
+            ArgumentType::Fs => fields.rd = Some(fpu_register(arg)?),
+            ArgumentType::Ft => fields.rt = Some(fpu_register(arg)?),
+            ArgumentType::Cd => fields.rd = Some(cp0_register(arg)?),
+            ArgumentType::Shamt => fields.shamt = Some(number(arg, resolver)? as u32),
+            ArgumentType::ExtSize => fields.rd = Some(number(arg, resolver)? as u32 - 1),
+            ArgumentType::InsSize => {
+                fields.rd = Some(fields.shamt.unwrap_or(0) + number(arg, resolver)? as u32 - 1);
+            }
+            ArgumentType::Hint => fields.rt = Some(number(arg, resolver)? as u32),
+            ArgumentType::I16 if relative_label => {
+                fields.imm = Some(branch_offset(arg, addr, resolver)?);
+            }
+            ArgumentType::I16 | ArgumentType::U16 => {
+                fields.imm = Some(number(arg, resolver)? as u32 & 0xFFFF);
+            }
+            ArgumentType::J => {
+                let target = number(arg, resolver)? as u32;
+                if let Some(label) = label_reference(arg, resolver) {
+                    resolver.relocate(addr, RelocationType::Mips26, label);
+                }
+
+                fields.target = Some((target >> 2) & 0x03FF_FFFF);
+            }
+            ArgumentType::OffRs | ArgumentType::OffRt => {
+                let (offset, base) = offset_register(arg, resolver)?;
+
+                fields.imm = Some(offset as u32 & 0xFFFF);
+                match arg_type {
+                    ArgumentType::OffRs => fields.rs = Some(base),
+                    _ => fields.rt = Some(base),
+                }
+            }
+            // only pseudo-instructions take float or 32-bit operands
+            ArgumentType::F32
+            | ArgumentType::F64
+            | ArgumentType::I32
+            | ArgumentType::U32
+            | ArgumentType::Off32Rs
+            | ArgumentType::Off32Rt => {
+                return Err(Mismatch::Format);
+            }
+        }
+    }
+
+    Ok(assemble(signature.runtime_sig(), &fields))
+}
+
+/// Packs the fields into an instruction word, with any fields fixed
+/// by the signature (e.g. the `rt` of `bgez`) taking precedence.
+fn assemble(runtime: &RuntimeSignature, fields: &Fields) -> u32 {
+    let field = |fixed: Option<u8>, arg: Option<u32>| fixed.map(u32::from).or(arg).unwrap_or(0) & 0x1F;
+
+    match *runtime {
+        RuntimeSignature::R {
+            opcode,
+            funct,
+            shamt,
+            rs,
+            rt,
+            rd,
+        } => {
+            (opcode as u32) << 26
+                | field(rs, fields.rs) << 21
+                | field(rt, fields.rt) << 16
+                | field(rd, fields.rd) << 11
+                | field(shamt, fields.shamt) << 6
+                | funct as u32 & 0x3F
+        }
+        RuntimeSignature::I { opcode, rs, rt } => {
+            (opcode as u32) << 26
+                | field(rs, fields.rs) << 21
+                | field(rt, fields.rt) << 16
+                | fields.imm.unwrap_or(0) & 0xFFFF
+        }
+        RuntimeSignature::J { opcode } => (opcode as u32) << 26 | fields.target.unwrap_or(0),
+    }
+}
+
+pub(super) fn register(arg: &MpArgument) -> Result<u32, Mismatch> {
+    match arg {
+        MpArgument::Register(MpRegister::Normal(ident)) => Ok(resolve_register(ident)?),
+        _ => Err(Mismatch::Format),
+    }
+}
+
+/// A floating point register, `$f0` to `$f31` (or a bare number, as for `cfc1 $t0, $31`).
+fn fpu_register(arg: &MpArgument) -> Result<u32, Mismatch> {
+    let number = match arg {
+        MpArgument::Register(MpRegister::Normal(MpRegisterIdentifier::Numbered(num))) => Some(*num as u32),
+        MpArgument::Register(MpRegister::Normal(MpRegisterIdentifier::Named(name))) => name
+            .strip_prefix(['f', 'F'])
+            .and_then(|num| num.parse::<u32>().ok()),
+        _ => None,
+    };
+
+    number.filter(|&num| num < 32).ok_or(Mismatch::Format)
+}
+
+/// Coprocessor 0 registers only go by number, e.g. `$12` for Status.
+fn cp0_register(arg: &MpArgument) -> Result<u32, Mismatch> {
+    match arg {
+        MpArgument::Register(MpRegister::Normal(MpRegisterIdentifier::Numbered(num))) if *num < 32 => {
+            Ok(*num as u32)
+        }
+        _ => Err(Mismatch::Format),
+    }
+}
+
+fn resolve_register(ident: &MpRegisterIdentifier) -> RushInternalResult<u32> {
+    match ident {
+        MpRegisterIdentifier::Numbered(num) => Register::from_number(*num as i32),
+        MpRegisterIdentifier::Named(name) => Register::from_str(name),
+    }
+    .map(|reg| reg.to_u32())
+}
+
+/// Like `number`, but labels are rejected as their address isn't known during layout.
+fn constant(arg: &MpArgument, resolver: &Resolver) -> Result<i64, Mismatch> {
+    match references_label(arg, resolver) {
+        true => Err(Mismatch::Format),
+        false => number(arg, resolver),
+    }
+}
+
+fn references_label(arg: &MpArgument, resolver: &Resolver) -> bool {
+    !labels(arg, resolver).is_empty()
+}
+
+/// The label `arg` is relative to, if it refers to exactly one (see `Resolver::label_reference`).
+pub(super) fn label_reference(arg: &MpArgument, resolver: &Resolver) -> Option<String> {
+    let mut labels = labels(arg, resolver);
+
+    match labels.len() {
+        1 => labels.pop(),
+        _ => None,
+    }
+}
+
+fn labels(arg: &MpArgument, resolver: &Resolver) -> Vec<String> {
+    let label = |imm: &MpImmediate| match imm {
+        MpImmediate::LabelReference(name) if !resolver.is_constant(name) => Some(name.to_string()),
+        _ => None,
+    };
+
+    match arg {
+        MpArgument::Number(MpNumber::Immediate(imm))
+        | MpArgument::Register(MpRegister::Offset(imm, _)) => label(imm).into_iter().collect(),
+        MpArgument::Number(MpNumber::BinaryOpImmediate(imm1, _, imm2))
+        | MpArgument::Register(MpRegister::BinaryOpOffset(imm1, _, imm2, _)) => {
+            label(imm1).into_iter().chain(label(imm2)).collect()
+        }
+        _ => vec![],
+    }
+}
+
+pub(super) fn number(arg: &MpArgument, resolver: &Resolver) -> Result<i64, Mismatch> {
+    match arg {
+        MpArgument::Number(MpNumber::Immediate(imm)) => Ok(immediate(imm, resolver)?),
+        MpArgument::Number(MpNumber::BinaryOpImmediate(imm1, op, imm2)) => {
+            let (imm1, imm2) = (immediate(imm1, resolver)?, immediate(imm2, resolver)?);
+
+            Ok(match op {
+                MpImmediateBinaryOp::Plus => imm1.wrapping_add(imm2),
+                MpImmediateBinaryOp::Minus => imm1.wrapping_sub(imm2),
+            })
+        }
+        MpArgument::Number(MpNumber::Char(char)) => Ok(*char as i64),
+        _ => Err(Mismatch::Format),
+    }
+}
+
+pub(super) fn immediate(imm: &MpImmediate, resolver: &Resolver) -> RushInternalResult<i64> {
+    Ok(match imm {
+        MpImmediate::I16(imm) => *imm as i64,
+        MpImmediate::U16(imm) => *imm as i64,
+        MpImmediate::I32(imm) => *imm as i64,
+        MpImmediate::U32(imm) => *imm as i64,
+        MpImmediate::LabelReference(name) => resolver.name(name)?,
+    })
+}
+
+/// Branches to a label are encoded relative to the following instruction,
+/// while a plain number is taken to already be an offset (in instructions).
+fn branch_offset(arg: &MpArgument, addr: u32, resolver: &Resolver) -> Result<u32, Mismatch> {
+    // branches are relative, so can only reach labels in the same object
+    if let Some(label) = label_reference(arg, resolver).filter(|label| resolver.is_external(label)) {
+        return Err(Mismatch::Error(InternalError::Compiler(compiler::Error::ExternalBranch { label })));
+    }
+
+    let value = number(arg, resolver)?;
+    let offset = if references_label(arg, resolver) {
+        (value as i32).wrapping_sub(addr.wrapping_add(4) as i32) >> 2
+    } else {
+        value as i32
+    };
+
+    match i16::try_from(offset) {
+        Ok(offset) => Ok(offset as u16 as u32),
+        Err(_) => Err(Mismatch::Error(InternalError::Compiler(
+            compiler::Error::BranchOutOfRange { offset },
+        ))),
+    }
+}
+
+pub(super) fn offset_register(arg: &MpArgument, resolver: &Resolver) -> Result<(i64, u32), Mismatch> {
+    match arg {
+        MpArgument::Register(MpRegister::Offset(imm, ident)) => {
+            Ok((immediate(imm, resolver)?, resolve_register(ident)?))
+        }
+        MpArgument::Register(MpRegister::BinaryOpOffset(imm1, op, imm2, ident)) => {
+            let (imm1, imm2) = (immediate(imm1, resolver)?, immediate(imm2, resolver)?);
+            let offset = match op {
+                MpOffsetOperator::Plus => imm1.wrapping_add(imm2),
+                MpOffsetOperator::Minus => imm1.wrapping_sub(imm2),
+            };
+
+            Ok((offset, resolve_register(ident)?))
+        }
+        _ => Err(Mismatch::Format),
+    }
+}
@@ -0,0 +1,161 @@
+use std::{fmt, rc::Rc};
+
+use crate::{
+    inst::{ArgumentType, InstSet, InstSignature, RuntimeSignature},
+    Binary, Register,
+};
+
+/// An instruction word, decoded back into assembly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decompiled {
+    pub opcode: u32,
+    pub addr: u32,
+    pub inst_sig: Option<InstSignature>,
+    pub inst_name: Option<String>,
+    pub arguments: Vec<String>,
+    pub labels: Vec<String>,
+    pub location: Option<(Rc<str>, u32)>,
+}
+
+impl fmt::Display for Decompiled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inst_name {
+            Some(name) if self.arguments.is_empty() => write!(f, "{}", name),
+            Some(name) => write!(f, "{:7} {}", name, self.arguments.join(", ")),
+            None => write!(f, "{:7} 0x{:08x}", ".word", self.opcode),
+        }
+    }
+}
+
+/// Disassembles `inst` as if it were at `addr`, e.g. `addiu $sp, $sp, -16`.
+pub fn decompile_inst(binary: &Binary, iset: &InstSet, inst: u32, addr: u32) -> String {
+    decompile_inst_into_parts(binary, iset, inst, addr).to_string()
+}
+
+/// Decodes `inst` as if it were at `addr`, using the first signature in `iset` that matches.
+///
+/// Registers use their ABI names, immediates are sign-extended where the instruction
+/// sign-extends them, and branch and jump targets are resolved to a label where possible.
+pub fn decompile_inst_into_parts(binary: &Binary, iset: &InstSet, inst: u32, addr: u32) -> Decompiled {
+    let labels = binary
+        .labels
+        .iter()
+        .filter(|&(_, &label_addr)| label_addr == addr)
+        .map(|(label, _)| label.to_string())
+        .collect();
+
+    let location = binary
+        .source_line(addr)
+        .map(|(file, line)| (Rc::from(file), line));
+
+    let inst_sig = iset
+        .native_set()
+        .iter()
+        .find(|sig| matches(sig.runtime_sig(), inst))
+        .cloned();
+
+    let (inst_name, arguments) = match &inst_sig {
+        Some(sig) => (
+            Some(sig.name().to_string()),
+            sig.compile_sig()
+                .format()
+                .iter()
+                .map(|arg| decompile_arg(binary, arg, sig.compile_sig().relative_label(), inst, addr))
+                .collect(),
+        ),
+        None => (None, vec![]),
+    };
+
+    Decompiled {
+        opcode: inst,
+        addr,
+        inst_sig,
+        inst_name,
+        arguments,
+        labels,
+        location,
+    }
+}
+
+/// Whether `inst` has the opcode (and funct, and any fixed fields) of `runtime`.
+fn matches(runtime: &RuntimeSignature, inst: u32) -> bool {
+    let fixed = |field: Option<u8>, shift: u32| field.is_none_or(|field| (inst >> shift) & 0x1F == field as u32);
+
+    match *runtime {
+        RuntimeSignature::R {
+            opcode,
+            funct,
+            shamt,
+            rs,
+            rt,
+            rd,
+        } => {
+            inst >> 26 == opcode as u32
+                && inst & 0x3F == funct as u32
+                && fixed(rs, 21)
+                && fixed(rt, 16)
+                && fixed(rd, 11)
+                && fixed(shamt, 6)
+        }
+        RuntimeSignature::I { opcode, rs, rt } => inst >> 26 == opcode as u32 && fixed(rs, 21) && fixed(rt, 16),
+        RuntimeSignature::J { opcode } => inst >> 26 == opcode as u32,
+    }
+}
+
+fn decompile_arg(binary: &Binary, arg: &ArgumentType, relative_label: bool, inst: u32, addr: u32) -> String {
+    let rs = (inst >> 21) & 0x1F;
+    let rt = (inst >> 16) & 0x1F;
+    let rd = (inst >> 11) & 0x1F;
+    let shamt = (inst >> 6) & 0x1F;
+    let imm = inst as u16;
+
+    match arg {
+        ArgumentType::Rd => register(rd),
+        ArgumentType::Rs => register(rs),
+        ArgumentType::Rt => register(rt),
+        ArgumentType::Fd => format!("$f{}", shamt),
+        ArgumentType::Fs => format!("$f{}", rd),
+        ArgumentType::Ft => format!("$f{}", rt),
+        ArgumentType::Cd => format!("${}", rd),
+        ArgumentType::Shamt => shamt.to_string(),
+        // `ext` encodes its size less one, and `ins` the last bit it inserts into
+        ArgumentType::ExtSize => (rd + 1).to_string(),
+        ArgumentType::InsSize => ((rd + 1) as i32 - shamt as i32).to_string(),
+        ArgumentType::Hint => rt.to_string(),
+        ArgumentType::I16 if relative_label => {
+            let target = addr
+                .wrapping_add(4)
+                .wrapping_add(((imm as i16 as i32) << 2) as u32);
+
+            target_label(binary, target)
+        }
+        ArgumentType::I16 => (imm as i16).to_string(),
+        ArgumentType::U16 => imm.to_string(),
+        ArgumentType::J => {
+            let target = (addr.wrapping_add(4) & 0xF000_0000) | ((inst & 0x03FF_FFFF) << 2);
+
+            target_label(binary, target)
+        }
+        ArgumentType::OffRs => format!("{}({})", imm as i16, register(rs)),
+        ArgumentType::OffRt => format!("{}({})", imm as i16, register(rt)),
+        // natives never take these, they are only used by pseudo-instructions
+        ArgumentType::F32
+        | ArgumentType::F64
+        | ArgumentType::I32
+        | ArgumentType::U32
+        | ArgumentType::Off32Rs
+        | ArgumentType::Off32Rt => "?".to_string(),
+    }
+}
+
+fn register(num: u32) -> String {
+    format!("${}", Register::from_u32(num).map_or("?", |reg| reg.to_lower_str()))
+}
+
+/// The label at `target`, or else the address itself.
+fn target_label(binary: &Binary, target: u32) -> String {
+    match binary.nearest_label(target) {
+        Some((label, 0)) => label.to_string(),
+        _ => format!("0x{:08x}", target),
+    }
+}
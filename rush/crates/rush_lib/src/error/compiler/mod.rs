@@ -1,9 +1,68 @@
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
-use std::fmt::Display;
+use std::{fmt::Display, rc::Rc};
 
-use crate::{inst::instruction::Signature};
+use crate::inst::instruction::Signature;
 use rush_parser::{MpDirective, MpInstruction};
 
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct CompilerError {
+    // boxed, as the instruction ASTs in `Error` would make every `RushResult` huge
+    error: Box<Error>,
+    file_tag: Rc<str>,
+    line: u32,
+    col: u32,
+    col_end: u32,
+}
+
+impl CompilerError {
+    pub fn new(error: Error, file_tag: Rc<str>, line: u32, col: u32, col_end: u32) -> Self {
+        Self {
+            error: Box::new(error),
+            file_tag,
+            line,
+            col,
+            col_end,
+        }
+    }
+
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    pub fn file_tag(&self) -> Rc<str> {
+        self.file_tag.clone()
+    }
+
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    pub fn col_end(&self) -> u32 {
+        self.col_end
+    }
+
+    /// Prints the error along with the offending line of `file`, if it can be found.
+    pub fn show_error(&self, file: Rc<str>) {
+        println!(
+            "{}{} {}",
+            "error".bright_red().bold(),
+            ":".bold(),
+            self.error.message()
+        );
+
+        super::show_source(&file, &self.file_tag, self.line, self.col, self.col_end);
+
+        for tip in self.error.tips() {
+            println!("{}{} {}", "tip".yellow().bold(), ":".bold(), tip);
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum Error {
     NumberedRegisterOutOfRange {
@@ -29,6 +88,13 @@ pub enum Error {
         similar_instns: Vec<Signature>,
     },
 
+    BranchOutOfRange {
+        offset: i32,
+    },
+    ExternalBranch {
+        label: String,
+    },
+
     RedefinedLabel {
         label: String,
     },
@@ -43,6 +109,7 @@ pub enum Error {
     UnresolvedConstant {
         label: String,
     },
+    DivisionByZero,
 
     ConstantValueDoesNotFit {
         directive_type: DirectiveType,
@@ -59,6 +126,14 @@ pub enum Error {
     TooMuchData {
         data_size: u32,
     },
+
+    KernelSegmentInObject {
+        directive_type: MpDirective,
+    },
+    SegmentAddressOutOfRange {
+        directive_type: MpDirective,
+        addr: i64,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -85,3 +160,128 @@ impl Display for DirectiveType {
         )
     }
 }
+
+impl Error {
+    pub fn message(&self) -> String {
+        match self {
+            Error::NumberedRegisterOutOfRange { reg_num } => format!(
+                "register {} is out of range, registers go from {} to {}",
+                format!("${}", reg_num).bold(),
+                "$0".bold(),
+                "$31".bold()
+            ),
+            Error::NamedRegisterOutOfRange {
+                reg_name,
+                reg_index,
+            } => format!(
+                "register {} does not exist",
+                format!("${}{}", reg_name, reg_index).bold()
+            ),
+            Error::UnknownRegister { reg_name } => {
+                format!("unknown register {}", format!("${}", reg_name).bold())
+            }
+            Error::UnknownInstruction { inst_ast } => {
+                format!("unknown instruction `{}`", inst_ast.name().bold())
+            }
+            Error::InstructionBadFormat { inst_ast, .. } => format!(
+                "wrong arguments for instruction `{}`",
+                inst_ast.name().bold()
+            ),
+            Error::InstructionSimName { inst_ast, .. } => {
+                format!("unknown instruction `{}`", inst_ast.name().bold())
+            }
+            Error::BranchOutOfRange { offset } => format!(
+                "branch target is too far away ({} instructions, the limit is {})",
+                offset.to_string().bold(),
+                i16::MAX
+            ),
+            Error::ExternalBranch { label } => {
+                format!("cannot branch to `{}`, as it is defined in another file", label.bold())
+            }
+            Error::RedefinedLabel { label } => {
+                format!("label `{}` is defined more than once", label.bold())
+            }
+            Error::UnresolvedLabel { label, .. } => {
+                format!("cannot find label `{}`", label.bold())
+            }
+            Error::RedefinedConstant { label } => {
+                format!("constant `{}` is defined more than once", label.bold())
+            }
+            Error::UnresolvedConstant { label } => {
+                format!("cannot find constant `{}`", label.bold())
+            }
+            Error::DivisionByZero => "division by zero in constant expression".to_string(),
+            Error::ConstantValueDoesNotFit {
+                directive_type,
+                value,
+                range_low,
+                range_high,
+            } => format!(
+                "value {} does not fit in a .{} (expected {} to {})",
+                value.to_string().bold(),
+                directive_type,
+                range_low,
+                range_high
+            ),
+            Error::DataInTextSegment { directive_type } => format!(
+                "`.{}` can only be used in the data segment",
+                directive_type.to_string().bold()
+            ),
+            Error::InstructionInDataSegment => {
+                "instructions can only be used in the text segment".to_string()
+            }
+            Error::TooMuchData { data_size } => format!(
+                "the data segment is too large ({} bytes)",
+                data_size.to_string().bold()
+            ),
+            Error::KernelSegmentInObject { directive_type } => format!(
+                "`.{}` can't be used in an object, as the kernel segments can't be relocated",
+                directive_type.to_string().bold()
+            ),
+            Error::SegmentAddressOutOfRange { directive_type, addr } => format!(
+                "`.{}` can't start at {}",
+                directive_type.to_string().bold(),
+                format!("{:#x}", addr).bold()
+            ),
+        }
+    }
+
+    pub fn tips(&self) -> Vec<String> {
+        match self {
+            Error::InstructionBadFormat {
+                correct_formats, ..
+            } => correct_formats
+                .iter()
+                .map(|sig| format!("try `{}`", sig))
+                .collect(),
+            Error::InstructionSimName { similar_instns, .. } => similar_instns
+                .iter()
+                .map(|sig| format!("did you mean `{}`?", sig))
+                .collect(),
+            Error::UnresolvedLabel { similar, .. } if !similar.is_empty() => {
+                vec![format!("did you mean `{}`?", similar.join("`, `"))]
+            }
+            Error::BranchOutOfRange { .. } | Error::ExternalBranch { .. } => {
+                vec![format!("try jumping with `{}` instead", "j".bold())]
+            }
+            Error::DataInTextSegment { .. } => {
+                vec![format!("try adding `{}` before it", ".data".bold())]
+            }
+            Error::InstructionInDataSegment => {
+                vec![format!("try adding `{}` before it", ".text".bold())]
+            }
+            Error::KernelSegmentInObject { .. } => {
+                vec!["try running the file directly, without linking it".to_string()]
+            }
+            Error::SegmentAddressOutOfRange { directive_type, .. } => {
+                let (low, high) = match directive_type {
+                    MpDirective::KText(_) => ("0x80000000", "0x8ffffffc, word aligned"),
+                    _ => ("0x90000000", "0xffffffff"),
+                };
+
+                vec![format!("the address must be between {} and {}", low.bold(), high.bold())]
+            }
+            _ => vec![],
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use rush_utils::Endianness;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Error {
+    UndefinedSymbol {
+        symbol: String,
+        object: String,
+    },
+    RedefinedSymbol {
+        symbol: String,
+        first: String,
+        second: String,
+    },
+    JumpOutOfRange {
+        addr: u32,
+        target: u32,
+        object: String,
+    },
+    TooMuchData {
+        data_size: u32,
+    },
+    WrongEndianness {
+        object: String,
+        expected: Endianness,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::UndefinedSymbol { symbol, object } => {
+                write!(f, "undefined reference to `{}` in `{}`", symbol, object)
+            }
+            Error::RedefinedSymbol { symbol, first, second } => {
+                write!(f, "`{}` is defined in both `{}` and `{}`", symbol, first, second)
+            }
+            Error::JumpOutOfRange { addr, target, object } => write!(
+                f,
+                "jump at {:#010x} in `{}` cannot reach {:#010x}, as it is in a different 256MB region",
+                addr, object, target
+            ),
+            Error::TooMuchData { data_size } => {
+                write!(f, "the linked program has too much data ({} bytes)", data_size)
+            }
+            Error::WrongEndianness { object, expected } => {
+                let expected = match expected {
+                    Endianness::Big => "big",
+                    Endianness::Little => "little",
+                };
+
+                write!(f, "`{}` is not {}-endian, like the rest of the program", object, expected)
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use rush_utils::Endianness;
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Error {
+    CannotReadFile {
+        path: String,
+        os_error: String,
+    },
+
+    NotElf32,
+    UnsupportedMachine {
+        machine: u16,
+    },
+    UnsupportedType {
+        elf_type: u16,
+    },
+    NotRelocatable {
+        elf_type: u16,
+    },
+    UnsupportedRelocation {
+        rel_type: u8,
+    },
+    Truncated {
+        what: &'static str,
+    },
+    NoLoadableSegments,
+    BadSegmentSize {
+        vaddr: u32,
+        filesz: u32,
+        memsz: u32,
+    },
+    WrongEndianness {
+        expected: Endianness,
+        found: Endianness,
+    },
+
+    MissingTextRange,
+    MissingStartAddr,
+
+    MalformedSymbolMap {
+        path: String,
+        line: u32,
+    },
+
+    CannotSetUpDevice {
+        base: u32,
+        reason: String,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::CannotReadFile { path, os_error } => {
+                write!(f, "failed to read file `{}`: {}", path, os_error)
+            }
+            Error::NotElf32 => write!(f, "not a 32-bit ELF file"),
+            Error::UnsupportedMachine { machine } => {
+                write!(f, "ELF file is not a MIPS executable (e_machine = {})", machine)
+            }
+            Error::UnsupportedType { elf_type } => {
+                write!(f, "ELF file is not an executable (e_type = {})", elf_type)
+            }
+            Error::NotRelocatable { elf_type } => {
+                write!(f, "ELF file is not a relocatable object (e_type = {})", elf_type)
+            }
+            Error::UnsupportedRelocation { rel_type } => {
+                write!(f, "ELF file uses an unsupported relocation type ({})", rel_type)
+            }
+            Error::Truncated { what } => write!(f, "ELF file is truncated ({} out of bounds)", what),
+            Error::NoLoadableSegments => write!(f, "ELF file has no PT_LOAD segments"),
+            Error::BadSegmentSize { vaddr, filesz, memsz } => write!(
+                f,
+                "ELF segment at 0x{:08x} has an impossible size (p_filesz = {:#x}, p_memsz = {:#x})",
+                vaddr, filesz, memsz,
+            ),
+            Error::WrongEndianness { expected, found } => {
+                let name = |endian: &Endianness| match endian {
+                    Endianness::Big => "big",
+                    Endianness::Little => "little",
+                };
+
+                write!(
+                    f,
+                    "ELF file is {}-endian, but the config asks for `endianness: {}`",
+                    name(found),
+                    name(expected),
+                )
+            }
+            Error::MissingTextRange => {
+                write!(f, "flat binaries need `memory.text` to be set in the config")
+            }
+            Error::MissingStartAddr => {
+                write!(f, "flat binaries need `start_addr` to be set in the config")
+            }
+            Error::MalformedSymbolMap { path, line } => {
+                write!(f, "malformed symbol map `{}` at line {}", path, line)
+            }
+            Error::CannotSetUpDevice { base, reason } => {
+                write!(f, "couldn't set up the device at 0x{:08x}: {}", base, reason)
+            }
+        }
+    }
+}
@@ -1,22 +1,33 @@
+use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::rc::Rc;
 
+use crate::compile::TAB_SIZE;
+
 pub mod compiler;
+pub mod linker;
+pub mod loader;
+pub mod parser;
 pub mod runtime;
 
 pub type RushResult<T> = Result<T, RushError>;
 pub type RuntimeError = runtime::RuntimeError;
+pub type CompilerError = compiler::CompilerError;
+pub type ParserError = parser::ParserError;
 
 pub type RushInternalResult<T> = Result<T, InternalError>;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum RushError {
+    Parser(parser::ParserError),
+    Compiler(compiler::CompilerError),
     Runtime(runtime::RuntimeError),
 }
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum InternalError {
-    Runtime(runtime::Error)
+    Compiler(compiler::Error),
+    Runtime(runtime::Error),
 }
 
 pub trait ToRushResult<T> {
@@ -61,31 +72,61 @@ impl<T> ToRushResult<T> for RushInternalResult<T> {
 }
 
 impl InternalError {
-    pub fn into_parser_rush_error(self, _file_tag: Rc<str>, _line: u32, _col: u32) -> RushError {
-        match self {
-            InternalError::Runtime(error) => RushError::Runtime(RuntimeError::new(error)),
-        }
+    pub fn into_parser_rush_error(self, file_tag: Rc<str>, line: u32, col: u32) -> RushError {
+        self.into_compiler_rush_error(file_tag, line, col, col)
     }
 
     pub fn into_compiler_rush_error(
         self,
-        _file_tag: Rc<str>,
-        _line: u32,
-        _col: u32,
-        _col_end: u32,
+        file_tag: Rc<str>,
+        line: u32,
+        col: u32,
+        col_end: u32,
     ) -> RushError {
         match self {
+            InternalError::Compiler(error) => RushError::Compiler(CompilerError::new(
+                error, file_tag, line, col, col_end,
+            )),
             InternalError::Runtime(error) => RushError::Runtime(RuntimeError::new(error)),
         }
     }
 
     pub fn into_runtime_rush_error(self) -> RushError {
-        match self {
-            InternalError::Runtime(error) => RushError::Runtime(RuntimeError::new(error)),
-        }
+        self.into_compiler_rush_error(Rc::from(""), 0, 0, 0)
     }
 }
 
+/// Prints a `--> file:line:col` pointer followed by the source line,
+/// with the columns `col..col_end` underlined.
+pub(crate) fn show_source(file: &str, file_tag: &str, line: u32, col: u32, col_end: u32) {
+    let location = if file_tag.is_empty() {
+        format!("{}:{}", line, col)
+    } else {
+        format!("{}:{}:{}", file_tag, line, col)
+    };
+    println!(" {} {}", "-->".blue().bold(), location);
+
+    let file = rush_parser::tabs_to_spaces(file, TAB_SIZE);
+    let source_line = match line.checked_sub(1).and_then(|index| file.lines().nth(index as usize)) {
+        Some(source_line) => source_line,
+        None => return,
+    };
+
+    let line_number = line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let underline = "^".repeat(col_end.saturating_sub(col).max(1) as usize);
+
+    println!("{} {}", gutter, "|".blue().bold());
+    println!("{} {} {}", line_number.blue().bold(), "|".blue().bold(), source_line);
+    println!(
+        "{} {} {}{}",
+        gutter,
+        "|".blue().bold(),
+        " ".repeat(col.saturating_sub(1) as usize),
+        underline.bright_red().bold()
+    );
+}
+
 #[macro_export]
 macro_rules! cerr {
     ($err:expr) => {
@@ -0,0 +1,63 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::rc::Rc;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ParserError {
+    error: Error,
+    file_tag: Rc<str>,
+    line: u32,
+    col: u32,
+}
+
+impl ParserError {
+    pub fn new(error: Error, file_tag: Rc<str>, line: u32, col: u32) -> Self {
+        Self {
+            error,
+            file_tag,
+            line,
+            col,
+        }
+    }
+
+    pub fn error(&self) -> &Error {
+        &self.error
+    }
+
+    pub fn file_tag(&self) -> Rc<str> {
+        self.file_tag.clone()
+    }
+
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+
+    pub fn col(&self) -> u32 {
+        self.col
+    }
+
+    /// Prints the error along with the offending line of `file`, if it can be found.
+    pub fn show_error(&self, file: Rc<str>) {
+        println!(
+            "{}{} {}",
+            "error".bright_red().bold(),
+            ":".bold(),
+            self.error.message()
+        );
+
+        super::show_source(&file, &self.file_tag, self.line, self.col, self.col + 1);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Error {
+    ParseFailure,
+}
+
+impl Error {
+    pub fn message(&self) -> String {
+        match self {
+            Error::ParseFailure => "failed to parse".to_string(),
+        }
+    }
+}
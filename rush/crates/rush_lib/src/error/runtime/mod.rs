@@ -1,8 +1,10 @@
 use crate::{
+    decompile::{decompile_inst, decompile_inst_into_parts},
+    inst::InstSet,
     util::Segment,
-    Register, Runtime,
+    Binary, Register, Runtime,
 };
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -22,6 +24,8 @@ impl RuntimeError {
     pub fn show_error(
         &self,
         context: ErrorContext,
+        iset: &InstSet,
+        binary: &Binary,
         runtime: &Runtime,
     ) {
         println!(
@@ -29,11 +33,48 @@ impl RuntimeError {
             "error".bright_red().bold(),
             ":".bold(),
             self.error
-                .message(context, runtime)
+                .message(context, iset, binary, runtime)
         );
     }
 }
 
+/// A register read in the delay slot of a load to it, which (with `load_delay`) still had its old value.
+/// On an R2000/R3000 the result is whatever was there before, so this is only a warning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct LoadDelayHazard {
+    pub load_addr: u32,
+    pub addr: u32,
+    pub reg_num: u32,
+}
+
+impl LoadDelayHazard {
+    pub fn show_warning(&self, iset: &InstSet, binary: &Binary, runtime: &Runtime) {
+        println!(
+            "{}{} {}",
+            "warning".yellow().bold(),
+            ":".bold(),
+            self.message(iset, binary, runtime)
+        );
+    }
+
+    pub fn message(&self, iset: &InstSet, binary: &Binary, runtime: &Runtime) -> String {
+        let describe = |addr: u32| match runtime.state.read_mem_word(addr) {
+            Ok(inst) => describe_inst(iset, binary, addr, inst),
+            Err(_) => describe_addr(binary, addr),
+        };
+
+        format!(
+            "{}{} was read in the delay slot of a load to it, so it still had its old value\n\
+             the load was:\n{}\n\
+             the instruction that read it was:\n{}\n",
+            "$".yellow(),
+            Register::from_u32(self.reg_num).unwrap().to_lower_str().bold(),
+            describe(self.load_addr),
+            describe(self.addr),
+        )
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum ErrorContext {
     Binary,
@@ -46,6 +87,11 @@ pub enum Error {
     UnknownInstruction {
         addr: u32,
     },
+    /// An instruction word that doesn't encode anything rush implements.
+    ReservedInstruction {
+        addr: u32,
+        inst: u32,
+    },
     Uninitialised {
         value: Uninitialised,
     },
@@ -65,6 +111,22 @@ pub enum Error {
         syscall: i32,
         reason: InvalidSyscallReason,
     },
+    /// With an MMU, a user mode access to a kernel address.
+    AddressError {
+        addr: u32,
+        access: SegmentationFaultAccessType,
+    },
+    /// With an MMU, an access to a page that no TLB entry maps (a refill),
+    /// or whose entry isn't valid.
+    TlbMiss {
+        addr: u32,
+        access: SegmentationFaultAccessType,
+        refill: bool,
+    },
+    /// With an MMU, a store to a page whose TLB entry isn't dirty (i.e. writable).
+    TlbModified {
+        addr: u32,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -73,6 +135,7 @@ pub enum Uninitialised {
     Half { addr: u32 },
     Word { addr: u32 },
     Register { reg_num: u32 },
+    FpuRegister { reg_num: u32 },
     Lo,
     Hi,
 }
@@ -81,6 +144,7 @@ pub enum Uninitialised {
 pub enum AlignmentRequirement {
     Half,
     Word,
+    Double,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -100,14 +164,61 @@ impl Error {
     pub fn message(
         &self,
         context: ErrorContext,
+        iset: &InstSet,
+        binary: &Binary,
         runtime: &Runtime,
     ) -> String {
         match self {
             Error::UnknownInstruction { addr } => {
                 let message = "could not find instruction at";
-                let zero_x = "0x".yellow();
 
-                format!("{} {}{:08x}\n", message, zero_x, addr)
+                format!("{} {}\n", message, describe_addr(binary, *addr))
+            }
+
+            &Error::ReservedInstruction { addr, inst } => {
+                let mut error = String::new();
+
+                error.push_str(&format!("reserved instruction at {}\n", describe_addr(binary, addr)));
+
+                if let ErrorContext::Binary | ErrorContext::Interactive = context {
+                    error.push_str("\nthe instruction that failed was:\n");
+                    error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
+                    error.push('\n');
+                }
+
+                if runtime.state.micromips() {
+                    error.push_str(&format!(
+                        "\nthis happened because no microMIPS instruction rush implements is encoded as `{}{}`.\n",
+                        "0x".bold(),
+                        format!("{:04x}", inst).bold(),
+                    ));
+
+                    return error;
+                }
+
+                // the assembler knows every instruction, including those only some configs implement
+                match decompile_inst_into_parts(binary, iset, inst, addr).inst_name {
+                    Some(name) => error.push_str(&format!(
+                        "\nthis happened because `{}` isn't implemented with this config (see `isa` and `mips64`):\n",
+                        name.to_lowercase().bold(),
+                    )),
+                    None => error.push_str(&format!(
+                        "\nthis happened because no instruction is encoded as `{}{}`:\n",
+                        "0x".bold(),
+                        format!("{:08x}", inst).bold(),
+                    )),
+                }
+
+                let field = |name: &str, value: u32| format!(" - {} = {}\n", name.yellow(), format!("{:#04x}", value).bold());
+                error.push_str(&field("opcode", inst >> 26));
+                error.push_str(&field("rs", (inst >> 21) & 0x1F));
+                error.push_str(&field("rt", (inst >> 16) & 0x1F));
+                error.push_str(&field("rd", (inst >> 11) & 0x1F));
+                error.push_str(&field("shamt", (inst >> 6) & 0x1F));
+                error.push_str(&field("funct", inst & 0x3F));
+
+                error.push('\n');
+                error
             }
 
             Error::Uninitialised { value } => {
@@ -123,37 +234,28 @@ impl Error {
                         };
 
                         let message = "is uninitialised";
-                        let zero_x = "0x".yellow();
-                        return format!("{} at {}{:08x} {}", size, zero_x, addr, message);
+                        return format!("{} at {} {}", size, describe_addr(binary, *addr), message);
                     }
 
                     Uninitialised::Register { reg_num } => {
-                        let name = Register::from_u32(*reg_num).unwrap().to_lower_str();
-
-                        name
+                        Register::from_u32(*reg_num).unwrap().to_lower_str().to_string()
                     }
 
-                    Uninitialised::Lo => {
-                        let name = "lo";
+                    Uninitialised::FpuRegister { reg_num } => format!("f{reg_num}"),
 
-                        name
-                    }
+                    Uninitialised::Lo => "lo".to_string(),
 
-                    Uninitialised::Hi => {
-                        let name = "hi";
-
-                        name
-                    }
+                    Uninitialised::Hi => "hi".to_string(),
                 };
 
                 let mut error = String::new();
                 error.push_str("program tried to read an uninitialised register\n");
 
-                let inst = runtime.state.read_mem_pc().unwrap();
+                let inst = failed_inst(runtime);
 
                 if let ErrorContext::Binary | ErrorContext::Interactive = context {
                     error.push_str("the instruction that failed was:\n");
-                    error.push_str(&format!("{:#X}", inst));
+                    error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
                     error.push('\n');
                 }
 
@@ -177,18 +279,19 @@ impl Error {
 
                 error.push_str("unaligned access\n");
 
-                let inst = runtime.state.read_mem_pc().unwrap();
+                let inst = failed_inst(runtime);
 
                 let alignment_bytes = match alignment_requirement {
                     AlignmentRequirement::Half => 2,
                     AlignmentRequirement::Word => 4,
+                    AlignmentRequirement::Double => 8,
                 };
 
                 if let ErrorContext::Binary | ErrorContext::Interactive = context {
                     error.push_str("\nerror at address:\n");
-                    error.push_str(&format!("{:#X}", addr));
+                    error.push_str(&describe_addr(binary, *addr));
                     error.push_str("\nthe instruction that failed was:\n");
-                    error.push_str(&format!("{:X}", inst));
+                    error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
                     error.push_str("\nalignment must be:\n");
                     error.push_str(&format!("{}", alignment_bytes));
                     error.push('\n');
@@ -202,16 +305,16 @@ impl Error {
                 let mut error = String::new();
                 error.push_str("integer overflow\n");
 
-                let inst = runtime.state.read_mem_pc().unwrap();
+                let inst = failed_inst(runtime);
 
                 if let ErrorContext::Binary | ErrorContext::Interactive = context {
                     error.push_str("\nthe instruction that failed was:\n");
-                    error.push_str(&format!("{:#X}", inst));
+                    error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
                     error.push('\n');
                 }
 
                 let rs = (inst >> 21) & 0x1F;
-                let rs_value = runtime.state.read_register(rs).unwrap();
+                let rs_value = runtime.state.read_register64(rs).unwrap();
                 error.push_str("values:\n");
                 error.push_str(&format!(
                     " - {}{} = {}\n",
@@ -228,11 +331,11 @@ impl Error {
 
                 error.push_str("division by zero\n");
 
-                let inst = runtime.state.read_mem_word(runtime.state.pc()).unwrap();
+                let inst = failed_inst(runtime);
 
                 if let ErrorContext::Binary | ErrorContext::Interactive = context {
                     error.push_str("\nthe instruction that failed was:\n");
-                    error.push_str(&format!("{:#X}", inst));
+                    error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
                     error.push('\n');
                 }
 
@@ -245,14 +348,14 @@ impl Error {
                     " - {}{} = {}\n",
                     "$".yellow(),
                     Register::from_u32(rs).unwrap().to_lower_str().bold(),
-                    runtime.state.read_register(rs).unwrap()
+                    runtime.state.read_register64(rs).unwrap()
                 ));
 
                 error.push_str(&format!(
                     " - {}{} = {}\n",
                     "$".yellow(),
                     Register::from_u32(rt).unwrap().to_lower_str().bold(),
-                    runtime.state.read_register(rt).unwrap()
+                    runtime.state.read_register64(rt).unwrap()
                 ));
 
                 error
@@ -270,9 +373,10 @@ impl Error {
                             "read".yellow()
                         ));
                         error.push_str(&format!(
-                            "the address `{}{}`, which is not a valid address to read from\n",
+                            "the address `{}{}`{}, which is not a valid address to read from\n",
                             "0x".bold(),
-                            format!("{:08x}", addr).bold()
+                            format!("{:08x}", addr).bold(),
+                            describe_label(binary, addr),
                         ));
                     }
                     SegmentationFaultAccessType::Write => {
@@ -281,9 +385,10 @@ impl Error {
                             "write".yellow()
                         ));
                         error.push_str(&format!(
-                            "the address `{}{}`, which is not a valid address to write to\n",
+                            "the address `{}{}`{}, which is not a valid address to write to\n",
                             "0x".bold(),
-                            format!("{:08x}", addr).bold()
+                            format!("{:08x}", addr).bold(),
+                            describe_label(binary, addr),
                         ));
                     }
                     SegmentationFaultAccessType::Execute => {
@@ -292,9 +397,10 @@ impl Error {
                             "execute".yellow()
                         ));
                         error.push_str(&format!(
-                            "the address `{}{}`, which is not a valid address to execute\n",
+                            "the address `{}{}`{}, which is not a valid address to execute\n",
                             "0x".bold(),
-                            format!("{:08x}", addr).bold()
+                            format!("{:08x}", addr).bold(),
+                            describe_label(binary, addr),
                         ));
                     }
                 }
@@ -302,11 +408,11 @@ impl Error {
                 if access == SegmentationFaultAccessType::Read
                     || access == SegmentationFaultAccessType::Write
                 {
-                    let inst = runtime.state.read_mem_pc().unwrap();
+                    let inst = failed_inst(runtime);
 
                     if let ErrorContext::Binary | ErrorContext::Interactive = context {
                         error.push_str("\nthe instruction that failed was:\n");
-                        error.push_str(&format!("{:#X}", inst));
+                        error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
                         error.push('\n');
                     }
                 } else if runtime.get_segment(runtime.state.pc()) == Segment::Text
@@ -315,7 +421,7 @@ impl Error {
 
                     if let ErrorContext::Binary | ErrorContext::Interactive = context {
                         error.push_str("\nthe instruction that got us here was:\n");
-                        error.push_str(&format!("{:#X}", inst));
+                        error.push_str(&describe_inst(iset, binary, runtime.state.pc() - 4, inst));
                         error.push('\n');
                     }
                 }
@@ -345,11 +451,11 @@ impl Error {
                     }
                 }
 
-                let inst = runtime.state.read_mem_word(runtime.state.pc()).unwrap();
+                let inst = failed_inst(runtime);
 
                 if let ErrorContext::Binary | ErrorContext::Interactive = context {
                     error.push_str("\nthe instruction that failed was:\n");
-                    error.push_str(&format!("{:#X}", inst));
+                    error.push_str(&describe_failed_inst(iset, binary, runtime, inst));
                     error.push('\n');
                 }
 
@@ -364,6 +470,145 @@ impl Error {
 
                 error
             }
+
+            &Error::AddressError { addr, access } => {
+                let mut error = String::new();
+
+                error.push_str("address error\n");
+                error.push_str(&format!(
+                    "\nthis happened because you tried to {} the kernel address `{}{}`{} from user mode\n",
+                    describe_access(access),
+                    "0x".bold(),
+                    format!("{:08x}", addr).bold(),
+                    describe_label(binary, addr),
+                ));
+                error.push_str(&describe_mapped_access(context, iset, binary, runtime, access));
+
+                error
+            }
+
+            &Error::TlbMiss { addr, access, refill } => {
+                let mut error = String::new();
+
+                error.push_str("TLB miss\n");
+                error.push_str(&format!(
+                    "\nthis happened because you tried to {} the address `{}{}`{},\n",
+                    describe_access(access),
+                    "0x".bold(),
+                    format!("{:08x}", addr).bold(),
+                    describe_label(binary, addr),
+                ));
+                error.push_str(match refill {
+                    true => "which no TLB entry maps\n",
+                    false => "whose TLB entry isn't valid (its V bit is clear)\n",
+                });
+                error.push_str(&describe_mapped_access(context, iset, binary, runtime, access));
+
+                error
+            }
+
+            &Error::TlbModified { addr } => {
+                let mut error = String::new();
+
+                error.push_str("TLB modified\n");
+                error.push_str(&format!(
+                    "\nthis happened because you tried to {} the address `{}{}`{},\n",
+                    "write to".yellow(),
+                    "0x".bold(),
+                    format!("{:08x}", addr).bold(),
+                    describe_label(binary, addr),
+                ));
+                error.push_str("whose TLB entry isn't writable (its D bit is clear)\n");
+                error.push_str(&describe_mapped_access(context, iset, binary, runtime, SegmentationFaultAccessType::Write));
+
+                error
+            }
+        }
+    }
+}
+
+fn describe_access(access: SegmentationFaultAccessType) -> ColoredString {
+    match access {
+        SegmentationFaultAccessType::Read => "read from".yellow(),
+        SegmentationFaultAccessType::Write => "write to".yellow(),
+        SegmentationFaultAccessType::Execute => "execute".yellow(),
+    }
+}
+
+/// The instruction that made a load or store the MMU didn't allow. A bad fetch has no instruction.
+fn describe_mapped_access(
+    context: ErrorContext,
+    iset: &InstSet,
+    binary: &Binary,
+    runtime: &Runtime,
+    access: SegmentationFaultAccessType,
+) -> String {
+    let mut error = String::new();
+
+    if access != SegmentationFaultAccessType::Execute {
+        if let ErrorContext::Binary | ErrorContext::Interactive = context {
+            error.push_str("\nthe instruction that failed was:\n");
+            error.push_str(&describe_failed_inst(iset, binary, runtime, failed_inst(runtime)));
+            error.push('\n');
         }
     }
-}
\ No newline at end of file
+
+    error.push('\n');
+    error
+}
+/// The instruction that caused the error, which may be in the delay slot of the one at `pc`.
+fn failed_inst(runtime: &Runtime) -> u32 {
+    runtime.state.read_inst(runtime.state.faulting_addr()).unwrap().0
+}
+
+/// Like `describe_inst`, but also shows the branch if `inst` was in its delay slot.
+fn describe_failed_inst(iset: &InstSet, binary: &Binary, runtime: &Runtime, inst: u32) -> String {
+    let describe = |addr: u32, inst: u32| match runtime.state.micromips() {
+        true => describe_micromips_inst(binary, addr, inst),
+        false => describe_inst(iset, binary, addr, inst),
+    };
+    let described = describe(runtime.state.faulting_addr(), inst);
+
+    if !runtime.state.faulted_in_delay_slot() {
+        return described;
+    }
+
+    let branch_addr = runtime.state.pc();
+    let branch = runtime.state.read_inst(branch_addr).unwrap().0;
+
+    format!(
+        "{}
+in the delay slot of:
+{}",
+        described,
+        describe(branch_addr, branch),
+    )
+}
+
+/// `0x00400010 <main+16>`, or just the address if there's no label nearby.
+fn describe_addr(binary: &Binary, addr: u32) -> String {
+    format!("{}{:08x}{}", "0x".yellow(), addr, describe_label(binary, addr))
+}
+
+/// ` <main+16>`, or nothing if there's no label nearby.
+fn describe_label(binary: &Binary, addr: u32) -> String {
+    match binary.symbolize(addr) {
+        Some(label) => format!(" <{}>", label.yellow().bold()),
+        None => String::new(),
+    }
+}
+
+/// `[0x00400010 <main+16>] 0x4643  (microMIPS)`, as the disassembler only knows MIPS32.
+fn describe_micromips_inst(binary: &Binary, addr: u32, inst: u32) -> String {
+    format!("[{}] {:#06X}  {}", describe_addr(binary, addr), inst, "(microMIPS)".bold())
+}
+
+/// `[0x00400010 <main+16>] 0x8FA40000  lw      $a0, 0($sp)`
+fn describe_inst(iset: &InstSet, binary: &Binary, addr: u32, inst: u32) -> String {
+    format!(
+        "[{}] {:#010X}  {}",
+        describe_addr(binary, addr),
+        inst,
+        decompile_inst(binary, iset, inst, addr).bold(),
+    )
+}
@@ -1,6 +1,46 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InstSet {
+    native_set: Vec<InstSignature>,
+    pseudo_set: Vec<PseudoSignature>,
+}
+
+impl InstSet {
+    pub fn new(native_set: Vec<InstSignature>, pseudo_set: Vec<PseudoSignature>) -> Self {
+        Self {
+            native_set,
+            pseudo_set,
+        }
+    }
+
+    pub fn native_set(&self) -> &[InstSignature] {
+        &self.native_set
+    }
+
+    pub fn pseudo_set(&self) -> &[PseudoSignature] {
+        &self.pseudo_set
+    }
+
+    /// Every signature (native or pseudo) with the given name, ignoring case.
+    pub fn find_by_name(&self, name: &str) -> Vec<SignatureRef<'_>> {
+        let natives = self
+            .native_set
+            .iter()
+            .filter(|sig| sig.name.eq_ignore_ascii_case(name))
+            .map(SignatureRef::Native);
+
+        let pseudos = self
+            .pseudo_set
+            .iter()
+            .filter(|sig| sig.name.eq_ignore_ascii_case(name))
+            .map(SignatureRef::Pseudo);
+
+        natives.chain(pseudos).collect()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct InstSignature {
     name: String,
@@ -26,6 +66,26 @@ impl InstSignature {
             meta,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn compile_sig(&self) -> &CompileSignature {
+        &self.compile
+    }
+
+    pub fn runtime_sig(&self) -> &RuntimeSignature {
+        &self.runtime
+    }
+
+    pub fn runtime_meta(&self) -> &RuntimeMetadata {
+        &self.runtime_meta
+    }
+
+    pub fn meta(&self) -> &InstMetadata {
+        &self.meta
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -41,6 +101,14 @@ impl CompileSignature {
             relative_label,
         }
     }
+
+    pub fn format(&self) -> &[ArgumentType] {
+        &self.format
+    }
+
+    pub fn relative_label(&self) -> bool {
+        self.relative_label
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -48,7 +116,14 @@ pub enum ArgumentType {
     Rd,
     Rs,
     Rt,
+    Fd,
+    Fs,
+    Ft,
+    Cd,
     Shamt,
+    ExtSize,
+    InsSize,
+    Hint,
     I16,
     U16,
     J,
@@ -76,6 +151,7 @@ pub enum RuntimeSignature {
     },
     I {
         opcode: u8,
+        rs: Option<u8>,
         rt: Option<u8>,
     },
     J {
@@ -92,6 +168,10 @@ impl RuntimeMetadata {
     pub fn new(reads: Vec<ReadsRegisterType>) -> Self {
         Self { reads }
     }
+
+    pub fn reads(&self) -> &[ReadsRegisterType] {
+        &self.reads
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -115,6 +195,14 @@ impl InstMetadata {
             desc_long,
         }
     }
+
+    pub fn desc_short(&self) -> Option<&str> {
+        self.desc_short.as_deref()
+    }
+
+    pub fn desc_long(&self) -> Option<&str> {
+        self.desc_long.as_deref()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -132,6 +220,18 @@ impl PseudoSignature {
             expand,
         }
     }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn compile_sig(&self) -> &CompileSignature {
+        &self.compile
+    }
+
+    pub fn expansion(&self) -> &[PseudoExpand] {
+        &self.expand
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -144,6 +244,14 @@ impl PseudoExpand {
     pub fn new(inst: String, data: Vec<String>) -> Self {
         Self { inst, data }
     }
+
+    pub fn inst(&self) -> &str {
+        &self.inst
+    }
+
+    pub fn data(&self) -> &[String] {
+        &self.data
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -158,13 +266,71 @@ pub enum SignatureRef<'a> {
     Pseudo(&'a PseudoSignature),
 }
 
+impl Signature {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Native(sig) => sig.name(),
+            Self::Pseudo(sig) => sig.name(),
+        }
+    }
+
+    pub fn compile_sig(&self) -> &CompileSignature {
+        match self {
+            Self::Native(sig) => sig.compile_sig(),
+            Self::Pseudo(sig) => sig.compile_sig(),
+        }
+    }
+}
+
+impl<'a> SignatureRef<'a> {
+    pub fn name(&self) -> &'a str {
+        match self {
+            Self::Native(sig) => sig.name(),
+            Self::Pseudo(sig) => sig.name(),
+        }
+    }
+
+    pub fn compile_sig(&self) -> &'a CompileSignature {
+        match self {
+            Self::Native(sig) => sig.compile_sig(),
+            Self::Pseudo(sig) => sig.compile_sig(),
+        }
+    }
+
+    pub fn to_owned_sig(&self) -> Signature {
+        match self {
+            Self::Native(sig) => Signature::Native((*sig).clone()),
+            Self::Pseudo(sig) => Signature::Pseudo((*sig).clone()),
+        }
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())?;
+
+        for (i, arg) in self.compile_sig().format().iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{}{}", separator, arg)?;
+        }
+
+        Ok(())
+    }
+}
+
 impl fmt::Display for ArgumentType {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ArgumentType::Rd => write!(f, "$Rd"),
             ArgumentType::Rs => write!(f, "$Rs"),
             ArgumentType::Rt => write!(f, "$Rt"),
+            ArgumentType::Fd => write!(f, "$Fd"),
+            ArgumentType::Fs => write!(f, "$Fs"),
+            ArgumentType::Ft => write!(f, "$Ft"),
+            ArgumentType::Cd => write!(f, "$Cd"),
             ArgumentType::Shamt => write!(f, "shift"),
+            ArgumentType::ExtSize | ArgumentType::InsSize => write!(f, "size"),
+            ArgumentType::Hint => write!(f, "hint"),
             ArgumentType::I16 => write!(f, "i16"),
             ArgumentType::U16 => write!(f, "u16"),
             ArgumentType::J => write!(f, "label"),
@@ -180,12 +346,24 @@ impl fmt::Display for ArgumentType {
     }
 }
 
+/// A variable that can be used in the `data` of a `PseudoExpand`, e.g. `$Rt` or `$I32uHi`.
+///
+/// Registers are bound from the pseudo-instruction's arguments, including the base
+/// register of an offset (`$Rs` in `i32($Rs)`). Numbers are bound as written, and
+/// can be split into halves with a `uHi`/`uLo` (for `lui` + `ori`) or `sHi`/`sLo`
+/// (for `lui` + a sign-extended immediate) suffix.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-enum PseudoVariable {
+pub(crate) enum PseudoVariable {
     Rd,
     Rs,
     Rt,
+    Fd,
+    Fs,
+    Ft,
+    Cd,
     Shamt,
+    Size,
+    Hint,
     I16,
     U16,
     J,
@@ -202,12 +380,42 @@ enum PseudoVariable {
 }
 
 impl PseudoVariable {
-    fn name(&self) -> String {
+    const ALL: [Self; 21] = [
+        Self::Rd,
+        Self::Rs,
+        Self::Rt,
+        Self::Fd,
+        Self::Fs,
+        Self::Ft,
+        Self::Cd,
+        Self::Shamt,
+        Self::Size,
+        Self::Hint,
+        Self::I16,
+        Self::U16,
+        Self::J,
+        Self::OffRs,
+        Self::OffRt,
+        Self::F32,
+        Self::F64,
+        Self::Off,
+        Self::I32,
+        Self::U32,
+        Self::Off32,
+    ];
+
+    pub(crate) fn name(&self) -> String {
         match self {
             Self::Rd => "rd",
             Self::Rs => "rs",
             Self::Rt => "rt",
+            Self::Fd => "fd",
+            Self::Fs => "fs",
+            Self::Ft => "ft",
+            Self::Cd => "cd",
             Self::Shamt => "shamt",
+            Self::Size => "size",
+            Self::Hint => "hint",
             Self::I16 => "i16",
             Self::U16 => "u16",
             Self::J => "j",
@@ -224,4 +432,11 @@ impl PseudoVariable {
         }
         .to_string()
     }
+
+    /// Looks up a variable by name, ignoring case.
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|var| var.name().eq_ignore_ascii_case(name))
+    }
 }
@@ -2,6 +2,6 @@ pub mod instruction;
 pub mod register;
 
 pub use instruction::{
-    ArgumentType, CompileSignature, InstMetadata, InstSignature,
-    PseudoExpand, PseudoSignature, ReadsRegisterType, RuntimeMetadata, RuntimeSignature, Signature,
+    ArgumentType, CompileSignature, InstMetadata, InstSet, InstSignature, PseudoExpand,
+    PseudoSignature, ReadsRegisterType, RuntimeMetadata, RuntimeSignature, Signature, SignatureRef,
 };
@@ -1,4 +1,4 @@
-use crate::error::{InternalError, RushInternalResult, runtime};
+use crate::error::{compiler, InternalError, RushInternalResult};
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use std::{fmt::Display, str::FromStr};
@@ -70,10 +70,23 @@ impl FromStr for Register {
             }
         }
 
+        // $t10, $s9, ...
+        let mut chars = name.chars();
+        if let (Some(reg_name), Ok(reg_index)) = (chars.next(), chars.as_str().parse::<i32>()) {
+            if "vatsk".contains(reg_name.to_ascii_lowercase()) {
+                return Err(InternalError::Compiler(
+                    compiler::Error::NamedRegisterOutOfRange {
+                        reg_name: reg_name.to_ascii_lowercase(),
+                        reg_index,
+                    },
+                ));
+            }
+        }
+
         // who knows
-        Err(InternalError::Runtime(
-            runtime::Error::IntegerOverflow
-        ))
+        Err(InternalError::Compiler(compiler::Error::UnknownRegister {
+            reg_name: name.to_string(),
+        }))
     }
 }
 
@@ -161,7 +174,7 @@ impl Register {
             29 => Ok(Self::Sp),
             30 => Ok(Self::Fp),
             31 => Ok(Self::Ra),
-            _  => unreachable!()
+            _  => Err(InternalError::Compiler(compiler::Error::NumberedRegisterOutOfRange { reg_num: num })),
         }
     }
 
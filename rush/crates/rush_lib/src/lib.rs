@@ -1,20 +1,96 @@
 pub mod compile;
+pub mod decompile;
 pub mod error;
 pub mod inst;
 pub mod runtime;
 pub mod util;
 
-pub use rush_parser::MpProgram;
+use std::rc::Rc;
 
-pub use compile::Binary;
+use error::parser;
+use rush_utils::{Endianness, RushConfig};
+
+pub use rush_parser::{MpProgram, TaggedFile};
+
+pub use compile::{Binary, Object};
 pub use compile::{
-    DATA_BOT, GLOBAL_BOT, GLOBAL_PTR, HEAP_BOT, KDATA_BOT, KTEXT_BOT, STACK_BOT, STACK_PTR,
+    KERNEL, KERNEL_TAG, DATA_BOT, GLOBAL_BOT, GLOBAL_PTR, HEAP_BOT, KDATA_BOT, KTEXT_BOT, STACK_BOT, STACK_PTR,
     STACK_TOP, TEXT_BOT, TEXT_TOP,
 };
 pub use error::{
-    runtime::Uninitialised, RushError, RushResult, RuntimeError,
+    linker::Error as LinkerError, runtime::Uninitialised, CompilerError, ParserError, RushError, RushResult,
+    RuntimeError,
 };
 pub use inst::instruction::ArgumentType;
+pub use inst::InstSet;
 pub use inst::register::Register;
 pub use runtime::{Runtime, State};
 pub use util::Safe;
+
+/// Assembles `files` (along with the startup code in `KERNEL`) into a `Binary`.
+pub fn compile(iset: &InstSet, files: Vec<TaggedFile<'_, '_>>, config: &RushConfig) -> RushResult<Binary> {
+    let mut all_files = vec![TaggedFile::new(Some(KERNEL_TAG), KERNEL)];
+    all_files.extend(files);
+
+    compile::compile(&parse(all_files)?, config, iset)
+}
+
+/// Assembles a single file into an `Object` of the given byte order,
+/// which can be linked with others by `link`.
+pub fn compile_object(iset: &InstSet, file: TaggedFile<'_, '_>, endian: Endianness) -> RushResult<Object> {
+    let name = file.tag().unwrap_or_default().to_string();
+
+    compile::compile_object(&name, &parse(vec![file])?, iset, endian)
+}
+
+/// Links `objects` into a `Binary`. Unless one of them defines `__start`,
+/// the startup code in `KERNEL` is linked in ahead of them.
+pub fn link(iset: &InstSet, objects: Vec<Object>, config: &RushConfig) -> Result<Binary, LinkerError> {
+    let mut all_objects = vec![];
+    if !objects.iter().any(|object| object.defined_symbol("__start").is_some()) {
+        let endian = compile::link::endianness(&objects, config);
+        let kernel = compile_object(iset, TaggedFile::new(Some(KERNEL_TAG), KERNEL), endian);
+        all_objects.push(kernel.expect("the kernel should always assemble"));
+    }
+    all_objects.extend(objects);
+
+    compile::link::link(&all_objects, config)
+}
+
+fn parse(files: Vec<TaggedFile<'_, '_>>) -> RushResult<MpProgram> {
+    rush_parser::parse_mips(files, compile::TAB_SIZE).map_err(|error| {
+        RushError::Parser(ParserError::new(
+            parser::Error::ParseFailure,
+            error.file_name.unwrap_or_else(|| Rc::from("")),
+            error.line,
+            error.col as u32,
+        ))
+    })
+}
+
+/// Whether `path` looks like assembly source rather than a binary.
+pub fn is_assembly(path: &str) -> bool {
+    path.ends_with(".s") || path.ends_with(".asm")
+}
+
+/// Whether `path` is an ELF file, e.g. one written by `link`, rather than a config.
+pub fn is_elf(path: &str) -> bool {
+    let mut magic = [0; 4];
+
+    std::fs::File::open(path)
+        .and_then(|mut file| std::io::Read::read_exact(&mut file, &mut magic))
+        .is_ok_and(|_| compile::elf::is_elf(&magic))
+}
+
+/// The source of the file tagged `file_tag`, for showing alongside errors.
+pub fn source_of(files: &[TaggedFile<'_, '_>], file_tag: &str) -> Rc<str> {
+    if file_tag == KERNEL_TAG {
+        return Rc::from(KERNEL);
+    }
+
+    files
+        .iter()
+        .find(|file| file.tag() == Some(file_tag))
+        .map(|file| Rc::from(file.file_contents()))
+        .unwrap_or_else(|| Rc::from(""))
+}
@@ -0,0 +1,168 @@
+use rush_utils::{ReplacementPolicy, RushConfigCache, WritePolicy};
+
+#[derive(Clone, Copy, Default)]
+struct Line {
+    valid: bool,
+    dirty: bool,
+    tag: u32,
+    /// When the line was last accessed and filled, in accesses to the cache.
+    last_used: u64,
+    filled: u64,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct CacheStats {
+    pub reads: u64,
+    pub read_misses: u64,
+    pub writes: u64,
+    pub write_misses: u64,
+    pub write_backs: u64,
+}
+
+impl CacheStats {
+    pub fn accesses(&self) -> u64 {
+        self.reads + self.writes
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.read_misses + self.write_misses
+    }
+}
+
+/// A set-associative cache, which only keeps tags: the data itself is always read from `State`.
+pub struct Cache {
+    config: RushConfigCache,
+    sets: Vec<Vec<Line>>,
+    stats: CacheStats,
+    accesses: u64,
+    /// xorshift state, for `ReplacementPolicy::Random`.
+    random: u32,
+}
+
+impl Cache {
+    /// Panics on a geometry that `RushConfig::new` would have rejected.
+    pub fn new(config: &RushConfigCache) -> Self {
+        assert!(config.line_size.is_power_of_two(), "cache line size must be a power of two");
+        assert!(config.associativity > 0, "cache associativity must be at least 1");
+
+        let num_sets = config.size / (config.line_size * config.associativity);
+        assert!(
+            num_sets > 0 && num_sets * config.line_size * config.associativity == config.size,
+            "cache size must be a multiple of line size * associativity",
+        );
+
+        Self {
+            config: config.clone(),
+            sets: vec![vec![Line::default(); config.associativity as usize]; num_sets as usize],
+            stats: CacheStats::default(),
+            accesses: 0,
+            random: 0x2545F491,
+        }
+    }
+
+    pub fn config(&self) -> &RushConfigCache {
+        &self.config
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    /// Looks up `addr`, filling its line on a miss, and returns the cycles lost to the miss.
+    pub fn access(&mut self, addr: u32, write: bool) -> u64 {
+        self.accesses += 1;
+        match write {
+            true => self.stats.writes += 1,
+            false => self.stats.reads += 1,
+        }
+
+        let write_back = self.config.write == WritePolicy::WriteBack;
+        let line_num = addr / self.config.line_size;
+        let num_sets = self.sets.len() as u32;
+        let tag = line_num / num_sets;
+        let set = &mut self.sets[(line_num % num_sets) as usize];
+
+        if let Some(line) = set.iter_mut().find(|line| line.valid && line.tag == tag) {
+            line.last_used = self.accesses;
+            line.dirty |= write && write_back;
+
+            return 0;
+        }
+
+        match write {
+            true => self.stats.write_misses += 1,
+            false => self.stats.read_misses += 1,
+        }
+
+        // the store goes straight to memory, leaving the cache as it was
+        if write && !write_back {
+            return 0;
+        }
+
+        let victim = match set.iter().position(|line| !line.valid) {
+            Some(way) => way,
+            None => match self.config.replacement {
+                ReplacementPolicy::Lru => Self::oldest(set, |line| line.last_used),
+                ReplacementPolicy::Fifo => Self::oldest(set, |line| line.filled),
+                ReplacementPolicy::Random => {
+                    self.random ^= self.random << 13;
+                    self.random ^= self.random >> 17;
+                    self.random ^= self.random << 5;
+
+                    (self.random % set.len() as u32) as usize
+                }
+            },
+        };
+
+        let mut cycles = self.config.miss_penalty;
+        if set[victim].valid && set[victim].dirty {
+            self.stats.write_backs += 1;
+            cycles += self.config.miss_penalty;
+        }
+
+        set[victim] = Line {
+            valid: true,
+            dirty: write,
+            tag,
+            last_used: self.accesses,
+            filled: self.accesses,
+        };
+
+        cycles
+    }
+
+    fn oldest(set: &[Line], time: fn(&Line) -> u64) -> usize {
+        (0..set.len())
+            .min_by_key(|&way| time(&set[way]))
+            .unwrap_or_default()
+    }
+
+    /// The cache's geometry and statistics, for `info cache` and the end of a run.
+    pub fn summary(&self, name: &str) -> String {
+        let stats = self.stats;
+        let mut summary = format!(
+            "{}: {} bytes, {}-way, {}-byte lines, {} replacement",
+            name,
+            self.config.size,
+            self.config.associativity,
+            self.config.line_size,
+            match self.config.replacement {
+                ReplacementPolicy::Lru => "LRU",
+                ReplacementPolicy::Fifo => "FIFO",
+                ReplacementPolicy::Random => "random",
+            },
+        );
+
+        summary += &format!("\n  reads:       {} ({} misses)", stats.reads, stats.read_misses);
+        if stats.writes > 0 {
+            summary += &format!("\n  writes:      {} ({} misses)", stats.writes, stats.write_misses);
+            summary += &format!("\n  write backs: {}", stats.write_backs);
+        }
+        if stats.accesses() > 0 {
+            let hit_rate = (stats.accesses() - stats.misses()) as f64 / stats.accesses() as f64;
+            summary += &format!("\n  hit rate:    {:.2}%", hit_rate * 100.0);
+        }
+
+        summary
+    }
+}
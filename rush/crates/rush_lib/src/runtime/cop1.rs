@@ -0,0 +1,212 @@
+use super::{ExtendSign, Runtime, Truncate};
+use crate::RushResult;
+
+pub const FMT_S: u32 = 0x10;
+pub const FMT_D: u32 = 0x11;
+pub const FMT_W: u32 = 0x14;
+
+impl Runtime {
+    /// Executes a coprocessor 1 (floating point) instruction.
+    /// FPU exceptions aren't modelled, so invalid operations just produce their default results.
+    pub(super) fn execute_cop1(
+        &mut self,
+        funct: u32,
+        fs: u32,
+        fmt: u32,
+        ft: u32,
+        fd: u32,
+        imm: i16,
+    ) -> RushResult<()> {
+        match fmt {
+            // MFC1 $Rt, $Fs
+            0x00 => {
+                self.state.write_register_uninit(ft, self.state.fpu_registers()[fs as usize].extend_sign());
+            }
+
+            // CFC1 $Rt, $Fs
+            0x02 => {
+                self.state.write_register(ft, self.state.read_fpu_control(fs) as i32);
+            }
+
+            // MTC1 $Rt, $Fs
+            0x04 => {
+                self.state.write_fpu_register_uninit(fs, self.state.read_register_uninit(ft).truncate());
+            }
+
+            // CTC1 $Rt, $Fs
+            0x06 => {
+                self.state.write_fpu_control(fs, self.state.read_register(ft)? as u32);
+            }
+
+            // BC1F / BC1T  Cc, Im
+            0x08 => {
+                if self.state.fpu_condition(ft >> 2) == (ft & 1 == 1) {
+                    self.state.branch(imm);
+                }
+            }
+
+            FMT_S => self.execute_single(funct, fd, fs, ft)?,
+
+            FMT_D => self.execute_double(funct, fd, fs, ft)?,
+
+            FMT_W => {
+                let value = self.state.read_fpu_register(fs)? as i32;
+
+                match funct {
+                    // CVT.S.W $Fd, $Fs
+                    0x20 => self.state.write_f32(fd, value as f32),
+
+                    // CVT.D.W $Fd, $Fs
+                    0x21 => self.state.write_f64(fd, value as f64),
+
+                    _ => return Err(self.reserved_instruction()),
+                }
+            }
+
+            _ => return Err(self.reserved_instruction()),
+        }
+
+        Ok(())
+    }
+
+    fn execute_single(&mut self, funct: u32, fd: u32, fs: u32, ft: u32) -> RushResult<()> {
+        match funct {
+            // ADD.S $Fd, $Fs, $Ft
+            0x00 => self.state.write_f32(fd, self.state.read_f32(fs)? + self.state.read_f32(ft)?),
+
+            // SUB.S $Fd, $Fs, $Ft
+            0x01 => self.state.write_f32(fd, self.state.read_f32(fs)? - self.state.read_f32(ft)?),
+
+            // MUL.S $Fd, $Fs, $Ft
+            0x02 => self.state.write_f32(fd, self.state.read_f32(fs)? * self.state.read_f32(ft)?),
+
+            // DIV.S $Fd, $Fs, $Ft
+            0x03 => self.state.write_f32(fd, self.state.read_f32(fs)? / self.state.read_f32(ft)?),
+
+            // SQRT.S $Fd, $Fs
+            0x04 => self.state.write_f32(fd, self.state.read_f32(fs)?.sqrt()),
+
+            // ABS.S $Fd, $Fs
+            0x05 => self.state.write_f32(fd, self.state.read_f32(fs)?.abs()),
+
+            // MOV.S $Fd, $Fs
+            0x06 => {
+                self.state.write_fpu_register_uninit(fd, self.state.fpu_registers()[fs as usize]);
+            }
+
+            // NEG.S $Fd, $Fs
+            0x07 => self.state.write_f32(fd, -self.state.read_f32(fs)?),
+
+            // ROUND.W.S / TRUNC.W.S / CEIL.W.S / FLOOR.W.S $Fd, $Fs
+            0x0C..=0x0F => {
+                self.state.write_fpu_register(fd, round_to_word(self.state.read_f32(fs)? as f64, funct & 0b11));
+            }
+
+            // CVT.D.S $Fd, $Fs
+            0x21 => self.state.write_f64(fd, self.state.read_f32(fs)? as f64),
+
+            // CVT.W.S $Fd, $Fs
+            0x24 => {
+                let mode = self.state.fpu_rounding_mode();
+
+                self.state.write_fpu_register(fd, round_to_word(self.state.read_f32(fs)? as f64, mode));
+            }
+
+            // C.cond.S Cc, $Fs, $Ft
+            0x30..=0x3F => {
+                let result = compare(funct, self.state.read_f32(fs)? as f64, self.state.read_f32(ft)? as f64);
+
+                self.state.set_fpu_condition(fd >> 2, result);
+            }
+
+            _ => return Err(self.reserved_instruction()),
+        }
+
+        Ok(())
+    }
+
+    fn execute_double(&mut self, funct: u32, fd: u32, fs: u32, ft: u32) -> RushResult<()> {
+        match funct {
+            // ADD.D $Fd, $Fs, $Ft
+            0x00 => self.state.write_f64(fd, self.state.read_f64(fs)? + self.state.read_f64(ft)?),
+
+            // SUB.D $Fd, $Fs, $Ft
+            0x01 => self.state.write_f64(fd, self.state.read_f64(fs)? - self.state.read_f64(ft)?),
+
+            // MUL.D $Fd, $Fs, $Ft
+            0x02 => self.state.write_f64(fd, self.state.read_f64(fs)? * self.state.read_f64(ft)?),
+
+            // DIV.D $Fd, $Fs, $Ft
+            0x03 => self.state.write_f64(fd, self.state.read_f64(fs)? / self.state.read_f64(ft)?),
+
+            // SQRT.D $Fd, $Fs
+            0x04 => self.state.write_f64(fd, self.state.read_f64(fs)?.sqrt()),
+
+            // ABS.D $Fd, $Fs
+            0x05 => self.state.write_f64(fd, self.state.read_f64(fs)?.abs()),
+
+            // MOV.D $Fd, $Fs
+            0x06 => {
+                let registers = self.state.fpu_registers();
+                let (lo, hi) = (registers[(fs & !1) as usize], registers[(fs | 1) as usize]);
+
+                self.state.write_fpu_register_uninit(fd & !1, lo);
+                self.state.write_fpu_register_uninit(fd | 1, hi);
+            }
+
+            // NEG.D $Fd, $Fs
+            0x07 => self.state.write_f64(fd, -self.state.read_f64(fs)?),
+
+            // ROUND.W.D / TRUNC.W.D / CEIL.W.D / FLOOR.W.D $Fd, $Fs
+            0x0C..=0x0F => {
+                self.state.write_fpu_register(fd, round_to_word(self.state.read_f64(fs)?, funct & 0b11));
+            }
+
+            // CVT.S.D $Fd, $Fs
+            0x20 => self.state.write_f32(fd, self.state.read_f64(fs)? as f32),
+
+            // CVT.W.D $Fd, $Fs
+            0x24 => {
+                let mode = self.state.fpu_rounding_mode();
+
+                self.state.write_fpu_register(fd, round_to_word(self.state.read_f64(fs)?, mode));
+            }
+
+            // C.cond.D Cc, $Fs, $Ft
+            0x30..=0x3F => {
+                let result = compare(funct, self.state.read_f64(fs)?, self.state.read_f64(ft)?);
+
+                self.state.set_fpu_condition(fd >> 2, result);
+            }
+
+            _ => return Err(self.reserved_instruction()),
+        }
+
+        Ok(())
+    }
+}
+
+/// Rounds to a word using an FCSR rounding mode (which is also the order of ROUND/TRUNC/CEIL/FLOOR).
+/// NaNs and out of range values give 2^31 - 1, as they would with the invalid exception disabled.
+fn round_to_word(value: f64, mode: u32) -> u32 {
+    let rounded = match mode {
+        0 => value.round_ties_even(),
+        1 => value.trunc(),
+        2 => value.ceil(),
+        _ => value.floor(),
+    };
+
+    if rounded.is_nan() || rounded < i32::MIN as f64 || rounded > i32::MAX as f64 {
+        return i32::MAX as u32;
+    }
+
+    rounded as i32 as u32
+}
+
+/// The low 3 bits of a C.cond.fmt's condition select less than, equal and unordered.
+/// The signalling conditions (bit 3) compare the same way.
+pub(super) fn compare(cond: u32, fs: f64, ft: f64) -> bool {
+    let unordered = fs.is_nan() || ft.is_nan();
+
+    (cond & 0b100 != 0 && fs < ft) || (cond & 0b010 != 0 && fs == ft) || (cond & 0b001 != 0 && unordered)
+}
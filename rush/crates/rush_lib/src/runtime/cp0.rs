@@ -0,0 +1,457 @@
+use super::{tlb::{Tlb, TlbFault}, Runtime, SteppedRuntime};
+use crate::{
+    error::runtime::{Error, SegmentationFaultAccessType},
+    RushError, RushResult, RuntimeError,
+};
+
+/// Coprocessor 0 registers, as numbered by `mfc0`/`mtc0`.
+pub const BAD_VADDR: u32 = 8;
+pub const COUNT: u32 = 9;
+pub const COMPARE: u32 = 11;
+pub const STATUS: u32 = 12;
+pub const CAUSE: u32 = 13;
+pub const EPC: u32 = 14;
+
+/// Where exceptions are handled, as in spim (i.e. `.ktext 0x80000180`).
+pub const EXCEPTION_VECTOR: u32 = 0x80000180;
+/// Where TLB refills are handled, unless they happen while handling another exception.
+pub const TLB_REFILL_VECTOR: u32 = 0x80000000;
+
+/// The unmapped segments, which are aliases of the first 512MB of physical memory,
+/// cached (kseg0) and uncached (kseg1). Everything from kseg0 up is kernel only.
+pub const KSEG0: u32 = 0x80000000;
+pub const KSEG1: u32 = 0xA0000000;
+pub const KSEG2: u32 = 0xC0000000;
+
+/// Like spim: coprocessors 0 and 1 usable, all interrupts unmasked (but disabled), user mode.
+const STATUS_RESET: u32 = 0x3000FF10;
+const STATUS_IE: u32 = 1 << 0;
+const STATUS_EXL: u32 = 1 << 1;
+const STATUS_ERL: u32 = 1 << 2;
+/// Set (as KSU = 0b10) for user mode.
+const STATUS_UM: u32 = 1 << 4;
+const STATUS_IM: u32 = 0xFF << 8;
+const CAUSE_BD: u32 = 1 << 31;
+const CAUSE_TI: u32 = 1 << 30;
+const CAUSE_EXC_CODE: u32 = 0x1F << 2;
+/// The two software interrupt bits, which are the only part of Cause that `mtc0` can write.
+const CAUSE_SOFTWARE_IP: u32 = 0b11 << 8;
+
+/// The number of hardware interrupt lines, which set Cause.IP2 to Cause.IP7.
+pub const INTERRUPT_LINES: u32 = 6;
+
+/// The hardware interrupt line that the Count/Compare timer raises (IP7).
+pub const TIMER_INTERRUPT: u32 = 5;
+
+/// The `ExcCode` recorded in Cause when an exception is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExceptionCode {
+    Interrupt = 0,
+    TlbModified = 1,
+    TlbLoad = 2,
+    TlbStore = 3,
+    AddressErrorLoad = 4,
+    AddressErrorStore = 5,
+    InstructionBusError = 6,
+    DataBusError = 7,
+    Syscall = 8,
+    Breakpoint = 9,
+    ReservedInstruction = 10,
+    CoprocessorUnusable = 11,
+    Overflow = 12,
+    Trap = 13,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cp0 {
+    bad_vaddr: u32,
+    count: u32,
+    compare: u32,
+    status: u32,
+    cause: u32,
+    epc: u32,
+    /// Only there with an MMU, which makes kuseg and kseg2 mapped.
+    tlb: Option<Tlb>,
+}
+
+impl Cp0 {
+    /// Coprocessor 0 with a TLB of `tlb_entries` entries. As after a reset, it starts with Status.ERL set,
+    /// so in kernel mode and with kuseg unmapped, until the TLB has been set up and ERL is cleared.
+    pub fn with_tlb(tlb_entries: u32) -> Self {
+        Self {
+            status: STATUS_RESET & !STATUS_UM | STATUS_ERL,
+            tlb: Some(Tlb::new(tlb_entries)),
+            ..Self::default()
+        }
+    }
+
+    pub fn tlb(&self) -> Option<&Tlb> {
+        self.tlb.as_ref()
+    }
+
+    pub fn read(&self, reg_num: u32) -> u32 {
+        match reg_num {
+            BAD_VADDR => self.bad_vaddr,
+            COUNT => self.count,
+            COMPARE => self.compare,
+            STATUS => self.status,
+            CAUSE => self.cause,
+            EPC => self.epc,
+            _ => self.tlb.as_ref().and_then(|tlb| tlb.read(reg_num)).unwrap_or(0),
+        }
+    }
+
+    /// Writes a register for `mtc0`. BadVAddr is read-only, as is most of Cause.
+    pub fn write(&mut self, reg_num: u32, value: u32) {
+        match reg_num {
+            COUNT => self.count = value,
+            // acknowledges the timer interrupt
+            COMPARE => {
+                self.compare = value;
+                self.cause &= !CAUSE_TI;
+                self.set_interrupt(TIMER_INTERRUPT, false);
+            }
+            STATUS => self.status = value,
+            CAUSE => self.cause = (self.cause & !CAUSE_SOFTWARE_IP) | (value & CAUSE_SOFTWARE_IP),
+            EPC => self.epc = value,
+            _ => {
+                if let Some(tlb) = self.tlb.as_mut() {
+                    tlb.write(reg_num, value);
+                }
+            }
+        }
+    }
+
+    pub fn status(&self) -> u32 {
+        self.status
+    }
+
+    pub fn cause(&self) -> u32 {
+        self.cause
+    }
+
+    pub fn epc(&self) -> u32 {
+        self.epc
+    }
+
+    pub fn bad_vaddr(&self) -> u32 {
+        self.bad_vaddr
+    }
+
+    /// Whether an exception is being handled (Status.EXL).
+    pub fn exception_level(&self) -> bool {
+        self.status & STATUS_EXL != 0
+    }
+
+    /// Whether the processor is in kernel mode: outside of user mode, or handling an exception.
+    pub fn kernel_mode(&self) -> bool {
+        self.status & STATUS_UM == 0 || self.status & (STATUS_EXL | STATUS_ERL) != 0
+    }
+
+    /// The physical address that `addr` maps to. Without an MMU, that's `addr`.
+    /// With one, kseg0 and kseg1 are unmapped, and kuseg and kseg2 are mapped by the TLB
+    /// (except that kuseg is unmapped while Status.ERL is set).
+    pub fn translate(&self, addr: u32, access: SegmentationFaultAccessType) -> RushResult<u32> {
+        let Some(tlb) = &self.tlb else {
+            return Ok(addr);
+        };
+
+        let error = |error| Err(RushError::Runtime(RuntimeError::new(error)));
+        if addr >= KSEG0 && !self.kernel_mode() {
+            return error(Error::AddressError { addr, access });
+        }
+
+        match addr {
+            KSEG0..KSEG2 => Ok(addr & 0x1FFFFFFF),
+            _ if addr < KSEG0 && self.status & STATUS_ERL != 0 => Ok(addr),
+            _ => match tlb.lookup(addr, access == SegmentationFaultAccessType::Write) {
+                Ok(addr) => Ok(addr),
+                Err(TlbFault::Modified) => error(Error::TlbModified { addr }),
+                Err(fault) => error(Error::TlbMiss { addr, access, refill: fault == TlbFault::Refill }),
+            },
+        }
+    }
+
+    /// Like `translate`, but for the debugger (and loader), so regardless of the mode
+    /// and without raising anything: `None` if `addr` isn't mapped.
+    pub fn translate_unchecked(&self, addr: u32) -> Option<u32> {
+        let Some(tlb) = &self.tlb else {
+            return Some(addr);
+        };
+
+        match addr {
+            KSEG0..KSEG2 => Some(addr & 0x1FFFFFFF),
+            _ => tlb.lookup(addr, false).ok(),
+        }
+    }
+
+    /// The physical address that `addr` is cached at, or `None` for kseg1, which is uncached.
+    pub fn cached_addr(&self, addr: u32) -> Option<u32> {
+        match addr {
+            KSEG1..KSEG2 if self.tlb.is_some() => None,
+            _ => self.translate_unchecked(addr),
+        }
+    }
+
+    /// Advances Count by a cycle, raising the timer interrupt when it reaches Compare.
+    pub(super) fn tick(&mut self) {
+        self.count = self.count.wrapping_add(1);
+        if let Some(tlb) = self.tlb.as_mut() {
+            tlb.tick();
+        }
+
+        if self.count == self.compare {
+            self.cause |= CAUSE_TI;
+            self.set_interrupt(TIMER_INTERRUPT, true);
+        }
+    }
+
+    /// Asserts or deasserts hardware interrupt `line` (0 to 5).
+    /// The interrupt stays pending in Cause until the line is deasserted.
+    pub fn set_interrupt(&mut self, line: u32, asserted: bool) {
+        assert!(line < INTERRUPT_LINES, "there are only {} interrupt lines", INTERRUPT_LINES);
+
+        self.set_interrupt_bit(line + 2, asserted);
+    }
+
+    /// Sets or clears Cause.IP bit `ip` (0 to 7): 0 and 1 are the software interrupts
+    /// (which MARS's devices raise), and 2 to 7 are hardware interrupt lines 0 to 5.
+    pub fn set_interrupt_bit(&mut self, ip: u32, asserted: bool) {
+        let bit = 1 << (8 + ip);
+        match asserted {
+            true => self.cause |= bit,
+            false => self.cause &= !bit,
+        }
+    }
+
+    /// Whether Status.IM unmasks Cause.IP bit `ip`.
+    pub fn interrupt_unmasked(&self, ip: u32) -> bool {
+        self.status & 1 << (8 + ip) != 0
+    }
+
+    /// Sets Status.IE, for simulating processors (such as MARS's) whose interrupts start enabled.
+    pub(super) fn enable_interrupts(&mut self) {
+        self.status |= STATUS_IE;
+    }
+
+    /// Whether a pending interrupt is unmasked (by Status.IM), whether or not it's enabled.
+    pub fn interrupt_requested(&self) -> bool {
+        self.cause & self.status & STATUS_IM != 0
+    }
+
+    /// Whether a pending interrupt is unmasked (by Status.IM) and enabled (by Status.IE,
+    /// outside of an exception), and so should be taken before the next instruction.
+    pub fn interrupt_pending(&self) -> bool {
+        self.status & STATUS_IE != 0
+            && self.status & (STATUS_EXL | STATUS_ERL) == 0
+            && self.interrupt_requested()
+    }
+
+    /// Records an exception at `epc` (the branch, if it happened in a delay slot).
+    /// During another exception (i.e. with Status.EXL set), EPC and Cause.BD are left as they are,
+    /// so that a TLB refill handler that misses itself still returns to the instruction that missed first.
+    fn enter_exception(&mut self, code: ExceptionCode, epc: u32, in_delay_slot: bool, bad_vaddr: Option<u32>) {
+        self.cause = (self.cause & !CAUSE_EXC_CODE) | (code as u32) << 2;
+
+        if let Some(bad_vaddr) = bad_vaddr {
+            self.bad_vaddr = bad_vaddr;
+
+            if let (ExceptionCode::TlbModified | ExceptionCode::TlbLoad | ExceptionCode::TlbStore, Some(tlb)) = (code, self.tlb.as_mut()) {
+                tlb.record_fault(bad_vaddr);
+            }
+        }
+
+        if !self.exception_level() {
+            self.epc = epc;
+            self.cause = match in_delay_slot {
+                true => self.cause | CAUSE_BD,
+                false => self.cause & !CAUSE_BD,
+            };
+        }
+
+        self.status |= STATUS_EXL;
+    }
+
+    /// Leaves the exception level for `eret`, returning where to resume.
+    fn exception_return(&mut self) -> u32 {
+        self.status &= !STATUS_EXL;
+        self.epc
+    }
+}
+
+impl Default for Cp0 {
+    fn default() -> Self {
+        Self {
+            bad_vaddr: 0,
+            count: 0,
+            compare: 0,
+            status: STATUS_RESET,
+            cause: 0,
+            epc: 0,
+            tlb: None,
+        }
+    }
+}
+
+impl Runtime {
+    pub(super) fn execute_cop0(&mut self, funct: u32, rd: u32, rs: u32, rt: u32) -> RushResult<()> {
+        match (rs, funct) {
+            // MFC0 $Rt, $Cd
+            (0x00, _) => {
+                self.state.write_register(rt, self.state.cp0.read(rd) as i32);
+            }
+
+            // DMFC0 $Rt, $Cd (the registers rush implements are all 32 bits, so this is MFC0)
+            (0x01, _) => {
+                self.require_mips64()?;
+
+                self.state
+                    .write_register(rt, self.state.cp0.read(rd) as i32);
+            }
+
+            // MTC0 $Rt, $Cd
+            (0x04, _) => {
+                let value = self.state.read_register(rt)? as u32;
+                self.state.cp0.write(rd, value);
+            }
+
+            // DMTC0 $Rt, $Cd (only the lower half fits)
+            (0x05, _) => {
+                self.require_mips64()?;
+
+                let value = self.state.read_register(rt)? as u32;
+                self.state.cp0.write(rd, value);
+            }
+
+            // DI   $Rt / EI   $Rt
+            (0x0B, 0x00 | 0x20) => {
+                let status = self.state.cp0.status;
+                self.state.write_register(rt, status as i32);
+
+                match funct {
+                    0x00 => self.state.cp0.status &= !STATUS_IE,
+                    _ => self.state.cp0.status |= STATUS_IE,
+                }
+            }
+
+            // TLBR / TLBWI / TLBWR / TLBP
+            (0x10, 0x01 | 0x02 | 0x06 | 0x08) => {
+                let Some(tlb) = self.state.cp0.tlb.as_mut() else {
+                    return Err(self.reserved_instruction());
+                };
+
+                match funct {
+                    0x01 => tlb.read_indexed(),
+                    0x02 => tlb.write_indexed(),
+                    0x06 => tlb.write_random(),
+                    _ => tlb.probe(),
+                }
+            }
+
+            // ERET (which has no delay slot, and makes the next `sc` fail)
+            (0x10, 0x18) => {
+                let epc = self.state.cp0.exception_return();
+                self.state.enter(epc);
+                self.state.acknowledge_device_interrupts();
+                self.state.ll_bit = false;
+            }
+
+            // WAIT (the bits between CO and funct are implementation-dependent, and ignored)
+            // is executed again and again, a cycle at a time so that devices carry on, until an unmasked interrupt
+            // is requested. It's then taken after the `wait`, if it's enabled. With no handler, it's a `nop`.
+            (0x10..=0x1F, 0x20) => {
+                if self.state.has_exception_handler() && !self.state.cp0.interrupt_requested() {
+                    self.state.set_pc(self.state.inst_addr);
+                }
+            }
+
+            _ => return Err(self.reserved_instruction()),
+        }
+
+        Ok(())
+    }
+
+    /// The exception that `err` (raised by `inst`) becomes, and the address it was caused by,
+    /// if the program has a handler to deal with it. Otherwise, `err` ends the program as usual.
+    pub(super) fn exception_for(&self, err: &RushError, inst: u32) -> Option<(ExceptionCode, Option<u32>)> {
+        if !self.state.has_exception_handler() {
+            return None;
+        }
+
+        let RushError::Runtime(err) = err else {
+            return None;
+        };
+
+        match *err.error() {
+            Error::IntegerOverflow => Some((ExceptionCode::Overflow, None)),
+            Error::ReservedInstruction { .. } => Some((ExceptionCode::ReservedInstruction, None)),
+            Error::UnalignedAccess { addr, .. } => match inst >> 26 {
+                0x28..=0x2F | 0x38..=0x3F => Some((ExceptionCode::AddressErrorStore, Some(addr))),
+                _ => Some((ExceptionCode::AddressErrorLoad, Some(addr))),
+            },
+            Error::AddressError { addr, access } => match access {
+                SegmentationFaultAccessType::Write => Some((ExceptionCode::AddressErrorStore, Some(addr))),
+                _ => Some((ExceptionCode::AddressErrorLoad, Some(addr))),
+            },
+            Error::TlbMiss { addr, access, .. } => match access {
+                SegmentationFaultAccessType::Write => Some((ExceptionCode::TlbStore, Some(addr))),
+                _ => Some((ExceptionCode::TlbLoad, Some(addr))),
+            },
+            Error::TlbModified { addr } => Some((ExceptionCode::TlbModified, Some(addr))),
+            Error::SegmentationFault { addr, access } => match access {
+                SegmentationFaultAccessType::Execute => Some((ExceptionCode::InstructionBusError, Some(addr))),
+                _ => Some((ExceptionCode::DataBusError, Some(addr))),
+            },
+            _ => None,
+        }
+    }
+
+    /// Where the exception for `err` is handled: a TLB refill has a vector of its own,
+    /// unless it happens in the handler of another exception.
+    pub(super) fn exception_vector(&self, err: &RushError) -> u32 {
+        match err {
+            RushError::Runtime(err) if matches!(err.error(), Error::TlbMiss { refill: true, .. }) && !self.state.cp0.exception_level() => {
+                TLB_REFILL_VECTOR
+            }
+            _ => EXCEPTION_VECTOR,
+        }
+    }
+
+    /// Takes the pending interrupt before the instruction at `pc` is executed.
+    /// If that's a delay slot, EPC is its branch, so that both are executed on returning.
+    pub(super) fn take_interrupt(mut self) -> SteppedRuntime {
+        // the previous instruction has finished, including its delayed load
+        self.state.delayed_load = self.state.pending_load.take();
+        self.state.commit_delayed_load();
+
+        self.state.faulted_in_delay_slot = false;
+        if let Some(delay_slot) = self.state.delay_slot {
+            self.state.set_pc(delay_slot.branch_addr);
+            self.state.faulted_in_delay_slot = true;
+        }
+
+        self.state.take_device_interrupts();
+        self.raise_exception(ExceptionCode::Interrupt, None)
+    }
+
+    /// Takes an exception at `pc`, which is left on the faulting instruction (or its branch),
+    /// and continues in the handler at `EXCEPTION_VECTOR`, which is MIPS32 code.
+    /// Like a `jalr` target, EPC's low bit is the ISA mode to return to.
+    pub(super) fn raise_exception(self, code: ExceptionCode, bad_vaddr: Option<u32>) -> SteppedRuntime {
+        self.raise_exception_at(code, bad_vaddr, EXCEPTION_VECTOR)
+    }
+
+    /// Like `raise_exception`, but for an exception handled at `vector`.
+    pub(super) fn raise_exception_at(mut self, code: ExceptionCode, bad_vaddr: Option<u32>, vector: u32) -> SteppedRuntime {
+        let epc = self.state.pc() | self.state.micromips as u32;
+        let in_delay_slot = self.state.faulted_in_delay_slot;
+
+        self.state.cp0.enter_exception(code, epc, in_delay_slot, bad_vaddr);
+        self.state.faulted_in_delay_slot = false;
+        self.state.delay_slot = None;
+        self.state.branch_target = None;
+        self.state.enter(vector);
+
+        Ok(self)
+    }
+}
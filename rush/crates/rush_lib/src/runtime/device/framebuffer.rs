@@ -0,0 +1,240 @@
+use std::{fs, io};
+
+use rush_utils::{Endianness, PixelFormat, RushConfigFramebuffer};
+
+use crate::compile::elf;
+
+use super::Device;
+
+/// The vsync register, the first word after the pixels. Writing it says that a frame is finished,
+/// and reading it gives the number of times the display has been refreshed, to wait for the next refresh with.
+const VSYNC: u32 = 0x0;
+
+/// A bitmap display, like MARS's. Its pixels are laid out a row at a time from the top left,
+/// as `format` says, and are followed by its registers.
+/// Frames can be saved to images every `save_every` refreshes, on each vsync, or from the debugger.
+#[derive(Debug)]
+pub struct Framebuffer {
+    config: RushConfigFramebuffer,
+    endianness: Endianness,
+    pixels: Vec<u8>,
+    /// The offset of the registers, after the pixels (rounded up to a word).
+    registers: u32,
+    cycles_since_refresh: u64,
+    refreshes: u64,
+    images_saved: u64,
+}
+
+impl Framebuffer {
+    pub fn new(config: &RushConfigFramebuffer, endianness: Endianness) -> Self {
+        assert!(config.width > 0 && config.height > 0, "a framebuffer needs a width and height");
+        assert!(config.frame_cycles > 0, "a framebuffer's frame_cycles can't be 0");
+
+        let size = config.width * config.height * config.format.bytes_per_pixel();
+
+        Self {
+            config: config.clone(),
+            endianness,
+            pixels: vec![0; size as usize],
+            registers: (size + 3) & !0b11,
+            cycles_since_refresh: 0,
+            refreshes: 0,
+            images_saved: 0,
+        }
+    }
+
+    /// Saves the current frame to the `save` path, if there is one.
+    fn save_frame(&mut self) {
+        let Some(path) = &self.config.save else {
+            return;
+        };
+
+        let path = path.replace("{}", &format!("{:04}", self.images_saved));
+        self.images_saved += 1;
+
+        if let Err(err) = self.frame().expect("a framebuffer always has a frame").save(&path) {
+            eprintln!("couldn't save the framebuffer to {}: {}", path, err);
+        }
+    }
+}
+
+impl Device for Framebuffer {
+    fn size(&self) -> u32 {
+        self.registers + 4
+    }
+
+    fn read(&mut self, offset: u32, size: u32) -> u32 {
+        if offset >= self.registers {
+            return match (offset - self.registers) & !0b11 {
+                VSYNC => self.refreshes as u32,
+                _ => 0,
+            };
+        }
+
+        let byte = |i: u32| self.pixels.get((offset + i) as usize).copied().unwrap_or(0);
+
+        match size {
+            1 => byte(0) as u32,
+            2 => elf::u16_from_bytes(self.endianness, [byte(0), byte(1)]) as u32,
+            _ => elf::u32_from_bytes(self.endianness, [byte(0), byte(1), byte(2), byte(3)]),
+        }
+    }
+
+    fn write(&mut self, offset: u32, size: u32, value: u32) {
+        if offset >= self.registers {
+            if (offset - self.registers) & !0b11 == VSYNC && self.config.save_on_vsync {
+                self.save_frame();
+            }
+
+            return;
+        }
+
+        let bytes = match size {
+            1 => vec![value as u8],
+            2 => elf::u16_bytes(self.endianness, value as u16).to_vec(),
+            _ => elf::u32_bytes(self.endianness, value).to_vec(),
+        };
+
+        // (the bytes between the pixels and the registers, if any, are ignored)
+        for (i, byte) in bytes.into_iter().enumerate() {
+            if let Some(pixel) = self.pixels.get_mut(offset as usize + i) {
+                *pixel = byte;
+            }
+        }
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        self.cycles_since_refresh += cycles;
+
+        while self.cycles_since_refresh >= self.config.frame_cycles {
+            self.cycles_since_refresh -= self.config.frame_cycles;
+            self.refreshes += 1;
+
+            if self.config.save_every.is_some_and(|every| self.refreshes.is_multiple_of(every)) {
+                self.save_frame();
+            }
+        }
+    }
+
+    fn frame(&self) -> Option<Frame> {
+        let bytes_per_pixel = self.config.format.bytes_per_pixel() as usize;
+
+        let rgb = self.pixels.chunks(bytes_per_pixel)
+            .flat_map(|pixel| match self.config.format {
+                PixelFormat::Rgb888 => {
+                    let pixel = elf::u32_from_bytes(self.endianness, [pixel[0], pixel[1], pixel[2], pixel[3]]);
+                    [(pixel >> 16) as u8, (pixel >> 8) as u8, pixel as u8]
+                }
+                PixelFormat::Rgb565 => {
+                    let pixel = elf::u16_from_bytes(self.endianness, [pixel[0], pixel[1]]);
+                    let (red, green, blue) = ((pixel >> 11) & 0x1F, (pixel >> 5) & 0x3F, pixel & 0x1F);
+
+                    // (the top bits are repeated in the new low bits, so that full intensity is still 255)
+                    [(red << 3 | red >> 2) as u8, (green << 2 | green >> 4) as u8, (blue << 3 | blue >> 2) as u8]
+                }
+                PixelFormat::Gray8 => [pixel[0]; 3],
+            })
+            .collect();
+
+        Some(Frame { width: self.config.width, height: self.config.height, rgb })
+    }
+}
+
+/// What a display is showing, as 8-bit RGB, a row at a time from the top left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: u32,
+    pub height: u32,
+    pub rgb: Vec<u8>,
+}
+
+impl Frame {
+    /// Saves it as a PNG if `path` ends in `.png`, and as a (binary) PPM otherwise.
+    pub fn save(&self, path: &str) -> io::Result<()> {
+        let bytes = match path.to_lowercase().ends_with(".png") {
+            true => self.to_png(),
+            false => self.to_ppm(),
+        };
+
+        fs::write(path, bytes)
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend_from_slice(&self.rgb);
+
+        ppm
+    }
+
+    /// An uncompressed PNG, as images this size don't need compressing to be compared.
+    pub fn to_png(&self) -> Vec<u8> {
+        // each row starts with its filter type, 0 for none
+        let mut scanlines = Vec::with_capacity(self.rgb.len() + self.height as usize);
+        for row in self.rgb.chunks(self.width as usize * 3) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+
+        // a zlib stream of "stored" deflate blocks, which hold at most 0xFFFF bytes each
+        let mut zlib = vec![0x78, 0x01];
+        let blocks = scanlines.chunks(0xFFFF).count();
+        for (i, block) in scanlines.chunks(0xFFFF).enumerate() {
+            let len = block.len() as u16;
+
+            zlib.push((i + 1 == blocks) as u8);
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+        }
+        zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&self.width.to_be_bytes());
+        header.extend_from_slice(&self.height.to_be_bytes());
+        // 8 bits per channel, RGB, deflate, the usual filters, not interlaced
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib);
+        png_chunk(&mut png, b"IEND", &[]);
+
+        png
+    }
+}
+
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = match crc & 1 {
+                1 => (crc >> 1) ^ 0xEDB88320,
+                _ => crc >> 1,
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
@@ -0,0 +1,158 @@
+use std::io::{self, Write};
+
+use super::{terminal, Device};
+
+/// The control register, at `+0`, and the data register, at `+4`, of each half.
+const CONTROL: u32 = 0x0;
+const DATA: u32 = 0x4;
+
+const CONTROL_READY: u32 = 1 << 0;
+const CONTROL_IE: u32 = 1 << 1;
+
+fn control(ready: bool, interrupt_enable: bool) -> u32 {
+    (ready as u32 * CONTROL_READY) | (interrupt_enable as u32 * CONTROL_IE)
+}
+
+/// How often the keyboard is checked for a key, in cycles.
+const POLL_CYCLES: u64 = 100;
+
+/// The receiver of MARS's "Keyboard and Display MMIO Simulator", at `0xFFFF0000`.
+/// It's ready once a key has been typed at the terminal, until its data register is read.
+#[derive(Debug, Default)]
+pub struct MarsKeyboard {
+    ready: bool,
+    data: u8,
+    interrupt_enable: bool,
+    /// Set when a key arrives with interrupts enabled, until the handler returns.
+    interrupt_pending: bool,
+    cycles_since_poll: u64,
+}
+
+impl Device for MarsKeyboard {
+    fn size(&self) -> u32 {
+        8
+    }
+
+    fn read(&mut self, offset: u32, _size: u32) -> u32 {
+        match offset & !0b11 {
+            CONTROL => control(self.ready, self.interrupt_enable),
+            DATA => {
+                self.ready = false;
+                self.data as u32
+            }
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u32, _size: u32, value: u32) {
+        // (only the interrupt enable bit can be written)
+        if offset & !0b11 == CONTROL {
+            self.interrupt_enable = value & CONTROL_IE != 0;
+        }
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        self.cycles_since_poll += cycles;
+        if self.ready || self.cycles_since_poll < POLL_CYCLES {
+            return;
+        }
+        self.cycles_since_poll = 0;
+
+        if let Some(key) = terminal::read_stdin() {
+            self.data = key;
+            self.ready = true;
+            self.interrupt_pending |= self.interrupt_enable;
+        }
+    }
+
+    fn interrupt(&self) -> bool {
+        self.interrupt_pending
+    }
+
+    fn acknowledge(&mut self) {
+        self.interrupt_pending = false;
+    }
+
+    fn uses_terminal(&self) -> bool {
+        true
+    }
+}
+
+/// The transmitter of MARS's "Keyboard and Display MMIO Simulator", at `0xFFFF0008`.
+/// Characters written to its data register are shown on stdout straight away,
+/// but it isn't ready for another until `transmit_delay` cycles have gone by.
+#[derive(Debug)]
+pub struct MarsDisplay {
+    transmit_delay: u64,
+    /// Cycles until it's ready again, 0 when it's ready.
+    busy_cycles: u64,
+    interrupt_enable: bool,
+    /// Set when it becomes ready with interrupts enabled, until the handler returns.
+    interrupt_pending: bool,
+}
+
+impl MarsDisplay {
+    pub fn new(transmit_delay: u64) -> Self {
+        Self {
+            transmit_delay,
+            busy_cycles: 0,
+            interrupt_enable: false,
+            interrupt_pending: false,
+        }
+    }
+
+    fn become_ready(&mut self) {
+        self.busy_cycles = 0;
+        self.interrupt_pending |= self.interrupt_enable;
+    }
+}
+
+impl Device for MarsDisplay {
+    fn size(&self) -> u32 {
+        8
+    }
+
+    fn read(&mut self, offset: u32, _size: u32) -> u32 {
+        match offset & !0b11 {
+            CONTROL => control(self.busy_cycles == 0, self.interrupt_enable),
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u32, _size: u32, value: u32) {
+        match offset & !0b11 {
+            CONTROL => self.interrupt_enable = value & CONTROL_IE != 0,
+            // (a character written while it isn't ready is still shown, rather than lost)
+            DATA => {
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(&[value as u8]);
+                let _ = stdout.flush();
+
+                match self.transmit_delay {
+                    0 => self.become_ready(),
+                    delay => self.busy_cycles = delay,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        if self.busy_cycles == 0 {
+            return;
+        }
+
+        match self.busy_cycles <= cycles {
+            true => self.become_ready(),
+            false => self.busy_cycles -= cycles,
+        }
+    }
+
+    fn interrupt(&self) -> bool {
+        self.interrupt_pending
+    }
+
+    fn acknowledge(&mut self) {
+        self.interrupt_pending = false;
+    }
+}
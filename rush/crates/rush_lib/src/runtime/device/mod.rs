@@ -0,0 +1,124 @@
+mod framebuffer;
+mod mars;
+mod terminal;
+mod timer;
+mod uart;
+
+pub use self::framebuffer::{Frame, Framebuffer};
+pub use self::mars::{MarsDisplay, MarsKeyboard};
+pub use self::terminal::KeyboardMode;
+pub use self::timer::Timer;
+pub use self::uart::Uart;
+
+use std::{cell::RefCell, rc::Rc};
+
+use rush_utils::{DeviceKind, Endianness, RushConfigDevice};
+
+use super::cp0::INTERRUPT_LINES;
+use crate::error::loader::Error as LoaderError;
+
+/// A memory-mapped peripheral. Its registers take up `size` bytes from wherever it's mapped,
+/// and are read and written a byte, half or word at a time, as the program's loads and stores are.
+pub trait Device {
+    /// The number of bytes its registers take up.
+    fn size(&self) -> u32;
+
+    /// Reads `size` bytes (1, 2 or 4) at `offset`. Reads may have side effects,
+    /// such as taking a byte from a FIFO, so the debugger never makes them.
+    fn read(&mut self, offset: u32, size: u32) -> u32;
+
+    /// Writes the low `size` bytes (1, 2 or 4) of `value` at `offset`.
+    fn write(&mut self, offset: u32, size: u32, value: u32);
+
+    /// Advances the device by `cycles` cycles of the simulated clock.
+    fn tick(&mut self, _cycles: u64) {}
+
+    /// Whether it's asserting its interrupt line.
+    fn interrupt(&self) -> bool {
+        false
+    }
+
+    /// Called when the handler for an interrupt it raised returns (with `eret`), for devices
+    /// that interrupt once per event rather than for as long as something is the case.
+    fn acknowledge(&mut self) {}
+
+    /// Whether it reads the keyboard, and so needs the terminal in `KeyboardMode` while the program runs.
+    fn uses_terminal(&self) -> bool {
+        false
+    }
+
+    /// What it's showing, for displays.
+    fn frame(&self) -> Option<Frame> {
+        None
+    }
+}
+
+/// A device, along with where it's mapped and the interrupt it raises.
+#[derive(Clone)]
+pub struct MappedDevice {
+    pub base: u32,
+    pub size: u32,
+    /// The Cause.IP bit it raises (see `Cp0::set_interrupt_bit`).
+    pub ip: Option<u32>,
+    /// Shared by every clone of a `State`, as what a device has done to the outside world can't be undone.
+    pub device: Rc<RefCell<dyn Device>>,
+    /// Set while the handler for its interrupt runs, so that the handler's `eret` acknowledges it.
+    pub(super) interrupt_taken: bool,
+}
+
+impl MappedDevice {
+    /// A device at `base`, raising hardware interrupt line `irq` (0 to 5) if it raises one.
+    pub fn new(base: u32, irq: Option<u32>, device: impl Device + 'static) -> Self {
+        if let Some(irq) = irq {
+            assert!(irq < INTERRUPT_LINES, "there are only {} interrupt lines", INTERRUPT_LINES);
+        }
+
+        Self::with_ip(base, irq.map(|irq| irq + 2), device)
+    }
+
+    /// A device at `base`, raising Cause.IP bit `ip` (0 to 7) if it raises one.
+    pub fn with_ip(base: u32, ip: Option<u32>, device: impl Device + 'static) -> Self {
+        if let Some(ip) = ip {
+            assert!(ip < 8, "there are only 8 interrupt bits in Cause.IP");
+        }
+
+        Self {
+            base,
+            size: device.size(),
+            ip,
+            device: Rc::new(RefCell::new(device)),
+            interrupt_taken: false,
+        }
+    }
+
+    /// The devices that `config` declares: usually one, but MARS's keyboard and display are two,
+    /// as they raise different interrupts. Fails if they can't be set up, e.g. because a UART's files can't be opened.
+    /// `endianness` is the program's, for devices that hold memory.
+    pub fn from_config(config: &RushConfigDevice, endianness: Endianness) -> Result<Vec<Self>, LoaderError> {
+        Ok(match &config.kind {
+            DeviceKind::Timer => vec![Self::new(config.base, config.irq, Timer::default())],
+            DeviceKind::Uart { backend, reg_shift } => {
+                let uart = Uart::new(backend, *reg_shift).map_err(|err| LoaderError::CannotSetUpDevice {
+                    base: config.base,
+                    reason: err.to_string(),
+                })?;
+                if let Some(path) = uart.attached_to() {
+                    eprintln!("UART at {:#010x} is attached to {}", config.base, path);
+                }
+
+                vec![Self::new(config.base, config.irq, uart)]
+            }
+            // (like MARS, these raise the keyboard and display interrupts on IP0 and IP1, rather than `irq`)
+            DeviceKind::Mars { transmit_delay } => vec![
+                Self::with_ip(config.base, Some(0), MarsKeyboard::default()),
+                Self::with_ip(config.base + 8, Some(1), MarsDisplay::new(*transmit_delay)),
+            ],
+            DeviceKind::Framebuffer(framebuffer) => vec![Self::new(config.base, None, Framebuffer::new(framebuffer, endianness))],
+        })
+    }
+
+    /// Whether `addr` (a physical address) is one of its registers.
+    pub fn contains(&self, addr: u32) -> bool {
+        addr.wrapping_sub(self.base) < self.size
+    }
+}
@@ -0,0 +1,94 @@
+//! The terminal rush runs in, for the devices that read its keyboard.
+
+#[cfg(not(unix))]
+use std::{
+    io::{self, Read},
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex, OnceLock,
+    },
+    thread,
+};
+
+/// Reads a byte from stdin, if one is waiting, without blocking.
+#[cfg(unix)]
+pub(super) fn read_stdin() -> Option<u8> {
+    let mut stdin = libc::pollfd {
+        fd: libc::STDIN_FILENO,
+        events: libc::POLLIN,
+        revents: 0,
+    };
+    if unsafe { libc::poll(&mut stdin, 1, 0) } <= 0 || stdin.revents & libc::POLLIN == 0 {
+        return None;
+    }
+
+    let mut byte = 0u8;
+    match unsafe { libc::read(libc::STDIN_FILENO, &mut byte as *mut u8 as *mut libc::c_void, 1) } {
+        1 => Some(byte),
+        _ => None,
+    }
+}
+
+/// Reads a byte from stdin, if one is waiting, without blocking. Without `poll`,
+/// stdin is read on a thread (from the first time this is called), as reading it blocks.
+#[cfg(not(unix))]
+pub(super) fn read_stdin() -> Option<u8> {
+    static STDIN: OnceLock<Mutex<Receiver<u8>>> = OnceLock::new();
+
+    let receiver = STDIN.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for byte in io::stdin().lock().bytes() {
+                match byte {
+                    Ok(byte) if sender.send(byte).is_ok() => {}
+                    _ => break,
+                }
+            }
+        });
+
+        Mutex::new(receiver)
+    });
+
+    receiver.lock().ok()?.try_recv().ok()
+}
+
+/// While it's alive, keys typed at the terminal reach the program as soon as they're pressed,
+/// without being echoed, as on a board's console or in MARS (Ctrl-C still interrupts).
+/// It does nothing if stdin isn't a terminal.
+pub struct KeyboardMode {
+    #[cfg(unix)]
+    original: Option<libc::termios>,
+}
+
+impl KeyboardMode {
+    #[cfg(unix)]
+    pub fn enter() -> Self {
+        let mut termios = unsafe { std::mem::zeroed::<libc::termios>() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut termios) } != 0 {
+            return Self { original: None };
+        }
+
+        let mut keyboard = termios;
+        keyboard.c_lflag &= !(libc::ICANON | libc::ECHO);
+        keyboard.c_cc[libc::VMIN] = 1;
+        keyboard.c_cc[libc::VTIME] = 0;
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &keyboard) };
+
+        Self { original: Some(termios) }
+    }
+
+    #[cfg(not(unix))]
+    pub fn enter() -> Self {
+        Self {}
+    }
+}
+
+impl Drop for KeyboardMode {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(original) = &self.original {
+            unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, original) };
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use super::Device;
+
+/// The current count, which goes down once per cycle while the timer is enabled.
+const COUNT: u32 = 0x0;
+/// What the count starts again from after reaching zero. With 0, the timer stops instead.
+const RELOAD: u32 = 0x4;
+/// Enable (bit 0) and interrupt enable (bit 1).
+const CONTROL: u32 = 0x8;
+/// Set when the count reaches zero, until 1 is written to it.
+const STATUS: u32 = 0xC;
+
+const CONTROL_ENABLE: u32 = 1 << 0;
+const CONTROL_IE: u32 = 1 << 1;
+const STATUS_EXPIRED: u32 = 1 << 0;
+
+/// A countdown timer with four word registers, for firmware that expects a timer peripheral
+/// rather than Count/Compare. Byte and half accesses read and write a whole register.
+#[derive(Debug, Default)]
+pub struct Timer {
+    count: u32,
+    reload: u32,
+    control: u32,
+    status: u32,
+}
+
+impl Device for Timer {
+    fn size(&self) -> u32 {
+        0x10
+    }
+
+    fn read(&mut self, offset: u32, _size: u32) -> u32 {
+        match offset & !0b11 {
+            COUNT => self.count,
+            RELOAD => self.reload,
+            CONTROL => self.control,
+            STATUS => self.status,
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u32, _size: u32, value: u32) {
+        match offset & !0b11 {
+            COUNT => self.count = value,
+            RELOAD => self.reload = value,
+            CONTROL => self.control = value & (CONTROL_ENABLE | CONTROL_IE),
+            STATUS => self.status &= !value,
+            _ => {}
+        }
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        if self.control & CONTROL_ENABLE == 0 || cycles == 0 {
+            return;
+        }
+
+        if cycles < self.count as u64 {
+            self.count -= cycles as u32;
+            return;
+        }
+
+        self.status |= STATUS_EXPIRED;
+        match self.reload {
+            0 => {
+                self.count = 0;
+                self.control &= !CONTROL_ENABLE;
+            }
+            // (carrying on from the reload value with whatever cycles were left over)
+            reload => self.count = reload - ((cycles - self.count as u64) % reload as u64) as u32,
+        }
+    }
+
+    fn interrupt(&self) -> bool {
+        self.status & STATUS_EXPIRED != 0 && self.control & CONTROL_IE != 0
+    }
+}
@@ -0,0 +1,424 @@
+use std::{
+    collections::VecDeque,
+    fs::{self, File},
+    io::{self, Write},
+};
+
+#[cfg(unix)]
+use std::{
+    ffi::CStr,
+    io::Read,
+    os::unix::{
+        fs::FileTypeExt,
+        io::FromRawFd,
+        net::{UnixListener, UnixStream},
+    },
+};
+
+use rush_utils::UartBackend;
+
+use super::{terminal, Device};
+
+/// The registers, numbered as on a 16550 (their offsets are these shifted by `reg_shift`).
+/// With LCR.DLAB set, the first two are the divisor latch instead.
+const RBR_THR: u32 = 0;
+const IER: u32 = 1;
+const IIR_FCR: u32 = 2;
+const LCR: u32 = 3;
+const MCR: u32 = 4;
+const LSR: u32 = 5;
+const MSR: u32 = 6;
+const SCR: u32 = 7;
+
+const IER_RX: u32 = 1 << 0;
+const IER_THRE: u32 = 1 << 1;
+const IIR_NONE: u32 = 0x01;
+const IIR_THRE: u32 = 0x02;
+const IIR_RX: u32 = 0x04;
+const IIR_FIFO_ENABLED: u32 = 0xC0;
+const FCR_ENABLE: u32 = 1 << 0;
+const FCR_CLEAR_RX: u32 = 1 << 1;
+const LCR_DLAB: u32 = 1 << 7;
+const MCR_LOOPBACK: u32 = 1 << 4;
+const LSR_DR: u32 = 1 << 0;
+const LSR_OE: u32 = 1 << 1;
+const LSR_THRE: u32 = 1 << 5;
+const LSR_TEMT: u32 = 1 << 6;
+/// CTS, DSR and DCD, as if a terminal were always attached.
+const MSR_CONNECTED: u32 = 0xB0;
+
+const FIFO_SIZE: usize = 16;
+/// How often the backend is checked for input while RX interrupts are enabled, in cycles.
+const POLL_CYCLES: u64 = 1000;
+
+/// A 16550-compatible UART. Sending is instantaneous, so THR is always empty,
+/// and received bytes are only taken from the backend while there's room for them.
+pub struct Uart {
+    backend: Backend,
+    reg_shift: u32,
+    rx: VecDeque<u8>,
+    ier: u32,
+    lcr: u32,
+    mcr: u32,
+    scr: u32,
+    divisor: u32,
+    fifo_enabled: bool,
+    overrun: bool,
+    /// Raised whenever THR empties, until it's written or the interrupt is read from IIR.
+    thre_pending: bool,
+    cycles_since_poll: u64,
+}
+
+impl Uart {
+    pub fn new(backend: &UartBackend, reg_shift: u32) -> io::Result<Self> {
+        Ok(Self {
+            backend: Backend::new(backend)?,
+            reg_shift,
+            rx: VecDeque::new(),
+            ier: 0,
+            lcr: 0,
+            mcr: 0,
+            scr: 0,
+            divisor: 0,
+            fifo_enabled: false,
+            overrun: false,
+            thre_pending: false,
+            cycles_since_poll: 0,
+        })
+    }
+
+    /// Where the backend is, if the user needs telling (as a pseudo-terminal's path is picked by the OS).
+    pub fn attached_to(&self) -> Option<&str> {
+        match &self.backend {
+            #[cfg(unix)]
+            Backend::Pty { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    fn rx_capacity(&self) -> usize {
+        match self.fifo_enabled {
+            true => FIFO_SIZE,
+            false => 1,
+        }
+    }
+
+    fn receive(&mut self, byte: u8) {
+        match self.rx.len() < self.rx_capacity() {
+            true => self.rx.push_back(byte),
+            false => self.overrun = true,
+        }
+    }
+
+    /// Takes as many bytes from the backend as there's room for.
+    fn poll(&mut self) {
+        while self.rx.len() < self.rx_capacity() {
+            match self.backend.poll() {
+                Some(byte) => self.rx.push_back(byte),
+                None => break,
+            }
+        }
+    }
+
+    fn rx_interrupt(&self) -> bool {
+        self.ier & IER_RX != 0 && !self.rx.is_empty()
+    }
+
+    fn thre_interrupt(&self) -> bool {
+        self.ier & IER_THRE != 0 && self.thre_pending
+    }
+}
+
+impl Device for Uart {
+    fn size(&self) -> u32 {
+        8 << self.reg_shift
+    }
+
+    fn read(&mut self, offset: u32, _size: u32) -> u32 {
+        if offset & ((1 << self.reg_shift) - 1) != 0 {
+            return 0;
+        }
+
+        let dlab = self.lcr & LCR_DLAB != 0;
+        match offset >> self.reg_shift {
+            RBR_THR if dlab => self.divisor & 0xFF,
+            RBR_THR => {
+                if self.rx.is_empty() {
+                    self.poll();
+                }
+
+                self.rx.pop_front().unwrap_or_default() as u32
+            }
+            IER if dlab => self.divisor >> 8,
+            IER => self.ier,
+            IIR_FCR => {
+                let fifo = self.fifo_enabled as u32 * IIR_FIFO_ENABLED;
+
+                if self.rx_interrupt() {
+                    IIR_RX | fifo
+                } else if self.thre_interrupt() {
+                    self.thre_pending = false;
+                    IIR_THRE | fifo
+                } else {
+                    IIR_NONE | fifo
+                }
+            }
+            LCR => self.lcr,
+            MCR => self.mcr,
+            LSR => {
+                if self.rx.is_empty() {
+                    self.poll();
+                }
+
+                let mut lsr = LSR_THRE | LSR_TEMT;
+                if !self.rx.is_empty() {
+                    lsr |= LSR_DR;
+                }
+                if std::mem::take(&mut self.overrun) {
+                    lsr |= LSR_OE;
+                }
+
+                lsr
+            }
+            MSR => MSR_CONNECTED,
+            SCR => self.scr,
+            _ => 0,
+        }
+    }
+
+    fn write(&mut self, offset: u32, _size: u32, value: u32) {
+        if offset & ((1 << self.reg_shift) - 1) != 0 {
+            return;
+        }
+
+        let value = value & 0xFF;
+        let dlab = self.lcr & LCR_DLAB != 0;
+        match offset >> self.reg_shift {
+            RBR_THR if dlab => self.divisor = (self.divisor & 0xFF00) | value,
+            RBR_THR => {
+                match self.mcr & MCR_LOOPBACK != 0 {
+                    true => self.receive(value as u8),
+                    false => self.backend.send(value as u8),
+                }
+
+                // (and it's empty again straight away)
+                self.thre_pending = true;
+            }
+            IER if dlab => self.divisor = (self.divisor & 0xFF) | value << 8,
+            IER => {
+                if value & IER_THRE != 0 && self.ier & IER_THRE == 0 {
+                    self.thre_pending = true;
+                }
+
+                self.ier = value & 0x0F;
+            }
+            IIR_FCR => {
+                self.fifo_enabled = value & FCR_ENABLE != 0;
+                if value & FCR_CLEAR_RX != 0 {
+                    self.rx.clear();
+                }
+                self.rx.truncate(self.rx_capacity());
+            }
+            LCR => self.lcr = value,
+            MCR => self.mcr = value & 0x1F,
+            SCR => self.scr = value,
+            _ => {}
+        }
+    }
+
+    fn tick(&mut self, cycles: u64) {
+        // without RX interrupts, the backend is only read when the program reads LSR or RBR
+        if self.ier & IER_RX == 0 {
+            return;
+        }
+
+        self.cycles_since_poll += cycles;
+        if self.cycles_since_poll >= POLL_CYCLES {
+            self.cycles_since_poll = 0;
+            self.poll();
+        }
+    }
+
+    fn interrupt(&self) -> bool {
+        self.rx_interrupt() || self.thre_interrupt()
+    }
+
+    fn uses_terminal(&self) -> bool {
+        matches!(self.backend, Backend::Stdio)
+    }
+}
+
+/// Where a UART's bytes go to and come from. None of them block: reading returns `None`
+/// when there's nothing to read, and bytes sent while nothing is attached are lost.
+enum Backend {
+    /// The terminal rush runs in.
+    Stdio,
+    File {
+        input: VecDeque<u8>,
+        output: File,
+    },
+    #[cfg(unix)]
+    Socket {
+        path: String,
+        listener: UnixListener,
+        stream: Option<UnixStream>,
+    },
+    #[cfg(unix)]
+    Pty {
+        path: String,
+        master: File,
+    },
+}
+
+impl Backend {
+    fn new(backend: &UartBackend) -> io::Result<Self> {
+        match backend {
+            UartBackend::Stdio => Ok(Backend::Stdio),
+            UartBackend::File { input, output } => Ok(Backend::File {
+                input: match input {
+                    Some(input) => fs::read(input)?.into(),
+                    None => VecDeque::new(),
+                },
+                output: File::create(output)?,
+            }),
+            #[cfg(unix)]
+            UartBackend::Socket(path) => {
+                // a socket left behind by an earlier run would stop it being bound again
+                if fs::metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket()) {
+                    fs::remove_file(path)?;
+                }
+
+                let listener = UnixListener::bind(path)?;
+                listener.set_nonblocking(true)?;
+
+                Ok(Backend::Socket {
+                    path: path.clone(),
+                    listener,
+                    stream: None,
+                })
+            }
+            #[cfg(unix)]
+            UartBackend::Pty => open_pty().map(|(master, path)| Backend::Pty { path, master }),
+            #[cfg(not(unix))]
+            UartBackend::Socket(_) | UartBackend::Pty => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "sockets and pseudo-terminals are only supported on unix",
+            )),
+        }
+    }
+
+    fn poll(&mut self) -> Option<u8> {
+        match self {
+            Backend::Stdio => terminal::read_stdin(),
+            Backend::File { input, .. } => input.pop_front(),
+            #[cfg(unix)]
+            Backend::Socket { listener, stream, .. } => {
+                let connection = Self::connection(listener, stream)?;
+
+                let mut byte = [0];
+                match connection.read(&mut byte) {
+                    Ok(1) => Some(byte[0]),
+                    Err(err) if err.kind() == io::ErrorKind::WouldBlock => None,
+                    // the other end has gone, so wait for another
+                    _ => {
+                        *stream = None;
+                        None
+                    }
+                }
+            }
+            #[cfg(unix)]
+            Backend::Pty { master, .. } => {
+                // (reading fails until a terminal opens the other end)
+                let mut byte = [0];
+                match master.read(&mut byte) {
+                    Ok(1) => Some(byte[0]),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    fn send(&mut self, byte: u8) {
+        match self {
+            Backend::Stdio => {
+                let mut stdout = io::stdout();
+                let _ = stdout.write_all(&[byte]);
+                let _ = stdout.flush();
+            }
+            Backend::File { output, .. } => {
+                let _ = output.write_all(&[byte]);
+            }
+            #[cfg(unix)]
+            Backend::Socket { listener, stream, .. } => {
+                let Some(connection) = Self::connection(listener, stream) else {
+                    return;
+                };
+
+                match connection.write(&[byte]) {
+                    Err(err) if err.kind() != io::ErrorKind::WouldBlock => *stream = None,
+                    _ => {}
+                }
+            }
+            #[cfg(unix)]
+            Backend::Pty { master, .. } => {
+                let _ = master.write(&[byte]);
+            }
+        }
+    }
+
+    /// The socket's connection, accepting one if there isn't one yet.
+    #[cfg(unix)]
+    fn connection<'a>(listener: &UnixListener, stream: &'a mut Option<UnixStream>) -> Option<&'a mut UnixStream> {
+        if stream.is_none() {
+            let (connection, _) = listener.accept().ok()?;
+            connection.set_nonblocking(true).ok()?;
+            *stream = Some(connection);
+        }
+
+        stream.as_mut()
+    }
+}
+
+impl Drop for Backend {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Backend::Socket { path, .. } = self {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Opens a new pseudo-terminal in raw mode, returning its (non-blocking) master and the path of its slave.
+#[cfg(unix)]
+fn open_pty() -> io::Result<(File, String)> {
+    let fd = unsafe { libc::posix_openpt(libc::O_RDWR | libc::O_NOCTTY) };
+    if fd < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let master = unsafe { File::from_raw_fd(fd) };
+
+    unsafe {
+        if libc::grantpt(fd) != 0 || libc::unlockpt(fd) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags < 0 || libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+
+        // so that bytes get to the program as they're typed, without being echoed or translated
+        let mut termios = std::mem::zeroed();
+        if libc::tcgetattr(fd, &mut termios) == 0 {
+            libc::cfmakeraw(&mut termios);
+            libc::tcsetattr(fd, libc::TCSANOW, &termios);
+        }
+
+        let name = libc::ptsname(fd);
+        if name.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok((master, CStr::from_ptr(name).to_string_lossy().into_owned()))
+    }
+}
@@ -0,0 +1,622 @@
+use rush_utils::IsaRevision;
+
+use super::{
+    r6::sign_extend, ExtendSign, Runtime, SteppedRuntime, Truncate, COP0, REGIMM, SPECIAL, SPECIAL2,
+    SPECIAL3,
+};
+use crate::{Register, RushError, RushResult};
+
+/// The registers that the 3-bit fields of 16-bit instructions name.
+const GPR3: [u32; 8] = [16, 17, 2, 3, 4, 5, 6, 7];
+
+/// The sources of `sb16`, `sh16` and `sw16`, which can store `$zero` instead of `$s0`.
+const GPR3_STORE: [u32; 8] = [0, 17, 2, 3, 4, 5, 6, 7];
+
+/// The pairs of registers that `movep` writes, and those it reads from.
+const MOVEP_DESTS: [(u32, u32); 8] = [(5, 6), (5, 7), (6, 7), (4, 21), (4, 22), (4, 5), (4, 6), (4, 7)];
+const MOVEP_SOURCES: [u32; 8] = [0, 17, 2, 3, 16, 18, 19, 20];
+
+/// The immediates that `addiur2` and `andi16` can encode.
+const ADDIUR2_IMMS: [i32; 8] = [1, 4, 8, 12, 16, 20, 24, -1];
+const ANDI16_IMMS: [i32; 16] = [128, 1, 2, 3, 4, 7, 8, 15, 16, 31, 32, 63, 64, 255, 32768, 65535];
+
+const SP: u32 = Register::Sp.to_number() as u32;
+const GP: u32 = Register::Gp.to_number() as u32;
+const RA: u32 = Register::Ra.to_number() as u32;
+
+/// Whether `first_half` is the whole of a 16-bit instruction, which the low 3 bits of the
+/// major opcode say, rather than the first halfword of a 32-bit one.
+pub(super) fn is_16_bit(first_half: u16) -> bool {
+    matches!((first_half >> 10) & 0x7, 1..=3)
+}
+
+impl Runtime {
+    /// Executes `inst`, a microMIPS instruction of `size` bytes (see `State::read_inst`).
+    /// Most have a MIPS32 equivalent that's executed instead, which leaves the branches and jumps
+    /// (which count their offsets in halfwords, and stay in microMIPS mode) and a few others.
+    pub(super) fn execute_micromips(mut self, inst: u32, size: u32) -> Result<SteppedRuntime, (Runtime, RushError)> {
+        // microMIPS32 Release 6 re-encodes much of it again
+        if self.state.isa == IsaRevision::R6 {
+            let err = self.reserved_instruction();
+            return Err((self, err));
+        }
+
+        if let Some(equivalent) = translate(inst, size) {
+            return self.execute_in_current_state(equivalent);
+        }
+
+        let result = match size {
+            2 => self.execute_micromips_16(inst),
+            _ => self.execute_micromips_32(inst),
+        };
+
+        match result {
+            Ok(()) => Ok(Ok(self)),
+            Err(err) => Err((self, err)),
+        }
+    }
+
+    fn execute_micromips_16(&mut self, inst: u32) -> RushResult<()> {
+        let gpr3 = |shift: u32| GPR3[((inst >> shift) & 0x7) as usize];
+        let rs = inst & 0x1F;
+
+        match inst >> 10 {
+            // POOL16C
+            0x11 => match ((inst >> 6) & 0xF, (inst >> 5) & 0x1F) {
+                // LWM16 Reglist, Im($sp) / SWM16 Reglist, Im($sp)
+                (0x4 | 0x5, _) => {
+                    let count = ((inst >> 4) & 0x3) + 1;
+                    let regs = (16..16 + count).chain([RA]);
+
+                    self.load_store_multiple(regs, SP, ((inst & 0xF) << 2) as i32, inst & 0x40 != 0)?;
+                }
+
+                // JR16 $Rs
+                (_, 0x0C) => self.state.jump(self.state.read_register(rs)? as u32),
+
+                // JRC  $Rs
+                (_, 0x0D) => self.state.enter(self.state.read_register(rs)? as u32),
+
+                // JALR16 $Rs / JALRS16 $Rs (with a 16-bit delay slot)
+                (_, 0x0E | 0x0F) => {
+                    let target = self.state.read_register(rs)? as u32;
+
+                    self.link_micromips(RA, if inst & 0x20 == 0 { 4 } else { 2 });
+                    self.state.jump(target);
+                }
+
+                // JRADDIUSP Im
+                (_, 0x18) => {
+                    let target = self.state.read_register(RA)? as u32;
+                    let sp = self.state.read_register(SP)?.wrapping_add(((inst & 0x1F) << 2) as i32);
+
+                    self.state.write_register(SP, sp);
+                    self.state.enter(target);
+                }
+
+                _ => return Err(self.reserved_instruction()),
+            },
+
+            // MOVEP $Rd, $Re, $Rs, $Rt
+            0x21 if inst & 1 == 0 => {
+                let (rd, re) = MOVEP_DESTS[((inst >> 7) & 0x7) as usize];
+                let rs_val = self.state.read_register64_uninit(MOVEP_SOURCES[((inst >> 1) & 0x7) as usize]);
+                let rt_val = self.state.read_register64_uninit(MOVEP_SOURCES[((inst >> 4) & 0x7) as usize]);
+
+                self.state.write_register64_uninit(rd, rs_val);
+                self.state.write_register64_uninit(re, rt_val);
+            }
+
+            // BEQZ16 $Rs, Im / BNEZ16 $Rs, Im
+            0x23 | 0x2B => {
+                let is_zero = self.state.read_register64(gpr3(7))? == 0;
+
+                self.branch_micromips(is_zero == (inst >> 10 == 0x23), sign_extend(inst, 7));
+            }
+
+            // B16  Im
+            0x33 => self.branch_micromips(true, sign_extend(inst, 10)),
+
+            _ => return Err(self.reserved_instruction()),
+        }
+
+        Ok(())
+    }
+
+    fn execute_micromips_32(&mut self, inst: u32) -> RushResult<()> {
+        // unlike MIPS32, rt comes before rs
+        let rt = (inst >> 21) & 0x1F;
+        let rs = (inst >> 16) & 0x1F;
+        let imm = inst as i16 as i32;
+
+        match inst >> 26 {
+            // JALR $Rt, $Rs / JALRS $Rt, $Rs (with a 16-bit delay slot), and their `.hb`s
+            0x00 if is_jalr(inst) => {
+                let target = self.state.read_register(rs)? as u32;
+
+                self.link_micromips(rt, if inst & (0x100 << 6) == 0 { 4 } else { 2 });
+                self.state.jump(target);
+            }
+
+            // POOL32B
+            0x08 => match (inst >> 12) & 0xF {
+                // LWP  $Rd, Im($Rs) / SWP  $Rd, Im($Rs)
+                0x1 | 0x9 => self.load_store_multiple(rt..rt + 2, rs, sign_extend(inst, 12), inst & 0x8000 != 0)?,
+
+                // LWM32 Reglist, Im($Rs) / SWM32 Reglist, Im($Rs)
+                0x5 | 0xD => {
+                    // `$s0` onwards (the ninth being `$fp`), then `$ra`
+                    let regs = (0..rt & 0xF)
+                        .map(|i| if i < 8 { 16 + i } else { Register::Fp.to_number() as u32 })
+                        .chain(Some(RA).filter(|_| rt & 0x10 != 0));
+
+                    self.load_store_multiple(regs, rs, sign_extend(inst, 12), inst & 0x8000 != 0)?;
+                }
+
+                _ => return Err(self.reserved_instruction()),
+            },
+
+            // POOL32I
+            0x10 => {
+                // (where `bc1f` and `bc1t` have their condition code)
+                let rs_val = match rt {
+                    0x1C | 0x1D => 0,
+                    _ => self.state.read_register64(rs)?,
+                };
+
+                match rt {
+                    // BLTZ $Rs, Im / BGEZ $Rs, Im / BLEZ $Rs, Im / BGTZ $Rs, Im
+                    0x00 => self.branch_micromips(rs_val < 0, imm),
+                    0x02 => self.branch_micromips(rs_val >= 0, imm),
+                    0x04 => self.branch_micromips(rs_val <= 0, imm),
+                    0x06 => self.branch_micromips(rs_val > 0, imm),
+
+                    // BLTZAL $Rs, Im / BGEZAL $Rs, Im, and the BLTZALS and BGEZALS
+                    // with a 16-bit delay slot (all of which link whether or not they're taken)
+                    0x01 | 0x03 | 0x11 | 0x13 => {
+                        self.link_micromips(RA, if rt & 0x10 == 0 { 4 } else { 2 });
+                        self.branch_micromips((rs_val < 0) == (rt & 0x2 == 0), imm);
+                    }
+
+                    // BNEZC $Rs, Im / BEQZC $Rs, Im (which have no delay slot)
+                    0x05 => self.compact_branch_micromips(rs_val != 0, imm),
+                    0x07 => self.compact_branch_micromips(rs_val == 0, imm),
+
+                    // BC1F Cc, Im / BC1T Cc, Im
+                    0x1C | 0x1D => {
+                        let condition = self.state.fpu_condition((inst >> 18) & 0x7);
+
+                        self.branch_micromips(condition == (rt == 0x1D), imm);
+                    }
+
+                    _ => return Err(self.reserved_instruction()),
+                }
+            }
+
+            // JALS32 addr (with a 16-bit delay slot), J32 addr, JAL32 addr
+            0x1D | 0x35 | 0x3D => {
+                let target = (self.state.pc() & 0xF800_0000) | ((inst & 0x3FF_FFFF) << 1) | 1;
+
+                match inst >> 26 {
+                    0x1D => self.link_micromips(RA, 2),
+                    0x3D => self.link_micromips(RA, 4),
+                    _ => {}
+                }
+                self.state.jump(target);
+            }
+
+            // JALX32 addr (into MIPS32 code)
+            0x3C => {
+                let target = (self.state.pc() & 0xF000_0000) | ((inst & 0x3FF_FFFF) << 2);
+
+                self.link_micromips(RA, 4);
+                self.state.jump(target);
+            }
+
+            // ADDIUPC $Rs, Im (relative to the word the instruction is in)
+            0x1E => {
+                let rs = GPR3[((inst >> 23) & 0x7) as usize];
+                let offset = sign_extend(inst, 23) << 2;
+
+                self.state.write_register(rs, (self.state.inst_addr & !3).wrapping_add(offset as u32) as i32);
+            }
+
+            // BEQ32 $Rs, $Rt, Im / BNE32 $Rs, $Rt, Im
+            0x25 | 0x2D => {
+                let equal = self.state.read_register64(rs)? == self.state.read_register64(rt)?;
+
+                self.branch_micromips(equal == (inst >> 26 == 0x25), imm);
+            }
+
+            _ => return Err(self.reserved_instruction()),
+        }
+
+        Ok(())
+    }
+
+    /// LWM and LWP (or SWM and SWP, if `store`): loads (or stores) each of `regs`,
+    /// from consecutive words starting at `offset` from `$base`.
+    fn load_store_multiple(
+        &mut self,
+        regs: impl Iterator<Item = u32>,
+        base: u32,
+        offset: i32,
+        store: bool,
+    ) -> RushResult<()> {
+        let addr = self.state.read_register(base)?.wrapping_add(offset) as u32;
+        self.check_alignment(addr, 4)?;
+
+        for (reg, addr) in regs.zip((addr..).step_by(4)) {
+            match store {
+                true => self.state.write_mem_word_uninit(addr, self.state.read_register_uninit(reg).truncate())?,
+                false => self.state.write_register_uninit(reg, self.state.read_mem_word_uninit(addr)?.extend_sign()),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Takes a branch if `taken`, `offset` halfwords from the instruction after it.
+    fn branch_micromips(&mut self, taken: bool, offset: i32) {
+        // the pc has already moved past the branch
+        if taken {
+            self.state.jump(self.state.pc().wrapping_add((offset << 1) as u32) | 1);
+        }
+    }
+
+    /// Like `branch_micromips`, for a branch without a delay slot.
+    fn compact_branch_micromips(&mut self, taken: bool, offset: i32) {
+        if taken {
+            self.state.enter(self.state.pc().wrapping_add((offset << 1) as u32) | 1);
+        }
+    }
+
+    /// Links `$Rd` to the instruction after a delay slot of `slot_size` bytes (as each jump
+    /// and link requires), with the low bit set to return to microMIPS code.
+    fn link_micromips(&mut self, rd: u32, slot_size: u32) {
+        let link_addr = match self.state.delay_slots {
+            true => self.state.pc().wrapping_add(slot_size),
+            false => self.state.pc(),
+        };
+
+        self.state.write_register(rd, (link_addr | 1) as i32);
+    }
+}
+
+/// Whether `inst`, a 32-bit POOL32A instruction, is a JALR or JALRS (or their `.hb`s).
+fn is_jalr(inst: u32) -> bool {
+    inst & 0x3F == 0x3C && matches!((inst >> 6) & 0x3FF, 0x03C | 0x07C | 0x13C | 0x17C)
+}
+
+/// Whether `inst` is a branch or jump with a delay slot.
+pub(super) fn is_branch(inst: u32, size: u32) -> bool {
+    if size == 2 {
+        return match inst >> 10 {
+            0x11 => matches!((inst >> 5) & 0x1F, 0x0C | 0x0E | 0x0F),
+            0x23 | 0x2B | 0x33 => true,
+            _ => false,
+        };
+    }
+
+    match inst >> 26 {
+        0x00 => is_jalr(inst),
+        0x10 => matches!((inst >> 21) & 0x1F, 0x00..=0x04 | 0x06 | 0x11 | 0x13 | 0x1C | 0x1D),
+        0x1D | 0x25 | 0x2D | 0x35 | 0x3C | 0x3D => true,
+        _ => false,
+    }
+}
+
+/// The MIPS32 instruction that does what `inst` (a microMIPS instruction of `size` bytes) does,
+/// if it isn't a branch or jump and there is one.
+pub(super) fn translate(inst: u32, size: u32) -> Option<u32> {
+    match size {
+        2 => translate_16(inst),
+        _ => translate_32(inst),
+    }
+}
+
+fn translate_16(inst: u32) -> Option<u32> {
+    let gpr3 = |shift: u32| GPR3[((inst >> shift) & 0x7) as usize];
+    let imm4 = inst & 0xF;
+
+    let translated = match inst >> 10 {
+        // ADDU16 $Rd, $Rs, $Rt / SUBU16 $Rd, $Rs, $Rt
+        0x01 => r_type(if inst & 1 == 0 { 0x21 } else { 0x23 }, gpr3(7), gpr3(1), gpr3(4), 0),
+
+        // LBU16 $Rt, Im($Rs) (where an offset of 15 is -1)
+        0x02 => i_type(0x24, gpr3(4), gpr3(7), if imm4 == 0xF { -1 } else { imm4 as i32 }),
+
+        // MOVE16 $Rd, $Rs
+        0x03 => r_type(0x21, (inst >> 5) & 0x1F, inst & 0x1F, 0, 0),
+
+        // SLL16 $Rd, $Rt, Sa / SRL16 $Rd, $Rt, Sa (where a shift of 0 is 8)
+        0x09 => {
+            let sa = match (inst >> 1) & 0x7 {
+                0 => 8,
+                sa => sa,
+            };
+            r_type(if inst & 1 == 0 { 0x00 } else { 0x02 }, gpr3(7), 0, gpr3(4), sa)
+        }
+
+        // LHU16 $Rt, Im($Rs)
+        0x0A => i_type(0x25, gpr3(4), gpr3(7), (imm4 << 1) as i32),
+
+        // ANDI16 $Rd, $Rs, Im
+        0x0B => i_type(0x0C, gpr3(4), gpr3(7), ANDI16_IMMS[imm4 as usize]),
+
+        // POOL16C
+        0x11 => {
+            let (rt, rs) = (gpr3(3), gpr3(0));
+
+            match (inst >> 6) & 0xF {
+                // NOT16 $Rt, $Rs
+                0x0 => r_type(0x27, rt, rs, 0, 0),
+
+                // XOR16 $Rt, $Rs / AND16 $Rt, $Rs / OR16 $Rt, $Rs
+                0x1 => r_type(0x26, rt, rt, rs, 0),
+                0x2 => r_type(0x24, rt, rt, rs, 0),
+                0x3 => r_type(0x25, rt, rt, rs, 0),
+
+                _ => match (inst >> 4) & 0x3F {
+                    // MFHI16 $Rd / MFLO16 $Rd
+                    0x20 | 0x21 => r_type(0x10, inst & 0x1F, 0, 0, 0),
+                    0x24 | 0x25 => r_type(0x12, inst & 0x1F, 0, 0, 0),
+
+                    // BREAK16 Code
+                    0x28 => SPECIAL << 26 | imm4 << 16 | 0x0D,
+
+                    _ => return None,
+                },
+            }
+        }
+
+        // LWSP16 $Rt, Im($sp)
+        0x12 => i_type(0x23, SP, (inst >> 5) & 0x1F, ((inst & 0x1F) << 2) as i32),
+
+        // ADDIUS5 $Rd, Im / ADDIUSP Im
+        0x13 => match inst & 1 {
+            0 => i_type(0x09, (inst >> 5) & 0x1F, (inst >> 5) & 0x1F, sign_extend(inst >> 1, 4)),
+            _ => i_type(0x09, SP, SP, addiusp_imm((inst >> 1) & 0x1FF)),
+        },
+
+        // LWGP16 $Rt, Im($gp)
+        0x19 => i_type(0x23, GP, gpr3(7), ((inst & 0x7F) << 2) as i32),
+
+        // LW16 $Rt, Im($Rs)
+        0x1A => i_type(0x23, gpr3(4), gpr3(7), (imm4 << 2) as i32),
+
+        // ADDIUR2 $Rd, $Rs, Im / ADDIUR1SP $Rd, Im
+        0x1B => match inst & 1 {
+            0 => i_type(0x09, gpr3(4), gpr3(7), ADDIUR2_IMMS[((inst >> 1) & 0x7) as usize]),
+            _ => i_type(0x09, SP, gpr3(7), (((inst >> 1) & 0x3F) << 2) as i32),
+        },
+
+        // SB16 $Rt, Im($Rs) / SH16 $Rt, Im($Rs) / SW16 $Rt, Im($Rs)
+        0x22 => i_type(0x28, gpr3(4), GPR3_STORE[((inst >> 7) & 0x7) as usize], imm4 as i32),
+        0x2A => i_type(0x29, gpr3(4), GPR3_STORE[((inst >> 7) & 0x7) as usize], (imm4 << 1) as i32),
+        0x3A => i_type(0x2B, gpr3(4), GPR3_STORE[((inst >> 7) & 0x7) as usize], (imm4 << 2) as i32),
+
+        // SWSP16 $Rt, Im($sp)
+        0x32 => i_type(0x2B, SP, (inst >> 5) & 0x1F, ((inst & 0x1F) << 2) as i32),
+
+        // LI16 $Rd, Im (where 127 is -1)
+        0x3B => i_type(0x09, 0, gpr3(7), if inst & 0x7F == 0x7F { -1 } else { (inst & 0x7F) as i32 }),
+
+        _ => return None,
+    };
+
+    Some(translated)
+}
+
+fn translate_32(inst: u32) -> Option<u32> {
+    let rt = (inst >> 21) & 0x1F;
+    let rs = (inst >> 16) & 0x1F;
+    let rd = (inst >> 11) & 0x1F;
+    let imm = inst as i16 as i32;
+
+    let translated = match inst >> 26 {
+        // POOL32A
+        0x00 => return translate_pool32a(inst, rt, rs, rd),
+
+        // ADDI32, ADDIU32, SLTI32, SLTIU32, ANDI32, ORI32, XORI32 $Rt, $Rs, Im
+        0x04 => i_type(0x08, rs, rt, imm),
+        0x0C => i_type(0x09, rs, rt, imm),
+        0x24 => i_type(0x0A, rs, rt, imm),
+        0x2C => i_type(0x0B, rs, rt, imm),
+        0x34 => i_type(0x0C, rs, rt, imm),
+        0x14 => i_type(0x0D, rs, rt, imm),
+        0x1C => i_type(0x0E, rs, rt, imm),
+
+        // LB32, LBU32, LH32, LHU32, LW32 $Rt, Im($Rs)
+        0x07 => i_type(0x20, rs, rt, imm),
+        0x05 => i_type(0x24, rs, rt, imm),
+        0x0F => i_type(0x21, rs, rt, imm),
+        0x0D => i_type(0x25, rs, rt, imm),
+        0x3F => i_type(0x23, rs, rt, imm),
+
+        // SB32, SH32, SW32 $Rt, Im($Rs)
+        0x06 => i_type(0x28, rs, rt, imm),
+        0x0E => i_type(0x29, rs, rt, imm),
+        0x3E => i_type(0x2B, rs, rt, imm),
+
+        // LWC1, SWC1, LDC1, SDC1 $Ft, Im($Rs)
+        0x27 => i_type(0x31, rs, rt, imm),
+        0x26 => i_type(0x39, rs, rt, imm),
+        0x2F => i_type(0x35, rs, rt, imm),
+        0x2E => i_type(0x3D, rs, rt, imm),
+
+        // CACHE Op, Im($Rs) (from POOL32B, with a 12-bit offset)
+        0x08 if (inst >> 12) & 0xF == 0x6 => i_type(0x2F, rs, rt, sign_extend(inst, 12)),
+
+        // POOL32C, whose offsets are 12 bits too
+        0x18 => {
+            let opcode = match (inst >> 12) & 0xF {
+                // LWL, LWR, PREF, LL
+                0x0 => 0x22,
+                0x1 => 0x26,
+                0x2 => 0x33,
+                0x3 => 0x30,
+                // SWL, SWR, SC
+                0x8 => 0x2A,
+                0x9 => 0x2E,
+                0xB => 0x38,
+                _ => return None,
+            };
+            i_type(opcode, rs, rt, sign_extend(inst, 12))
+        }
+
+        // POOL32I (the branches aside)
+        0x10 => match rt {
+            // TLTI, TGEI, TLTIU, TGEIU, TNEI, TEQI $Rs, Im
+            0x08 => i_type(REGIMM, rs, 0x0A, imm),
+            0x09 => i_type(REGIMM, rs, 0x08, imm),
+            0x0A => i_type(REGIMM, rs, 0x0B, imm),
+            0x0B => i_type(REGIMM, rs, 0x09, imm),
+            0x0C => i_type(REGIMM, rs, 0x0E, imm),
+            0x0E => i_type(REGIMM, rs, 0x0C, imm),
+
+            // LUI  $Rs, Im
+            0x0D => i_type(0x0F, 0, rs, imm),
+
+            // SYNCI Im($Rs)
+            0x10 => i_type(REGIMM, rs, 0x1F, imm),
+
+            _ => return None,
+        },
+
+        _ => return None,
+    };
+
+    Some(translated)
+}
+
+fn translate_pool32a(inst: u32, rt: u32, rs: u32, rd: u32) -> Option<u32> {
+    let translated = match inst & 0x3F {
+        // INS  $Rt, $Rs, Pos, Size / EXT  $Rt, $Rs, Pos, Size (which keep MIPS32's fields)
+        0x0C => SPECIAL3 << 26 | rs << 21 | rt << 16 | (inst & 0xFFC0) | 0x04,
+        0x2C => SPECIAL3 << 26 | rs << 21 | rt << 16 | (inst & 0xFFC0),
+
+        // BREAK Code
+        0x07 => SPECIAL << 26 | (inst & 0x03FF_0000) | 0x0D,
+
+        // POOL32AXf
+        0x3C => return translate_pool32axf(inst, rt, rs),
+
+        _ => match inst & 0x3FF {
+            // SLL32, SRL32, SRA, ROTR $Rt, $Rs, Sa (where rt is the destination)
+            0x000 => r_type(0x00, rt, 0, rs, rd),
+            0x040 => r_type(0x02, rt, 0, rs, rd),
+            0x080 => r_type(0x03, rt, 0, rs, rd),
+            0x0C0 => r_type(0x02, rt, 1, rs, rd),
+
+            // SLLV, SRLV, SRAV, ROTRV $Rd, $Rt, $Rs
+            0x010 => r_type(0x04, rd, rs, rt, 0),
+            0x050 => r_type(0x06, rd, rs, rt, 0),
+            0x090 => r_type(0x07, rd, rs, rt, 0),
+            0x0D0 => r_type(0x06, rd, rs, rt, 1),
+
+            // MOVN $Rd, $Rs, $Rt / MOVZ $Rd, $Rs, $Rt
+            0x018 => r_type(0x0B, rd, rs, rt, 0),
+            0x058 => r_type(0x0A, rd, rs, rt, 0),
+
+            // ADD, ADDU32, SUB, SUBU32, MUL, AND, OR32, NOR, XOR32, SLT, SLTU $Rd, $Rs, $Rt
+            0x110 => r_type(0x20, rd, rs, rt, 0),
+            0x150 => r_type(0x21, rd, rs, rt, 0),
+            0x190 => r_type(0x22, rd, rs, rt, 0),
+            0x1D0 => r_type(0x23, rd, rs, rt, 0),
+            0x210 => SPECIAL2 << 26 | rs << 21 | rt << 16 | rd << 11 | 0x02,
+            0x250 => r_type(0x24, rd, rs, rt, 0),
+            0x290 => r_type(0x25, rd, rs, rt, 0),
+            0x2D0 => r_type(0x27, rd, rs, rt, 0),
+            0x310 => r_type(0x26, rd, rs, rt, 0),
+            0x350 => r_type(0x2A, rd, rs, rt, 0),
+            0x390 => r_type(0x2B, rd, rs, rt, 0),
+
+            _ => return None,
+        },
+    };
+
+    Some(translated)
+}
+
+fn translate_pool32axf(inst: u32, rt: u32, rs: u32) -> Option<u32> {
+    let code = (inst >> 16) & 0x3FF;
+
+    let translated = match (inst >> 6) & 0x3FF {
+        // MFC0 $Rt, $Rs, Sel / MTC0 $Rt, $Rs, Sel
+        minor if minor & 0x1F == 0x03 && minor < 0x100 => COP0 << 26 | rt << 16 | rs << 11 | minor >> 5,
+        minor if minor & 0x1F == 0x0B && minor < 0x100 => COP0 << 26 | 0x04 << 21 | rt << 16 | rs << 11 | minor >> 5,
+
+        // MULT, MULTU, DIV, DIVU $Rs, $Rt
+        0x22C => r_type(0x18, 0, rs, rt, 0),
+        0x26C => r_type(0x19, 0, rs, rt, 0),
+        0x2AC => r_type(0x1A, 0, rs, rt, 0),
+        0x2EC => r_type(0x1B, 0, rs, rt, 0),
+
+        // MADD, MADDU, MSUB, MSUBU $Rs, $Rt
+        0x32C => SPECIAL2 << 26 | rs << 21 | rt << 16,
+        0x36C => SPECIAL2 << 26 | rs << 21 | rt << 16 | 0x01,
+        0x3AC => SPECIAL2 << 26 | rs << 21 | rt << 16 | 0x04,
+        0x3EC => SPECIAL2 << 26 | rs << 21 | rt << 16 | 0x05,
+
+        // CLO  $Rt, $Rs / CLZ  $Rt, $Rs
+        0x12C => SPECIAL2 << 26 | rs << 21 | rt << 16 | rt << 11 | 0x21,
+        0x16C => SPECIAL2 << 26 | rs << 21 | rt << 16 | rt << 11 | 0x20,
+
+        // SEB  $Rt, $Rs / SEH  $Rt, $Rs / WSBH $Rt, $Rs
+        0x0AC => SPECIAL3 << 26 | rs << 16 | rt << 11 | 0x10 << 6 | 0x20,
+        0x0EC => SPECIAL3 << 26 | rs << 16 | rt << 11 | 0x18 << 6 | 0x20,
+        0x1EC => SPECIAL3 << 26 | rs << 16 | rt << 11 | 0x02 << 6 | 0x20,
+
+        // MFHI $Rs / MFLO $Rs / MTHI $Rs / MTLO $Rs
+        0x035 => r_type(0x10, rs, 0, 0, 0),
+        0x075 => r_type(0x12, rs, 0, 0, 0),
+        0x0B5 => r_type(0x11, 0, rs, 0, 0),
+        0x0F5 => r_type(0x13, 0, rs, 0, 0),
+
+        // SYSCALL Code / SYNC Stype
+        0x22D => SPECIAL << 26 | code << 6 | 0x0C,
+        0x1AD => r_type(0x0F, 0, 0, 0, rs),
+
+        // DI   $Rs / EI   $Rs
+        0x11D => COP0 << 26 | 0x0B << 21 | rs << 16 | 0x0C << 11,
+        0x15D => COP0 << 26 | 0x0B << 21 | rs << 16 | 0x0C << 11 | 0x20,
+
+        // ERET
+        0x3CD => COP0 << 26 | 0x10 << 21 | 0x18,
+
+        // TEQ, TGE, TGEU, TLT, TLTU, TNE $Rs, $Rt (with a code above the minor opcode)
+        minor => match minor & 0x3F {
+            0x00 => r_type(0x34, 0, rs, rt, 0),
+            0x08 => r_type(0x30, 0, rs, rt, 0),
+            0x10 => r_type(0x31, 0, rs, rt, 0),
+            0x20 => r_type(0x32, 0, rs, rt, 0),
+            0x28 => r_type(0x33, 0, rs, rt, 0),
+            0x30 => r_type(0x36, 0, rs, rt, 0),
+            _ => return None,
+        },
+    };
+
+    Some(translated)
+}
+
+/// The immediate of `addiusp`, which skips the values that `addius5 $sp` can encode.
+fn addiusp_imm(encoded: u32) -> i32 {
+    let imm = match encoded {
+        0 => 256,
+        1 => 257,
+        510 => -258,
+        511 => -257,
+        _ => sign_extend(encoded, 9),
+    };
+
+    imm << 2
+}
+
+fn r_type(funct: u32, rd: u32, rs: u32, rt: u32, shamt: u32) -> u32 {
+    SPECIAL << 26 | rs << 21 | rt << 16 | rd << 11 | shamt << 6 | funct
+}
+
+fn i_type(opcode: u32, rs: u32, rt: u32, imm: i32) -> u32 {
+    opcode << 26 | rs << 21 | rt << 16 | (imm as u32 & 0xFFFF)
+}
@@ -1,18 +1,28 @@
+#![allow(clippy::result_large_err)]
+
+pub mod cache;
+pub mod cp0;
+mod cop1;
+pub mod device;
+mod micromips;
+mod r6;
 pub mod state;
 pub mod system_clock;
+pub mod tlb;
 
-pub use self::state::State;
+pub use self::cp0::{Cp0, ExceptionCode, INTERRUPT_LINES, TIMER_INTERRUPT};
+pub use self::state::{DelaySlot, DelayedLoad, State};
 
 use crate::{error::runtime::{
     AlignmentRequirement, Error, InvalidSyscallReason, SegmentationFaultAccessType,
-}, Binary, RushError, RushResult, Register, RuntimeError, Safe, Uninitialised, HEAP_BOT};
+}, Binary, RushError, RushResult, Register, RuntimeError, Safe, Uninitialised, HEAP_BOT, KDATA_BOT, KTEXT_BOT};
 use std::cmp::Ordering;
-use std::str::from_utf8;
-use rush_utils::{RushConfig, RushConfigMemory};
-use crate::compile::PRINTF_ADDR;
+use rush_utils::{CycleCosts, Endianness, IsaRevision, RushConfig, RushConfigMemory};
+use crate::runtime::cache::Cache;
 use crate::runtime::system_clock::SystemClock;
 
 use crate::util::Segment;
+use crate::error::loader::Error as LoaderError;
 
 pub const PAGE_SIZE: usize = 64;
 pub const SYS1_PRINT_INT: i32 = 1;
@@ -37,8 +47,12 @@ pub const SPECIAL: u32 = 0b000000;
 pub const SPECIAL2: u32 = 0b011100;
 pub const SPECIAL3: u32 = 0b011111;
 
+pub const REGIMM: u32 = 0b000001;
 pub const JUMP: u32 = 0b000010;
 pub const JAL: u32 = 0b000011;
+pub const JALX: u32 = 0b011101;
+pub const COP0: u32 = 0b010000;
+pub const COP1: u32 = 0b010001;
 
 macro_rules! try_owned_self {
     ($self:ident, $res:expr) => {
@@ -54,6 +68,8 @@ pub struct Runtime {
     pub state: State,
     pub memory: RushConfigMemory,
     pub system_clock: SystemClock,
+    pub icache: Option<Cache>,
+    pub dcache: Option<Cache>,
 }
 
 impl Runtime {
@@ -64,6 +80,8 @@ impl Runtime {
         match addr {
             _ if addr < self.memory.text.start => Segment::None,
             _ if (self.memory.text.start..=self.memory.text.end).contains(&addr) => Segment::Text,
+            _ if (KTEXT_BOT..KDATA_BOT).contains(&addr) => Segment::KText,
+            _ if addr >= KDATA_BOT => Segment::KData,
             _ if (self.memory.global.bot..self.memory.stack.bot).contains(&addr) => Segment::Data,
             _ if (self.memory.stack.bot..=self.memory.stack.top).contains(&addr) => Segment::Stack,
             _ => Segment::None,
@@ -79,27 +97,56 @@ impl Runtime {
     pub fn state_mut(&mut self) -> &mut State { &mut self.state }
 
     pub fn current_inst(&self) -> RushResult<u32> {
-        self.state.read_mem_pc()
+        self.state.read_inst(self.state.pc()).map(|(inst, _)| inst)
     }
 
     pub fn step(mut self) -> Result<SteppedRuntime, (Runtime, RushError)> {
         // self.state.print();
+        self.state.update_device_interrupts();
+        if self.state.cp0.interrupt_pending() && self.state.has_exception_handler() {
+            return Ok(self.take_interrupt());
+        }
+
         let segment = self.get_pc_segment();
         match segment {
-            Segment::Text => {}
+            Segment::Text | Segment::KText => {}
+            // with an MMU, fetches are checked as they're translated
+            _ if self.state.cp0.tlb().is_some() => {}
             _ => {
                 let addr = self.state.pc();
-                return Err((
-                    self,
-                    RushError::Runtime(RuntimeError::new(Error::SegmentationFault {
-                        addr,
-                        access: SegmentationFaultAccessType::Execute,
-                    })),
-                ));
+                let err = RushError::Runtime(RuntimeError::new(Error::SegmentationFault {
+                    addr,
+                    access: SegmentationFaultAccessType::Execute,
+                }));
+
+                self.state.faulted_in_delay_slot = false;
+                return match self.exception_for(&err, 0) {
+                    Some((code, bad_vaddr)) => Ok(self.raise_exception(code, bad_vaddr)),
+                    None => Err((self, err)),
+                };
             }
         }
-        let inst = match self.state.read_mem_pc() {
-            Ok(inst) => inst,
+        let micromips = self.state.micromips;
+        let (inst, size) = match self.state.read_inst(self.state.pc()) {
+            Ok(fetched) => fetched,
+            Err(RushError::Runtime(err)) if matches!(err.error(), Error::TlbMiss { .. } | Error::AddressError { .. }) => {
+                let err = RushError::Runtime(err);
+
+                // EPC is the branch if this is its delay slot, as the branch has to be executed again
+                self.state.faulted_in_delay_slot = false;
+                if let Some(delay_slot) = self.state.delay_slot.take() {
+                    self.state.set_pc(delay_slot.branch_addr);
+                    self.state.faulted_in_delay_slot = true;
+                }
+
+                return match self.exception_for(&err, 0) {
+                    Some((code, bad_vaddr)) => {
+                        let vector = self.exception_vector(&err);
+                        Ok(self.raise_exception_at(code, bad_vaddr, vector))
+                    }
+                    None => Err((self, err)),
+                };
+            }
             Err(_) => {
                 let addr = self.state.pc();
                 return Err((
@@ -109,15 +156,181 @@ impl Runtime {
             }
         };
 
-        self.state.step_pc();
-        match self.execute_in_current_state(inst) {
-            Err((new_self, err)) => {
-                Err((new_self, err))
+        // (a microMIPS instruction costs the same as its MIPS32 equivalent)
+        let mips32_inst = match micromips {
+            true => micromips::translate(inst, size),
+            false => Some(inst),
+        };
+        let isa = self.state.isa;
+        let costs = self.system_clock.costs;
+        let mut cycles = mips32_inst.map_or(1, |inst| issue_cycles(inst, isa, &costs));
+        if let (Some(icache), Some(addr)) = (self.icache.as_mut(), self.state.cached_addr(self.state.pc())) {
+            cycles += icache.access(addr, false);
+        }
+        self.spend_cycles(cycles);
+
+        let inst_addr = self.state.pc();
+        let delay_slot = self.state.delay_slot.take();
+        self.state.faulted_in_delay_slot = false;
+
+        self.state.inst = inst;
+        self.state.inst_addr = inst_addr;
+        self.state.delayed_load = self.state.pending_load.take();
+
+        self.state.set_pc(inst_addr.wrapping_add(size));
+        if let Some(delay_slot) = delay_slot {
+            // the branch takes effect now, as its delay slot is executed
+            self.state.enter(delay_slot.target);
+        }
+        let next_pc = (self.state.pc(), self.state.micromips);
+
+        self.state.previous_load = self.state.loaded_register.take();
+        self.state.load_use_stall.set(false);
+        let data_access = mips32_inst.and_then(|inst| self.data_access(inst));
+
+        // leave pc on the faulting instruction so it can be reported,
+        // or on its branch (like EPC) so that the branch is executed again on resuming
+        let rewind = |runtime: &mut Runtime| {
+            match delay_slot {
+                Some(delay_slot) => {
+                    runtime.state.set_pc(delay_slot.branch_addr);
+                    runtime.state.faulted_in_delay_slot = true;
+                }
+                None => runtime.state.set_pc(inst_addr),
             }
-            ok => ok,
+            runtime.state.micromips = micromips;
+            runtime.state.branch_target = None;
+            runtime.state.commit_delayed_load();
+        };
+
+        let result = match micromips {
+            true => self.execute_micromips(inst, size),
+            false => self.execute_in_current_state(inst),
+        };
+
+        match result {
+            Err((mut new_self, err)) => {
+                rewind(&mut new_self);
+
+                // (whether an address error is a load or a store goes by the MIPS32 opcode)
+                let inst = match micromips {
+                    true => micromips::translate(inst, size).unwrap_or_default(),
+                    false => inst,
+                };
+                match new_self.exception_for(&err, inst) {
+                    Some((code, bad_vaddr)) => {
+                        let vector = new_self.exception_vector(&err);
+                        Ok(new_self.raise_exception_at(code, bad_vaddr, vector))
+                    }
+                    None => Err((new_self, err)),
+                }
+            }
+            // with a handler loaded, traps are taken like any other exception
+            Ok(Err(RuntimeSyscallGuard::Trap(mut new_self))) if new_self.state.has_exception_handler() => {
+                rewind(&mut new_self);
+
+                Ok(new_self.raise_exception(ExceptionCode::Trap, None))
+            }
+            Ok(Ok(mut new_self)) => {
+                new_self.state.commit_delayed_load();
+
+                let taken = new_self.state.branch_target.is_some() || (new_self.state.pc(), new_self.state.micromips) != next_pc;
+                new_self.spend_stall_cycles(taken);
+
+                let data_access = data_access.and_then(|(addr, write)| Some((new_self.state.cached_addr(addr)?, write)));
+                if let (Some(dcache), Some((addr, write))) = (new_self.dcache.as_mut(), data_access) {
+                    let cycles = dcache.access(addr, write);
+                    new_self.spend_cycles(cycles);
+                }
+
+                let has_delay_slot = match micromips {
+                    true => micromips::is_branch(inst, size),
+                    false => is_branch(inst, new_self.state.isa),
+                };
+
+                if new_self.state.delay_slots && has_delay_slot {
+                    // microMIPS delay slots may be either size
+                    let slot_addr = inst_addr.wrapping_add(size);
+                    let after_slot = slot_addr.wrapping_add(new_self.state.inst_size_at(slot_addr)) | micromips as u32;
+
+                    match new_self.state.branch_target.take() {
+                        // an untaken branch likely skips (nullifies) its delay slot
+                        None if !micromips && is_branch_likely(inst) => new_self.state.set_pc(after_slot),
+                        target => {
+                            new_self.state.delay_slot = Some(DelaySlot {
+                                branch_addr: inst_addr,
+                                target: target.unwrap_or(after_slot),
+                            });
+                        }
+                    }
+                }
+
+                Ok(Ok(new_self))
+            }
+            // a syscall in a load's delay slot has already read its arguments,
+            // but anything it writes (e.g. `$v0`) happens once it's been handled
+            Ok(Err(guard)) => Ok(Err(guard.map_runtime(|runtime| {
+                runtime.state.commit_delayed_load();
+                runtime.spend_stall_cycles(false);
+            }))),
+        }
+    }
+
+    /// The address `inst` (a MIPS32 encoding) loads from or stores to, and whether it's a store.
+    /// This is worked out before `inst` is executed, as a load may overwrite its own base register.
+    fn data_access(&self, inst: u32) -> Option<(u32, bool)> {
+        let write = match inst >> 26 {
+            // LB, LH, LWL, LW, LBU, LHU, LWR, LWU, LL, LWC1, LLD, LDC1, LD
+            0x20..=0x27 | 0x30 | 0x31 | 0x34 | 0x35 | 0x37 => false,
+            // LDL, LDR (which release 6 doesn't have)
+            0x1A | 0x1B if self.state.isa == IsaRevision::R2 => false,
+            // SB, SH, SWL, SW, SDL, SDR, SWR, SC, SWC1, SCD, SDC1, SD
+            0x28..=0x2E | 0x38 | 0x39 | 0x3C | 0x3D | 0x3F => true,
+            _ => return None,
+        };
+
+        let base = self.state.registers[((inst >> 21) & 0x1F) as usize].into_option()?;
+        Some(((base as u32).wrapping_add(inst as i16 as u32), write))
+    }
+
+    /// The statistics of each cache being simulated, if there are any.
+    pub fn cache_summary(&self) -> Option<String> {
+        let summaries = [("I-cache", &self.icache), ("D-cache", &self.dcache)]
+            .into_iter()
+            .filter_map(|(name, cache)| cache.as_ref().map(|cache| cache.summary(name)))
+            .collect::<Vec<_>>();
+
+        match summaries.is_empty() {
+            true => None,
+            false => Some(summaries.join("\n")),
         }
     }
 
+    /// Advances the simulated clock, and with it CP0 Count.
+    fn spend_cycles(&mut self, cycles: u64) {
+        self.system_clock.update(cycles);
+        for _ in 0..cycles {
+            self.state.cp0.tick();
+        }
+        self.state.tick_devices(cycles);
+    }
+
+    /// Spends the cycles the pipeline stalled for after the instruction just executed,
+    /// which read the register loaded by the one before it, or was a `taken` branch.
+    fn spend_stall_cycles(&mut self, taken: bool) {
+        let costs = self.system_clock.costs;
+        let mut cycles = 0;
+
+        if self.state.load_use_stall.take() {
+            cycles += costs.load_use;
+        }
+        if taken {
+            cycles += costs.taken_branch;
+        }
+
+        self.spend_cycles(cycles);
+    }
+
     pub fn exec_inst(self, opcode: u32) -> Result<SteppedRuntime, (Runtime, RushError)> {
         match self.execute_in_current_state(opcode) {
             Err((new_self, err)) => {
@@ -144,17 +357,32 @@ impl Runtime {
         let imm = (inst & 0xFFFF) as i16;
         let addr = inst & 0x3FFFFFF;
 
+        if self.state.isa == IsaRevision::R6 && try_owned_self!(self, self.execute_r6(inst)) {
+            return Ok(Ok(self));
+        }
+
         match opcode {
             SPECIAL | SPECIAL2 | SPECIAL3 => {
                 // R-Type
                 self.execute_r(opcode, funct, rd, rs, rt, shamt)
             }
-            JUMP | JAL => {
+            JUMP | JAL | JALX => {
                 // J-Type
                 self.execute_j(opcode, addr);
 
                 Ok(Ok(self))
             }
+            COP0 => {
+                try_owned_self!(self, self.execute_cop0(funct, rd, rs, rt));
+
+                Ok(Ok(self))
+            }
+            COP1 => {
+                // Floating point, the fd field sits where shamt would
+                try_owned_self!(self, self.execute_cop1(funct, rd, rs, rt, shamt, imm));
+
+                Ok(Ok(self))
+            }
             _ => {
                 // I-Type
                 self.execute_i(opcode, rs, rt, imm)
@@ -180,35 +408,15 @@ impl Runtime {
                 RuntimeSyscallGuard::PrintInt(PrintIntArgs { value }, self)
             }
             SYS2_PRINT_FLOAT => {
-                return Err((
-                    self,
-                    RushError::Runtime(RuntimeError::new(Error::InvalidSyscall {
-                        syscall,
-                        reason: InvalidSyscallReason::Unimplemented,
-                    })),
-                ));
+                let value = try_owned_self!(self, self.state().read_f32(12));
+
+                RuntimeSyscallGuard::PrintFloat(PrintFloatArgs { value }, self)
             }
-            // RuntimeSyscallGuard::PrintFloat(
-            //     PrintFloatArgs {
-            //         value: todo!(),
-            //     },
-            //     self
-            // ),
             SYS3_PRINT_DOUBLE => {
-                return Err((
-                    self,
-                    RushError::Runtime(RuntimeError::new(Error::InvalidSyscall {
-                        syscall,
-                        reason: InvalidSyscallReason::Unimplemented,
-                    })),
-                ));
+                let value = try_owned_self!(self, self.state().read_f64(12));
+
+                RuntimeSyscallGuard::PrintDouble(PrintDoubleArgs { value }, self)
             }
-            // RuntimeSyscallGuard::PrintDouble(
-            //     PrintDoubleArgs {
-            //         value: todo!(),
-            //     },
-            //     self
-            // ),
             SYS4_PRINT_STRING => {
                 let value = try_owned_self!(
                     self,
@@ -225,24 +433,14 @@ impl Runtime {
                     .write_register(Register::V0.to_u32(), value);
                 self
             })),
-            SYS6_READ_FLOAT => {
-                return Err((
-                    self,
-                    RushError::Runtime(RuntimeError::new(Error::InvalidSyscall {
-                        syscall,
-                        reason: InvalidSyscallReason::Unimplemented,
-                    })),
-                ));
-            }
-            SYS7_READ_DOUBLE => {
-                return Err((
-                    self,
-                    RushError::Runtime(RuntimeError::new(Error::InvalidSyscall {
-                        syscall,
-                        reason: InvalidSyscallReason::Unimplemented,
-                    })),
-                ));
-            }
+            SYS6_READ_FLOAT => RuntimeSyscallGuard::ReadFloat(Box::new(move |value| {
+                self.state_mut().write_f32(0, value);
+                self
+            })),
+            SYS7_READ_DOUBLE => RuntimeSyscallGuard::ReadDouble(Box::new(move |value| {
+                self.state_mut().write_f64(0, value);
+                self
+            })),
             SYS8_READ_STRING => {
                 let buf = try_owned_self!(
                     self,
@@ -443,10 +641,13 @@ impl Runtime {
             // BREAK
             (SPECIAL, 0x0D) => Ok(Err(RuntimeSyscallGuard::Breakpoint(self))),
 
+            // SDBBP (there's no EJTAG debug mode, so the debugger stands in for it, as for `break`)
+            (SPECIAL2, 0x3F) => Ok(Err(RuntimeSyscallGuard::Breakpoint(self))),
+
             // TGE  $Rs, $Rt
             (SPECIAL, 0x30) => {
-                if try_owned_self!(self, self.state.read_register(rs))
-                    >= try_owned_self!(self, self.state.read_register(rt))
+                if try_owned_self!(self, self.state.read_register64(rs))
+                    >= try_owned_self!(self, self.state.read_register64(rt))
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -456,8 +657,8 @@ impl Runtime {
 
             // TGEU $Rs, $Rt
             (SPECIAL, 0x31) => {
-                if try_owned_self!(self, self.state.read_register(rs)) as u32
-                    >= try_owned_self!(self, self.state.read_register(rt)) as u32
+                if try_owned_self!(self, self.state.read_register64(rs)) as u64
+                    >= try_owned_self!(self, self.state.read_register64(rt)) as u64
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -467,8 +668,8 @@ impl Runtime {
 
             // TLT  $Rs, $Rt
             (SPECIAL, 0x32) => {
-                if try_owned_self!(self, self.state.read_register(rs))
-                    < try_owned_self!(self, self.state.read_register(rt))
+                if try_owned_self!(self, self.state.read_register64(rs))
+                    < try_owned_self!(self, self.state.read_register64(rt))
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -478,8 +679,8 @@ impl Runtime {
 
             // TLTU $Rs, $Rt
             (SPECIAL, 0x33) => {
-                if (try_owned_self!(self, self.state.read_register(rs)) as u32)
-                    < try_owned_self!(self, self.state.read_register(rt)) as u32
+                if (try_owned_self!(self, self.state.read_register64(rs)) as u64)
+                    < try_owned_self!(self, self.state.read_register64(rt)) as u64
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -489,8 +690,8 @@ impl Runtime {
 
             // TEQ  $Rs, $Rt
             (SPECIAL, 0x34) => {
-                if try_owned_self!(self, self.state.read_register(rs))
-                    == try_owned_self!(self, self.state.read_register(rt))
+                if try_owned_self!(self, self.state.read_register64(rs))
+                    == try_owned_self!(self, self.state.read_register64(rt))
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -500,8 +701,8 @@ impl Runtime {
 
             // TNE  $Rs, $Rt
             (SPECIAL, 0x36) => {
-                if try_owned_self!(self, self.state.read_register(rs))
-                    != try_owned_self!(self, self.state.read_register(rt))
+                if try_owned_self!(self, self.state.read_register64(rs))
+                    != try_owned_self!(self, self.state.read_register64(rt))
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -539,8 +740,8 @@ impl Runtime {
                         );
                     }
 
-                    // Unused
-                    0x01 => {}
+                    // Reserved
+                    0x01 => return Err(self.reserved_instruction()),
 
                     0x02 => {
                         match rs {
@@ -560,7 +761,7 @@ impl Runtime {
                                 );
                             }
 
-                            _ => todo!(),
+                            _ => return Err(self.reserved_instruction()),
                         }
                     }
 
@@ -577,8 +778,8 @@ impl Runtime {
                         );
                     }
 
-                    // Unused
-                    0x05 => {}
+                    // Reserved
+                    0x05 => return Err(self.reserved_instruction()),
 
                     0x06 => {
                         match shamt {
@@ -602,7 +803,7 @@ impl Runtime {
                                 );
                             }
 
-                            _ => todo!(),
+                            _ => return Err(self.reserved_instruction()),
                         }
                     }
 
@@ -616,26 +817,30 @@ impl Runtime {
 
                     // JR   $Rs
                     0x08 => {
-                        self.state.set_pc(self.state.read_register(rs)? as u32);
+                        self.state.jump(self.state.read_register(rs)? as u32);
                     }
 
                     // JALR $Rs
                     0x09 => {
-                        self.state.write_register(rd, self.state.pc() as _);
-                        self.state.set_pc(self.state.read_register(rs)? as _);
+                        let target = self.state.read_register(rs)? as u32;
+
+                        self.state.write_register(rd, self.state.link_addr() as _);
+                        self.state.jump(target);
                     }
 
                     // MOVZ $Rd, $Rs, $Rt
                     0x0A => {
-                        if self.state.read_register(rt)? == 0 {
-                            self.state.write_register(rd, self.state.read_register(rs)?);
+                        if self.state.read_register64(rt)? == 0 {
+                            self.state
+                                .write_register64(rd, self.state.read_register64(rs)?);
                         }
                     }
 
                     // MOVN $Rd, $Rs, $Rt
                     0x0B => {
-                        if self.state.read_register(rt)? != 0 {
-                            self.state.write_register(rd, self.state.read_register(rs)?);
+                        if self.state.read_register64(rt)? != 0 {
+                            self.state
+                                .write_register64(rd, self.state.read_register64(rs)?);
                         }
                     }
 
@@ -645,16 +850,16 @@ impl Runtime {
                     // BREAK
                     0x0D => unreachable!("covered above"),
 
-                    // Unused
-                    0x0E => {}
+                    // Reserved
+                    0x0E => return Err(self.reserved_instruction()),
 
-                    // Unused
+                    // SYNC (memory accesses already complete in order)
                     0x0F => {}
 
                     0x10 => match shamt {
                         // MFHI $Rd
                         0x00 => {
-                            self.state.write_register(rd, self.state.read_hi()?);
+                            self.state.write_register64(rd, self.state.read_hi64()?);
                         }
 
                         // CLZ $Rd, $Rs
@@ -665,13 +870,13 @@ impl Runtime {
                             );
                         }
 
-                        _ => todo!(),
+                        _ => return Err(self.reserved_instruction()),
                     },
 
                     0x11 => match shamt {
                         // MTHI $Rs
                         0x00 => {
-                            self.state.write_hi(self.state.read_register(rs)?);
+                            self.state.write_hi64(self.state.read_register64(rs)?);
                         }
 
                         // CLO $Rd, $Rs
@@ -680,30 +885,58 @@ impl Runtime {
                                 .write_register(rd, self.state.read_register(rs)?.leading_ones() as i32);
                         }
 
-                        _ => todo!(),
+                        _ => return Err(self.reserved_instruction()),
                     },
 
                     // MFLO $Rd
                     0x12 => {
-                        self.state.write_register(rd, self.state.read_lo()?);
+                        self.state.write_register64(rd, self.state.read_lo64()?);
                     }
 
                     // MTLO $Rs
                     0x13 => {
-                        self.state.write_lo(self.state.read_register(rs)?);
+                        self.state.write_lo64(self.state.read_register64(rs)?);
+                    }
+
+                    // DSLLV $Rd, $Rt, $Rs
+                    0x14 => {
+                        self.require_mips64()?;
+
+                        let shift = self.state.read_register(rs)? as u32 & 0x3F;
+                        self.state
+                            .write_register64(rd, self.state.read_register64(rt)? << shift);
                     }
 
-                    // Unused
-                    0x14 => {}
+                    // Reserved
+                    0x15 => return Err(self.reserved_instruction()),
+
+                    0x16 => {
+                        self.require_mips64()?;
+
+                        let rt_val = self.state.read_register64(rt)? as u64;
+                        let shift = self.state.read_register(rs)? as u32 & 0x3F;
+
+                        match shamt {
+                            // DSRLV $Rd, $Rt, $Rs
+                            0x00 => self.state.write_register64(rd, (rt_val >> shift) as i64),
 
-                    // Unused
-                    0x15 => {}
+                            // DROTRV $Rd, $Rt, $Rs
+                            0x01 => self
+                                .state
+                                .write_register64(rd, rt_val.rotate_right(shift) as i64),
 
-                    // Unused
-                    0x16 => {}
+                            _ => return Err(self.reserved_instruction()),
+                        }
+                    }
 
-                    // Unused
-                    0x17 => {}
+                    // DSRAV $Rd, $Rt, $Rs
+                    0x17 => {
+                        self.require_mips64()?;
+
+                        let shift = self.state.read_register(rs)? as u32 & 0x3F;
+                        self.state
+                            .write_register64(rd, self.state.read_register64(rt)? >> shift);
+                    }
 
                     // MULT $Rs, $Rt
                     0x18 => {
@@ -755,17 +988,63 @@ impl Runtime {
                         self.state.write_hi((rs_val % rt_val) as i32);
                     }
 
-                    // Unused
-                    0x1C => {}
+                    // DMULT $Rs, $Rt
+                    0x1C => {
+                        self.require_mips64()?;
+
+                        let rs_val = self.state.read_register64(rs)?;
+                        let rt_val = self.state.read_register64(rt)?;
+
+                        let result = rs_val as i128 * rt_val as i128;
+                        self.state.write_hi64((result >> 64) as _);
+                        self.state.write_lo64(result as _);
+                    }
+
+                    // DMULTU $Rs, $Rt
+                    0x1D => {
+                        self.require_mips64()?;
+
+                        let rs_val = self.state.read_register64(rs)? as u64;
+                        let rt_val = self.state.read_register64(rt)? as u64;
+
+                        let result = rs_val as u128 * rt_val as u128;
+                        self.state.write_hi64((result >> 64) as _);
+                        self.state.write_lo64(result as _);
+                    }
+
+                    // DDIV $Rs, $Rt
+                    0x1E => {
+                        self.require_mips64()?;
+
+                        let rs_val = self.state.read_register64(rs)?;
+                        let rt_val = self.state.read_register64(rt)?;
+
+                        if rt_val == 0 {
+                            return Err(RushError::Runtime(RuntimeError::new(
+                                Error::DivisionByZero,
+                            )));
+                        }
+
+                        self.state.write_lo64(rs_val.wrapping_div(rt_val));
+                        self.state.write_hi64(rs_val.wrapping_rem(rt_val));
+                    }
 
-                    // Unused
-                    0x1D => {}
+                    // DDIVU $Rs, $Rt
+                    0x1F => {
+                        self.require_mips64()?;
 
-                    // Unused
-                    0x1E => {}
+                        let rs_val = self.state.read_register64(rs)? as u64;
+                        let rt_val = self.state.read_register64(rt)? as u64;
 
-                    // Unused
-                    0x1F => {}
+                        if rt_val == 0 {
+                            return Err(RushError::Runtime(RuntimeError::new(
+                                Error::DivisionByZero,
+                            )));
+                        }
+
+                        self.state.write_lo64((rs_val / rt_val) as i64);
+                        self.state.write_hi64((rs_val % rt_val) as i64);
+                    }
 
                     // ADD  $Rd, $Rs, $Rt
                     0x20 => {
@@ -805,47 +1084,47 @@ impl Runtime {
 
                     // AND  $Rd, $Rs, $Rt
                     0x24 => {
-                        self.state.write_register(
+                        self.state.write_register64(
                             rd,
-                            self.state.read_register(rs)? & self.state.read_register(rt)?,
+                            self.state.read_register64(rs)? & self.state.read_register64(rt)?,
                         );
                     }
 
                     // OR   $Rd, $Rs, $Rt
                     0x25 => {
-                        self.state.write_register(
+                        self.state.write_register64(
                             rd,
-                            self.state.read_register(rs)? | self.state.read_register(rt)?,
+                            self.state.read_register64(rs)? | self.state.read_register64(rt)?,
                         );
                     }
 
                     // XOR  $Rd, $Rs, $Rt
                     0x26 => {
-                        self.state.write_register(
+                        self.state.write_register64(
                             rd,
-                            self.state.read_register(rs)? ^ self.state.read_register(rt)?,
+                            self.state.read_register64(rs)? ^ self.state.read_register64(rt)?,
                         );
                     }
 
                     // NOR  $Rd, $Rs, $Rt
                     0x27 => {
-                        self.state.write_register(
+                        self.state.write_register64(
                             rd,
-                            !(self.state.read_register(rs)? | self.state.read_register(rt)?),
+                            !(self.state.read_register64(rs)? | self.state.read_register64(rt)?),
                         );
                     }
 
-                    // Unused
-                    0x28 => {}
+                    // Reserved
+                    0x28 => return Err(self.reserved_instruction()),
 
-                    // Unused
-                    0x29 => {}
+                    // Reserved
+                    0x29 => return Err(self.reserved_instruction()),
 
                     // SLT  $Rd, $Rs, $Rt
                     0x2A => {
                         self.state.write_register(
                             rd,
-                            if self.state.read_register(rs)? < self.state.read_register(rt)? {
+                            if self.state.read_register64(rs)? < self.state.read_register64(rt)? {
                                 1
                             } else {
                                 0
@@ -857,7 +1136,7 @@ impl Runtime {
                     0x2B => {
                         self.state.write_register(
                             rd,
-                            if (self.state.read_register(rs)? as u32) < self.state.read_register(rt)? as u32 {
+                            if (self.state.read_register64(rs)? as u64) < self.state.read_register64(rt)? as u64 {
                                 1
                             } else {
                                 0
@@ -865,8 +1144,98 @@ impl Runtime {
                         );
                     }
 
-                    // Unused
-                    0x2C..=0x3F => {}
+                    // DADD $Rd, $Rs, $Rt
+                    0x2C => {
+                        self.require_mips64()?;
+
+                        self.state.write_register64(
+                            rd,
+                            checked_add64(
+                                self.state.read_register64(rs)?,
+                                self.state.read_register64(rt)?,
+                            )?,
+                        );
+                    }
+
+                    // DADDU $Rd, $Rs, $Rt
+                    0x2D => {
+                        self.require_mips64()?;
+
+                        self.state.write_register64(
+                            rd,
+                            self.state
+                                .read_register64(rs)?
+                                .wrapping_add(self.state.read_register64(rt)?),
+                        );
+                    }
+
+                    // DSUB $Rd, $Rs, $Rt
+                    0x2E => {
+                        self.require_mips64()?;
+
+                        self.state.write_register64(
+                            rd,
+                            checked_sub64(
+                                self.state.read_register64(rs)?,
+                                self.state.read_register64(rt)?,
+                            )?,
+                        );
+                    }
+
+                    // DSUBU $Rd, $Rs, $Rt
+                    0x2F => {
+                        self.require_mips64()?;
+
+                        self.state.write_register64(
+                            rd,
+                            self.state
+                                .read_register64(rs)?
+                                .wrapping_sub(self.state.read_register64(rt)?),
+                        );
+                    }
+
+                    // TGE, TGEU, TLT, TLTU, TEQ, TNE
+                    0x30..=0x34 | 0x36 => unreachable!("covered above"),
+
+                    // DSLL $Rd, $Rt, Sa / DSLL32 $Rd, $Rt, Sa
+                    0x38 | 0x3C => {
+                        self.require_mips64()?;
+
+                        let shift = shamt + if funct == 0x3C { 32 } else { 0 };
+                        self.state
+                            .write_register64(rd, self.state.read_register64(rt)? << shift);
+                    }
+
+                    0x3A | 0x3E => {
+                        self.require_mips64()?;
+
+                        let rt_val = self.state.read_register64(rt)? as u64;
+                        let shift = shamt + if funct == 0x3E { 32 } else { 0 };
+
+                        match rs {
+                            // DSRL $Rd, $Rt, Sa / DSRL32 $Rd, $Rt, Sa
+                            0x00 => self.state.write_register64(rd, (rt_val >> shift) as i64),
+
+                            // DROTR $Rd, $Rt, Sa / DROTR32 $Rd, $Rt, Sa
+                            0x01 => self
+                                .state
+                                .write_register64(rd, rt_val.rotate_right(shift) as i64),
+
+                            _ => return Err(self.reserved_instruction()),
+                        }
+                    }
+
+                    // DSRA $Rd, $Rt, Sa / DSRA32 $Rd, $Rt, Sa
+                    0x3B | 0x3F => {
+                        self.require_mips64()?;
+
+                        let shift = shamt + if funct == 0x3F { 32 } else { 0 };
+                        self.state
+                            .write_register64(rd, self.state.read_register64(rt)? >> shift);
+                    }
+
+                    // Reserved
+                    0x35 | 0x37 | 0x39 | 0x3D => return Err(self.reserved_instruction()),
 
                     // Doesn't fit in 6 bits
                     _ => unreachable!(),
@@ -879,8 +1248,8 @@ impl Runtime {
                         let rs_val = self.state.read_register(rs)?;
                         let rt_val = self.state.read_register(rt)?;
 
-                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u64;
-                        let result = original + (rs_val as i64 * rt_val as i64) as u64;
+                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u32 as u64;
+                        let result = original.wrapping_add((rs_val as i64 * rt_val as i64) as u64);
 
                         self.state.write_hi((result >> 32) as _);
                         self.state.write_lo((result & 0xFFFF_FFFF) as _);
@@ -888,11 +1257,11 @@ impl Runtime {
 
                     // MADDU
                     0x01 => {
-                        let rs_val = self.state.read_register(rs)?;
-                        let rt_val = self.state.read_register(rt)?;
+                        let rs_val = self.state.read_register(rs)? as u32;
+                        let rt_val = self.state.read_register(rt)? as u32;
 
-                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u64;
-                        let result = original + rs_val as u64 * rt_val as u64;
+                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u32 as u64;
+                        let result = original.wrapping_add(rs_val as u64 * rt_val as u64);
 
                         self.state.write_hi((result >> 32) as _);
                         self.state.write_lo((result & 0xFFFF_FFFF) as _);
@@ -920,8 +1289,8 @@ impl Runtime {
                         let rs_val = self.state.read_register(rs)?;
                         let rt_val = self.state.read_register(rt)?;
 
-                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u64;
-                        let result = original - (rs_val as i64 * rt_val as i64) as u64;
+                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u32 as u64;
+                        let result = original.wrapping_sub((rs_val as i64 * rt_val as i64) as u64);
 
                         self.state.write_hi((result >> 32) as _);
                         self.state.write_lo((result & 0xFFFF_FFFF) as _);
@@ -929,22 +1298,101 @@ impl Runtime {
 
                     // MSUBU
                     0x05 => {
-                        let rs_val = self.state.read_register(rs)?;
-                        let rt_val = self.state.read_register(rt)?;
+                        let rs_val = self.state.read_register(rs)? as u32;
+                        let rt_val = self.state.read_register(rt)? as u32;
 
-                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u64;
-                        let result = original - rs_val as u64 * rt_val as u64;
+                        let original = ((self.state.read_hi()? as u64) << 32) | self.state.read_lo()? as u32 as u64;
+                        let result = original.wrapping_sub(rs_val as u64 * rt_val as u64);
 
                         self.state.write_hi((result >> 32) as _);
                         self.state.write_lo((result & 0xFFFF_FFFF) as _);
                     }
 
-                    // Unimplemented
-                    _ => {}
+                    // CLZ  $Rd, $Rs (the Release 2 encoding)
+                    0x20 => {
+                        self.state.write_register(rd, self.state.read_register(rs)?.leading_zeros() as i32);
+                    }
+
+                    // CLO  $Rd, $Rs (the Release 2 encoding)
+                    0x21 => {
+                        self.state.write_register(rd, self.state.read_register(rs)?.leading_ones() as i32);
+                    }
+
+                    // DCLZ $Rd, $Rs
+                    0x24 => {
+                        self.require_mips64()?;
+
+                        self.state.write_register(
+                            rd,
+                            self.state.read_register64(rs)?.leading_zeros() as i32,
+                        );
+                    }
+
+                    // DCLO $Rd, $Rs
+                    0x25 => {
+                        self.require_mips64()?;
+
+                        self.state.write_register(
+                            rd,
+                            self.state.read_register64(rs)?.leading_ones() as i32,
+                        );
+                    }
+
+                    _ => return Err(self.reserved_instruction()),
                 }
             }
             SPECIAL3 => {
                 match funct {
+                    // EXT  $Rt, $Rs, Pos, Size
+                    0x00 => {
+                        let (pos, size) = (shamt, rd + 1);
+                        let mask = u32::MAX >> (32 - size);
+
+                        self.state.write_register(
+                            rt,
+                            ((self.state.read_register(rs)? as u32 >> pos) & mask) as i32,
+                        );
+                    }
+
+                    // INS  $Rt, $Rs, Pos, Size
+                    0x04 => {
+                        let (pos, size) = (shamt, (rd + 1).wrapping_sub(shamt));
+
+                        // a field that ends before it starts is UNPREDICTABLE, so leave $Rt be
+                        if size == 0 || size > 32 - pos {
+                            return Ok(());
+                        }
+
+                        let mask = (u32::MAX >> (32 - size)) << pos;
+                        let rs_val = self.state.read_register(rs)? as u32;
+                        let rt_val = self.state.read_register(rt)? as u32;
+
+                        self.state.write_register(rt, ((rt_val & !mask) | ((rs_val << pos) & mask)) as i32);
+                    }
+
+                    // RDHWR $Rt, $Cd
+                    0x3B => {
+                        let value = match rd {
+                            // CPUNum, as there's only the one
+                            0 => 0,
+                            // SYNCI_Step, the smallest cache line (or 0, if nothing is cached)
+                            1 => [&self.icache, &self.dcache]
+                                .into_iter()
+                                .flatten()
+                                .map(|cache| cache.config().line_size)
+                                .min()
+                                .unwrap_or(0),
+                            // CC, CP0 Count
+                            2 => self.state.cp0.read(cp0::COUNT),
+                            // CCRes, as Count goes up every cycle
+                            3 => 1,
+                            // there's no UserLocal register, so reading it is left to the kernel to emulate
+                            _ => return Err(self.reserved_instruction()),
+                        };
+
+                        self.state.write_register(rt, value as i32);
+                    }
+
                     0x20 => {
                         match shamt {
                             // WSBH $Rd, $Rt
@@ -977,11 +1425,11 @@ impl Runtime {
                                 );
                             }
 
-                            _ => todo!(),
+                            _ => return Err(self.reserved_instruction()),
                         }
                     }
 
-                    _ => todo!(),
+                    _ => return Err(self.reserved_instruction()),
                 }
             }
             _ => unreachable!("special can only be SPECIAL, SPECIAL2, or SPECIAL3"),
@@ -1000,7 +1448,7 @@ impl Runtime {
         match (opcode, rt) {
             // TGEI
             (0x01, 0x08) => {
-                if try_owned_self!(self, self.state.read_register(rs)) >= imm.extend_sign() {
+                if try_owned_self!(self, self.state.read_register64(rs)) >= imm as i64 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
                     Ok(Ok(self))
@@ -1009,7 +1457,7 @@ impl Runtime {
 
             // TGEIU
             (0x01, 0x09) => {
-                if try_owned_self!(self, self.state.read_register(rs)) as u32 >= imm.extend_sign() as u32
+                if try_owned_self!(self, self.state.read_register64(rs)) as u64 >= imm as i64 as u64
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -1019,7 +1467,7 @@ impl Runtime {
 
             // TLTI
             (0x01, 0x0A) => {
-                if try_owned_self!(self, self.state.read_register(rs)) < imm.extend_sign() {
+                if try_owned_self!(self, self.state.read_register64(rs)) < imm as i64 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
                     Ok(Ok(self))
@@ -1028,8 +1476,8 @@ impl Runtime {
 
             // TLTIU
             (0x01, 0x0B) => {
-                if (try_owned_self!(self, self.state.read_register(rs)) as u32)
-                    < imm.extend_sign() as u32
+                if (try_owned_self!(self, self.state.read_register64(rs)) as u64)
+                    < imm as i64 as u64
                 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
@@ -1039,7 +1487,7 @@ impl Runtime {
 
             // TEQI
             (0x01, 0x0C) => {
-                if try_owned_self!(self, self.state.read_register(rs)) == imm.extend_sign() {
+                if try_owned_self!(self, self.state.read_register64(rs)) == imm as i64 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
                     Ok(Ok(self))
@@ -1048,7 +1496,7 @@ impl Runtime {
 
             // TNEI
             (0x01, 0x0E) => {
-                if try_owned_self!(self, self.state.read_register(rs)) != imm.extend_sign() {
+                if try_owned_self!(self, self.state.read_register64(rs)) != imm as i64 {
                     Ok(Err(RuntimeSyscallGuard::Trap(self)))
                 } else {
                     Ok(Ok(self))
@@ -1079,70 +1527,103 @@ impl Runtime {
             0x01 => match rt {
                 // BLTZ $Rs, Im
                 0x00 => {
-                    if self.state.read_register(rs)? < 0 {
+                    if self.state.read_register64(rs)? < 0 {
                         self.state.branch(imm);
                     }
                 }
 
                 // BGEZ $Rs, Im
                 0x01 => {
-                    if self.state.read_register(rs)? >= 0 {
+                    if self.state.read_register64(rs)? >= 0 {
                         self.state.branch(imm);
                     }
                 }
 
                 // BLTZAL $Rs, Im
                 0x10 => {
-                    self.state.write_register(Register::Ra.to_number() as u32, self.state.pc() as _);
+                    self.state.write_register(Register::Ra.to_number() as u32, self.state.link_addr() as _);
 
-                    if self.state.read_register(rs)? < 0 {
+                    if self.state.read_register64(rs)? < 0 {
                         self.state.branch(imm);
                     }
                 }
 
                 // BGEZAL $Rs, Im
                 0x11 => {
-                    self.state.write_register(Register::Ra.to_number() as u32, self.state.pc() as _);
+                    self.state.write_register(Register::Ra.to_number() as u32, self.state.link_addr() as _);
 
-                    if self.state.read_register(rs)? >= 0 {
+                    if self.state.read_register64(rs)? >= 0 {
                         self.state.branch(imm);
                     }
                 }
 
-                // Error
-                _ => todo!(),
-            },
+                // BLTZL $Rs, Im
+                0x02 => {
+                    if self.state.read_register64(rs)? < 0 {
+                        self.state.branch(imm);
+                    }
+                }
+
+                // BGEZL $Rs, Im
+                0x03 => {
+                    if self.state.read_register64(rs)? >= 0 {
+                        self.state.branch(imm);
+                    }
+                }
 
-            // Unused
-            0x02 => {}
+                // BLTZALL $Rs, Im
+                0x12 => {
+                    let rs_val = self.state.read_register64(rs)?;
+                    self.state.write_register(Register::Ra.to_number() as u32, self.state.link_addr() as _);
 
-            // Unused
-            0x03 => {}
+                    if rs_val < 0 {
+                        self.state.branch(imm);
+                    }
+                }
+
+                // BGEZALL $Rs, Im
+                0x13 => {
+                    let rs_val = self.state.read_register64(rs)?;
+                    self.state.write_register(Register::Ra.to_number() as u32, self.state.link_addr() as _);
+
+                    if rs_val >= 0 {
+                        self.state.branch(imm);
+                    }
+                }
+
+                // SYNCI Im($Rs) (there is no instruction cache to synchronise)
+                0x1F => {}
+
+                _ => return Err(self.reserved_instruction()),
+            },
+
+            // J-Type
+            0x02 | 0x03 => unreachable!(),
 
             // BEQ  $Rs, $Rt, Im
             0x04 => {
-                if self.state.read_register(rs)? == self.state.read_register(rt)? {
+                if self.state.read_register64(rs)? == self.state.read_register64(rt)? {
                     self.state.branch(imm);
                 }
             }
 
             // BNE  $Rs, $Rt, Im
             0x05 => {
-                if self.state.read_register(rs)? != self.state.read_register(rt)? {
+                if self.state.read_register64(rs)? != self.state.read_register64(rt)? {
                     self.state.branch(imm);
                 }
             }
 
             // BLEZ $Rs, Im
             0x06 => {
-                if self.state.read_register(rs)? <= 0 {
+                if self.state.read_register64(rs)? <= 0 {
                     self.state.branch(imm);
                 }
             }
 
             // BGTZ $Rs, Im
             0x07 => {
-                if self.state.read_register(rs)? > 0 {
+                if self.state.read_register64(rs)? > 0 {
                     self.state.branch(imm);
                 }
             }
@@ -1159,7 +1640,7 @@ impl Runtime {
 
             // SLTI $Rt, $Rs, Im
             0x0A => {
-                if self.state.read_register(rs)? < imm_sign_extend {
+                if self.state.read_register64(rs)? < imm_sign_extend as i64 {
                     self.state.write_register(rt, 1);
                 } else {
                     self.state.write_register(rt, 0);
@@ -1168,7 +1649,7 @@ impl Runtime {
 
             // SLTIU $Rt, $Rs, Im
             0x0B => {
-                if (self.state.read_register(rs)? as u32) < imm_sign_extend as u32 {
+                if (self.state.read_register64(rs)? as u64) < imm_sign_extend as i64 as u64 {
                     self.state.write_register(rt, 1);
                 } else {
                     self.state.write_register(rt, 0);
@@ -1177,17 +1658,17 @@ impl Runtime {
 
             // ANDI $Rt, $Rs, Im
             0x0C => {
-                self.state.write_register(rt, self.state.read_register(rs)? & imm_zero_extend);
+                self.state.write_register64(rt, self.state.read_register64(rs)? & imm_zero_extend as i64);
             }
 
             // ORI  $Rt, $Rs, Im
             0x0D => {
-                self.state.write_register(rt, self.state.read_register(rs)? | imm_zero_extend);
+                self.state.write_register64(rt, self.state.read_register64(rs)? | imm_zero_extend as i64);
             }
 
             // XORI $Rt, $Rs, Im
             0x0E => {
-                self.state.write_register(rt, self.state.read_register(rs)? ^ imm_zero_extend);
+                self.state.write_register64(rt, self.state.read_register64(rs)? ^ imm_zero_extend as i64);
             }
 
             // LUI  $Rt, Im
@@ -1195,12 +1676,82 @@ impl Runtime {
                 self.state.write_register(rt, imm_zero_extend << 16);
             }
 
-            // Unused
-            0x10..=0x1F => {}
+            // COP0 / COP1
+            0x10 | 0x11 => unreachable!(),
+
+            // COP2 / COP1X (there is no coprocessor 2, nor the indexed FPU instructions)
+            0x12 | 0x13 => return Err(self.reserved_instruction()),
+
+            // BEQL $Rs, $Rt, Im
+            0x14 => {
+                if self.state.read_register64(rs)? == self.state.read_register64(rt)? {
+                    self.state.branch(imm);
+                }
+            }
+
+            // BNEL $Rs, $Rt, Im
+            0x15 => {
+                if self.state.read_register64(rs)? != self.state.read_register64(rt)? {
+                    self.state.branch(imm);
+                }
+            }
+
+            // BLEZL $Rs, Im
+            0x16 => {
+                if self.state.read_register64(rs)? <= 0 {
+                    self.state.branch(imm);
+                }
+            }
+
+            // BGTZL $Rs, Im
+            0x17 => {
+                if self.state.read_register64(rs)? > 0 {
+                    self.state.branch(imm);
+                }
+            }
+
+            // DADDI $Rt, $Rs, Im
+            0x18 => {
+                self.require_mips64()?;
+
+                self.state.write_register64(
+                    rt,
+                    checked_add64(self.state.read_register64(rs)?, imm as i64)?,
+                )
+            }
+
+            // DADDIU $Rt, $Rs, Im
+            0x19 => {
+                self.require_mips64()?;
+
+                self.state
+                    .write_register64(rt, self.state.read_register64(rs)?.wrapping_add(imm as i64))
+            }
+
+            // LDL  $Rt, Im($Rs)
+            0x1A => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.load_part(rt, addr, true, 8)?;
+            }
+
+            // LDR  $Rt, Im($Rs)
+            0x1B => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.load_part(rt, addr, false, 8)?;
+            }
+
+            // Reserved
+            0x1C..=0x1F => return Err(self.reserved_instruction()),
 
             // LB   $Rt, Im($Rs)
             0x20 => {
-                self.state.write_register_uninit(
+                self.state.write_loaded_register(
                     rt,
                     self.state
                         .read_mem_byte_uninit(
@@ -1223,12 +1774,14 @@ impl Runtime {
                     )));
                 }
 
-                self.state.write_register_uninit(rt, self.state.read_mem_half_uninit(addr)?.extend_sign());
+                self.state.write_loaded_register(rt, self.state.read_mem_half_uninit(addr)?.extend_sign());
             }
 
             // LWL  $Rt, Im($Rs)
             0x22 => {
-                todo!();
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.load_part(rt, addr, true, 4)?;
             }
 
             // LW   $Rt, Im($Rs)
@@ -1244,12 +1797,12 @@ impl Runtime {
                     )));
                 }
 
-                self.state.write_register_uninit(rt, self.state.read_mem_word_uninit(addr)?.extend_sign());
+                self.state.write_loaded_register(rt, self.state.read_mem_word_uninit(addr)?.extend_sign());
             }
 
             // LBU  $Rt, Im($Rs)
             0x24 => {
-                self.state.write_register_uninit(
+                self.state.write_loaded_register(
                     rt,
                     self.state
                         .read_mem_byte_uninit(
@@ -1272,16 +1825,36 @@ impl Runtime {
                     )));
                 }
 
-                self.state.write_register_uninit(rt, self.state.read_mem_half_uninit(addr)?.extend_zero());
+                self.state.write_loaded_register(rt, self.state.read_mem_half_uninit(addr)?.extend_zero());
             }
 
             // LWR  $Rt, Im($Rs)
             0x26 => {
-                todo!();
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.load_part(rt, addr, false, 4)?;
             }
 
-            // Unused
-            0x27 => {}
+            // LWU  $Rt, Im($Rs)
+            0x27 => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                if addr % 4 != 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Word,
+                        },
+                    )));
+                }
+
+                self.state.write_loaded_register64(
+                    rt,
+                    self.state.read_mem_word_uninit(addr)?.map(i64::from),
+                );
+            }
 
             // SB   $Rt, Im($Rs)
             0x28 => {
@@ -1307,8 +1880,12 @@ impl Runtime {
                 self.state.write_mem_half_uninit(addr, self.state.read_register_uninit(rt).truncate())?;
             }
 
-            // Unused
-            0x2A => {}
+            // SWL  $Rt, Im($Rs)
+            0x2A => {
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.store_part(rt, addr, true, 4)?;
+            }
 
             // SW   $Rt, Im($Rs)
             0x2B => {
@@ -1326,69 +1903,203 @@ impl Runtime {
                 self.state.write_mem_word_uninit(addr, self.state.read_register_uninit(rt).truncate())?;
             }
 
-            // Unused
-            0x2C => {}
+            // SDL  $Rt, Im($Rs)
+            0x2C => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.store_part(rt, addr, true, 8)?;
+            }
+
+            // SDR  $Rt, Im($Rs)
+            0x2D => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
+
+                self.store_part(rt, addr, false, 8)?;
+            }
 
-            // Unused
-            0x2D => {}
+            // SWR  $Rt, Im($Rs)
+            0x2E => {
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as u32;
 
-            // Unused
-            0x2E => {}
+                self.store_part(rt, addr, false, 4)?;
+            }
 
-            // Unused
+            // CACHE Op, Im($Rs) (there are no caches to operate on)
             0x2F => {}
 
-            // Unused
-            0x30 => {}
+            // LL   $Rt, Im($Rs)
+            0x30 => {
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                self.load_linked(rt, addr, 4)?;
+            }
 
-            // LWC1 $Rt, Im($Rs)
+            // LWC1 $Ft, Im($Rs)
             0x31 => {
-                todo!()
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                if addr % 4 != 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Word,
+                        },
+                    )));
+                }
+
+                self.state.write_fpu_register_uninit(rt, self.state.read_mem_word_uninit(addr)?);
             }
 
-            // Unused
-            0x32 => {}
+            // Reserved
+            0x32 => return Err(self.reserved_instruction()),
 
-            // Unused
+            // PREF Hint, Im($Rs)
             0x33 => {}
 
-            // Unused
-            0x34 => {}
+            // LLD  $Rt, Im($Rs)
+            0x34 => {
+                self.require_mips64()?;
 
-            // Unused
-            0x35 => {}
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
 
-            // Unused
-            0x36 => {}
+                self.load_linked(rt, addr, 8)?;
+            }
 
-            // Unused
-            0x37 => {}
+            // LDC1 $Ft, Im($Rs)
+            0x35 => {
+                let addr: u32 = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
 
-            // Unused
-            0x38 => {}
+                if !addr.is_multiple_of(8) {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Double,
+                        },
+                    )));
+                }
+
+                let (lo_addr, hi_addr) = self.double_word_addrs(addr);
+                let lo = self.state.read_mem_word_uninit(lo_addr)?;
+                let hi = self.state.read_mem_word_uninit(hi_addr)?;
+
+                self.state.write_fpu_register_uninit(rt & !1, lo);
+                self.state.write_fpu_register_uninit(rt | 1, hi);
+            }
+
+            // Reserved
+            0x36 => return Err(self.reserved_instruction()),
+
+            // LD   $Rt, Im($Rs)
+            0x37 => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
 
-            // SWC1 $Rt, Im($Rs)
+                if addr % 8 != 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Double,
+                        },
+                    )));
+                }
+
+                self.state.write_loaded_register64(
+                    rt,
+                    self.state
+                        .read_mem_dword_uninit(addr)?
+                        .map(|value| value as i64),
+                );
+            }
+
+            // SC   $Rt, Im($Rs)
+            0x38 => {
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                self.store_conditional(rt, addr, 4)?;
+            }
+
+            // SWC1 $Ft, Im($Rs)
             0x39 => {
-                todo!()
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                if addr % 4 != 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Word,
+                        },
+                    )));
+                }
+
+                self.state.write_mem_word_uninit(addr, self.state.fpu_registers()[rt as usize])?;
             }
 
-            // Unused
-            0x3A => {}
+            // Reserved
+            0x3A => return Err(self.reserved_instruction()),
 
-            // Unused
-            0x3B => {}
+            // Reserved
+            0x3B => return Err(self.reserved_instruction()),
 
-            // Unused
-            0x3C => {}
+            // SCD  $Rt, Im($Rs)
+            0x3C => {
+                self.require_mips64()?;
 
-            // Unused
-            0x3D => {}
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
 
-            // Unused
-            0x3E => {}
+                self.store_conditional(rt, addr, 8)?;
+            }
 
-            // Unused
-            0x3F => {}
+            // SDC1 $Ft, Im($Rs)
+            0x3D => {
+                let addr: u32 = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                if !addr.is_multiple_of(8) {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Double,
+                        },
+                    )));
+                }
+
+                let registers = self.state.fpu_registers();
+                let (lo, hi) = (registers[(rt & !1) as usize], registers[(rt | 1) as usize]);
+
+                let (lo_addr, hi_addr) = self.double_word_addrs(addr);
+                self.state.write_mem_word_uninit(lo_addr, lo)?;
+                self.state.write_mem_word_uninit(hi_addr, hi)?;
+            }
+
+            // Reserved
+            0x3E => return Err(self.reserved_instruction()),
+
+            // SD   $Rt, Im($Rs)
+            0x3F => {
+                self.require_mips64()?;
+
+                let addr = self.state.read_register(rs)?.wrapping_add(imm_sign_extend) as _;
+
+                if addr % 8 != 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(
+                        Error::UnalignedAccess {
+                            addr,
+                            alignment_requirement: AlignmentRequirement::Double,
+                        },
+                    )));
+                }
+
+                self.state.write_mem_dword_uninit(
+                    addr,
+                    self.state
+                        .read_register64_uninit(rt)
+                        .map(|value| value as u64),
+                )?;
+            }
 
             // Doesn't fit in 6 bits
             _ => unreachable!(),
@@ -1399,30 +2110,181 @@ impl Runtime {
 
 
     fn execute_j(&mut self, opcode: u32, target: u32) {
-        if (self.state.pc() & 0xF000_0000) | (target << 2) == PRINTF_ADDR {
-            let fmt_addr = self.state.read_register(Register::A0.to_u32()).unwrap();
-            let fmt_str_vec = &self.state.read_mem_string(fmt_addr as u32).unwrap();
-            let fmt = from_utf8(fmt_str_vec).unwrap();
-            println!("fmt -> {}", fmt);
-
-            let value = self.state.read_register(Register::A1.to_u32()).unwrap();
-            println!("value -> {}", value);
-            return;
-        }
-
         match opcode {
             // J    addr
-            0x02 => self.state.set_pc((self.state.pc() & 0xF000_0000) | (target << 2)),
+            0x02 => self.state.jump((self.state.pc() & 0xF000_0000) | (target << 2)),
 
             // JAL  addr
             0x03 => {
-                self.state.write_register(Register::Ra.to_number() as u32, self.state.pc() as _);
-                self.state.set_pc((self.state.pc() & 0xF000_0000) | (target << 2));
+                self.state.write_register(Register::Ra.to_number() as u32, self.state.link_addr() as _);
+                self.state.jump((self.state.pc() & 0xF000_0000) | (target << 2));
+            }
+
+            // JALX addr (into microMIPS code)
+            0x1D => {
+                self.state.write_register(Register::Ra.to_number() as u32, self.state.link_addr() as _);
+                self.state.jump((self.state.pc() & 0xF000_0000) | (target << 2) | 1);
             }
 
             _ => unreachable!(),
         }
     }
+
+    /// The error for an instruction that doesn't encode anything (or anything rush implements),
+    /// which becomes a reserved instruction exception if the program has a handler.
+    fn reserved_instruction(&self) -> RushError {
+        RushError::Runtime(RuntimeError::new(Error::ReservedInstruction {
+            addr: self.state.inst_addr,
+            inst: self.state.inst,
+        }))
+    }
+
+    /// The doubleword instructions only exist on a 64-bit processor (see `RushConfig::mips64`).
+    fn require_mips64(&self) -> RushResult<()> {
+        match self.state.mips64 {
+            true => Ok(()),
+            false => Err(self.reserved_instruction()),
+        }
+    }
+
+    /// LL (a `size` of 4) / LLD (8): loads `$Rt` from `addr`, and sets the LL bit
+    /// that the `sc` or `scd` after it needs to succeed.
+    fn load_linked(&mut self, rt: u32, addr: u32, size: u32) -> RushResult<()> {
+        self.check_alignment(addr, size)?;
+
+        match size {
+            4 => self.state.write_loaded_register(rt, self.state.read_mem_word_uninit(addr)?.extend_sign()),
+            _ => self.state.write_loaded_register64(
+                rt,
+                self.state.read_mem_dword_uninit(addr)?.map(|value| value as i64),
+            ),
+        }
+        self.state.ll_bit = true;
+
+        Ok(())
+    }
+
+    /// SC (a `size` of 4) / SCD (8): stores `$Rt` to `addr` if the LL bit is still set,
+    /// and sets `$Rt` to whether it was.
+    fn store_conditional(&mut self, rt: u32, addr: u32, size: u32) -> RushResult<()> {
+        self.check_alignment(addr, size)?;
+
+        // with a single processor, this only fails if there's been an `sc` or `eret` since the `ll`
+        if self.state.ll_bit {
+            match size {
+                4 => self.state.write_mem_word_uninit(addr, self.state.read_register_uninit(rt).truncate())?,
+                _ => self.state.write_mem_dword_uninit(
+                    addr,
+                    self.state.read_register64_uninit(rt).map(|value| value as u64),
+                )?,
+            }
+        }
+
+        self.state.write_register(rt, self.state.ll_bit as i32);
+        self.state.ll_bit = false;
+
+        Ok(())
+    }
+
+    /// Raises an unaligned access unless `addr` is a multiple of `size` (4 or 8).
+    fn check_alignment(&self, addr: u32, size: u32) -> RushResult<()> {
+        if addr.is_multiple_of(size) {
+            return Ok(());
+        }
+
+        Err(RushError::Runtime(RuntimeError::new(Error::UnalignedAccess {
+            addr,
+            alignment_requirement: match size {
+                4 => AlignmentRequirement::Word,
+                _ => AlignmentRequirement::Double,
+            },
+        })))
+    }
+
+    /// LWL (`left`) / LWR, and LDL / LDR with a `size` of 8: merges the bytes from `addr` to
+    /// the least (left) or most (right) significant end of its aligned word (or doubleword)
+    /// into the most (left) or least (right) significant bytes of `$Rt`, which for a word
+    /// are then sign-extended.
+    /// A load still on its way to `$Rt` is merged with, as on the R2000, so that a pair
+    /// needs no nop between them. An uninitialised `$Rt` is merged with as if it were 0.
+    fn load_part(&mut self, rt: u32, addr: u32, left: bool, size: u32) -> RushResult<()> {
+        let (base, significance) = (addr & !(size - 1), self.byte_significance(addr, size));
+
+        let current = match self.state.delayed_load.filter(|load| load.reg_num == rt) {
+            Some(load) => load.value,
+            None => self.state.read_register64_uninit(rt),
+        };
+        let mut bytes = current.into_option().unwrap_or(0).to_le_bytes().map(Safe::Valid);
+
+        for i in Self::part_bytes(significance, left, size) {
+            let byte = self.state.read_mem_byte_uninit(base + self.byte_significance(i, size))?;
+            bytes[Self::part_shift(i, significance, left, size) as usize] = byte;
+        }
+
+        let value = match bytes[..size as usize].iter().all(|byte| byte.as_option().is_some()) {
+            true => Safe::Valid(i64::from_le_bytes(bytes.map(|byte| byte.into_option().unwrap_or(0)))),
+            false => Safe::Uninitialised,
+        };
+        let value = match size {
+            4 => value.map(|value| value as i32 as i64),
+            _ => value,
+        };
+        self.state.write_loaded_register64(rt, value);
+
+        Ok(())
+    }
+
+    /// SWL (`left`) / SWR, and SDL / SDR: the reverse of `load_part`, storing the most (left)
+    /// or least (right) significant bytes of `$Rt` up to or from `addr` in its aligned word.
+    fn store_part(&mut self, rt: u32, addr: u32, left: bool, size: u32) -> RushResult<()> {
+        let (base, significance) = (addr & !(size - 1), self.byte_significance(addr, size));
+
+        let bytes = match self.state.read_register64_uninit(rt) {
+            Safe::Valid(value) => value.to_le_bytes().map(Safe::Valid),
+            Safe::Uninitialised => [Safe::Uninitialised; 8],
+        };
+
+        for i in Self::part_bytes(significance, left, size) {
+            let byte = bytes[Self::part_shift(i, significance, left, size) as usize];
+            self.state.write_mem_byte_uninit(base + self.byte_significance(i, size), byte)?;
+        }
+
+        Ok(())
+    }
+
+    /// Where the low and high words of the double at `addr` are, for LDC1 and SDC1.
+    fn double_word_addrs(&self, addr: u32) -> (u32, u32) {
+        match self.state.endianness {
+            Endianness::Little => (addr, addr.wrapping_add(4)),
+            Endianness::Big => (addr.wrapping_add(4), addr),
+        }
+    }
+
+    /// How significant the byte at `addr` is within its aligned word (or doubleword),
+    /// from 0 (least) to `size - 1`. The mapping is its own inverse, so it also gives
+    /// a byte's offset from its significance.
+    fn byte_significance(&self, addr: u32, size: u32) -> u32 {
+        match self.state.endianness {
+            Endianness::Little => addr & (size - 1),
+            Endianness::Big => size - 1 - (addr & (size - 1)),
+        }
+    }
+
+    /// The significances of the memory bytes a left or right partial access touches.
+    fn part_bytes(significance: u32, left: bool, size: u32) -> std::ops::RangeInclusive<u32> {
+        match left {
+            true => 0..=significance,
+            false => significance..=size - 1,
+        }
+    }
+
+    /// Where the memory byte of significance `i` lands in the register.
+    fn part_shift(i: u32, significance: u32, left: bool, size: u32) -> u32 {
+        match left {
+            true => i + size - 1 - significance,
+            false => i - significance,
+        }
+    }
 }
 
 pub type SteppedRuntime = Result<Runtime, RuntimeSyscallGuard>;
@@ -1503,7 +2365,7 @@ pub struct ExitStatusArgs {
     pub exit_code: i32,
 }
 
-pub(self) trait SafeToUninitResult {
+trait SafeToUninitResult {
     type Output;
 
     fn to_result(&self, value_type: Uninitialised) -> RushResult<Self::Output>;
@@ -1734,39 +2596,115 @@ impl Truncate<Safe<u32>> for Safe<i32> {
     }
 }
 
+impl RuntimeSyscallGuard {
+    /// Applies `f` to the runtime, once the syscall has been handled.
+    fn map_runtime(self, f: fn(&mut Runtime)) -> Self {
+        use RuntimeSyscallGuard::*;
+
+        let with = move |mut runtime: Runtime| {
+            f(&mut runtime);
+            runtime
+        };
+
+        match self {
+            PrintInt(args, runtime) => PrintInt(args, with(runtime)),
+            PrintFloat(args, runtime) => PrintFloat(args, with(runtime)),
+            PrintDouble(args, runtime) => PrintDouble(args, with(runtime)),
+            PrintString(args, runtime) => PrintString(args, with(runtime)),
+            ReadInt(guard) => ReadInt(Box::new(move |value| with(guard(value)))),
+            ReadFloat(guard) => ReadFloat(Box::new(move |value| with(guard(value)))),
+            ReadDouble(guard) => ReadDouble(Box::new(move |value| with(guard(value)))),
+            ReadString(args, guard) => ReadString(args, Box::new(move |value| with(guard(value)))),
+            Sbrk(args, runtime) => Sbrk(args, with(runtime)),
+            Exit(runtime) => Exit(with(runtime)),
+            PrintChar(args, runtime) => PrintChar(args, with(runtime)),
+            ReadChar(guard) => ReadChar(Box::new(move |value| with(guard(value)))),
+            Open(args, guard) => Open(args, Box::new(move |value| with(guard(value)))),
+            Read(args, guard) => Read(args, Box::new(move |value| with(guard(value)))),
+            Write(args, guard) => Write(args, Box::new(move |value| with(guard(value)))),
+            Close(args, guard) => Close(args, Box::new(move |value| with(guard(value)))),
+            ExitStatus(args, runtime) => ExitStatus(args, with(runtime)),
+            Breakpoint(runtime) => Breakpoint(with(runtime)),
+            Trap(runtime) => Trap(with(runtime)),
+        }
+    }
+}
+
 impl Runtime {
-    pub fn new(binary: &Binary, rush_config: RushConfig) -> Self {
-        let mut initial_state = State::new(&rush_config);
+    /// A runtime with `binary` loaded, ready to run from its entry point. Fails if the config's devices
+    /// can't be set up, e.g. because a UART's files can't be opened.
+    pub fn new(binary: &Binary, mut rush_config: RushConfig) -> Result<Self, LoaderError> {
+        // (the ELF says which way round it is, and devices holding memory need to know too)
+        rush_config.endianness = Some(binary.endian);
+        let mut initial_state = State::new(&rush_config)?;
+
+        for segment in &binary.segments {
+            Self::fill_state(segment.addr, &segment.bytes, &mut initial_state);
+        }
 
-        Self::fill_all_state(rush_config.memory.text.start, &binary.text, &mut initial_state);
-        Self::fill_valid_state(rush_config.memory.data_bot, &binary.data, &mut initial_state);
+        // an odd entry point (as microMIPS ELFs have) starts in microMIPS mode
+        initial_state.enter(binary.entry);
+
+        if let Some((text_start, text_end)) = binary.text_bounds() {
+            rush_config.memory.text.start = text_start;
+            rush_config.memory.text.end = text_end;
+        }
 
         initial_state.write_register(Register::Sp.to_number() as _, (rush_config.memory.stack.top - 0x30) as _);
         initial_state.write_register(Register::Fp.to_number() as _, (rush_config.memory.stack.top - 0x30) as _);
         initial_state.write_register(Register::Gp.to_number() as _, rush_config.memory.global.ptr as _);
 
-        Self {
+        Ok(Self {
             state: initial_state,
             memory: rush_config.memory,
-            system_clock: SystemClock::new(),
-        }
+            system_clock: SystemClock::new(&rush_config.timing),
+            icache: rush_config.cache.icache.as_ref().map(Cache::new),
+            dcache: rush_config.cache.dcache.as_ref().map(Cache::new),
+        })
     }
 
-    fn fill_all_state(mut starting_addr: u32, data: &[Safe<u8>], state: &mut State) {
-        for &byte in data {
-            state.write_mem_byte_uninit(starting_addr, byte).unwrap();
-            starting_addr += 1;
+    /// Segments are placed wherever the binary asks for them,
+    /// so this deliberately bypasses the segfault checks.
+    fn fill_state(starting_addr: u32, data: &[Safe<u8>], state: &mut State) {
+        for (offset, &byte) in data.iter().enumerate() {
+            state.write_mem_byte_uninit_unchecked(starting_addr.wrapping_add(offset as u32), byte);
         }
     }
+}
 
-    fn fill_valid_state(mut starting_addr: u32, data: &[Safe<u8>], state: &mut State) {
-        for &byte in data {
-            if let Safe::Valid(byte) = byte {
-                state.write_mem_byte(starting_addr, byte).unwrap();
-            }
+/// Whether `inst` is a branch or jump, and so has a delay slot (whether or not it's taken).
+fn is_branch(inst: u32, isa: IsaRevision) -> bool {
+    if isa == IsaRevision::R6 {
+        return r6::is_branch(inst);
+    }
 
-            starting_addr += 1;
-        }
+    match inst >> 26 {
+        SPECIAL => matches!(inst & 0x3F, 0x08 | 0x09),
+        REGIMM => matches!((inst >> 16) & 0x1F, 0x00..=0x03 | 0x10..=0x13),
+        JUMP | JAL | JALX | 0x04..=0x07 | 0x14..=0x17 => true,
+        COP1 => (inst >> 21) & 0x1F == 0x08,
+        _ => false,
+    }
+}
+
+/// The cycles `inst` takes to issue, before any stalls.
+fn issue_cycles(inst: u32, isa: IsaRevision, costs: &CycleCosts) -> u64 {
+    match (inst >> 26, inst & 0x3F) {
+        (SPECIAL, 0x0C) => costs.syscall,
+        // (release 6 reuses these for `mul`, `muh`, `div`, `mod`, ...)
+        (SPECIAL, 0x18 | 0x19 | 0x1C | 0x1D) => costs.mult,
+        (SPECIAL, 0x1A | 0x1B | 0x1E | 0x1F) => costs.div,
+        (SPECIAL2, 0x00..=0x02 | 0x04 | 0x05) if isa == IsaRevision::R2 => costs.mult,
+        _ => 1,
+    }
+}
+
+/// Whether `inst` is a branch likely, whose delay slot is only executed if it's taken.
+fn is_branch_likely(inst: u32) -> bool {
+    match inst >> 26 {
+        REGIMM => matches!((inst >> 16) & 0x1F, 0x02 | 0x03 | 0x12 | 0x13),
+        0x14..=0x17 => true,
+        _ => false,
     }
 }
 
@@ -1787,3 +2725,21 @@ fn checked_sub(x: i32, y: i32) -> RushResult<i32> {
         ))),
     }
 }
+
+fn checked_add64(x: i64, y: i64) -> RushResult<i64> {
+    match x.checked_add(y) {
+        Some(z) => Ok(z),
+        None => Err(RushError::Runtime(RuntimeError::new(
+            Error::IntegerOverflow,
+        ))),
+    }
+}
+
+fn checked_sub64(x: i64, y: i64) -> RushResult<i64> {
+    match x.checked_sub(y) {
+        Some(z) => Ok(z),
+        None => Err(RushError::Runtime(RuntimeError::new(
+            Error::IntegerOverflow,
+        ))),
+    }
+}
@@ -0,0 +1,666 @@
+use super::{
+    cop1::{self, FMT_D, FMT_S},
+    ExtendSign, Runtime, COP1, JAL, JUMP, REGIMM, SPECIAL, SPECIAL2, SPECIAL3,
+};
+use crate::{error::runtime::Error, Register, RushError, RushResult, RuntimeError, Safe};
+
+/// The `fmt`s that Release 6's `cmp.cond.fmt` uses for singles and doubles.
+const FMT_CMP_S: u32 = 0x14;
+const FMT_CMP_D: u32 = 0x15;
+
+impl Runtime {
+    /// Executes `inst` if Release 6 decodes it differently to Release 2, returning whether it did.
+    /// What Release 6 removed (without reusing the encoding) is a reserved instruction,
+    /// and everything else is left to the Release 2 decoder.
+    pub(super) fn execute_r6(&mut self, inst: u32) -> RushResult<bool> {
+        let opcode = inst >> 26;
+        let rs = (inst >> 21) & 0x1F;
+        let rt = (inst >> 16) & 0x1F;
+        let rd = (inst >> 11) & 0x1F;
+        let shamt = (inst >> 6) & 0x1F;
+        let funct = inst & 0x3F;
+        let imm = inst as i16;
+
+        match opcode {
+            SPECIAL => self.execute_r6_special(funct, rd, rs, rt, shamt),
+
+            // all of SPECIAL2 was removed, `clz` and `clo` live in SPECIAL instead
+            SPECIAL2 => Err(self.reserved_instruction()),
+
+            SPECIAL3 => self.execute_r6_special3(inst, funct, rd, rs, rt, shamt),
+
+            REGIMM => self.execute_r6_regimm(rs, rt, imm),
+
+            // the fmt field sits where rs would, and fd where shamt would
+            COP1 => self.execute_r6_cop1(funct, rd, rs, rt, shamt, imm),
+
+            _ => self.execute_r6_i(inst, opcode, rs, rt, imm),
+        }
+    }
+
+    fn execute_r6_special(&mut self, funct: u32, rd: u32, rs: u32, rt: u32, shamt: u32) -> RushResult<bool> {
+        match (funct, shamt) {
+            // LSA  $Rd, $Rs, $Rt, Sa (which encodes the shift less one)
+            (0x05, 0x00..=0x03) => {
+                let shifted = self.state.read_register(rs)? << (shamt + 1);
+
+                self.state.write_register(rd, shifted.wrapping_add(self.state.read_register(rt)?));
+            }
+
+            // JR (now `jalr $zero`), MOVZ, MOVN
+            (0x08 | 0x0A | 0x0B, _) => return Err(self.reserved_instruction()),
+
+            // MFHI, MTHI, MFLO, MTLO (there is no HI or LO)
+            (0x10 | 0x11, 0x00) | (0x12 | 0x13, _) => return Err(self.reserved_instruction()),
+
+            // DLSA $Rd, $Rs, $Rt, Sa
+            (0x15, 0x00..=0x03) => {
+                self.require_mips64()?;
+
+                let shifted = self.state.read_register64(rs)? << (shamt + 1);
+                self.state.write_register64(rd, shifted.wrapping_add(self.state.read_register64(rt)?));
+            }
+
+            // MUL  $Rd, $Rs, $Rt / MUH  $Rd, $Rs, $Rt
+            (0x18, 0x02 | 0x03) => {
+                let product = self.state.read_register(rs)? as i64 * self.state.read_register(rt)? as i64;
+
+                self.state.write_register(rd, if shamt == 0x02 { product as i32 } else { (product >> 32) as i32 });
+            }
+
+            // MULU $Rd, $Rs, $Rt / MUHU $Rd, $Rs, $Rt
+            (0x19, 0x02 | 0x03) => {
+                let product = self.state.read_register(rs)? as u32 as u64 * self.state.read_register(rt)? as u32 as u64;
+
+                self.state.write_register(rd, if shamt == 0x02 { product as i32 } else { (product >> 32) as i32 });
+            }
+
+            // DIV  $Rd, $Rs, $Rt / MOD  $Rd, $Rs, $Rt
+            (0x1A, 0x02 | 0x03) => {
+                let rs_val = self.state.read_register(rs)?;
+                let rt_val = self.state.read_register(rt)?;
+
+                if rt_val == 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(Error::DivisionByZero)));
+                }
+
+                self.state.write_register(
+                    rd,
+                    if shamt == 0x02 { rs_val.wrapping_div(rt_val) } else { rs_val.wrapping_rem(rt_val) },
+                );
+            }
+
+            // DIVU $Rd, $Rs, $Rt / MODU $Rd, $Rs, $Rt
+            (0x1B, 0x02 | 0x03) => {
+                let rs_val = self.state.read_register(rs)? as u32;
+                let rt_val = self.state.read_register(rt)? as u32;
+
+                if rt_val == 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(Error::DivisionByZero)));
+                }
+
+                self.state.write_register(
+                    rd,
+                    if shamt == 0x02 { (rs_val / rt_val) as i32 } else { (rs_val % rt_val) as i32 },
+                );
+            }
+
+            // DMUL $Rd, $Rs, $Rt / DMUH $Rd, $Rs, $Rt
+            (0x1C, 0x02 | 0x03) => {
+                self.require_mips64()?;
+
+                let product = self.state.read_register64(rs)? as i128 * self.state.read_register64(rt)? as i128;
+                self.state.write_register64(rd, if shamt == 0x02 { product as i64 } else { (product >> 64) as i64 });
+            }
+
+            // DMULU $Rd, $Rs, $Rt / DMUHU $Rd, $Rs, $Rt
+            (0x1D, 0x02 | 0x03) => {
+                self.require_mips64()?;
+
+                let product = self.state.read_register64(rs)? as u64 as u128 * self.state.read_register64(rt)? as u64 as u128;
+                self.state.write_register64(rd, if shamt == 0x02 { product as i64 } else { (product >> 64) as i64 });
+            }
+
+            // DDIV $Rd, $Rs, $Rt / DMOD $Rd, $Rs, $Rt
+            (0x1E, 0x02 | 0x03) => {
+                self.require_mips64()?;
+
+                let rs_val = self.state.read_register64(rs)?;
+                let rt_val = self.state.read_register64(rt)?;
+
+                if rt_val == 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(Error::DivisionByZero)));
+                }
+
+                self.state.write_register64(
+                    rd,
+                    if shamt == 0x02 { rs_val.wrapping_div(rt_val) } else { rs_val.wrapping_rem(rt_val) },
+                );
+            }
+
+            // DDIVU $Rd, $Rs, $Rt / DMODU $Rd, $Rs, $Rt
+            (0x1F, 0x02 | 0x03) => {
+                self.require_mips64()?;
+
+                let rs_val = self.state.read_register64(rs)? as u64;
+                let rt_val = self.state.read_register64(rt)? as u64;
+
+                if rt_val == 0 {
+                    return Err(RushError::Runtime(RuntimeError::new(Error::DivisionByZero)));
+                }
+
+                self.state.write_register64(
+                    rd,
+                    if shamt == 0x02 { (rs_val / rt_val) as i64 } else { (rs_val % rt_val) as i64 },
+                );
+            }
+
+            // MULT, MULTU, DIV, DIVU and their doubleword versions (which used HI and LO)
+            (0x18..=0x1F, _) => return Err(self.reserved_instruction()),
+
+            // SELEQZ $Rd, $Rs, $Rt
+            (0x35, 0x00) => {
+                let value = match self.state.read_register64(rt)? {
+                    0 => self.state.read_register64(rs)?,
+                    _ => 0,
+                };
+                self.state.write_register64(rd, value);
+            }
+
+            // SELNEZ $Rd, $Rs, $Rt
+            (0x37, 0x00) => {
+                let value = match self.state.read_register64(rt)? {
+                    0 => 0,
+                    _ => self.state.read_register64(rs)?,
+                };
+                self.state.write_register64(rd, value);
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn execute_r6_special3(
+        &mut self,
+        inst: u32,
+        funct: u32,
+        rd: u32,
+        rs: u32,
+        rt: u32,
+        shamt: u32,
+    ) -> RushResult<bool> {
+        // LL, SC and friends moved here, with a 9-bit offset
+        let offset = (inst as i32) << 16 >> 23;
+        let addr = |runtime: &Runtime| -> RushResult<u32> {
+            Ok(runtime.state.read_register(rs)?.wrapping_add(offset) as u32)
+        };
+
+        match (funct, shamt) {
+            // BITSWAP $Rd, $Rt
+            (0x20, 0x00) => {
+                let value = self.state.read_register(rt)? as u32;
+
+                self.state.write_register(rd, value.reverse_bits().swap_bytes() as i32);
+            }
+
+            // ALIGN $Rd, $Rs, $Rt, Bp
+            (0x20, 0x08..=0x0B) => {
+                let (rs_val, rt_val) = (self.state.read_register(rs)? as u32, self.state.read_register(rt)? as u32);
+                let shift = (shamt & 0b11) * 8;
+
+                let value = match shift {
+                    0 => rt_val,
+                    _ => rt_val << shift | rs_val >> (32 - shift),
+                };
+                self.state.write_register(rd, value as i32);
+            }
+
+            // CACHE Op, Im($Rs) / PREF Hint, Im($Rs)
+            (0x25 | 0x35, _) => {}
+
+            // SC   $Rt, Im($Rs)
+            (0x26, _) => {
+                let addr = addr(self)?;
+                self.store_conditional(rt, addr, 4)?;
+            }
+
+            // SCD  $Rt, Im($Rs)
+            (0x27, _) => {
+                self.require_mips64()?;
+
+                let addr = addr(self)?;
+                self.store_conditional(rt, addr, 8)?;
+            }
+
+            // LL   $Rt, Im($Rs)
+            (0x36, _) => {
+                let addr = addr(self)?;
+                self.load_linked(rt, addr, 4)?;
+            }
+
+            // LLD  $Rt, Im($Rs)
+            (0x37, _) => {
+                self.require_mips64()?;
+
+                let addr = addr(self)?;
+                self.load_linked(rt, addr, 8)?;
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn execute_r6_regimm(&mut self, rs: u32, rt: u32, imm: i16) -> RushResult<bool> {
+        match rt {
+            // the branch likelies, and the trap immediates
+            0x02 | 0x03 | 0x12 | 0x13 | 0x08..=0x0C | 0x0E => return Err(self.reserved_instruction()),
+
+            // BLTZAL and BGEZAL are only left as NAL and BAL, with an rs of $zero
+            0x10 | 0x11 if rs != 0 => return Err(self.reserved_instruction()),
+
+            // DAHI $Rs, Im
+            0x06 => {
+                self.require_mips64()?;
+
+                let value = self.state.read_register64(rs)?.wrapping_add((imm as i64) << 32);
+                self.state.write_register64(rs, value);
+            }
+
+            // DATI $Rs, Im
+            0x1E => {
+                self.require_mips64()?;
+
+                let value = self.state.read_register64(rs)?.wrapping_add((imm as i64) << 48);
+                self.state.write_register64(rs, value);
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    fn execute_r6_i(&mut self, inst: u32, opcode: u32, rs: u32, rt: u32, imm: i16) -> RushResult<bool> {
+        let offset = imm as i32;
+
+        match opcode {
+            // BLEZ $Rs, Im is unchanged
+            0x06 if rt == 0 => return Ok(false),
+
+            0x06 => {
+                let rt_val = self.state.read_register64(rt)?;
+
+                match rs {
+                    // BLEZALC $Rt, Im
+                    0 => self.compact_branch_link(rt_val <= 0, offset),
+
+                    // BGEZALC $Rt, Im
+                    _ if rs == rt => self.compact_branch_link(rt_val >= 0, offset),
+
+                    // BGEUC $Rs, $Rt, Im
+                    _ => self.compact_branch(self.state.read_register64(rs)? as u64 >= rt_val as u64, offset),
+                }
+            }
+
+            // BGTZ $Rs, Im is unchanged
+            0x07 if rt == 0 => return Ok(false),
+
+            0x07 => {
+                let rt_val = self.state.read_register64(rt)?;
+
+                match rs {
+                    // BGTZALC $Rt, Im
+                    0 => self.compact_branch_link(rt_val > 0, offset),
+
+                    // BLTZALC $Rt, Im
+                    _ if rs == rt => self.compact_branch_link(rt_val < 0, offset),
+
+                    // BLTUC $Rs, $Rt, Im
+                    _ => self.compact_branch((self.state.read_register64(rs)? as u64) < rt_val as u64, offset),
+                }
+            }
+
+            // in place of ADDI
+            0x08 => {
+                let rt_val = self.state.read_register64(rt)?;
+
+                match rs {
+                    // BOVC $Rs, $Rt, Im
+                    _ if rs >= rt => {
+                        let rs_val = self.state.read_register64(rs)?;
+
+                        self.compact_branch((rs_val as i32).checked_add(rt_val as i32).is_none(), offset);
+                    }
+
+                    // BEQZALC $Rt, Im
+                    0 => self.compact_branch_link(rt_val == 0, offset),
+
+                    // BEQC $Rs, $Rt, Im
+                    _ => self.compact_branch(self.state.read_register64(rs)? == rt_val, offset),
+                }
+            }
+
+            // AUI  $Rt, $Rs, Im (LUI when rs is $zero)
+            0x0F if rs != 0 => {
+                let value = self.state.read_register(rs)?.wrapping_add((imm as i32) << 16);
+
+                self.state.write_register(rt, value);
+            }
+
+            // BEQL, BNEL
+            0x14 | 0x15 => return Err(self.reserved_instruction()),
+
+            // in place of BLEZL
+            0x16 | 0x17 if rt == 0 => return Err(self.reserved_instruction()),
+
+            0x16 => {
+                let rt_val = self.state.read_register64(rt)?;
+
+                match rs {
+                    // BLEZC $Rt, Im
+                    0 => self.compact_branch(rt_val <= 0, offset),
+
+                    // BGEZC $Rt, Im
+                    _ if rs == rt => self.compact_branch(rt_val >= 0, offset),
+
+                    // BGEC $Rs, $Rt, Im
+                    _ => self.compact_branch(self.state.read_register64(rs)? >= rt_val, offset),
+                }
+            }
+
+            // in place of BGTZL
+            0x17 => {
+                let rt_val = self.state.read_register64(rt)?;
+
+                match rs {
+                    // BGTZC $Rt, Im
+                    0 => self.compact_branch(rt_val > 0, offset),
+
+                    // BLTZC $Rt, Im
+                    _ if rs == rt => self.compact_branch(rt_val < 0, offset),
+
+                    // BLTC $Rs, $Rt, Im
+                    _ => self.compact_branch(self.state.read_register64(rs)? < rt_val, offset),
+                }
+            }
+
+            // in place of DADDI
+            0x18 => {
+                let rt_val = self.state.read_register64(rt)?;
+
+                match rs {
+                    // BNVC $Rs, $Rt, Im
+                    _ if rs >= rt => {
+                        let rs_val = self.state.read_register64(rs)?;
+
+                        self.compact_branch((rs_val as i32).checked_add(rt_val as i32).is_some(), offset);
+                    }
+
+                    // BNEZALC $Rt, Im
+                    0 => self.compact_branch_link(rt_val != 0, offset),
+
+                    // BNEC $Rs, $Rt, Im
+                    _ => self.compact_branch(self.state.read_register64(rs)? != rt_val, offset),
+                }
+            }
+
+            // LDL, LDR
+            0x1A | 0x1B => return Err(self.reserved_instruction()),
+
+            // DAUI $Rt, $Rs, Im
+            0x1D if rs != 0 => {
+                self.require_mips64()?;
+
+                let value = self.state.read_register64(rs)?.wrapping_add((imm as i64) << 16);
+                self.state.write_register64(rt, value);
+            }
+
+            // JALX (there's no microMIPS mode to switch to)
+            0x1D => return Err(self.reserved_instruction()),
+
+            // LWL, LWR, SWL, SWR, SDL, SDR, CACHE, LL, PREF, LLD, SC, SCD
+            0x22 | 0x26 | 0x2A | 0x2E | 0x2C | 0x2D | 0x2F | 0x30 | 0x33 | 0x34 | 0x38 | 0x3C => {
+                return Err(self.reserved_instruction())
+            }
+
+            // BC   Im
+            0x32 => self.compact_branch(true, sign_extend(inst, 26)),
+
+            // BALC Im
+            0x3A => self.compact_branch_link(true, sign_extend(inst, 26)),
+
+            0x36 => match rs {
+                // JIC  $Rt, Im
+                0 => self.state.set_pc((self.state.read_register(rt)? as u32).wrapping_add(offset as u32)),
+
+                // BEQZC $Rs, Im
+                _ => self.compact_branch(self.state.read_register64(rs)? == 0, sign_extend(inst, 21)),
+            },
+
+            // PC-relative, where the PC is that of the instruction itself
+            0x3B => {
+                let pc = self.state.inst_addr;
+
+                match rt {
+                    // ADDIUPC $Rs, Im
+                    0x00..=0x07 => {
+                        self.state.write_register(rs, pc.wrapping_add((sign_extend(inst, 19) << 2) as u32) as i32);
+                    }
+
+                    // LWPC $Rs, Im
+                    0x08..=0x0F => {
+                        let addr = pc.wrapping_add((sign_extend(inst, 19) << 2) as u32);
+
+                        self.state.write_loaded_register(rs, self.state.read_mem_word_uninit(addr)?.extend_sign());
+                    }
+
+                    // LWUPC $Rs, Im
+                    0x10..=0x17 => {
+                        self.require_mips64()?;
+
+                        let addr = pc.wrapping_add((sign_extend(inst, 19) << 2) as u32);
+                        self.state.write_loaded_register64(
+                            rs,
+                            self.state.read_mem_word_uninit(addr)?.map(|value| value as i64),
+                        );
+                    }
+
+                    // LDPC $Rs, Im
+                    0x18 | 0x19 => {
+                        self.require_mips64()?;
+
+                        let addr = (pc & !7).wrapping_add((sign_extend(inst, 18) << 3) as u32);
+                        self.state.write_loaded_register64(
+                            rs,
+                            self.state.read_mem_dword_uninit(addr)?.map(|value| value as i64),
+                        );
+                    }
+
+                    // AUIPC $Rs, Im
+                    0x1E => self.state.write_register(rs, pc.wrapping_add((offset << 16) as u32) as i32),
+
+                    // ALUIPC $Rs, Im
+                    0x1F => {
+                        self.state.write_register(rs, (pc.wrapping_add((offset << 16) as u32) & !0xFFFF) as i32);
+                    }
+
+                    _ => return Err(self.reserved_instruction()),
+                }
+            }
+
+            0x3E => match rs {
+                // JIALC $Rt, Im
+                0 => {
+                    let target = (self.state.read_register(rt)? as u32).wrapping_add(offset as u32);
+
+                    self.link_compact();
+                    self.state.set_pc(target);
+                }
+
+                // BNEZC $Rs, Im
+                _ => self.compact_branch(self.state.read_register64(rs)? != 0, sign_extend(inst, 21)),
+            },
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// The FPU is still the 32-bit one (see `execute_cop1`), so doubles are even/odd pairs,
+    /// and the masks that `cmp.cond.d` writes fill both registers.
+    fn execute_r6_cop1(&mut self, funct: u32, fs: u32, fmt: u32, ft: u32, fd: u32, imm: i16) -> RushResult<bool> {
+        match fmt {
+            // BC1F / BC1T (there are no condition codes any more)
+            0x08 => return Err(self.reserved_instruction()),
+
+            // BC1EQZ $Ft, Im / BC1NEZ $Ft, Im
+            0x09 | 0x0D => {
+                if (self.state.read_fpu_register(ft)? & 1 == 1) == (fmt == 0x0D) {
+                    self.state.branch(imm);
+                }
+            }
+
+            // CMP.cond.S $Fd, $Fs, $Ft / CMP.cond.D $Fd, $Fs, $Ft
+            FMT_CMP_S | FMT_CMP_D if funct < 0x20 => {
+                // the negated conditions are only `or`, `une` and `ne` (and their signalling versions)
+                if funct & 0x10 != 0 && !matches!(funct & 0b111, 0b001..=0b011) {
+                    return Err(self.reserved_instruction());
+                }
+
+                let (fs_val, ft_val) = match fmt {
+                    FMT_CMP_S => (self.state.read_f32(fs)? as f64, self.state.read_f32(ft)? as f64),
+                    _ => (self.state.read_f64(fs)?, self.state.read_f64(ft)?),
+                };
+                let mask = match cop1::compare(funct, fs_val, ft_val) != (funct & 0x10 != 0) {
+                    true => u32::MAX,
+                    false => 0,
+                };
+
+                match fmt {
+                    FMT_CMP_S => self.state.write_fpu_register(fd, mask),
+                    _ => {
+                        self.state.write_fpu_register(fd & !1, mask);
+                        self.state.write_fpu_register(fd | 1, mask);
+                    }
+                }
+            }
+
+            FMT_S | FMT_D => {
+                let double = fmt == FMT_D;
+                let low = |reg: u32| if double { reg & !1 } else { reg };
+
+                match funct {
+                    // SEL.fmt $Fd, $Fs, $Ft
+                    0x10 => {
+                        let selected = match self.state.read_fpu_register(low(fd))? & 1 {
+                            0 => fs,
+                            _ => ft,
+                        };
+                        self.select_fpu_register(fd, Some(selected), double);
+                    }
+
+                    // MOVF / MOVT, MOVZ.fmt, MOVN.fmt
+                    0x11..=0x13 => return Err(self.reserved_instruction()),
+
+                    // SELEQZ.fmt $Fd, $Fs, $Ft
+                    0x14 => {
+                        let zero = self.state.read_fpu_register(low(ft))? & 1 == 0;
+                        self.select_fpu_register(fd, zero.then_some(fs), double);
+                    }
+
+                    // SELNEZ.fmt $Fd, $Fs, $Ft
+                    0x17 => {
+                        let zero = self.state.read_fpu_register(low(ft))? & 1 == 0;
+                        self.select_fpu_register(fd, (!zero).then_some(fs), double);
+                    }
+
+                    // MIN.fmt $Fd, $Fs, $Ft / MAX.fmt $Fd, $Fs, $Ft (a NaN loses to a number)
+                    0x1C | 0x1D => {
+                        let pick = |x: f64, y: f64| if funct == 0x1C { x.min(y) } else { x.max(y) };
+
+                        match double {
+                            true => {
+                                let value = pick(self.state.read_f64(fs)?, self.state.read_f64(ft)?);
+                                self.state.write_f64(fd, value);
+                            }
+                            false => {
+                                let value = pick(self.state.read_f32(fs)? as f64, self.state.read_f32(ft)? as f64);
+                                self.state.write_f32(fd, value as f32);
+                            }
+                        }
+                    }
+
+                    // C.cond.fmt, replaced by CMP.cond.fmt
+                    0x30..=0x3F => return Err(self.reserved_instruction()),
+
+                    _ => return Ok(false),
+                }
+            }
+
+            _ => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Copies `$Fs` (a pair of registers, for a double) into `$Fd`, or zeroes `$Fd` without one.
+    fn select_fpu_register(&mut self, fd: u32, fs: Option<u32>, double: bool) {
+        let (fd, fs, count) = match double {
+            true => (fd & !1, fs.map(|fs| fs & !1), 2),
+            false => (fd, fs, 1),
+        };
+
+        for i in 0..count {
+            let value = match fs {
+                Some(fs) => self.state.fpu_registers()[(fs + i) as usize],
+                None => Safe::Valid(0),
+            };
+            self.state.write_fpu_register_uninit(fd + i, value);
+        }
+    }
+
+    /// Takes a compact branch if `taken`. Unlike the others, they have no delay slot.
+    fn compact_branch(&mut self, taken: bool, offset: i32) {
+        // the pc has already moved past the branch
+        if taken {
+            self.state.set_pc(self.state.pc().wrapping_add((offset << 2) as u32));
+        }
+    }
+
+    /// A compact branch and link, which links whether or not it's taken.
+    fn compact_branch_link(&mut self, taken: bool, offset: i32) {
+        self.link_compact();
+        self.compact_branch(taken, offset);
+    }
+
+    /// Links `$ra` to the instruction after a compact branch, as there's no delay slot to skip.
+    fn link_compact(&mut self) {
+        self.state.write_register(Register::Ra.to_number() as u32, self.state.pc() as i32);
+    }
+}
+
+/// The low `bits` of `inst`, sign-extended (e.g. the 26-bit offset of `bc`).
+pub(super) fn sign_extend(inst: u32, bits: u32) -> i32 {
+    ((inst << (32 - bits)) as i32) >> (32 - bits)
+}
+
+/// Whether `inst` is a branch or jump with a delay slot, as Release 6 decodes it.
+/// The compact branches that replaced the branch likelies (and others) have none.
+pub(super) fn is_branch(inst: u32) -> bool {
+    let rs = (inst >> 21) & 0x1F;
+    let rt = (inst >> 16) & 0x1F;
+
+    match inst >> 26 {
+        SPECIAL => inst & 0x3F == 0x09,
+        REGIMM => matches!(rt, 0x00 | 0x01 | 0x10 | 0x11),
+        JUMP | JAL | 0x04 | 0x05 => true,
+        0x06 | 0x07 => rt == 0,
+        COP1 => matches!(rs, 0x09 | 0x0D),
+        _ => false,
+    }
+}
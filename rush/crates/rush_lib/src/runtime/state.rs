@@ -1,13 +1,14 @@
 use std::{
+    cell::Cell,
     collections::{HashMap},
     rc::Rc,
 };
-use rush_utils::RushConfig;
+use rush_utils::{DeviceKind, Endianness, IsaRevision, RushConfig};
 
-use super::{SafeToUninitResult, PAGE_SIZE};
+use super::{cp0::{Cp0, EXCEPTION_VECTOR}, device::{Frame, MappedDevice}, micromips, SafeToUninitResult, PAGE_SIZE};
 use crate::{
-    compile::TEXT_TOP,
-    error::runtime::{self, RuntimeError, SegmentationFaultAccessType},
+    compile::{elf, TEXT_TOP},
+    error::{loader::Error as LoaderError, runtime::{self, LoadDelayHazard, RuntimeError, SegmentationFaultAccessType}},
     RushError, RushResult, Safe, Uninitialised, GLOBAL_BOT, HEAP_BOT, KTEXT_BOT, STACK_BOT,
     STACK_TOP, TEXT_BOT,
 };
@@ -15,27 +16,121 @@ use crate::{
 pub const WRITE_MARKER_LO: u32 = 32;
 pub const WRITE_MARKER_HI: u32 = 32;
 
+/// FPU control registers, as numbered by `cfc1`/`ctc1`.
+pub const FIR: u32 = 0;
+pub const FCCR: u32 = 25;
+pub const FCSR: u32 = 31;
+
+/// Single, double and word formats are implemented, with a 32-bit FPU (FR=0).
+const FIR_VALUE: u32 = 1 << 16 | 1 << 17 | 1 << 20;
+
+/// A branch or jump whose delay slot is the next instruction to execute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DelaySlot {
+    pub branch_addr: u32,
+    /// Where execution continues after the delay slot, whether or not the branch was taken.
+    pub target: u32,
+}
+
+/// A load whose value isn't visible to the instruction after it (see `RushConfig::load_delay`).
+#[derive(Debug, Clone, Copy)]
+pub struct DelayedLoad {
+    pub load_addr: u32,
+    pub reg_num: u32,
+    pub value: Safe<i64>,
+}
+
 pub struct State {
     pub(super) pages: HashMap<u32, Rc<[Safe<u8>; PAGE_SIZE]>>,
     pub(super) pc: u32,
-    pub(super) registers: [Safe<i32>; 32],
+    /// The GPRs, HI and LO are 64 bits wide, so that they can hold `mips64` values.
+    /// 32-bit instructions read the lower half and sign-extend what they write,
+    /// so in 32-bit mode every value is a sign-extended 32-bit one.
+    pub(super) registers: [Safe<i64>; 32],
     pub(super) write_marker: u64,
-    pub(super) hi: Safe<i32>,
-    pub(super) lo: Safe<i32>,
+    pub(super) hi: Safe<i64>,
+    pub(super) lo: Safe<i64>,
+    pub(super) fpu_registers: [Safe<u32>; 32],
+    pub(super) fcsr: u32,
+    pub(super) cp0: Cp0,
+    /// Set by `ll`, and needed for the `sc` after it to succeed.
+    pub(super) ll_bit: bool,
     pub(super) heap_size: u32,
+    pub(super) mips64: bool,
+    /// Which release's encodings are decoded, see `Runtime::execute_r6`.
+    pub(super) isa: IsaRevision,
+    /// The ISA mode, set while executing microMIPS code (see `State::enter`).
+    pub(super) micromips: bool,
+    pub(super) delay_slots: bool,
+    pub(super) branch_target: Option<u32>,
+    pub(super) delay_slot: Option<DelaySlot>,
+    pub(super) faulted_in_delay_slot: bool,
+    pub(super) load_delay: bool,
+    /// The byte order of every half and word in memory, instructions included.
+    pub(super) endianness: Endianness,
+    /// The instruction being executed, and where it was fetched from.
+    pub(super) inst: u32,
+    pub(super) inst_addr: u32,
+    pub(super) pending_load: Option<DelayedLoad>,
+    pub(super) delayed_load: Option<DelayedLoad>,
+    pub(super) load_hazard: Cell<Option<LoadDelayHazard>>,
+    /// The register loaded by this instruction, and by the one before it,
+    /// which stalls this one if it's read (see `CycleCosts::load_use`).
+    pub(super) loaded_register: Option<u32>,
+    pub(super) previous_load: Option<u32>,
+    pub(super) load_use_stall: Cell<bool>,
+    /// Memory-mapped peripherals, which are accessed instead of `pages` in their address ranges.
+    pub(super) devices: Vec<MappedDevice>,
 }
 
 impl State {
-    pub fn new(rush_config: &RushConfig) -> Self {
-        Self {
+    pub fn new(rush_config: &RushConfig) -> Result<Self, LoaderError> {
+        let mut cp0 = match rush_config.mmu {
+            true => Cp0::with_tlb(rush_config.tlb_entries),
+            false => Cp0::default(),
+        };
+        // MARS's programs rely on its Status, which starts with interrupts enabled
+        if rush_config.devices.iter().any(|device| matches!(device.kind, DeviceKind::Mars { .. })) {
+            cp0.enable_interrupts();
+        }
+
+        let endianness = rush_config.endianness.unwrap_or(Endianness::Little);
+        let mut devices = vec![];
+        for device in &rush_config.devices {
+            devices.extend(MappedDevice::from_config(device, endianness)?);
+        }
+
+        Ok(Self {
             pages: HashMap::new(),
-            pc: rush_config.start_addr,
+            pc: rush_config.start_addr.unwrap_or_default(),
             heap_size: 0,
             registers: [Safe::Valid(0); 32],
             write_marker: 0,
             hi: Safe::Valid(0),
             lo: Safe::Valid(0),
-        }
+            fpu_registers: [Safe::Valid(0); 32],
+            fcsr: 0,
+            cp0,
+            ll_bit: false,
+            mips64: rush_config.mips64,
+            isa: rush_config.isa,
+            micromips: false,
+            delay_slots: rush_config.delay_slots,
+            branch_target: None,
+            delay_slot: None,
+            faulted_in_delay_slot: false,
+            load_delay: rush_config.load_delay,
+            endianness,
+            inst: 0,
+            inst_addr: 0,
+            pending_load: None,
+            delayed_load: None,
+            load_hazard: Cell::new(None),
+            loaded_register: None,
+            previous_load: None,
+            load_use_stall: Cell::new(false),
+            devices,
+        })
     }
 
 
@@ -53,6 +148,60 @@ impl State {
 
     pub fn step_pc(&mut self) { self.pc += 4}
 
+    /// Whether `pc` is in microMIPS code, rather than MIPS32.
+    pub fn micromips(&self) -> bool {
+        self.micromips
+    }
+
+    /// Continues at `target`, whose low bit selects the ISA mode (set for microMIPS),
+    /// as with the targets of `jr` and `jalr`.
+    pub fn enter(&mut self, target: u32) {
+        self.pc = target & !1;
+        self.micromips = target & 1 != 0;
+    }
+
+    /// Whether branches and jumps take effect after the instruction following them.
+    pub fn delay_slots(&self) -> bool {
+        self.delay_slots
+    }
+
+    /// The branch whose delay slot is about to be executed, if any.
+    pub fn delay_slot(&self) -> Option<DelaySlot> {
+        self.delay_slot
+    }
+
+    /// Whether the last error happened in a delay slot.
+    /// `pc` is then left on the branch, so that resuming executes both again.
+    pub fn faulted_in_delay_slot(&self) -> bool {
+        self.faulted_in_delay_slot
+    }
+
+    /// The address of the instruction that caused the last error.
+    pub fn faulting_addr(&self) -> u32 {
+        match self.faulted_in_delay_slot {
+            true => self.pc.wrapping_add(self.inst_size_at(self.pc)),
+            false => self.pc,
+        }
+    }
+
+    /// The return address for `jal`, `jalr` and the `...al` branches,
+    /// which skips over the delay slot if there is one.
+    /// This must be called after `pc` has been stepped past the instruction.
+    pub fn link_addr(&self) -> u32 {
+        match self.delay_slots {
+            true => self.pc.wrapping_add(4),
+            false => self.pc,
+        }
+    }
+
+    /// Transfers control to `target` (see `enter`), either now or after the delay slot.
+    pub fn jump(&mut self, target: u32) {
+        match self.delay_slots {
+            true => self.branch_target = Some(target),
+            false => self.enter(target),
+        }
+    }
+
     pub fn heap_size(&self) -> u32 {
         self.heap_size
     }
@@ -69,63 +218,378 @@ impl State {
         self.write_marker = write_marker;
     }
 
-    pub fn registers(&self) -> &[Safe<i32>] {
+    pub fn registers(&self) -> &[Safe<i64>] {
         &self.registers
     }
 
+    /// Whether the doubleword instructions are available.
+    pub fn mips64(&self) -> bool {
+        self.mips64
+    }
+
+    pub fn isa(&self) -> IsaRevision {
+        self.isa
+    }
+
+    /// The lower 32 bits of a register, which is what 32-bit instructions operate on.
     pub fn read_register(&self, reg_num: u32) -> RushResult<i32> {
-        self.registers[reg_num as usize].to_result(Uninitialised::Register { reg_num })
+        self.read_register64(reg_num).map(|value| value as i32)
     }
 
     pub fn read_register_uninit(&self, reg_num: u32) -> Safe<i32> {
+        self.read_register64_uninit(reg_num)
+            .map(|value| value as i32)
+    }
+
+    pub fn read_register64(&self, reg_num: u32) -> RushResult<i64> {
+        self.check_load_hazard(reg_num);
+        self.registers[reg_num as usize].to_result(Uninitialised::Register { reg_num })
+    }
+
+    pub fn read_register64_uninit(&self, reg_num: u32) -> Safe<i64> {
+        self.check_load_hazard(reg_num);
         self.registers[reg_num as usize]
     }
 
+    /// Whether loaded values only become visible after the instruction following the load.
+    pub fn load_delay(&self) -> bool {
+        self.load_delay
+    }
+
+    /// The byte order of halves and words in memory.
+    pub fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    /// A load that hasn't reached its register yet, as the next instruction is in its delay slot.
+    pub fn pending_load(&self) -> Option<DelayedLoad> {
+        self.pending_load
+    }
+
+    /// The register read in a load's delay slot since this was last called, if any.
+    pub fn take_load_hazard(&mut self) -> Option<LoadDelayHazard> {
+        self.load_hazard.take()
+    }
+
+    fn check_load_hazard(&self, reg_num: u32) {
+        if self.previous_load == Some(reg_num) {
+            self.load_use_stall.set(true);
+        }
+
+        if let Some(load) = self.delayed_load.filter(|load| load.reg_num == reg_num) {
+            self.load_hazard.set(Some(LoadDelayHazard {
+                load_addr: load.load_addr,
+                addr: self.inst_addr,
+                reg_num,
+            }));
+        }
+    }
+
+    /// Writes the result of a load, which with `load_delay` waits until after the next instruction.
+    pub fn write_loaded_register(&mut self, reg_num: u32, value: Safe<i32>) {
+        self.write_loaded_register64(reg_num, value.map(i64::from));
+    }
+
+    pub fn write_loaded_register64(&mut self, reg_num: u32, value: Safe<i64>) {
+        match self.load_delay {
+            true if reg_num != 0 => {
+                self.pending_load = Some(DelayedLoad {
+                    load_addr: self.inst_addr,
+                    reg_num,
+                    value,
+                })
+            }
+            // without a load delay slot, the pipeline has to wait for the value instead
+            _ => {
+                self.loaded_register = Some(reg_num).filter(|&reg_num| reg_num != 0);
+                self.write_register64_uninit(reg_num, value)
+            }
+        }
+    }
+
+    /// Lets the load whose delay slot was just executed write its register.
+    pub(super) fn commit_delayed_load(&mut self) {
+        if let Some(load) = self.delayed_load.take() {
+            self.write_register64_uninit(load.reg_num, load.value);
+        }
+    }
+
+    /// If the delay slot of a load writes the same register, the load is discarded.
+    fn cancel_delayed_load(&mut self, reg_num: u32) {
+        if self.delayed_load.is_some_and(|load| load.reg_num == reg_num) {
+            self.delayed_load = None;
+        }
+    }
+
     pub fn read_hi(&self) -> RushResult<i32> {
-        self.hi.to_result(Uninitialised::Hi)
+        self.read_hi64().map(|value| value as i32)
     }
 
     pub fn read_lo(&self) -> RushResult<i32> {
+        self.read_lo64().map(|value| value as i32)
+    }
+
+    pub fn read_hi64(&self) -> RushResult<i64> {
+        self.hi.to_result(Uninitialised::Hi)
+    }
+
+    pub fn read_lo64(&self) -> RushResult<i64> {
         self.lo.to_result(Uninitialised::Lo)
     }
 
+    /// Sign-extends `value` into the whole register, as every 32-bit instruction does.
     pub fn write_register(&mut self, reg_num: u32, value: i32) {
-        if reg_num == 0 {
-            return;
-        }
+        self.write_register64(reg_num, value as i64);
+    }
 
-        assert!(reg_num < 32);
+    pub fn write_register_uninit(&mut self, reg_num: u32, value: Safe<i32>) {
+        self.write_register64_uninit(reg_num, value.map(i64::from));
+    }
 
-        self.registers[reg_num as usize] = Safe::Valid(value);
-        self.write_marker |= 1u64 << reg_num;
+    pub fn write_register64(&mut self, reg_num: u32, value: i64) {
+        self.write_register64_uninit(reg_num, Safe::Valid(value));
     }
 
-    pub fn write_register_uninit(&mut self, reg_num: u32, value: Safe<i32>) {
+    pub fn write_register64_uninit(&mut self, reg_num: u32, value: Safe<i64>) {
         if reg_num == 0 {
             return;
         }
 
         assert!(reg_num < 32);
 
+        self.cancel_delayed_load(reg_num);
+
         self.registers[reg_num as usize] = value;
         self.write_marker |= 1u64 << reg_num;
     }
 
     pub fn write_hi(&mut self, value: i32) {
+        self.write_hi64(value as i64);
+    }
+
+    pub fn write_lo(&mut self, value: i32) {
+        self.write_lo64(value as i64);
+    }
+
+    pub fn write_hi64(&mut self, value: i64) {
         self.hi = Safe::Valid(value);
         self.write_marker |= 1u64 << WRITE_MARKER_HI;
     }
 
-    pub fn write_lo(&mut self, value: i32) {
+    pub fn write_lo64(&mut self, value: i64) {
         self.lo = Safe::Valid(value);
         self.write_marker |= 1u64 << WRITE_MARKER_LO;
     }
 
+    pub fn fpu_registers(&self) -> &[Safe<u32>] {
+        &self.fpu_registers
+    }
+
+    pub fn read_fpu_register(&self, reg_num: u32) -> RushResult<u32> {
+        self.fpu_registers[reg_num as usize].to_result(Uninitialised::FpuRegister { reg_num })
+    }
+
+    pub fn write_fpu_register(&mut self, reg_num: u32, value: u32) {
+        self.fpu_registers[reg_num as usize] = Safe::Valid(value);
+    }
+
+    pub fn write_fpu_register_uninit(&mut self, reg_num: u32, value: Safe<u32>) {
+        self.fpu_registers[reg_num as usize] = value;
+    }
+
+    pub fn read_f32(&self, reg_num: u32) -> RushResult<f32> {
+        self.read_fpu_register(reg_num).map(f32::from_bits)
+    }
+
+    pub fn write_f32(&mut self, reg_num: u32, value: f32) {
+        self.write_fpu_register(reg_num, value.to_bits());
+    }
+
+    /// Doubles are held in an even/odd pair of registers, with the low word in the even one.
+    /// An odd register number is taken to mean the pair it's in.
+    pub fn read_f64(&self, reg_num: u32) -> RushResult<f64> {
+        let (lo, hi) = (self.read_fpu_register(reg_num & !1)?, self.read_fpu_register(reg_num | 1)?);
+
+        Ok(f64::from_bits((hi as u64) << 32 | lo as u64))
+    }
+
+    pub fn write_f64(&mut self, reg_num: u32, value: f64) {
+        let bits = value.to_bits();
+
+        self.write_fpu_register(reg_num & !1, bits as u32);
+        self.write_fpu_register(reg_num | 1, (bits >> 32) as u32);
+    }
+
+    pub fn fcsr(&self) -> u32 {
+        self.fcsr
+    }
+
+    /// Reads an FPU control register, for `cfc1`.
+    pub fn read_fpu_control(&self, reg_num: u32) -> u32 {
+        match reg_num {
+            FIR => FIR_VALUE,
+            FCCR => (self.fcsr >> 24 & 0xFE) | (self.fcsr >> 23 & 1),
+            FCSR => self.fcsr,
+            _ => 0,
+        }
+    }
+
+    /// Writes an FPU control register, for `ctc1`. `FIR` is read-only.
+    pub fn write_fpu_control(&mut self, reg_num: u32, value: u32) {
+        match reg_num {
+            FCCR => {
+                self.fcsr = (self.fcsr & !0xFE80_0000) | (value & 0xFE) << 24 | (value & 1) << 23;
+            }
+            FCSR => self.fcsr = value,
+            _ => {}
+        }
+    }
+
+    /// Condition code `cc` (0 to 7) of the FCSR, as set by `c.cond.fmt`.
+    pub fn fpu_condition(&self, cc: u32) -> bool {
+        self.fcsr & fcc_bit(cc) != 0
+    }
+
+    pub fn set_fpu_condition(&mut self, cc: u32, value: bool) {
+        match value {
+            true => self.fcsr |= fcc_bit(cc),
+            false => self.fcsr &= !fcc_bit(cc),
+        }
+    }
+
+    /// The rounding mode in the FCSR: 0 to nearest, 1 towards zero, 2 up, or 3 down.
+    pub fn fpu_rounding_mode(&self) -> u32 {
+        self.fcsr & 0b11
+    }
+
+    pub fn cp0(&self) -> &Cp0 {
+        &self.cp0
+    }
+
+    /// Asserts or deasserts hardware interrupt `line` (0 to 5), for devices to interrupt the program.
+    pub fn set_interrupt(&mut self, line: u32, asserted: bool) {
+        self.cp0.set_interrupt(line, asserted);
+    }
+
+    pub fn devices(&self) -> &[MappedDevice] {
+        &self.devices
+    }
+
+    /// Maps a device into memory, for peripherals that aren't in `DeviceKind`.
+    pub fn add_device(&mut self, device: MappedDevice) {
+        self.devices.push(device);
+    }
+
+    /// The device mapped at physical address `addr`, if there is one.
+    pub fn device_at(&self, addr: u32) -> Option<&MappedDevice> {
+        self.devices.iter().find(|device| device.contains(addr))
+    }
+
+    /// The physical address that the caches see for `addr`, or `None` if it's uncached,
+    /// as kseg1 and device registers are.
+    pub fn cached_addr(&self, addr: u32) -> Option<u32> {
+        self.cp0.cached_addr(addr).filter(|&addr| self.device_at(addr).is_none())
+    }
+
+    /// Advances every device by `cycles` cycles.
+    pub(super) fn tick_devices(&mut self, cycles: u64) {
+        for device in &self.devices {
+            device.device.borrow_mut().tick(cycles);
+        }
+    }
+
+    /// Sets each interrupt bit that devices are connected to, if any of those devices are asserting it.
+    pub(super) fn update_device_interrupts(&mut self) {
+        let mut connected = [false; 8];
+        let mut asserted = [false; 8];
+
+        for device in &self.devices {
+            if let Some(ip) = device.ip {
+                connected[ip as usize] = true;
+                asserted[ip as usize] |= device.device.borrow().interrupt();
+            }
+        }
+
+        for ip in 0..8 {
+            if connected[ip as usize] {
+                self.cp0.set_interrupt_bit(ip, asserted[ip as usize]);
+            }
+        }
+    }
+
+    /// Notes which devices' interrupts are being taken, for `acknowledge_device_interrupts`.
+    pub(super) fn take_device_interrupts(&mut self) {
+        for device in &mut self.devices {
+            if let Some(ip) = device.ip {
+                device.interrupt_taken = self.cp0.interrupt_unmasked(ip) && device.device.borrow().interrupt();
+            }
+        }
+    }
+
+    /// Acknowledges the interrupts taken by `take_device_interrupts`, as their handler has returned.
+    pub(super) fn acknowledge_device_interrupts(&mut self) {
+        for device in &mut self.devices {
+            if std::mem::take(&mut device.interrupt_taken) {
+                device.device.borrow_mut().acknowledge();
+            }
+        }
+    }
+
+    /// Whether any device reads the keyboard, so the terminal should be in `KeyboardMode` while the program runs.
+    pub fn uses_terminal(&self) -> bool {
+        self.devices.iter().any(|device| device.device.borrow().uses_terminal())
+    }
+
+    /// What the first framebuffer is showing, if there is one.
+    pub fn frame(&self) -> Option<Frame> {
+        self.devices.iter().find_map(|device| device.device.borrow().frame())
+    }
+
+    /// The device that `address` is a register of, and the offset of that register.
+    /// Devices are looked up before the segment checks, so that they can be mapped anywhere.
+    fn device_for(&self, address: u32, access: SegmentationFaultAccessType) -> RushResult<Option<(&MappedDevice, u32)>> {
+        if self.devices.is_empty() {
+            return Ok(None);
+        }
+
+        let address = match self.cp0.tlb() {
+            Some(_) => self.cp0.translate(address, access)?,
+            None => address,
+        };
+
+        Ok(self.device_at(address).map(|device| (device, address - device.base)))
+    }
+
+    /// Reads `size` bytes from the device at `address`, or `None` if it's memory.
+    fn read_device(&self, address: u32, size: u32) -> RushResult<Option<u32>> {
+        Ok(self
+            .device_for(address, SegmentationFaultAccessType::Read)?
+            .map(|(device, offset)| device.device.borrow_mut().read(offset, size)))
+    }
+
+    /// Writes `size` bytes to the device at `address`, returning whether there was one.
+    fn write_device(&self, address: u32, size: u32, value: u32) -> RushResult<bool> {
+        Ok(self
+            .device_for(address, SegmentationFaultAccessType::Write)?
+            .map(|(device, offset)| device.device.borrow_mut().write(offset, size, value))
+            .is_some())
+    }
+
+    /// Whether the program has an exception handler at `EXCEPTION_VECTOR`, to take exceptions to.
+    pub fn has_exception_handler(&self) -> bool {
+        matches!(self.read_mem_byte_uninit_unchecked(EXCEPTION_VECTOR), Ok(Safe::Valid(_)))
+    }
+
+    /// Whether `address` can be accessed. With an MMU, that's whether it can be translated.
     pub fn check_segfault(
         &self,
         address: u32,
         access: SegmentationFaultAccessType,
     ) -> RushResult<()> {
+        if self.cp0.tlb().is_some() {
+            return self.cp0.translate(address, access).map(|_| ());
+        }
+
         let segfault = match address {
             _ if address < TEXT_BOT => true,
             _ if (TEXT_BOT..=TEXT_TOP).contains(&address) => false,
@@ -152,8 +616,20 @@ impl State {
         }
     }
 
+    /// Where `address` is in `pages`, which are physical memory.
+    fn physical_addr(&self, address: u32, access: SegmentationFaultAccessType) -> RushResult<u32> {
+        match self.cp0.tlb() {
+            Some(_) => self.cp0.translate(address, access),
+            None => self.check_segfault(address, access).map(|_| address),
+        }
+    }
+
     pub fn read_mem_byte(&self, address: u32) -> RushResult<u8> {
-        self.check_segfault(address, SegmentationFaultAccessType::Read)?;
+        if let Some(value) = self.read_device(address, 1)? {
+            return Ok(value as u8);
+        }
+
+        let address = self.physical_addr(address, SegmentationFaultAccessType::Read)?;
 
         self.get_page(address)
             .and_then(|page| {
@@ -165,40 +641,102 @@ impl State {
     }
 
     pub fn read_mem_half(&self, address: u32) -> RushResult<u16> {
+        if let Ok(Some(value)) = self.read_device(address, 2) {
+            return Ok(value as u16);
+        }
+
         let result: RushResult<_> = (|| {
             let byte1 = self.read_mem_byte(address)?;
             let byte2 = self.read_mem_byte(address + 1)?;
 
-            Ok(u16::from_le_bytes([byte1, byte2]))
+            Ok(elf::u16_from_bytes(self.endianness, [byte1, byte2]))
         })();
 
         result.ok().to_result(Uninitialised::Half { addr: address })
     }
 
     pub fn read_mem_word(&self, address: u32) -> RushResult<u32> {
+        if let Ok(Some(value)) = self.read_device(address, 4) {
+            return Ok(value);
+        }
+
         let result: RushResult<_> = (|| {
             let byte1 = self.read_mem_byte(address)?;
             let byte2 = self.read_mem_byte(address + 1)?;
             let byte3 = self.read_mem_byte(address + 2)?;
             let byte4 = self.read_mem_byte(address + 3)?;
 
-            Ok(u32::from_be_bytes([byte1, byte2, byte3, byte4]))
+            Ok(elf::u32_from_bytes(self.endianness, [byte1, byte2, byte3, byte4]))
         })();
 
         result.ok().to_result(Uninitialised::Word { addr: self.pc })
     }
 
+    /// Reads an instruction word for the debugger, which may look at the kernel from user mode.
+    pub fn read_mem_word_unchecked(&self, address: u32) -> Option<u32> {
+        let mut bytes = [0; 4];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = self
+                .read_mem_byte_uninit_unchecked(address.wrapping_add(i as u32))
+                .ok()?
+                .into_option()?;
+        }
+
+        Some(elf::u32_from_bytes(self.endianness, bytes))
+    }
+
+    /// Fetches the instruction at `addr` along with its size, which is 2 bytes for
+    /// the 16-bit microMIPS instructions (returned in the low half) and 4 for anything else.
+    /// A 32-bit microMIPS instruction is fetched a halfword at a time, first halfword highest.
+    pub fn read_inst(&self, addr: u32) -> RushResult<(u32, u32)> {
+        // (fetching from an unmapped page has to be reported, rather than the word being uninitialised)
+        if self.cp0.tlb().is_some() {
+            self.cp0.translate(addr, SegmentationFaultAccessType::Execute)?;
+        }
+
+        if !self.micromips {
+            return Ok((self.read_mem_word(addr)?, 4));
+        }
+
+        let first = self.read_mem_half(addr)?;
+        match micromips::is_16_bit(first) {
+            true => Ok((first as u32, 2)),
+            false => Ok(((first as u32) << 16 | self.read_mem_half(addr.wrapping_add(2))? as u32, 4)),
+        }
+    }
+
+    /// The size of the instruction at `addr`, in the current ISA mode.
+    pub fn inst_size_at(&self, addr: u32) -> u32 {
+        match self.read_inst(addr) {
+            Ok((_, size)) => size,
+            Err(_) => 4,
+        }
+    }
+
     pub fn read_mem_pc(&self) -> RushResult<u32> {
         self.read_mem_word(self.pc)
     }
 
     pub fn read_mem_byte_uninit(&self, address: u32) -> RushResult<Safe<u8>> {
-        self.check_segfault(address, SegmentationFaultAccessType::Read)?;
-        self.read_mem_byte_uninit_unchecked(address)
+        if let Some(value) = self.read_device(address, 1)? {
+            return Ok(Safe::Valid(value as u8));
+        }
+
+        let address = self.physical_addr(address, SegmentationFaultAccessType::Read)?;
+        Ok(self.read_physical_byte(address))
     }
 
+    /// Reads a byte for the debugger, which may look at the kernel from user mode.
+    /// Addresses that aren't mapped read as uninitialised.
     pub fn read_mem_byte_uninit_unchecked(&self, address: u32) -> RushResult<Safe<u8>> {
-        Ok(self
+        Ok(match self.cp0.translate_unchecked(address) {
+            Some(address) => self.read_physical_byte(address),
+            None => Safe::Uninitialised,
+        })
+    }
+
+    fn read_physical_byte(&self, address: u32) -> Safe<u8> {
+        self
             .get_page(address)
             .and_then(|page| {
                 let offset = Self::offset_in_page(address);
@@ -206,10 +744,14 @@ impl State {
                 page[offset as usize].as_option().copied()
             })
             .map(Safe::Valid)
-            .unwrap_or(Safe::Uninitialised))
+            .unwrap_or(Safe::Uninitialised)
     }
 
     pub fn read_mem_half_uninit(&self, address: u32) -> RushResult<Safe<u16>> {
+        if let Some(value) = self.read_device(address, 2)? {
+            return Ok(Safe::Valid(value as u16));
+        }
+
         self.check_segfault(address, SegmentationFaultAccessType::Read)?;
         self.check_segfault(address + 2, SegmentationFaultAccessType::Read)?;
 
@@ -217,13 +759,17 @@ impl State {
             let byte1 = self.read_mem_byte(address)?;
             let byte2 = self.read_mem_byte(address + 1)?;
 
-            Ok(u16::from_le_bytes([byte1, byte2]))
+            Ok(elf::u16_from_bytes(self.endianness, [byte1, byte2]))
         })();
 
         Ok(result.map(Safe::Valid).unwrap_or(Safe::Uninitialised))
     }
 
     pub fn read_mem_word_uninit(&self, address: u32) -> RushResult<Safe<u32>> {
+        if let Some(value) = self.read_device(address, 4)? {
+            return Ok(Safe::Valid(value));
+        }
+
         self.check_segfault(address, SegmentationFaultAccessType::Read)?;
         self.check_segfault(address + 1, SegmentationFaultAccessType::Read)?;
         self.check_segfault(address + 2, SegmentationFaultAccessType::Read)?;
@@ -235,14 +781,30 @@ impl State {
             let byte3 = self.read_mem_byte(address + 2)?;
             let byte4 = self.read_mem_byte(address + 3)?;
 
-            Ok(u32::from_le_bytes([byte1, byte2, byte3, byte4]))
+            Ok(elf::u32_from_bytes(self.endianness, [byte1, byte2, byte3, byte4]))
         })();
 
         Ok(result.map(Safe::Valid).unwrap_or(Safe::Uninitialised))
     }
 
+    pub fn read_mem_dword_uninit(&self, address: u32) -> RushResult<Safe<u64>> {
+        let mut bytes = [0; 8];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            match self.read_mem_byte_uninit(address + i as u32)? {
+                Safe::Valid(value) => *byte = value,
+                Safe::Uninitialised => return Ok(Safe::Uninitialised),
+            }
+        }
+
+        Ok(Safe::Valid(elf::u64_from_bytes(self.endianness, bytes)))
+    }
+
     pub fn write_mem_byte(&mut self, address: u32, byte: u8) -> RushResult<()> {
-        self.check_segfault(address, SegmentationFaultAccessType::Write)?;
+        if self.write_device(address, 1, byte as u32)? {
+            return Ok(());
+        }
+
+        let address = self.physical_addr(address, SegmentationFaultAccessType::Write)?;
 
         let page = self.get_mut_page_or_new(address);
         let offset = Self::offset_in_page(address);
@@ -253,7 +815,11 @@ impl State {
     }
 
     pub fn write_mem_half(&mut self, address: u32, half: u16) -> RushResult<()> {
-        let [b1, b2] = half.to_le_bytes();
+        if self.write_device(address, 2, half as u32)? {
+            return Ok(());
+        }
+
+        let [b1, b2] = elf::u16_bytes(self.endianness, half);
 
         self.write_mem_byte(address, b1)?;
         self.write_mem_byte(address + 1, b2)?;
@@ -262,7 +828,11 @@ impl State {
     }
 
     pub fn write_mem_word(&mut self, address: u32, word: u32) -> RushResult<()> {
-        let [b1, b2, b3, b4] = word.to_le_bytes();
+        if self.write_device(address, 4, word)? {
+            return Ok(());
+        }
+
+        let [b1, b2, b3, b4] = elf::u32_bytes(self.endianness, word);
 
         self.write_mem_byte(address, b1)?;
         self.write_mem_byte(address + 1, b2)?;
@@ -273,7 +843,12 @@ impl State {
     }
 
     pub fn write_mem_byte_uninit(&mut self, address: u32, byte: Safe<u8>) -> RushResult<()> {
-        self.check_segfault(address, SegmentationFaultAccessType::Write)?;
+        // (devices have no notion of an uninitialised value, so get zero)
+        if self.write_device(address, 1, byte.into_option().unwrap_or_default() as u32)? {
+            return Ok(());
+        }
+
+        let address = self.physical_addr(address, SegmentationFaultAccessType::Write)?;
 
         let page = self.get_mut_page_or_new(address);
         let offset = Self::offset_in_page(address);
@@ -283,6 +858,16 @@ impl State {
         Ok(())
     }
 
+    /// Writes a byte for the loader. Addresses that aren't mapped (as the TLB starts out empty)
+    /// are taken to be physical.
+    pub fn write_mem_byte_uninit_unchecked(&mut self, address: u32, byte: Safe<u8>) {
+        let address = self.cp0.translate_unchecked(address).unwrap_or(address);
+        let page = self.get_mut_page_or_new(address);
+        let offset = Self::offset_in_page(address);
+
+        page[offset as usize] = byte;
+    }
+
     pub fn write_mem_half_uninit(&mut self, address: u32, half: Safe<u16>) -> RushResult<()> {
         match half {
             Safe::Valid(half) => self.write_mem_half(address, half)?,
@@ -309,6 +894,19 @@ impl State {
         Ok(())
     }
 
+    pub fn write_mem_dword_uninit(&mut self, address: u32, dword: Safe<u64>) -> RushResult<()> {
+        let bytes = match dword {
+            Safe::Valid(dword) => elf::u64_bytes(self.endianness, dword).map(Safe::Valid),
+            Safe::Uninitialised => [Safe::Uninitialised; 8],
+        };
+
+        for (i, byte) in bytes.into_iter().enumerate() {
+            self.write_mem_byte_uninit(address + i as u32, byte)?;
+        }
+
+        Ok(())
+    }
+
     pub fn read_mem_string(&self, address: u32) -> RushResult<Vec<u8>> {
         let mut text = vec![];
 
@@ -343,7 +941,7 @@ impl State {
         let imm = imm * 4; // branch offset is in instructions
 
         let pc_offset = imm as u32;
-        self.pc = self.pc.wrapping_add(pc_offset);
+        self.jump(self.pc.wrapping_add(pc_offset));
     }
 
     fn get_page_index(address: u32) -> u32 {
@@ -384,6 +982,14 @@ impl State {
     }
 }
 
+/// FCC0 is bit 23 of the FCSR, with FCC1 to FCC7 above the flush-to-zero bit.
+fn fcc_bit(cc: u32) -> u32 {
+    match cc {
+        0 => 1 << 23,
+        _ => 1 << (24 + cc),
+    }
+}
+
 impl Clone for State {
     fn clone(&self) -> Self {
         let cow_pages = self
@@ -399,7 +1005,29 @@ impl Clone for State {
             write_marker: 0,
             hi: self.hi,
             lo: self.lo,
+            fpu_registers: self.fpu_registers,
+            fcsr: self.fcsr,
+            cp0: self.cp0.clone(),
+            ll_bit: self.ll_bit,
+            mips64: self.mips64,
+            isa: self.isa,
+            micromips: self.micromips,
             heap_size: self.heap_size,
+            delay_slots: self.delay_slots,
+            branch_target: self.branch_target,
+            delay_slot: self.delay_slot,
+            faulted_in_delay_slot: self.faulted_in_delay_slot,
+            load_delay: self.load_delay,
+            endianness: self.endianness,
+            inst: self.inst,
+            inst_addr: self.inst_addr,
+            pending_load: self.pending_load,
+            delayed_load: self.delayed_load,
+            load_hazard: self.load_hazard.clone(),
+            loaded_register: self.loaded_register,
+            previous_load: self.previous_load,
+            load_use_stall: self.load_use_stall.clone(),
+            devices: self.devices.clone(),
         }
     }
 }
@@ -414,6 +1042,28 @@ impl Default for State {
             write_marker: 0,
             hi: Default::default(),
             lo: Default::default(),
+            fpu_registers: [Safe::Valid(0); 32],
+            fcsr: 0,
+            cp0: Cp0::default(),
+            ll_bit: false,
+            mips64: false,
+            isa: IsaRevision::R2,
+            micromips: false,
+            delay_slots: false,
+            branch_target: None,
+            delay_slot: None,
+            faulted_in_delay_slot: false,
+            load_delay: false,
+            endianness: Endianness::Little,
+            inst: 0,
+            inst_addr: 0,
+            pending_load: None,
+            delayed_load: None,
+            load_hazard: Cell::new(None),
+            loaded_register: None,
+            previous_load: None,
+            load_use_stall: Cell::new(false),
+            devices: vec![],
         }
     }
 }
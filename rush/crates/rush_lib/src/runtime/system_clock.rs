@@ -1,3 +1,4 @@
+use rush_utils::{CycleCosts, RushConfigTiming};
 use std::time::SystemTime;
 
 pub fn get_curr_time_as_millis() -> u128 {
@@ -9,8 +10,11 @@ pub fn get_curr_time_as_millis() -> u128 {
 
 #[derive(Default)]
 pub struct SystemClock {
+    /// The emulated clock rate, in Hz.
     pub freq: u64,
-    pub total_cycles: f64,
+    /// How many cycles each kind of instruction takes.
+    pub costs: CycleCosts,
+    /// Cycles so far, which drive the CP0 Count register.
     pub total_ticks: u64,
     pub start_time: u128,
     pub stop_time: u128,
@@ -18,10 +22,10 @@ pub struct SystemClock {
 }
 
 impl SystemClock {
-    pub fn new() -> Self {
+    pub fn new(timing: &RushConfigTiming) -> Self {
         Self {
-            freq: 15_000_000,
-            total_cycles: 0f64,
+            freq: timing.freq(),
+            costs: timing.costs(),
             total_ticks: 0,
             start_time: 0,
             stop_time: 0,
@@ -30,8 +34,27 @@ impl SystemClock {
     }
 
     pub fn update(&mut self, cycles: u64) {
-        self.total_cycles = self.total_cycles + (cycles / self.freq) as f64;
-        self.total_ticks += 1
+        self.total_ticks += cycles;
+    }
+
+    /// How long the program has taken so far on the emulated CPU, in seconds.
+    pub fn simulated_time(&self) -> f64 {
+        self.total_ticks as f64 / self.freq as f64
+    }
+
+    /// The real time taken since `start_time`, how fast that was, and the simulated time.
+    pub fn summary(&self) -> String {
+        let delta_time_millis = (self.stop_time - self.start_time).max(1);
+        let ips = self.steps * 1000 / delta_time_millis;
+
+        format!(
+            "Emulation running on {:.3} sec., IPS = {}\nSimulated {} cycles, {:.6} sec. at {} MHz",
+            delta_time_millis as f64 / 1000.0,
+            ips,
+            self.total_ticks,
+            self.simulated_time(),
+            self.freq as f64 / 1_000_000.0,
+        )
     }
 }
 
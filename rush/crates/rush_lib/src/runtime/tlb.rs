@@ -0,0 +1,226 @@
+/// The coprocessor 0 registers that belong to the TLB, as numbered by `mfc0`/`mtc0`.
+pub const INDEX: u32 = 0;
+pub const RANDOM: u32 = 1;
+pub const ENTRY_LO0: u32 = 2;
+pub const ENTRY_LO1: u32 = 3;
+pub const CONTEXT: u32 = 4;
+pub const PAGE_MASK: u32 = 5;
+pub const WIRED: u32 = 6;
+pub const ENTRY_HI: u32 = 10;
+
+/// Set in Index by `tlbp` when no entry matches.
+const INDEX_P: u32 = 1 << 31;
+const ENTRY_HI_ASID: u32 = 0xFF;
+const ENTRY_HI_VPN2: u32 = 0xFFFFE000;
+const ENTRY_LO_G: u32 = 1 << 0;
+const ENTRY_LO_V: u32 = 1 << 1;
+const ENTRY_LO_D: u32 = 1 << 2;
+const PAGE_MASK_MASK: u32 = 0x1FFFE000;
+const CONTEXT_PTE_BASE: u32 = 0xFF800000;
+
+/// One entry of the JTLB, which maps a pair of adjacent (even and odd) pages.
+#[derive(Debug, Clone, Copy)]
+pub struct TlbEntry {
+    /// The bits of the VPN2 that aren't compared, as in PageMask (0 for 4KB pages).
+    pub page_mask: u32,
+    /// VPN2 and ASID, as in EntryHi.
+    pub entry_hi: u32,
+    /// Matches every ASID.
+    pub global: bool,
+    /// The even and odd pages, as in EntryLo0 and EntryLo1 (without G).
+    pub entry_lo: [u32; 2],
+}
+
+impl TlbEntry {
+    pub fn vpn2(&self) -> u32 {
+        self.entry_hi & ENTRY_HI_VPN2 & !self.page_mask
+    }
+
+    pub fn asid(&self) -> u32 {
+        self.entry_hi & ENTRY_HI_ASID
+    }
+
+    /// The size of each of the pair of pages, in bytes.
+    pub fn page_size(&self) -> u32 {
+        ((self.page_mask >> 1) | 0xFFF) + 1
+    }
+
+    fn matches(&self, addr: u32, asid: u32) -> bool {
+        let vpn2_mask = ENTRY_HI_VPN2 & !self.page_mask;
+
+        self.entry_hi & vpn2_mask == addr & vpn2_mask && (self.global || self.asid() == asid)
+    }
+}
+
+/// Why the TLB couldn't translate an address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TlbFault {
+    /// No entry matches, which has its own exception vector.
+    Refill,
+    /// The matching entry's V bit is clear.
+    Invalid,
+    /// A store to a page whose D bit is clear.
+    Modified,
+}
+
+/// A software-managed joint TLB, along with the coprocessor 0 registers used to fill it.
+#[derive(Debug, Clone)]
+pub struct Tlb {
+    entries: Vec<TlbEntry>,
+    index: u32,
+    random: u32,
+    entry_lo0: u32,
+    entry_lo1: u32,
+    context: u32,
+    page_mask: u32,
+    wired: u32,
+    entry_hi: u32,
+}
+
+impl Tlb {
+    /// A TLB of `size` entries. They start out invalid, each for a different kseg0 page
+    /// (which is never looked up), so that writing one entry can't make two of them match.
+    pub fn new(size: u32) -> Self {
+        assert!((1..=64).contains(&size), "the TLB must have between 1 and 64 entries");
+
+        let entries = (0..size)
+            .map(|i| TlbEntry {
+                page_mask: 0,
+                entry_hi: 0x80000000 + i * 0x2000,
+                global: false,
+                entry_lo: [0, 0],
+            })
+            .collect();
+
+        Self {
+            entries,
+            index: 0,
+            random: size - 1,
+            entry_lo0: 0,
+            entry_lo1: 0,
+            context: 0,
+            page_mask: 0,
+            wired: 0,
+            entry_hi: 0,
+        }
+    }
+
+    pub fn entries(&self) -> &[TlbEntry] {
+        &self.entries
+    }
+
+    pub fn wired(&self) -> u32 {
+        self.wired
+    }
+
+    /// The ASID that non-global entries must have to match.
+    pub fn asid(&self) -> u32 {
+        self.entry_hi & ENTRY_HI_ASID
+    }
+
+    pub(super) fn read(&self, reg_num: u32) -> Option<u32> {
+        match reg_num {
+            INDEX => Some(self.index),
+            RANDOM => Some(self.random),
+            ENTRY_LO0 => Some(self.entry_lo0),
+            ENTRY_LO1 => Some(self.entry_lo1),
+            CONTEXT => Some(self.context),
+            PAGE_MASK => Some(self.page_mask),
+            WIRED => Some(self.wired),
+            ENTRY_HI => Some(self.entry_hi),
+            _ => None,
+        }
+    }
+
+    /// Writes a register for `mtc0`. Random and Context.BadVPN2 are read-only.
+    pub(super) fn write(&mut self, reg_num: u32, value: u32) {
+        match reg_num {
+            INDEX => self.index = value % self.entries.len() as u32,
+            ENTRY_LO0 => self.entry_lo0 = value & 0x3FFFFFFF,
+            ENTRY_LO1 => self.entry_lo1 = value & 0x3FFFFFFF,
+            CONTEXT => self.context = (self.context & !CONTEXT_PTE_BASE) | (value & CONTEXT_PTE_BASE),
+            PAGE_MASK => self.page_mask = value & PAGE_MASK_MASK,
+            // Random starts again from the top whenever Wired is written
+            WIRED => {
+                self.wired = value % self.entries.len() as u32;
+                self.random = self.entries.len() as u32 - 1;
+            }
+            ENTRY_HI => self.entry_hi = value & (ENTRY_HI_VPN2 | ENTRY_HI_ASID),
+            _ => {}
+        }
+    }
+
+    /// Counts Random down a cycle, from the top entry to the first unwired one.
+    pub(super) fn tick(&mut self) {
+        self.random = match self.random <= self.wired {
+            true => self.entries.len() as u32 - 1,
+            false => self.random - 1,
+        };
+    }
+
+    /// The physical address that `addr` is mapped to.
+    pub(super) fn lookup(&self, addr: u32, write: bool) -> Result<u32, TlbFault> {
+        let entry = self.entries.iter()
+            .find(|entry| entry.matches(addr, self.asid()))
+            .ok_or(TlbFault::Refill)?;
+
+        let offset_mask = entry.page_size() - 1;
+        let entry_lo = entry.entry_lo[(addr & entry.page_size() != 0) as usize];
+
+        if entry_lo & ENTRY_LO_V == 0 {
+            return Err(TlbFault::Invalid);
+        }
+        if write && entry_lo & ENTRY_LO_D == 0 {
+            return Err(TlbFault::Modified);
+        }
+
+        let pfn = (entry_lo >> 6) & 0xFFFFFF;
+        Ok((pfn << 12) & !offset_mask | addr & offset_mask)
+    }
+
+    /// Sets EntryHi.VPN2 and Context.BadVPN2 to the page of `addr`, for the handler of a TLB exception.
+    pub(super) fn record_fault(&mut self, addr: u32) {
+        self.entry_hi = (addr & ENTRY_HI_VPN2) | self.asid();
+        self.context = (self.context & CONTEXT_PTE_BASE) | (addr >> 13) << 4;
+    }
+
+    /// `tlbp`: finds the entry matching EntryHi.
+    pub(super) fn probe(&mut self) {
+        self.index = match self.entries.iter().position(|entry| entry.matches(self.entry_hi, self.asid())) {
+            Some(index) => index as u32,
+            None => self.index | INDEX_P,
+        };
+    }
+
+    /// `tlbr`: reads the entry at Index into EntryHi, EntryLo0, EntryLo1 and PageMask.
+    pub(super) fn read_indexed(&mut self) {
+        let entry = self.entries[(self.index & !INDEX_P) as usize % self.entries.len()];
+        let global = entry.global as u32 * ENTRY_LO_G;
+
+        self.entry_hi = entry.entry_hi;
+        self.entry_lo0 = entry.entry_lo[0] | global;
+        self.entry_lo1 = entry.entry_lo[1] | global;
+        self.page_mask = entry.page_mask;
+    }
+
+    /// `tlbwi`: writes the entry at Index.
+    pub(super) fn write_indexed(&mut self) {
+        self.write_entry(self.index & !INDEX_P);
+    }
+
+    /// `tlbwr`: writes the entry at Random.
+    pub(super) fn write_random(&mut self) {
+        self.write_entry(self.random);
+    }
+
+    fn write_entry(&mut self, index: u32) {
+        let len = self.entries.len();
+
+        self.entries[index as usize % len] = TlbEntry {
+            page_mask: self.page_mask,
+            entry_hi: self.entry_hi & !self.page_mask,
+            global: self.entry_lo0 & self.entry_lo1 & ENTRY_LO_G != 0,
+            entry_lo: [self.entry_lo0 & !ENTRY_LO_G, self.entry_lo1 & !ENTRY_LO_G],
+        };
+    }
+}
@@ -1,9 +1,10 @@
 use crate::{DATA_BOT, GLOBAL_BOT, KDATA_BOT, KTEXT_BOT, STACK_BOT, STACK_TOP, TEXT_BOT, TEXT_TOP};
 use serde::{Deserialize, Serialize};
 
-#[derive(Copy, Debug, Serialize, Deserialize)]
+#[derive(Copy, Debug, Default, Serialize, Deserialize)]
 pub enum Safe<T> {
     Valid(T),
+    #[default]
     Uninitialised,
 }
 
@@ -25,6 +26,13 @@ impl<T> Safe<T> {
             Self::Uninitialised => None,
         }
     }
+
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Safe<U> {
+        match self {
+            Self::Valid(t) => Safe::Valid(f(t)),
+            Self::Uninitialised => Safe::Uninitialised,
+        }
+    }
 }
 
 impl<T> PartialEq for Safe<T>
@@ -52,12 +60,6 @@ where
     }
 }
 
-impl<T> Default for Safe<T> {
-    fn default() -> Self {
-        Self::Uninitialised
-    }
-}
-
 pub trait TruncImm {
     fn trunc_imm(&self) -> Self;
 }
@@ -2,7 +2,7 @@ use std::fmt::Display;
 
 use nom::branch::alt;
 use nom::bytes::complete::tag;
-use nom::character::complete::char;
+use nom::character::complete::{char, space0};
 use nom::combinator::map;
 use nom::multi::many0;
 use nom::sequence::tuple;
@@ -93,6 +93,30 @@ impl Display for MpConstValue {
     }
 }
 
+pub fn parse_constant(i: Span<'_>) -> IResult<Span<'_>, MpConst> {
+    let (remaining_data, (pos_start, label, _, _, _, value, pos_end)) = tuple((
+        position,
+        parse_ident,
+        space0,
+        char('='),
+        space0,
+        parse_constant_value,
+        position,
+    ))(i)?;
+
+    Ok((
+        remaining_data,
+        MpConst {
+            label,
+            value,
+            line: pos_start.location_line(),
+            col: pos_start.get_column() as _,
+            line_end: pos_end.location_line(),
+            col_end: pos_end.get_column() as _,
+        },
+    ))
+}
+
 pub fn parse_constant_value(i: Span<'_>) -> IResult<Span<'_>, MpConstValueLoc> {
     parse_or_value(i)
 }
@@ -1,10 +1,22 @@
 use std::fmt::Display;
 
 use crate::{
-    constant::{MpConstValueLoc},
+    constant::{parse_constant_value, MpConstValueLoc},
+    misc::{parse_escaped_char, parse_ident},
+    number::{parse_f32, parse_f64},
     parser::Position,
+    Span,
 };
-
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, one_of, space0, space1},
+    combinator::{map, not, opt},
+    multi::{many0, separated_list1},
+    sequence::{preceded, tuple},
+    IResult,
+};
+use nom_locate::position;
 use serde::{Deserialize, Serialize};
 
 pub type MpDirectiveLoc = (MpDirective, Position);
@@ -13,6 +25,9 @@ pub type MpDirectiveLoc = (MpDirective, Position);
 pub enum MpDirective {
     Text,
     Data,
+    /// `.ktext` and `.kdata` can be given the address to continue from, e.g. `.ktext 0x80000180`.
+    KText(Option<MpConstValueLoc>),
+    KData(Option<MpConstValueLoc>),
     Ascii(String),
     Asciiz(String),
     Byte(Vec<(MpConstValueLoc, Option<MpConstValueLoc>)>),
@@ -45,7 +60,172 @@ impl Display for MpDirective {
                 Globl(_) => "globl",
                 Text => "text",
                 Data => "data",
+                KText(_) => "ktext",
+                KData(_) => "kdata",
             }
         )
     }
 }
+
+pub fn parse_directive(i: Span<'_>) -> IResult<Span<'_>, MpDirectiveLoc> {
+    map(
+        tuple((
+            position,
+            alt((
+                parse_text,
+                parse_data,
+                parse_ktext,
+                parse_kdata,
+                parse_asciiz,
+                parse_ascii,
+                parse_byte,
+                parse_half,
+                parse_word,
+                parse_float,
+                parse_double,
+                parse_align,
+                parse_space,
+                parse_globl,
+            )),
+            position,
+        )),
+        |(pos_start, directive, pos_end)| {
+            (directive, Position::from_positions(pos_start, pos_end))
+        },
+    )(i)
+}
+
+fn parse_text(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(tuple((tag(".text"), not(parse_ident))), |_| MpDirective::Text)(i)
+}
+
+fn parse_data(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(tuple((tag(".data"), not(parse_ident))), |_| MpDirective::Data)(i)
+}
+
+fn parse_ktext(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".ktext"), not(parse_ident), opt(preceded(space1, parse_constant_value)))),
+        |(_, _, addr)| MpDirective::KText(addr),
+    )(i)
+}
+
+fn parse_kdata(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".kdata"), not(parse_ident), opt(preceded(space1, parse_constant_value)))),
+        |(_, _, addr)| MpDirective::KData(addr),
+    )(i)
+}
+
+fn parse_ascii(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".ascii"), space1, parse_string)),
+        |(_, _, string)| MpDirective::Ascii(string),
+    )(i)
+}
+
+fn parse_asciiz(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".asciiz"), space1, parse_string)),
+        |(_, _, string)| MpDirective::Asciiz(string),
+    )(i)
+}
+
+fn parse_byte(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".byte"), space1, parse_num_list)),
+        |(_, _, values)| MpDirective::Byte(values),
+    )(i)
+}
+
+fn parse_half(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".half"), space1, parse_num_list)),
+        |(_, _, values)| MpDirective::Half(values),
+    )(i)
+}
+
+fn parse_word(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".word"), space1, parse_num_list)),
+        |(_, _, values)| MpDirective::Word(values),
+    )(i)
+}
+
+fn parse_float(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((
+            tag(".float"),
+            space1,
+            separated_list1(
+                tuple((space0, char(','), space0)),
+                tuple((parse_f32, parse_repeat_count)),
+            ),
+        )),
+        |(_, _, values)| MpDirective::Float(values),
+    )(i)
+}
+
+fn parse_double(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((
+            tag(".double"),
+            space1,
+            separated_list1(
+                tuple((space0, char(','), space0)),
+                tuple((parse_f64, parse_repeat_count)),
+            ),
+        )),
+        |(_, _, values)| MpDirective::Double(values),
+    )(i)
+}
+
+fn parse_align(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".align"), space1, parse_constant_value)),
+        |(_, _, value)| MpDirective::Align(value),
+    )(i)
+}
+
+fn parse_space(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".space"), space1, parse_constant_value)),
+        |(_, _, value)| MpDirective::Space(value),
+    )(i)
+}
+
+fn parse_globl(i: Span<'_>) -> IResult<Span<'_>, MpDirective> {
+    map(
+        tuple((tag(".globl"), space1, parse_ident)),
+        |(_, _, label)| MpDirective::Globl(label),
+    )(i)
+}
+
+/// A comma separated list of values, each optionally followed
+/// by `:count` to repeat it, e.g. `.word 0:10, 1`.
+fn parse_num_list(
+    i: Span<'_>,
+) -> IResult<Span<'_>, Vec<(MpConstValueLoc, Option<MpConstValueLoc>)>> {
+    separated_list1(
+        tuple((space0, char(','), space0)),
+        tuple((parse_constant_value, parse_repeat_count)),
+    )(i)
+}
+
+fn parse_repeat_count(i: Span<'_>) -> IResult<Span<'_>, Option<MpConstValueLoc>> {
+    opt(preceded(
+        tuple((space0, char(':'), space0)),
+        parse_constant_value,
+    ))(i)
+}
+
+fn parse_string(i: Span<'_>) -> IResult<Span<'_>, String> {
+    map(
+        tuple((
+            char('"'),
+            many0(preceded(not(one_of("\"\n")), parse_escaped_char)),
+            char('"'),
+        )),
+        |(_, chars, _)| chars.into_iter().collect(),
+    )(i)
+}
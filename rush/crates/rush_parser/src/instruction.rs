@@ -1,14 +1,17 @@
 use std::fmt;
 
 use crate::{
+    misc::parse_ident,
     number::{parse_number, MpNumber},
     register::{parse_register, MpRegister},
     Span,
 };
 use nom::{
     branch::alt,
-    combinator::map,
-    sequence::tuple,
+    character::complete::{char, space0, space1},
+    combinator::{map, opt},
+    multi::separated_list1,
+    sequence::{preceded, tuple},
     IResult,
 };
 use nom_locate::position;
@@ -65,6 +68,33 @@ impl fmt::Display for MpArgument {
     }
 }
 
+pub fn parse_instruction(i: Span<'_>) -> IResult<Span<'_>, MpInstruction> {
+    let (remaining_data, (pos_start, name, pos_end, arguments)) = tuple((
+        position,
+        parse_ident,
+        position,
+        opt(preceded(
+            space1,
+            separated_list1(
+                // spim also accepts arguments separated by whitespace alone
+                alt((map(tuple((space0, char(','), space0)), |_| ()), map(space1, |_| ()))),
+                parse_argument,
+            ),
+        )),
+    ))(i)?;
+
+    Ok((
+        remaining_data,
+        MpInstruction {
+            name,
+            arguments: arguments.unwrap_or_default(),
+            line: pos_start.location_line(),
+            col: pos_start.get_column() as _,
+            col_end: pos_end.get_column() as _,
+        },
+    ))
+}
+
 pub fn parse_argument(i: Span<'_>) -> IResult<Span<'_>, (MpArgument, u32, u32)> {
     map(
         tuple((
@@ -1,3 +1,7 @@
+use crate::{misc::parse_ident, Span};
+use nom::{character::complete::char, sequence::tuple, IResult};
+use nom_locate::position;
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct MpLabel {
     label: String,
@@ -17,4 +21,17 @@ impl MpLabel {
     pub fn col_end(&self) -> u32 {
         self.col_end
     }
-}
\ No newline at end of file
+}
+pub fn parse_label(i: Span<'_>) -> IResult<Span<'_>, MpLabel> {
+    let (remaining_data, (pos_start, label, pos_end, _)) =
+        tuple((position, parse_ident, position, char(':')))(i)?;
+
+    Ok((
+        remaining_data,
+        MpLabel {
+            label,
+            col: pos_start.get_column() as _,
+            col_end: pos_end.get_column() as _,
+        },
+    ))
+}
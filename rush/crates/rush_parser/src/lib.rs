@@ -11,9 +11,22 @@ pub use directive::MpDirective;
 pub use instruction::{MpArgument, MpInstruction};
 pub use misc::{tabs_to_spaces, ErrorLocation};
 pub use number::{MpImmediate, MpImmediateBinaryOp, MpNumber};
-pub use parser::{MpItem, MpProgram, TaggedFile};
+pub use parser::{parse_mips, MpAttributedItem, MpItem, MpProgram, TaggedFile};
 pub use register::{MpOffsetOperator, MpRegister, MpRegisterIdentifier};
 
+pub fn parse_instruction<T>(input: T, tab_size: u32) -> Result<MpInstruction, ErrorLocation>
+where
+    T: AsRef<str>,
+{
+    let string = misc::tabs_to_spaces(input, tab_size);
+
+    parse_result(
+        Span::new(string.as_bytes()),
+        None,
+        instruction::parse_instruction,
+    )
+}
+
 pub fn parse_argument<T>(input: T, tab_size: u32) -> Result<MpArgument, ErrorLocation>
 where
     T: AsRef<str>,
@@ -162,11 +162,13 @@ where
 }
 
 #[cfg(test)]
+#[allow(dead_code)]
 pub(crate) fn span(string: &str) -> Span<'_> {
     Span::new(string.as_bytes())
 }
 
 #[cfg(test)]
+#[allow(dead_code)]
 pub(crate) fn unspan<T>(tuple: (Span<'_>, T)) -> (String, T) {
     let (span, t) = tuple;
     (String::from_utf8_lossy(span.fragment()).to_string(), t)
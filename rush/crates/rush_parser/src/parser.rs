@@ -1,14 +1,22 @@
-use crate::directive::MpDirectiveLoc;
+use crate::directive::{parse_directive, MpDirectiveLoc};
 use std::rc::Rc;
 
 use crate::{
-    attribute::{Attribute},
-    constant::{MpConst},
-    instruction::{MpInstruction},
-    label::{MpLabel},
+    attribute::Attribute,
+    constant::{parse_constant, MpConst},
+    instruction::{parse_instruction, MpInstruction},
+    label::{parse_label, MpLabel},
+    misc::{comment_multispace0, parse_result, tabs_to_spaces, ErrorLocation},
+    Span,
 };
-use nom::AsBytes;
-use nom_locate::LocatedSpan;
+use nom::{
+    branch::alt,
+    combinator::map,
+    multi::many0,
+    sequence::tuple,
+    AsBytes, IResult,
+};
+use nom_locate::{position, LocatedSpan};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug)]
@@ -152,4 +160,49 @@ impl MpProgram {
     pub fn items_mut(&mut self) -> &mut Vec<MpAttributedItem> {
         &mut self.items
     }
-}
\ No newline at end of file
+}
+/// Parses each of the `files` in turn, concatenating their items into a single program.
+///
+/// Items remember which file and line they came from, so that later errors can point back at them.
+pub fn parse_mips(files: Vec<TaggedFile<'_, '_>>, tab_size: u32) -> Result<MpProgram, ErrorLocation> {
+    let mut items = vec![];
+
+    for file in files {
+        let file_tag: Option<Rc<str>> = file.tag().map(Rc::from);
+        let contents = tabs_to_spaces(file.file_contents(), tab_size);
+
+        let file_items = parse_result(
+            Span::new(contents.as_bytes()),
+            file_tag.clone(),
+            parse_mips_file,
+        )?;
+
+        items.extend(file_items.into_iter().map(|(item, line_number)| {
+            MpAttributedItem::new(item, vec![], file_tag.clone(), line_number)
+        }));
+    }
+
+    Ok(MpProgram::new(items, vec![]))
+}
+
+fn parse_mips_file(i: Span<'_>) -> IResult<Span<'_>, Vec<(MpItem, u32)>> {
+    map(
+        tuple((
+            comment_multispace0,
+            many0(map(
+                tuple((position, parse_mips_item, comment_multispace0)),
+                |(pos, item, _)| (item, pos.location_line()),
+            )),
+        )),
+        |(_, items)| items,
+    )(i)
+}
+
+fn parse_mips_item(i: Span<'_>) -> IResult<Span<'_>, MpItem> {
+    alt((
+        map(parse_constant, MpItem::Constant),
+        map(parse_label, MpItem::Label),
+        map(parse_directive, MpItem::Directive),
+        map(parse_instruction, MpItem::Instruction),
+    ))(i)
+}
@@ -1,9 +1,38 @@
 use serde::{Deserialize, Serialize};
 use std::{
+    fmt::Display,
     fs::File,
     io::Read,
     path::PathBuf
 };
+
+pub type ConfigResult<T> = Result<T, ConfigError>;
+
+/// Why a config couldn't be used.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ConfigError {
+    CannotReadFile {
+        path: String,
+        os_error: String,
+    },
+    Malformed {
+        path: String,
+        reason: String,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::CannotReadFile { path, os_error } => {
+                write!(f, "failed to read config `{}`: {}", path, os_error)
+            }
+            ConfigError::Malformed { path, reason } => {
+                write!(f, "malformed config `{}`: {}", path, reason)
+            }
+        }
+    }
+}
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct RushConfigMemoryText {
     pub start: u32,
@@ -24,6 +53,8 @@ pub struct RushConfigMemoryStack {
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct RushConfigMemory {
+    /// Only needed for flat binaries, ELF executables describe their own text segment.
+    #[serde(default)]
     pub text: RushConfigMemoryText,
     pub global: RushConfigMemoryGlobal,
     pub data_bot: u32,
@@ -36,21 +67,420 @@ pub struct RushConfigMemory {
 pub struct RushConfig {
     pub memory: RushConfigMemory,
     pub executable: String,
-    pub start_addr: u32
+    /// Only needed for flat binaries, ELF executables provide `e_entry`.
+    #[serde(default)]
+    pub start_addr: Option<u32>,
+    /// Optional `nm`-style symbol map, e.g. `nm prog.elf > prog.map`.
+    /// Symbols from the map are added to (and override) those read from the executable.
+    #[serde(default)]
+    pub symbol_map: Option<String>,
+    /// Execute the instruction after each branch or jump before it takes effect, as real MIPS does.
+    /// Needed for code from compilers such as `mips-none-elf-gcc`, but not for spim-style assembly.
+    #[serde(default)]
+    pub delay_slots: bool,
+    /// Make loaded values invisible to the instruction after the load, as on the R2000/R3000,
+    /// and warn when that instruction reads the loaded register.
+    #[serde(default)]
+    pub load_delay: bool,
+    /// Run as a 64-bit (MIPS64) processor, with 64-bit GPRs, HI and LO and the doubleword
+    /// instructions (`ld`, `daddu`, `dsll32`, ...), as needed for `mips64` toolchain output.
+    #[serde(default)]
+    pub mips64: bool,
+    /// Which release of the ISA to decode, `r2` (the default) or `r6`. Release 6 reuses
+    /// many encodings (e.g. for compact branches), so code built with `-mips32r6` needs `r6`.
+    #[serde(default)]
+    pub isa: IsaRevision,
+    /// The byte order of memory, `big` or `little`. ELF executables default to their own
+    /// (`mips-none-elf` targets are big-endian), and anything else to little-endian, as spim is.
+    #[serde(default)]
+    pub endianness: Option<Endianness>,
+    /// Translate addresses as a MIPS32 MMU does: kseg0 and kseg1 are unmapped aliases of
+    /// physical memory, and kuseg and kseg2 go through a software-managed TLB.
+    /// Without it, virtual addresses are used as they are, with spim's memory layout.
+    #[serde(default)]
+    pub mmu: bool,
+    /// The number of entries in the TLB (1 to 64).
+    #[serde(default = "default_tlb_entries")]
+    pub tlb_entries: u32,
+    /// How many cycles instructions take, for the simulated clock.
+    #[serde(default)]
+    pub timing: RushConfigTiming,
+    /// L1 caches to simulate, for hit and miss figures. Without them memory is uncached.
+    #[serde(default)]
+    pub cache: RushConfigCaches,
+    /// Memory-mapped peripherals, which take the place of memory in their address ranges.
+    #[serde(default)]
+    pub devices: Vec<RushConfigDevice>
+}
+
+/// A memory-mapped peripheral, e.g. `{ type: timer, base: 0x1F000000, irq: 2 }`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RushConfigDevice {
+    /// The physical address its registers start at (the same as the virtual one without an MMU).
+    pub base: u32,
+    /// The hardware interrupt line (0 to 5) it raises, if it raises one.
+    #[serde(default)]
+    pub irq: Option<u32>,
+    #[serde(flatten)]
+    pub kind: DeviceKind
+}
+
+/// The kinds of device that can be declared in `devices`, along with their settings.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum DeviceKind {
+    /// A countdown timer, which interrupts whenever it reaches zero.
+    Timer,
+    /// A 16550-compatible UART, e.g. `{ type: uart, base: 0x1F000900, backend: pty }`.
+    Uart {
+        /// Where the bytes it sends go, and the bytes it receives come from.
+        #[serde(default)]
+        backend: UartBackend,
+        /// Its registers are `1 << reg_shift` bytes apart, as they're wired on some boards.
+        #[serde(default)]
+        reg_shift: u32
+    },
+    /// MARS's memory-mapped keyboard and display, at `base` `0xFFFF0000` as in MARS.
+    Mars {
+        /// Cycles the display takes to show a character, during which it isn't ready for another.
+        #[serde(default)]
+        transmit_delay: u64
+    },
+    /// A bitmap display, like MARS's, e.g. `{ type: framebuffer, base: 0x10040000, width: 64, height: 64 }`.
+    Framebuffer(RushConfigFramebuffer),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RushConfigFramebuffer {
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub format: PixelFormat,
+    /// Cycles between refreshes of the display, which is what counts as a frame for `save_every`.
+    #[serde(default = "default_frame_cycles")]
+    pub frame_cycles: u64,
+    /// Where to save frames to (as PPM, or PNG if it ends in `.png`), with `{}` replaced by the number of the image.
+    #[serde(default)]
+    pub save: Option<String>,
+    /// Save every this many frames.
+    #[serde(default)]
+    pub save_every: Option<u64>,
+    /// Save whenever the program writes the vsync register.
+    #[serde(default)]
+    pub save_on_vsync: bool
+}
+
+/// How a framebuffer's pixels are laid out in memory.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PixelFormat {
+    /// A word per pixel, `0x00RRGGBB`, as in MARS's Bitmap Display.
+    #[default]
+    Rgb888,
+    /// A half per pixel, with 5 bits of red, 6 of green and 5 of blue.
+    Rgb565,
+    /// A byte per pixel, of grey.
+    Gray8,
+}
+
+impl PixelFormat {
+    pub fn bytes_per_pixel(self) -> u32 {
+        match self {
+            PixelFormat::Rgb888 => 4,
+            PixelFormat::Rgb565 => 2,
+            PixelFormat::Gray8 => 1,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UartBackend {
+    /// rush's own stdin and stdout.
+    #[default]
+    Stdio,
+    /// Receives the contents of `input` (if given) and sends to `output`, for scripted tests.
+    File {
+        #[serde(default)]
+        input: Option<String>,
+        output: String
+    },
+    /// Listens on a Unix socket at this path, for attaching a terminal with e.g. `socat`.
+    Socket(String),
+    /// A new pseudo-terminal, for attaching a terminal with e.g. `screen`. Its path is printed at startup.
+    Pty,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RushConfigCaches {
+    #[serde(default)]
+    pub icache: Option<RushConfigCache>,
+    #[serde(default)]
+    pub dcache: Option<RushConfigCache>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RushConfigCache {
+    /// The total size, in bytes.
+    pub size: u32,
+    pub line_size: u32,
+    /// The number of lines in each set, 1 for a direct-mapped cache.
+    #[serde(default = "default_associativity")]
+    pub associativity: u32,
+    #[serde(default)]
+    pub replacement: ReplacementPolicy,
+    /// Ignored for the I-cache, which is never written.
+    #[serde(default)]
+    pub write: WritePolicy,
+    /// Cycles added for each miss, and for writing back each dirty line that's replaced.
+    pub miss_penalty: u64
+}
+
+impl RushConfigCache {
+    /// Checks that the lines can be split into at least one set of `associativity` of them.
+    fn validate(&self) -> Result<(), String> {
+        if !self.line_size.is_power_of_two() {
+            return Err(format!("has a `line_size` of {}, which isn't a power of two", self.line_size));
+        }
+        if self.associativity == 0 {
+            return Err("has an `associativity` of 0, but a set needs at least one line".to_string());
+        }
+
+        match self.line_size.checked_mul(self.associativity) {
+            Some(set_size) if self.size >= set_size && self.size.is_multiple_of(set_size) => Ok(()),
+            _ => Err(format!(
+                "has a `size` of {}, which isn't a multiple of `line_size` * `associativity` ({} * {})",
+                self.size, self.line_size, self.associativity,
+            )),
+        }
+    }
+}
+
+fn default_tlb_entries() -> u32 {
+    16
+}
+
+fn default_associativity() -> u32 {
+    1
+}
+
+/// 60 frames a second at the default 15 MHz.
+fn default_frame_cycles() -> u64 {
+    250_000
+}
+
+/// Which line of a full set is replaced on a miss.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplacementPolicy {
+    /// The least recently used.
+    #[default]
+    Lru,
+    /// The one filled longest ago.
+    Fifo,
+    /// Any, chosen pseudo-randomly (but the same way on every run).
+    Random,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WritePolicy {
+    /// Stores only write the cache, allocating a line on a miss, and dirty lines are written back when replaced.
+    #[default]
+    WriteBack,
+    /// Stores also write memory (through a write buffer, so they don't stall), and a miss doesn't allocate a line.
+    WriteThrough,
+}
+
+/// A CPU profile, optionally with some of its numbers overridden, e.g.
+/// `timing: { profile: r4000, freq: 80000000 }`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct RushConfigTiming {
+    #[serde(default)]
+    pub profile: CpuProfile,
+    /// The clock rate, in Hz.
+    #[serde(default)]
+    pub freq: Option<u64>,
+    #[serde(default)]
+    pub mult: Option<u64>,
+    #[serde(default)]
+    pub div: Option<u64>,
+    #[serde(default)]
+    pub syscall: Option<u64>,
+    #[serde(default)]
+    pub load_use: Option<u64>,
+    #[serde(default)]
+    pub taken_branch: Option<u64>
+}
+
+impl RushConfigTiming {
+    pub fn freq(&self) -> u64 {
+        self.freq.unwrap_or(self.profile.freq())
+    }
+
+    pub fn costs(&self) -> CycleCosts {
+        let costs = self.profile.costs();
+
+        CycleCosts {
+            mult: self.mult.unwrap_or(costs.mult),
+            div: self.div.unwrap_or(costs.div),
+            syscall: self.syscall.unwrap_or(costs.syscall),
+            load_use: self.load_use.unwrap_or(costs.load_use),
+            taken_branch: self.taken_branch.unwrap_or(costs.taken_branch)
+        }
+    }
+}
+
+/// How many cycles instructions take. Anything not listed takes one.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct CycleCosts {
+    /// Cycles taken by a multiply (`mult`, `mul`, `madd`, ...).
+    pub mult: u64,
+    /// Cycles taken by a divide (`div`, `divu`, ...).
+    pub div: u64,
+    /// Cycles taken by a `syscall`, standing in for the trip through the kernel.
+    pub syscall: u64,
+    /// Extra cycles for an instruction that reads the register loaded by the one before it.
+    pub load_use: u64,
+    /// Extra cycles for a taken branch or jump.
+    pub taken_branch: u64
+}
+
+/// The processors whose timing can be simulated. Their numbers are rough figures
+/// from the manuals, for comparing against benchmarks rather than cycle-exact.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CpuProfile {
+    /// One cycle per instruction at 15 MHz, as rush has always counted.
+    #[default]
+    Ideal,
+    /// No interlocks, so loads and branches are covered by their delay slots.
+    R3000,
+    /// An interlocked load delay of two cycles and a branch delay of three.
+    R4000,
+    #[serde(rename = "24k")]
+    Mips24k,
+}
+
+impl CpuProfile {
+    pub fn freq(self) -> u64 {
+        match self {
+            CpuProfile::Ideal => 15_000_000,
+            CpuProfile::R3000 => 33_000_000,
+            CpuProfile::R4000 => 100_000_000,
+            CpuProfile::Mips24k => 400_000_000,
+        }
+    }
+
+    pub fn costs(self) -> CycleCosts {
+        let (mult, div, syscall, load_use, taken_branch) = match self {
+            CpuProfile::Ideal => (1, 1, 1, 0, 0),
+            CpuProfile::R3000 => (12, 35, 100, 0, 0),
+            CpuProfile::R4000 => (10, 69, 150, 2, 2),
+            CpuProfile::Mips24k => (5, 35, 200, 1, 1),
+        };
+
+        CycleCosts { mult, div, syscall, load_use, taken_branch }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endianness {
+    Big,
+    /// (the default for anything but an ELF executable, which has its own, as spim is little-endian)
+    #[default]
+    Little,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IsaRevision {
+    #[default]
+    R2,
+    R6,
 }
 
 impl RushConfig {
-    pub fn new(config_path_str: &String) -> Self {
+    pub fn new(config_path_str: &String) -> ConfigResult<Self> {
         let config_path = PathBuf::from(config_path_str);
 
-        let mut file = File::open(&config_path).unwrap();
+        let cannot_read = |err: std::io::Error| ConfigError::CannotReadFile {
+            path: config_path_str.clone(),
+            os_error: err.to_string(),
+        };
+
+        let mut file = File::open(&config_path).map_err(cannot_read)?;
         let mut contents = String::new();
 
-        file.read_to_string(&mut contents).unwrap();
+        file.read_to_string(&mut contents).map_err(cannot_read)?;
+
+        let malformed = |reason: String| ConfigError::Malformed {
+            path: config_path_str.clone(),
+            reason,
+        };
+
+        let config: Self = serde_yaml::from_str(&contents).map_err(|err| malformed(err.to_string()))?;
+        config.validate().map_err(malformed)?;
+
+        Ok(config)
+    }
 
-        let config = serde_yaml::from_str(&contents).unwrap();
+    /// Checks the settings that can be out of range even though they parse, so that they're
+    /// reported along with the config rather than when the simulator is built from it.
+    fn validate(&self) -> Result<(), String> {
+        if !(1..=64).contains(&self.tlb_entries) {
+            return Err(format!("`tlb_entries` must be between 1 and 64, not {}", self.tlb_entries));
+        }
+
+        for (name, cache) in [("icache", &self.cache.icache), ("dcache", &self.cache.dcache)] {
+            if let Some(cache) = cache {
+                cache.validate().map_err(|reason| format!("`cache.{}` {}", name, reason))?;
+            }
+        }
+
+        for device in &self.devices {
+            if let Some(irq) = device.irq.filter(|&irq| irq > 5) {
+                return Err(format!("the device at {:#010x} has `irq: {}`, but the interrupt lines are 0 to 5", device.base, irq));
+            }
+        }
+
+        Ok(())
+    }
 
-        return config
+    /// The memory layout spim uses, for running assembly source without a config file.
+    pub fn spim(executable: String) -> Self {
+        Self {
+            memory: RushConfigMemory {
+                text: RushConfigMemoryText {
+                    start: 0x00400000,
+                    end: 0x0FFFFFFF
+                },
+                global: RushConfigMemoryGlobal {
+                    bot: 0x10000000,
+                    ptr: 0x10008000
+                },
+                data_bot: 0x10010000,
+                heap_bot: 0x10040000,
+                stack: RushConfigMemoryStack {
+                    top: 0x80000000,
+                    bot: 0x7FFF0000
+                }
+            },
+            executable,
+            start_addr: None,
+            symbol_map: None,
+            delay_slots: false,
+            load_delay: false,
+            mips64: false,
+            isa: IsaRevision::R2,
+            endianness: None,
+            mmu: false,
+            tlb_entries: default_tlb_entries(),
+            timing: RushConfigTiming::default(),
+            cache: RushConfigCaches::default(),
+            devices: vec![]
+        }
     }
 }
 
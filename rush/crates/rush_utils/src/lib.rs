@@ -2,6 +2,22 @@ mod config;
 mod expand;
 
 pub use config::RushConfig;
+pub use config::ConfigError;
+pub use config::ConfigResult;
 pub use config::RushConfigMemory;
+pub use config::Endianness;
+pub use config::IsaRevision;
+pub use config::RushConfigTiming;
+pub use config::CycleCosts;
+pub use config::CpuProfile;
+pub use config::RushConfigCaches;
+pub use config::RushConfigCache;
+pub use config::ReplacementPolicy;
+pub use config::WritePolicy;
+pub use config::RushConfigDevice;
+pub use config::DeviceKind;
+pub use config::UartBackend;
+pub use config::RushConfigFramebuffer;
+pub use config::PixelFormat;
 
 pub use expand::expand_tilde;
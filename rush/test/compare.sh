@@ -10,7 +10,7 @@ for test_file in test_files/success/*; do
         continue
     fi
 
-    rush_out=$( yes 3 2>/dev/null | ./target/debug/rush   "$test_file" 2>&1 | sed -E "/Loaded: .+/d")
+    rush_out=$( yes 3 2>/dev/null | ./target/debug/rush   "$test_file" 2>&1 | sed -E "/Loaded: .+/d; /^Emulation running on .+/d; /^Simulated [0-9]+ cycles, .+/d")
     spim_out=$( yes 3 2>/dev/null |               spim -f "$test_file" 2>&1 | sed -E "/Loaded: .+/d")
 
     if diff <(echo "$rush_out") <(echo "$spim_out") >/dev/null; then
@@ -34,24 +34,167 @@ trap 'rm -rf "$RUSH_OUT"' EXIT
 shopt -s globstar
 shopt -s nullglob
 
-for test_file in test_files/instructions/**/*.s; do
-    EXPECTED_FILE="${test_file%.s}.out"
-    OBSERVED_FILE="$RUSH_OUT/$(basename "$EXPECTED_FILE")"
+# a test with a config next to it (e.g. `LB.2.yaml` for `LB.2.s`) is run with that, and any other with spim's layout.
+# The config's `executable` is usually the test itself, but is a flat binary built from it for code rush can't
+# assemble, such as release 6 or microMIPS code (see the top of the test for how it was built).
+# A test with input next to it (e.g. `mars.stdin` for `mars.s`) reads that from stdin.
+run_rush() {
+    local config_file="${1%.s}.yaml"
+    local input_file="${1%.s}.stdin"
+
+    if [ ! -f "$input_file" ]; then
+        input_file=/dev/stdin
+    fi
+
+    if [ -f "$config_file" ]; then
+        ./target/debug/rush "$config_file" < "$input_file"
+    else
+        ./target/debug/rush "$1" < "$input_file"
+    fi
+}
+
+check_output() {
+    local test_file="$1"
+    local expected_file="${test_file%.s}.out"
+    local observed_file="$RUSH_OUT/$(basename "$expected_file")"
+
+    # a test with an image next to it (e.g. `rgb888.ppm` for `rgb888.s`) saves a frame to `target/`, which must be the same
+    local expected_image="${test_file%.s}.ppm"
+    local observed_image="target/$(basename "$expected_image")"
+    rm -f "$observed_image"
 
     echo -n "Checking \"$test_file\"... "
 
-    ./target/debug/rush "$test_file" > "$OBSERVED_FILE"
+    # (less the summary, which is on stderr, and only checked against a `.err` next to the test, e.g. `exit.err` for `exit.s`,
+    # without the wall clock emulation time)
+    local expected_errors="${test_file%.s}.err"
+    local observed_errors="$RUSH_OUT/$(basename "$expected_errors")"
+    run_rush "$test_file" 2>"$observed_errors" > "$observed_file"
+    sed -i -E "/^Emulation running on .+/d" "$observed_errors"
+
+    if ! diff "$observed_file" "$expected_file" >/dev/null; then
+        echo "FAILED"
+
+        echo "----- < Observed Output - Expected Output > -----"
+        diff -s "$observed_file" "$expected_file" --label "'Observed Output'" --label "'Expected Output'"
+        echo "-------------------------------------------------"
+
+        texts_failed=$((texts_failed + 1))
+    elif [ -f "$expected_errors" ] && ! diff "$observed_errors" "$expected_errors" >/dev/null; then
+        echo "FAILED"
+
+        echo "----- < Observed Errors - Expected Errors > -----"
+        diff -s "$observed_errors" "$expected_errors" --label "'Observed Errors'" --label "'Expected Errors'"
+        echo "-------------------------------------------------"
 
-    if diff "$OBSERVED_FILE" "$EXPECTED_FILE" >/dev/null; then
+        texts_failed=$((texts_failed + 1))
+    elif [ -f "$expected_image" ] && ! cmp -s "$observed_image" "$expected_image"; then
+        echo "FAILED"
+        echo "    the frame saved to $observed_image isn't $expected_image"
+
+        texts_failed=$((texts_failed + 1))
+    else
         echo "PASSED"
+    fi
+}
+
+for test_file in test_files/instructions/**/*.s; do
+    check_output "$test_file"
+done
+
+for test_file in test_files/loader/*.s; do
+    check_output "$test_file"
+done
+
+for test_file in test_files/devices/**/*.s; do
+    check_output "$test_file"
+done
+
+for test_file in test_files/mmu/*.s; do
+    check_output "$test_file"
+done
+
+for test_file in test_files/pipeline/*.s; do
+    check_output "$test_file"
+done
+
+for test_file in test_files/summary/*.s; do
+    check_output "$test_file"
+done
+
+# a directory of objects to link: every `.s` is assembled with `asm -c` and linked with any `.o` (from other assemblers)
+# into `prog`, whose output must be `prog.out`, and which must be the same as `prog.elf` byte for byte
+check_link() {
+    local test_dir="${1%/}"
+    local objects=()
+
+    echo -n "Checking \"$test_dir\"... "
+
+    for source_file in "$test_dir"/*.s; do
+        objects+=("$RUSH_OUT/$(basename "${source_file%.s}").o")
+        ./target/debug/rush asm -c -o "${objects[-1]}" "$source_file" >/dev/null 2>&1
+    done
+    objects+=("$test_dir"/*.o)
+
+    ./target/debug/rush link -o "$RUSH_OUT/prog" "${objects[@]}" >/dev/null 2>&1
+    ./target/debug/rush "$RUSH_OUT/prog" 2>/dev/null > "$RUSH_OUT/prog.out"
+
+    if ! diff "$RUSH_OUT/prog.out" "$test_dir/prog.out" >/dev/null; then
+        echo "FAILED"
+
+        echo "----- < Observed Output - Expected Output > -----"
+        diff -s "$RUSH_OUT/prog.out" "$test_dir/prog.out" --label "'Observed Output'" --label "'Expected Output'"
+        echo "-------------------------------------------------"
+
+        texts_failed=$((texts_failed + 1))
+    elif ! cmp -s "$RUSH_OUT/prog" "$test_dir/prog.elf"; then
+        echo "FAILED"
+        echo "    the linked executable isn't $test_dir/prog.elf"
+
+        texts_failed=$((texts_failed + 1))
     else
+        echo "PASSED"
+    fi
+
+    rm -f "$RUSH_OUT"/*.o "$RUSH_OUT/prog"
+}
+
+for test_dir in test_files/link/*/; do
+    check_link "$test_dir"
+done
+
+# a REPL script (e.g. `dwarf4.repl`) is piped into the REPL one command per line, and what it prints
+# (errors included) must be `dwarf4.out`
+check_repl() {
+    local test_file="$1"
+    local expected_file="${test_file%.repl}.out"
+    local observed_file="$RUSH_OUT/$(basename "$expected_file")"
+
+    echo -n "Checking \"$test_file\"... "
+
+    ./target/debug/rush < "$test_file" > "$observed_file" 2>&1
+
+    if ! diff "$observed_file" "$expected_file" >/dev/null; then
         echo "FAILED"
 
         echo "----- < Observed Output - Expected Output > -----"
-        diff -s "$OBSERVED_FILE" "$EXPECTED_FILE" --label "'Observed Output'" --label "'Expected Output'"
+        diff -s "$observed_file" "$expected_file" --label "'Observed Output'" --label "'Expected Output'"
         echo "-------------------------------------------------"
 
         texts_failed=$((texts_failed + 1))
+    else
+        echo "PASSED"
+    fi
+}
+
+for test_file in test_files/repl/*.repl; do
+    check_repl "$test_file"
+done
+
+# only the runtime errors with an `.out` are checked against it
+for test_file in test_files/runtime_error/*.s; do
+    if [ -f "${test_file%.s}.out" ]; then
+        check_output "$test_file"
     fi
 done
 
@@ -0,0 +1,27 @@
+# draw a 3x2 frame, a half per pixel, big-endian, and save it on vsync (see rgb565.yaml)
+	.text
+main:
+	li	$t0, 0x10040000
+
+	# the top row
+	li	$t1, 0xF800
+	sh	$t1, 0($t0)
+	li	$t1, 0x07E0
+	sh	$t1, 2($t0)
+	li	$t1, 0x001F
+	sh	$t1, 4($t0)
+
+	# the bottom row, the second pixel a byte at a time (in memory order)
+	li	$t1, 0xFFFF
+	sh	$t1, 6($t0)
+	li	$t1, 0x84
+	sb	$t1, 8($t0)
+	li	$t1, 0x10
+	sb	$t1, 9($t0)
+	sh	$zero, 10($t0)
+
+	# the frame is finished, so it's saved
+	sw	$zero, 12($t0)
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,32 @@
+# draw a 4x2 frame, a word per pixel, and save it on vsync (see rgb888.yaml)
+	.text
+main:
+	li	$t0, 0x10040000
+
+	# the top row
+	li	$t1, 0x00FF0000
+	sw	$t1, 0($t0)
+	li	$t1, 0x0000FF00
+	sw	$t1, 4($t0)
+	li	$t1, 0x000000FF
+	sw	$t1, 8($t0)
+	li	$t1, 0x00FFFFFF
+	sw	$t1, 12($t0)
+
+	# the bottom row (the top byte of a pixel is ignored)
+	sw	$zero, 16($t0)
+	li	$t1, 0xFF123456
+	sw	$t1, 20($t0)
+	li	$t1, 0x00808080
+	sw	$t1, 24($t0)
+	li	$t1, 0x00ABCDEF
+	sw	$t1, 28($t0)
+
+	# the frame is finished, so it's saved
+	sw	$zero, 32($t0)
+
+	# (and what's drawn after that isn't)
+	sw	$zero, 0($t0)
+
+	li	$v0, 10
+	syscall
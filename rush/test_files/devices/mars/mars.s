@@ -0,0 +1,77 @@
+# MARS's keyboard and display, reading `mars.stdin`: the first line is echoed by polling,
+# waiting for the display (which takes 500 cycles a character) to be ready before each character,
+# and the second is taken a key at a time by the keyboard's interrupt, then printed
+	.data
+	.word 0			# (so that the key before the first can be looked at)
+line:	.word 0, 0, 0, 0
+end:	.word line
+
+	.ktext 0x80000180
+	mfc0	$k0, $13
+	andi	$k0, $k0, 0x100		# IP0, the keyboard
+	beqz	$k0, done
+	lui	$k0, 0xFFFF
+	lw	$k0, 4($k0)		# the key, which acknowledges it
+	lw	$k1, end
+	sb	$k0, 0($k1)
+	addiu	$k1, $k1, 1
+	sw	$k1, end
+done:
+	eret
+
+	.text
+main:
+	lui	$s0, 0xFFFF		# the keyboard, and the display at +8
+	li	$s1, 0			# how many times the display wasn't ready
+
+echo:
+	lw	$t0, 0($s0)
+	andi	$t0, $t0, 1
+	beqz	$t0, echo		# until a key's been typed
+	lw	$t1, 4($s0)
+wait:
+	lw	$t0, 8($s0)
+	andi	$t0, $t0, 1
+	bnez	$t0, ready
+	addiu	$s1, $s1, 1
+	b	wait
+ready:
+	sw	$t1, 12($s0)
+	li	$t2, '\n'
+	bne	$t1, $t2, echo
+
+	move	$a0, $s1
+	li	$v0, 1
+	syscall				# 378, as the display is busy for a while after each of the 5 characters
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# MARS starts with interrupts enabled, so only the keyboard's needs enabling. It only interrupts
+	# for keys that come in while it's enabled, so one that's already come in is taken here
+	# (with interrupts disabled, so that the key after it can't be stored first).
+	di
+	li	$t0, 2
+	sw	$t0, 0($s0)
+	lw	$t0, 0($s0)
+	andi	$t0, $t0, 1
+	beqz	$t0, enabled
+	lw	$t0, 4($s0)
+	lw	$t1, end
+	sb	$t0, 0($t1)
+	addiu	$t1, $t1, 1
+	sw	$t1, end
+enabled:
+	ei
+
+wait_line:
+	lw	$t0, end
+	lbu	$t0, -1($t0)
+	li	$t1, '\n'
+	bne	$t0, $t1, wait_line
+	sw	$zero, 0($s0)
+
+	la	$a0, line
+	li	$v0, 4
+	syscall				# irq
+	jr	$ra
@@ -0,0 +1,79 @@
+# The countdown timer, first polled as a one-shot (with no reload, it stops at zero and disables itself),
+# then interrupting on line 2 every 200 cycles, until the handler has seen 3 interrupts
+	.data
+ticks:	.word 0
+
+	.ktext 0x80000180
+	lw	$k0, ticks
+	addiu	$k0, $k0, 1
+	sw	$k0, ticks
+	lui	$k0, 0x1F00
+	li	$k1, 1
+	sw	$k1, 0xC($k0)		# acknowledged, by clearing the status
+	eret
+
+	.text
+main:
+	lui	$s0, 0x1F00		# the timer
+
+	li	$t0, 50
+	sw	$t0, 0x0($s0)		# count
+	sw	$zero, 0x4($s0)		# no reload
+	li	$t0, 1
+	sw	$t0, 0x8($s0)		# enabled, without its interrupt
+poll:
+	lw	$t0, 0xC($s0)
+	beqz	$t0, poll
+
+	lw	$a0, 0x8($s0)
+	li	$v0, 1
+	syscall				# 0, as it's disabled itself
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	lw	$a0, 0x0($s0)
+	li	$v0, 1
+	syscall				# 0
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	li	$t0, 1
+	sw	$t0, 0xC($s0)
+	lw	$a0, 0xC($s0)
+	li	$v0, 1
+	syscall				# 0, once cleared
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$t0, 200
+	sw	$t0, 0x0($s0)
+	sw	$t0, 0x4($s0)		# every 200 cycles
+	li	$t0, 3
+	sw	$t0, 0x8($s0)		# enabled, with its interrupt
+	mfc0	$t0, $12
+	ori	$t0, $t0, 1
+	mtc0	$t0, $12		# interrupts enabled
+
+	li	$s1, 0			# cycles spent waiting, roughly
+wait:
+	addiu	$s1, $s1, 1
+	lw	$t0, ticks
+	li	$t1, 3
+	bne	$t0, $t1, wait
+
+	sw	$zero, 0x8($s0)		# stopped
+
+	lw	$a0, ticks
+	li	$v0, 1
+	syscall				# 3
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	move	$a0, $s1
+	li	$v0, 1
+	syscall				# 117 times round the loop, about 600 cycles
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
@@ -0,0 +1,61 @@
+# The UART, with registers a word apart (reg_shift: 2), receiving from `uart.in` and sending to stdout.
+# It echoes its input in upper case, then checks the scratch register, the divisor latch and loopback.
+	.text
+main:
+	lui	$s0, 0x1F00
+	ori	$s0, $s0, 0x0900	# the UART
+
+	li	$t0, 1
+	sw	$t0, 0x08($s0)		# FIFOs on
+
+echo:
+	lw	$t0, 0x14($s0)		# LSR
+	andi	$t0, $t0, 1
+	beqz	$t0, echo		# until a byte's been received
+	lw	$t1, 0x00($s0)
+	li	$t2, 'a'
+	blt	$t1, $t2, send
+	li	$t2, 'z'
+	bgt	$t1, $t2, send
+	addiu	$t1, $t1, -32		# in upper case
+send:
+	sw	$t1, 0x00($s0)
+	li	$t2, '\n'
+	bne	$t1, $t2, echo
+
+	# the scratch register keeps whatever's written to it
+	li	$t0, 'S'
+	sw	$t0, 0x1C($s0)
+	lw	$t1, 0x1C($s0)
+	sw	$t1, 0x00($s0)
+
+	# with LCR.DLAB set, the first two registers are the divisor latch, and THR is left alone
+	li	$t0, 0x83
+	sw	$t0, 0x0C($s0)
+	li	$t0, 'D'
+	sw	$t0, 0x00($s0)
+	li	$t0, 'L'
+	sw	$t0, 0x04($s0)
+	lw	$t1, 0x00($s0)
+	lw	$t2, 0x04($s0)
+	li	$t0, 0x03
+	sw	$t0, 0x0C($s0)
+	sw	$t1, 0x00($s0)
+	sw	$t2, 0x00($s0)
+
+	# in loopback mode, what's sent is received instead
+	li	$t0, 0x10
+	sw	$t0, 0x10($s0)
+	li	$t0, 'B'
+	sw	$t0, 0x00($s0)
+	lw	$t1, 0x14($s0)
+	andi	$t1, $t1, 1		# received
+	lw	$t2, 0x00($s0)
+	sw	$zero, 0x10($s0)
+	addiu	$t1, $t1, '0'
+	sw	$t1, 0x00($s0)
+	sw	$t2, 0x00($s0)
+
+	li	$t0, '\n'
+	sw	$t0, 0x00($s0)
+	jr	$ra
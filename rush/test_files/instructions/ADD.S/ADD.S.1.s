@@ -0,0 +1,19 @@
+	.data
+x:	.float 1.5
+y:	.float 2.25
+
+	.text
+main:
+	l.s	$f0, x
+	l.s	$f2, y
+	add.s	$f12, $f0, $f2
+
+	li	$v0, 2
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
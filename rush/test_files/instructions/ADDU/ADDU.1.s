@@ -0,0 +1,39 @@
+# run as a 64-bit processor, by ADDU.1.yaml
+	.text
+main:
+	# 32-bit results are sign-extended to 64 bits
+	li	$t0, 0x7fffffff
+	addiu	$a1, $t0, 1
+	jal	print64
+
+	addu	$a1, $t0, $t0
+	jal	print64
+
+	lui	$a1, 0x8000
+	jal	print64
+
+	# and 32-bit operations only see the low word
+	li	$t1, 1
+	dsll32	$t1, $t1, 0
+	ori	$t1, $t1, 5
+	sll	$a1, $t1, 1
+	jal	print64
+
+	li	$v0, 10
+	syscall
+
+# prints $a1 as its high and low words
+print64:
+	dsra32	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	sll	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
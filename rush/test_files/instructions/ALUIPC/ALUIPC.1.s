@@ -0,0 +1,32 @@
+# release 6 code, which rush can't assemble, run from ALUIPC.1.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r6 -filetype=obj ALUIPC.1.s -o ALUIPC.1.o
+#   llvm-objcopy -O binary -j .text ALUIPC.1.o ALUIPC.1.bin
+
+	.macro	print_int reg
+	move	$a0, \reg
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	.endm
+
+	.set	noreorder
+	.text
+main:
+	# the address of the aluipc, with its low 16 bits cleared, plus the immediate << 16
+	aluipc	$t0, 0
+	print_int $t0
+	aluipc	$t0, 2
+	print_int $t0
+	aluipc	$t0, -1
+	print_int $t0
+
+	# unlike auipc, which keeps the low bits
+	auipc	$t0, 1
+	lapc	$t1, main
+	subu	$t0, $t0, $t1
+	print_int $t0
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,34 @@
+# release 6 code, which rush can't assemble, run from BALC.1.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r6 -filetype=obj BALC.1.s -o BALC.1.o
+#   llvm-objcopy -O binary -j .text BALC.1.o BALC.1.bin
+
+	.macro	print_int reg
+	move	$a0, \reg
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	.endm
+
+	.set	noreorder
+	.text
+main:
+	# the return address is straight after the balc, with no delay slot
+	li	$t0, 1
+	balc	sub
+	li	$t0, 3
+	print_int $t0
+
+	aluipc	$t1, 0
+	subu	$t1, $ra, $t1
+	print_int $t1
+
+	li	$v0, 10
+	syscall
+
+sub:
+	print_int $t0
+	li	$t0, 2
+	jic	$ra, 0
+	li	$t0, 4
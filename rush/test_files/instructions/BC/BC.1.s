@@ -0,0 +1,42 @@
+# release 6 code, which rush can't assemble, run from BC.1.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r6 -filetype=obj BC.1.s -o BC.1.o
+#   llvm-objcopy -O binary -j .text BC.1.o BC.1.bin
+
+	.macro	print_int reg
+	move	$a0, \reg
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	.endm
+
+	.set	noreorder
+	.text
+main:
+	# compact branches have no delay slot
+	li	$t9, 0
+	li	$t0, 1
+	bc	1f
+	li	$t0, 2
+1:	print_int $t0
+
+	# and a conditional one only skips what follows it when taken
+	li	$t0, 3
+	beqzc	$t9, 1f
+	li	$t0, 4
+1:	print_int $t0
+
+	li	$t0, 5
+	bnezc	$t9, 1f
+	li	$t0, 6
+1:	print_int $t0
+
+	# unlike a branch with a delay slot
+	li	$t0, 7
+	beq	$zero, $zero, 1f
+	li	$t0, 8
+1:	print_int $t0
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,38 @@
+main:
+	move	$s0, $ra
+	li	$t0, 0
+	li	$t1, 1
+	li	$t2, -1
+	bgezall	$t1, func
+	addiu	$t0, $t0, 1
+	li	$ra, 0
+	bgezall	$t2, func
+after:
+	addiu	$t0, $t0, 10
+	la	$t3, after
+	subu	$t3, $ra, $t3
+	j	end
+func:
+	addiu	$t0, $t0, 100
+	jr	$ra
+end:
+	move	$ra, $s0
+
+	move	$a0, $t0
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,26 @@
+main:
+	move	$s0, $ra
+	li	$t1, 0
+	li	$t2, -1
+	li	$t0, 0
+	bgezl	$t1, taken
+	addiu	$t0, $t0, 1
+taken:
+	bgezl	$t2, skip
+	addiu	$t0, $t0, 10
+	j	end
+skip:
+	addiu	$t0, $t0, 100
+end:
+	move	$ra, $s0
+
+	move	$a0, $t0
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,33 @@
+	.data
+x:	.float 1.5
+y:	.float 2.25
+
+	.text
+main:
+	l.s	$f0, x
+	l.s	$f2, y
+
+	li	$a0, 0
+	c.lt.s	$f0, $f2
+	bc1f	skip
+	nop
+	li	$a0, 1
+skip:
+	li	$v0, 1
+	syscall
+
+	li	$a0, 0
+	c.lt.s	$f2, $f0
+	bc1f	skip2
+	nop
+	li	$a0, 1
+skip2:
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,20 @@
+	.data
+buf:	.word 0
+	.text
+main:
+	la	$t0, buf
+	li	$t1, 42
+	sw	$t1, 0($t0)
+	cache	0x15, 0($t0)
+	lw	$t2, 0($t0)
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,33 @@
+main:
+	li	$t0, 0xFF0F0000
+	clo	$t1, $t0
+	clo	$t2, $zero
+	li	$t0, -1
+	clo	$t3, $t0
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,33 @@
+main:
+	li	$t0, 0x00F00000
+	clz	$t1, $t0
+	clz	$t2, $zero
+	li	$t0, -1
+	clz	$t3, $t0
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,28 @@
+	.data
+x:	.float 2.5
+y:	.float -3.5
+
+	.text
+main:
+	l.s	$f0, x
+	cvt.w.s	$f2, $f0
+	mfc1	$a0, $f2
+	li	$v0, 1
+	syscall
+
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+
+	l.s	$f0, y
+	cvt.w.s	$f2, $f0
+	mfc1	$a0, $f2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,53 @@
+# run as a 64-bit processor, by DMULT.1.yaml
+	.text
+main:
+	# 2^32 * 2^32 = 2^64, all in HI
+	li	$t0, 1
+	dsll32	$t0, $t0, 0
+	dmult	$t0, $t0
+	mfhi	$a1
+	jal	print64
+	mflo	$a1
+	jal	print64
+
+	# -3 * 5, sign-extended into HI
+	li	$t0, -3
+	li	$t1, 5
+	dmult	$t0, $t1
+	mfhi	$a1
+	jal	print64
+	mflo	$a1
+	jal	print64
+
+	# -1 * -1 = 1, where dmultu gives 2^128 - 2^65 + 1
+	li	$t0, -1
+	dmult	$t0, $t0
+	mfhi	$a1
+	jal	print64
+	mflo	$a1
+	jal	print64
+
+	dmultu	$t0, $t0
+	mfhi	$a1
+	jal	print64
+	mflo	$a1
+	jal	print64
+
+	li	$v0, 10
+	syscall
+
+# prints $a1 as its high and low words
+print64:
+	dsra32	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	sll	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
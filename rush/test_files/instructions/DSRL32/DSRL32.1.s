@@ -0,0 +1,37 @@
+# run as a 64-bit processor, by DSRL32.1.yaml
+	.text
+main:
+	li	$t0, 0x12345678
+	dsll32	$t0, $t0, 4
+	dsrl32	$a1, $t0, 4
+	jal	print64
+
+	# zeros are shifted in, unlike dsra32
+	li	$t0, -1
+	dsrl32	$a1, $t0, 0
+	jal	print64
+
+	dsra32	$a1, $t0, 0
+	jal	print64
+
+	dsrl32	$a1, $t0, 31
+	jal	print64
+
+	li	$v0, 10
+	syscall
+
+# prints $a1 as its high and low words
+print64:
+	dsra32	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	sll	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
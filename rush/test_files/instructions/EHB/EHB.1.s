@@ -0,0 +1,14 @@
+main:
+	li	$t0, 4
+	ehb
+
+	move	$a0, $t0
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
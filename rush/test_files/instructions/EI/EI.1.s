@@ -0,0 +1,25 @@
+main:
+	ei	$t0
+	mfc0	$t1, $12
+	di
+	andi	$t0, $t0, 1
+	andi	$t1, $t1, 1
+
+	move	$a0, $t0
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
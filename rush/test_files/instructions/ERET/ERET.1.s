@@ -0,0 +1,40 @@
+	.kdata
+msg:	.asciiz "caught exception "
+nl:	.asciiz "\n"
+save_a0: .word 0
+save_v0: .word 0
+
+	.ktext 0x80000180
+	sw	$a0, save_a0
+	sw	$v0, save_v0
+	la	$a0, msg
+	li	$v0, 4
+	syscall
+	mfc0	$k0, $13
+	srl	$a0, $k0, 2
+	andi	$a0, $a0, 0x1f
+	li	$v0, 1
+	syscall
+	la	$a0, nl
+	li	$v0, 4
+	syscall
+	mfc0	$k0, $14
+	addiu	$k0, $k0, 4
+	mtc0	$k0, $14
+	lw	$a0, save_a0
+	lw	$v0, save_v0
+	eret
+
+	.text
+main:
+	li	$t0, 0x7fffffff
+	add	$t1, $t0, $t0
+	teq	$zero, $zero
+	lw	$t2, 1($zero)
+	lw	$t2, 2($sp)
+	sw	$t2, 0($zero)
+	li	$a0, 42
+	li	$v0, 1
+	syscall
+	li	$v0, 10
+	syscall
@@ -0,0 +1,42 @@
+main:
+	li	$t0, 0x12345678
+	ext	$t1, $t0, 4, 8
+	ext	$t2, $t0, 0, 32
+	ext	$t3, $t0, 31, 1
+	li	$t4, -1
+	ext	$t4, $t4, 16, 16
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t4
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
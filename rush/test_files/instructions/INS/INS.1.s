@@ -0,0 +1,35 @@
+main:
+	li	$t0, 0x12345678
+	li	$t1, -1
+	ins	$t1, $t0, 8, 12
+	li	$t2, 0
+	ins	$t2, $t0, 0, 32
+	li	$t3, 0
+	ins	$t3, $t0, 31, 1
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
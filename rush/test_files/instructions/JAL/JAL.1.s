@@ -0,0 +1,22 @@
+# a call to a function 0x35C bytes into the text, which used to be taken as printf, built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r2 -filetype=obj JAL.1.s -o JAL.1.o
+#   llvm-objcopy -O binary -j .text JAL.1.o JAL.1.bin
+	.text
+	.set	noreorder
+main:
+	li	$a0, 7
+	jal	0x0040035C
+	nop
+	move	$a0, $v0
+	li	$v0, 1
+	syscall
+	li	$a0, 10
+	li	$v0, 11
+	syscall
+	li	$v0, 10
+	syscall
+
+	.org	0x35C
+add35:
+	jr	$ra
+	addiu	$v0, $a0, 35
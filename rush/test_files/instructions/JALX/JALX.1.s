@@ -0,0 +1,62 @@
+# MIPS32 and microMIPS code, which rush can't assemble, run from JALX.1.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r2 -filetype=obj JALX.1.s -o JALX.1.o
+#   llvm-objcopy -O binary -j .text JALX.1.o JALX.1.bin
+# Each routine is at a fixed address (from 0x00400000), so that jalx can jump to it without relocations.
+
+	.macro	print_int reg
+	move	$a0, \reg
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	.endm
+
+	.set	noreorder
+	.text
+
+	.set	nomicromips
+main:
+	li	$t0, 10
+	jalx	0x00400080
+	nop
+
+	# ((10 + 5 + 1000) * 2) - 1
+	print_int $t0
+
+	# jalx links past its delay slot, in the mode it was in
+	li	$t1, 0x00400000
+	subu	$t1, $s0, $t1
+	print_int $t1
+
+	andi	$t1, $s1, 1
+	print_int $t1
+
+	li	$v0, 10
+	syscall
+
+	.org	0x80
+	.set	micromips
+umips:
+	# 16-bit and 32-bit instructions mixed together
+	addius5	$t0, 5
+	addiu	$t0, $t0, 1000
+	move	$s0, $ra
+
+	# and back to MIPS32
+	jalx	0x00400100
+	nop
+
+	addius5	$t0, -1
+	move	$ra, $s0
+
+	# jrc has no delay slot, and returns to MIPS32 as $ra's low bit is clear
+	jrc	$ra
+	li	$t0, 99
+
+	.org	0x100
+	.set	nomicromips
+mips32:
+	move	$s1, $ra
+	jr	$ra
+	sll	$t0, $t0, 1
@@ -0,0 +1,40 @@
+# release 6 code, which rush can't assemble, run from JIC.1.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r6 -filetype=obj JIC.1.s -o JIC.1.o
+#   llvm-objcopy -O binary -j .text JIC.1.o JIC.1.bin
+
+	.macro	print_int reg
+	move	$a0, \reg
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	.endm
+
+	.set	noreorder
+	.text
+main:
+	# jumps to the register plus a signed offset, with no delay slot
+	lapc	$t9, target
+	li	$t0, 1
+	jic	$t9, 8
+	li	$t0, 2
+
+target:
+	li	$t0, 3
+	li	$t0, 4
+	print_int $t0
+
+	# jialc links, as balc does
+	lapc	$t9, sub
+	jialc	$t9, 0
+	li	$t0, 6
+	print_int $t0
+
+	li	$v0, 10
+	syscall
+
+sub:
+	print_int $t0
+	li	$t0, 5
+	jrc	$ra
@@ -0,0 +1,56 @@
+	.data
+word:	.word 0
+
+	.text
+main:
+	# the bytes of a stored word, in memory order
+	li	$t0, 0x11223384
+	sw	$t0, word
+
+	lb	$a0, word
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lb	$a0, word+1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lb	$a0, word+2
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lbu	$a0, word+3
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# and a word put together from stored bytes
+	li	$t0, 0x44
+	sb	$t0, word
+	li	$t0, 0x33
+	sb	$t0, word+1
+	li	$t0, 0x22
+	sb	$t0, word+2
+	li	$t0, 0x11
+	sb	$t0, word+3
+
+	lw	$a0, word
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
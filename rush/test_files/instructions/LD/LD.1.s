@@ -0,0 +1,51 @@
+# run as a 64-bit processor, by LD.1.yaml
+	.data
+	.align	3
+dword:	.word 0, 0
+
+	.text
+main:
+	# 0x0123456789abcdef
+	li	$t0, 0x01234567
+	dsll32	$t0, $t0, 0
+	li	$t1, 0x89abcdef
+	dsll32	$t1, $t1, 0
+	dsrl32	$t1, $t1, 0
+	or	$t0, $t0, $t1
+
+	la	$t2, dword
+	sd	$t0, 0($t2)
+	ld	$a1, 0($t2)
+	jal	print64
+
+	# the words of the doubleword, in memory order
+	lw	$a1, dword
+	jal	print64
+	lw	$a1, dword+4
+	jal	print64
+
+	# and the doubleword from its words
+	li	$t0, -1
+	sw	$t0, dword
+	sw	$zero, dword+4
+	ld	$a1, 0($t2)
+	jal	print64
+
+	li	$v0, 10
+	syscall
+
+# prints $a1 as its high and low words
+print64:
+	dsra32	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	sll	$a0, $a1, 0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
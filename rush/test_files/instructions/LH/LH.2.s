@@ -0,0 +1,46 @@
+# run big-endian, by LH.2.yaml
+	.data
+word:	.word 0
+
+	.text
+main:
+	# the halves of a stored word, in memory order
+	li	$t0, 0x8001ABCD
+	sw	$t0, word
+
+	lh	$a0, word
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lh	$a0, word+2
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lhu	$a0, word+2
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# and a word put together from stored halves
+	li	$t0, 0x3344
+	sh	$t0, word
+	li	$t0, 0x1122
+	sh	$t0, word+2
+
+	lw	$a0, word
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,17 @@
+	.data
+buf:	.word 0, -5
+	.text
+main:
+	la	$t0, buf
+	ll	$t1, 4($t0)
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,31 @@
+# release 6 code, which rush can't assemble, run from LSA.1.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r6 -filetype=obj LSA.1.s -o LSA.1.o
+#   llvm-objcopy -O binary -j .text LSA.1.o LSA.1.bin
+
+	.macro	print_int reg
+	move	$a0, \reg
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	.endm
+
+	.set	noreorder
+	.text
+main:
+	# $rd = ($rs << sa) + $rt
+	li	$t0, 3
+	li	$t1, 100
+	lsa	$t2, $t0, $t1, 1
+	print_int $t2
+	lsa	$t2, $t0, $t1, 4
+	print_int $t2
+
+	# only the low 32 bits are kept
+	li	$t0, 0x40000001
+	lsa	$t2, $t0, $zero, 3
+	print_int $t2
+
+	li	$v0, 10
+	syscall
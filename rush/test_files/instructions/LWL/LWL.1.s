@@ -0,0 +1,48 @@
+	.data
+bytes:	.byte 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88
+	.text
+main:
+	la	$t0, bytes
+	li	$t1, 0x7AAAAAAA
+	lwl	$t1, 4($t0)
+	li	$t2, 0x7AAAAAAA
+	lwl	$t2, 5($t0)
+	li	$t3, 0x7AAAAAAA
+	lwl	$t3, 6($t0)
+	li	$t4, 0x7AAAAAAA
+	lwl	$t4, 7($t0)
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t4
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,18 @@
+	.data
+bytes:	.byte 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88
+	.text
+main:
+	la	$t0, bytes
+	lwr	$t1, 3($t0)
+	lwl	$t1, 6($t0)
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
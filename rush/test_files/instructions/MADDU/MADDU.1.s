@@ -0,0 +1,29 @@
+main:
+	li	$t0, 1
+	mthi	$t0
+	li	$t0, -1
+	mtlo	$t0
+	li	$t1, -3
+	li	$t2, 100000
+	maddu	$t1, $t2
+	mfhi	$t3
+	mflo	$t4
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t4
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
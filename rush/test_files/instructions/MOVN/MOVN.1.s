@@ -0,0 +1,26 @@
+main:
+	li	$t0, 7
+	li	$t1, 1
+	li	$t2, 2
+	movn	$t1, $t0, $t2
+	li	$t3, 3
+	movn	$t3, $t0, $zero
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,44 @@
+# The timer interrupt: Count reaches Compare every 100 cycles
+	.kdata
+save_at: .word 0
+
+	.ktext 0x80000180
+	addu	$k1, $at, $zero
+	sw	$k1, save_at
+
+	addiu	$s1, $s1, 1
+	mfc0	$k0, $9
+	addiu	$k0, $k0, 100
+	mtc0	$k0, $11
+
+	lw	$k1, save_at
+	addu	$at, $k1, $zero
+	eret
+
+	.text
+main:
+	li	$s1, 0
+
+	li	$t0, 100
+	mtc0	$t0, $11
+	mfc0	$t0, $12
+	ori	$t0, $t0, 1
+	mtc0	$t0, $12
+
+wait:
+	blt	$s1, 3, wait
+
+	mfc0	$t0, $12
+	xori	$t0, $t0, 1
+	mtc0	$t0, $12
+
+	move	$a0, $s1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
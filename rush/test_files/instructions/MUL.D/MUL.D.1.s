@@ -0,0 +1,19 @@
+	.data
+x:	.double 3.5
+y:	.double -0.25
+
+	.text
+main:
+	l.d	$f0, x
+	l.d	$f2, y
+	mul.d	$f12, $f0, $f2
+
+	li	$v0, 3
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
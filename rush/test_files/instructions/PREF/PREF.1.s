@@ -0,0 +1,19 @@
+	.data
+buf:	.word 9, 0
+	.text
+main:
+	la	$t0, buf
+	pref	0, 0($t0)
+	pref	1, 4($t0)
+	lw	$t1, 0($t0)
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,62 @@
+	.kdata
+msg:	.asciiz "caught exception "
+nl:	.asciiz "\n"
+
+	.ktext 0x80000180
+	la	$a0, msg
+	li	$v0, 4
+	syscall
+	mfc0	$k0, $13
+	srl	$a0, $k0, 2
+	andi	$a0, $a0, 0x1f
+	li	$v0, 1
+	syscall
+	la	$a0, nl
+	li	$v0, 4
+	syscall
+	mfc0	$k0, $14
+	addiu	$k0, $k0, 4
+	mtc0	$k0, $14
+	eret
+
+	.text
+main:
+	# CPUNum
+	rdhwr	$a0, $0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# SYNCI_Step, with no caches
+	rdhwr	$a0, $1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# CC goes up, by CCRes a cycle
+	rdhwr	$t0, $2
+	nop
+	rdhwr	$t1, $2
+	sltu	$a0, $t0, $t1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	rdhwr	$a0, $3
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# there's no UserLocal
+	rdhwr	$t0, $29
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,23 @@
+main:
+	li	$t0, 0x12345678
+	rotr	$t1, $t0, 8
+	rotr	$t2, $t0, 0
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,15 @@
+main:
+	li	$t0, 0x12345678
+	li	$t3, 36
+	rotrv	$t1, $t0, $t3
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
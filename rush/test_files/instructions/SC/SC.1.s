@@ -0,0 +1,47 @@
+	.data
+buf:	.word 10
+	.text
+main:
+	la	$t0, buf
+	ll	$t1, 0($t0)
+	addiu	$t1, $t1, 1
+	sc	$t1, 0($t0)
+	lw	$t2, 0($t0)
+	li	$t3, 99
+	sc	$t3, 0($t0)
+	lw	$t4, 0($t0)
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t4
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
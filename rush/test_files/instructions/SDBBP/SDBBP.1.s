@@ -0,0 +1,33 @@
+	.kdata
+msg:	.asciiz "caught exception "
+
+	.ktext 0x80000180
+	la	$a0, msg
+	li	$v0, 4
+	syscall
+	mfc0	$k0, $13
+	srl	$a0, $k0, 2
+	andi	$a0, $a0, 0x1f
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	mfc0	$k0, $14
+	addiu	$k0, $k0, 4
+	mtc0	$k0, $14
+	eret
+
+	.text
+main:
+	# stops in the debugger, like `break`, and carries on without it
+	sdbbp
+	li	$a0, 1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
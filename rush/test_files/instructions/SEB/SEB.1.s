@@ -0,0 +1,24 @@
+main:
+	li	$t0, 0x12345680
+	seb	$t1, $t0
+	li	$t0, 0x7F
+	seb	$t2, $t0
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $t2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
@@ -0,0 +1,49 @@
+	.data
+buf:	.word 0, 0, 0, 0
+	.text
+main:
+	la	$t0, buf
+	li	$t1, 0x44332211
+	swl	$t1, 0($t0)
+	swl	$t1, 5($t0)
+	swl	$t1, 10($t0)
+	swl	$t1, 15($t0)
+	lw	$s0, 0($t0)
+	lw	$s1, 4($t0)
+	lw	$s2, 8($t0)
+	lw	$s3, 12($t0)
+
+	move	$a0, $s0
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $s1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $s2
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	move	$a0, $s3
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
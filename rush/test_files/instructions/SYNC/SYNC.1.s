@@ -0,0 +1,16 @@
+main:
+	li	$t0, 5
+	sync
+	sync	0
+	addiu	$t0, $t0, 1
+
+	move	$a0, $t0
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
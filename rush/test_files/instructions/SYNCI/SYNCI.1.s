@@ -0,0 +1,15 @@
+main:
+	la	$t0, main
+	synci	0($t0)
+	li	$t1, 3
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
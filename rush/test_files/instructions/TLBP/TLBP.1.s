@@ -0,0 +1,56 @@
+# tlbp sets Index to the entry matching EntryHi's VPN2 and ASID (or any ASID, for a global entry),
+# and sets Index.P (leaving the rest of Index alone) when none does
+	.text
+main:
+	li	$t0, 0x00800000		# not global, with ASID 0
+	mtc0	$t0, $10
+	li	$t0, 0x00020006		# PFN 0x800, D, V
+	mtc0	$t0, $2
+	li	$t0, 0x00020046		# PFN 0x801, D, V
+	mtc0	$t0, $3
+	li	$t0, 5
+	mtc0	$t0, $0
+	tlbwi
+
+	li	$t0, 0x00C00001		# global, so it matches with any ASID
+	mtc0	$t0, $10
+	li	$t0, 0x00030007
+	mtc0	$t0, $2
+	li	$t0, 0x00030047
+	mtc0	$t0, $3
+	li	$t0, 9
+	mtc0	$t0, $0
+	tlbwi
+
+	li	$a0, 0x00800000
+	jal	probe			# 5
+	li	$a0, 0x00801000		# the odd page, which the same entry maps
+	jal	probe			# 5
+	li	$a0, 0x00C00003		# another ASID
+	jal	probe			# 9
+	li	$a0, 0x00800003		# another ASID, which the non-global entry doesn't match
+	jal	probe			# Index.P, and the 9 from before
+	li	$a0, 0x00802000
+	jal	probe			# Index.P
+
+	li	$v0, 10
+	syscall
+
+# prints Index after probing for $a0
+probe:
+	mtc0	$a0, $10
+	tlbp
+	mfc0	$t0, $0
+	srl	$a0, $t0, 31
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	andi	$a0, $t0, 0x3F
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
@@ -0,0 +1,68 @@
+# tlbr reads the entry at Index back into EntryHi, EntryLo0, EntryLo1 and PageMask,
+# with G in both EntryLos only if the entry is global
+	.text
+main:
+	li	$t0, 0x00006000		# 16KB pages
+	mtc0	$t0, $5
+	li	$t0, 0x01236045		# the bits under the page mask aren't kept
+	mtc0	$t0, $10
+	li	$t0, 0x00040017		# PFN 0x1000, C = 0, D, V, G
+	mtc0	$t0, $2
+	li	$t0, 0x00040107		# PFN 0x1004, D, V, G
+	mtc0	$t0, $3
+	li	$t0, 2
+	mtc0	$t0, $0
+	tlbwi
+
+	mtc0	$zero, $5		# 4KB pages
+	li	$t0, 0x00400000
+	mtc0	$t0, $10
+	li	$t0, 0x00010003		# only EntryLo0 is global, so the entry isn't
+	mtc0	$t0, $2
+	li	$t0, 0x00010046
+	mtc0	$t0, $3
+	li	$t0, 3
+	mtc0	$t0, $0
+	tlbwi
+
+	li	$a0, 2
+	jal	read
+	li	$a0, 3
+	jal	read
+
+	li	$v0, 10
+	syscall
+
+# prints EntryHi, EntryLo0, EntryLo1 and PageMask after reading entry $a0
+read:
+	mtc0	$a0, $0
+	mtc0	$zero, $2
+	mtc0	$zero, $3
+	mtc0	$zero, $5
+	mtc0	$zero, $10
+	tlbr
+	mfc0	$a0, $10
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	mfc0	$a0, $2
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	mfc0	$a0, $3
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	mfc0	$a0, $5
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
@@ -0,0 +1,58 @@
+# tlbwi writes the entry at Index, and the mapping is used as soon as ERL is cleared
+	.text
+main:
+	# the text's pages map to themselves, from an entry that's global (as G is set in both EntryLos)
+	li	$t0, 0x00400000
+	mtc0	$t0, $10
+	li	$t0, 0x00010007		# PFN 0x400, D, V, G
+	mtc0	$t0, $2
+	li	$t0, 0x00010047		# PFN 0x401, D, V, G
+	mtc0	$t0, $3
+	mtc0	$zero, $0
+	tlbwi
+
+	# 0x00800000 maps to physical 0x10010000, which kseg0 reaches at 0x90010000
+	li	$t0, 0x00800000
+	mtc0	$t0, $10
+	li	$t0, 0x00400406		# PFN 0x10010, D, V
+	mtc0	$t0, $2
+	mtc0	$zero, $3
+	li	$t0, 1
+	mtc0	$t0, $0
+	tlbwi
+
+	mfc0	$t0, $12
+	li	$t1, 0xFFFFFFFB
+	and	$t0, $t0, $t1
+	mtc0	$t0, $12		# ERL cleared
+
+	li	$t0, 1234
+	sw	$t0, 0x00800010
+	lw	$a0, 0x90010010
+	li	$v0, 1
+	syscall				# 1234
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# rewriting the entry moves the page to physical 0x10011000
+	li	$t0, 0x00400446		# PFN 0x10011, D, V
+	mtc0	$t0, $2
+	tlbwi
+	li	$t0, 5678
+	sw	$t0, 0x00800010
+	lw	$a0, 0x90011010
+	li	$v0, 1
+	syscall				# 5678
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	lw	$a0, 0x90010010
+	li	$v0, 1
+	syscall				# 1234, still
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
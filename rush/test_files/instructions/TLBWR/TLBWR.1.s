@@ -0,0 +1,80 @@
+# tlbwr writes the entry at Random, which counts down a cycle at a time from the top entry
+# to Wired, and starts again from the top when Wired is written, so wired entries are never replaced
+	.text
+main:
+	li	$t0, 0x00400000		# wired entries 0 and 1
+	mtc0	$t0, $10
+	mtc0	$zero, $0
+	tlbwi
+	li	$t0, 0x00402000
+	mtc0	$t0, $10
+	li	$t0, 1
+	mtc0	$t0, $0
+	tlbwi
+
+	li	$t0, 2
+	mtc0	$t0, $6
+	mfc0	$a0, $1
+	li	$v0, 1
+	syscall				# 14, as a cycle has gone by since it was the top entry
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# 40 pages, from 0x01000000 on, written at Random
+	li	$s0, 0
+	li	$s1, 40
+	li	$s2, 0x01000000
+write:
+	sll	$t0, $s0, 13
+	addu	$t0, $t0, $s2
+	mtc0	$t0, $10
+	tlbwr
+	addiu	$s0, $s0, 1
+	bne	$s0, $s1, write
+
+	# the entries they were written at, which are never 0 or 1 (and `-` for the pages replaced since)
+	li	$s0, 0
+probe:
+	sll	$t0, $s0, 13
+	addu	$t0, $t0, $s2
+	mtc0	$t0, $10
+	tlbp
+	mfc0	$a0, $0
+	li	$v0, 1
+	bgez	$a0, found
+	li	$a0, '-'
+	li	$v0, 11
+found:
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	addiu	$s0, $s0, 1
+	bne	$s0, $s1, probe
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# the wired entries are still there
+	li	$t0, 0x00400000
+	mtc0	$t0, $10
+	tlbp
+	mfc0	$a0, $0
+	li	$v0, 1
+	syscall				# 0
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	li	$t0, 0x00402000
+	mtc0	$t0, $10
+	tlbp
+	mfc0	$a0, $0
+	li	$v0, 1
+	syscall				# 1
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
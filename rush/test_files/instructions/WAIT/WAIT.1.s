@@ -0,0 +1,39 @@
+	.kdata
+msg:	.asciiz "interrupt "
+
+	.ktext 0x80000180
+	la	$a0, msg
+	li	$v0, 4
+	syscall
+	mfc0	$k0, $13
+	srl	$a0, $k0, 2
+	andi	$a0, $a0, 0x1f
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	# acknowledge the timer, which won't go off again
+	mfc0	$k0, $9
+	addiu	$k0, $k0, -1
+	mtc0	$k0, $11
+	eret
+
+	.text
+main:
+	mfc0	$t0, $9
+	addiu	$t0, $t0, 1000
+	mtc0	$t0, $11
+	ei
+
+	# interrupted after the `wait`, rather than at it
+	wait
+	li	$a0, 1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,31 @@
+	.ktext 0x80000180
+	eret
+
+	.text
+main:
+	mfc0	$t0, $9
+	addiu	$t0, $t0, 1000
+	mtc0	$t0, $11
+
+	# with interrupts disabled, it still wakes up, but carries on without taking it
+	di
+	wait
+	mfc0	$t1, $9
+	sltu	$a0, $t1, $t0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	mfc0	$a0, $13
+	srl	$a0, $a0, 15
+	andi	$a0, $a0, 1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,14 @@
+main:
+	li	$t0, 0x11223344
+	wsbh	$t1, $t0
+
+	move	$a0, $t1
+	li	$v0, 1
+	syscall
+
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 0
+	jr	$ra
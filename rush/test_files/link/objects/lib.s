@@ -0,0 +1,10 @@
+	.data
+msg:	.asciiz	"lib\n"
+
+	.text
+	.globl	lib_print
+lib_print:
+	la	$a0, msg
+	li	$v0, 4
+	syscall
+	jr	$ra
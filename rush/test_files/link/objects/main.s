@@ -0,0 +1,29 @@
+# linked with lib.s and counter.o (see compare.sh), each `msg` staying local to its own file
+	.data
+msg:	.asciiz	"main\n"
+
+	.text
+	.globl	main
+main:
+	addiu	$sp, $sp, -4
+	sw	$ra, 0($sp)
+
+	la	$a0, msg
+	li	$v0, 4
+	syscall
+
+	jal	lib_print
+
+	# counter.o's count is in its .bss
+	jal	count
+	jal	count
+	move	$a0, $v0
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lw	$ra, 0($sp)
+	addiu	$sp, $sp, 4
+	jr	$ra
@@ -0,0 +1,39 @@
+# an ELF executable (with .data and .bss), big-endian from its header alone, built with:
+#   llvm-mc -triple=mips -filetype=obj elf_big.s -o elf_big.o
+#   rush link -o elf_big.elf elf_big.o
+	.text
+	.globl	main
+main:
+	# a word from .data, whose bytes are in the executable's byte order
+	lui	$t0, %hi(value)
+	lw	$a0, %lo(value)($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, 10
+	li	$v0, 11
+	syscall
+
+	lbu	$a0, %lo(value)($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, 10
+	li	$v0, 11
+	syscall
+
+	# .bss starts zeroed
+	lui	$t0, %hi(zeroes)
+	lw	$a0, %lo(zeroes)+4($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, 10
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
+
+	.data
+value:	.word	0x11223344
+
+	.bss
+zeroes:	.space	8
@@ -0,0 +1,24 @@
+# a flat binary, whose config doesn't say what byte order it's in, so it's little-endian, built with:
+#   llvm-mc -triple=mipsel -filetype=obj flat.s -o flat.o
+#   llvm-objcopy -O binary -j .text flat.o flat.bin
+	.text
+main:
+	# the first instruction (lui $t0, 0x40), read back as a word
+	lui	$t0, 0x40
+	lw	$a0, 0($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, 10
+	li	$v0, 11
+	syscall
+
+	# and its first byte, the low one
+	lbu	$a0, 0($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, 10
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,131 @@
+# A user store misses the TLB, and the refill handler's load of its page table entry (from kseg2)
+# misses as well. That second miss is taken at 0x80000180, as EXL is set, which maps the page table
+# and returns to the store (EPC is left alone during an exception), which is refilled again and done.
+	.kdata
+refill_msg:	.asciiz "refill "
+exception_msg:	.asciiz "exception "
+epc_msg:	.asciiz " epc "
+save_a0:	.word 0
+save_v0:	.word 0
+
+	.ktext 0x80000000
+	sw	$a0, save_a0
+	sw	$v0, save_v0
+	la	$a0, refill_msg
+	li	$v0, 4
+	syscall
+	mfc0	$a0, $8
+	li	$v0, 1
+	syscall				# BadVAddr
+	la	$a0, epc_msg
+	li	$v0, 4
+	syscall
+	mfc0	$a0, $14
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	mfc0	$k1, $4			# Context, where the page table entry is
+	lw	$k0, 0($k1)
+	mtc0	$k0, $2
+	lw	$k0, 4($k1)
+	mtc0	$k0, $3
+	tlbwr
+	lw	$a0, save_a0
+	lw	$v0, save_v0
+	eret
+
+	.ktext 0x80000180
+	sw	$a0, save_a0
+	sw	$v0, save_v0
+	la	$a0, exception_msg
+	li	$v0, 4
+	syscall
+	mfc0	$a0, $13
+	srl	$a0, $a0, 2
+	andi	$a0, $a0, 0x1F
+	li	$v0, 1
+	syscall				# ExcCode
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	mfc0	$a0, $8
+	li	$v0, 1
+	syscall				# BadVAddr
+	la	$a0, epc_msg
+	li	$v0, 4
+	syscall
+	mfc0	$a0, $14
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	# a miss in kseg2 is the page table, at physical 0x10020000 (EntryHi is already its page)
+	mfc0	$k0, $8
+	lui	$k1, 0xC000
+	sltu	$k0, $k0, $k1
+	bnez	$k0, skip
+	li	$k0, 0x00400806		# PFN 0x10020, D, V
+	mtc0	$k0, $2
+	mtc0	$zero, $3
+	tlbwr
+	b	return
+
+	# anything else is skipped
+skip:
+	mfc0	$k0, $14
+	addiu	$k0, $k0, 4
+	mtc0	$k0, $14
+
+return:
+	lw	$a0, save_a0
+	lw	$v0, save_v0
+	eret
+
+	.text
+main:
+	# the text's pages map to themselves, from wired entry 0
+	li	$t0, 0x00400000
+	mtc0	$t0, $10
+	li	$t0, 0x00010007		# PFN 0x400, D, V, G
+	mtc0	$t0, $2
+	li	$t0, 0x00010047		# PFN 0x401, D, V, G
+	mtc0	$t0, $3
+	mtc0	$zero, $0
+	tlbwi
+	li	$t0, 1
+	mtc0	$t0, $6
+
+	# the page table is at 0xC0000000, and the entry for 0x00800000 and 0x00801000 (at 0xC0004000)
+	# maps the even page to physical 0x10030000, and leaves the odd one invalid
+	lui	$t0, 0xC000
+	mtc0	$t0, $4
+	li	$t0, 0x00400C06		# PFN 0x10030, D, V
+	sw	$t0, 0x90020000
+	sw	$zero, 0x90020004
+
+	# into user mode, with ERL cleared
+	la	$t0, user
+	mtc0	$t0, $14
+	li	$t0, 0x3000FF12		# UM, EXL
+	mtc0	$t0, $12
+	eret
+
+# (addressing through its own registers, as the handlers use $at)
+user:
+	li	$t0, 4321
+	lui	$t1, 0x0080
+	sw	$t0, 0($t1)		# refilled, after the page table is
+	lw	$a0, 0($t1)
+	li	$v0, 1
+	syscall				# 4321
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	lw	$a0, 0x1000($t1)	# TLBL, as the odd page is invalid
+	li	$v0, 10
+	syscall
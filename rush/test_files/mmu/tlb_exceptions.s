@@ -0,0 +1,127 @@
+# The TLB's exceptions: a refill (for a page no entry maps) at 0x80000000, and TLBL/TLBS (for an invalid page)
+# and TLB Modified (for a store to a page that isn't dirty) at 0x80000180. Each sets BadVAddr,
+# Context.BadVPN2 and EntryHi.VPN2 (keeping the ASID) to the page, and is skipped by its handler.
+	.kdata
+refill_msg:	.asciiz "refill "
+exception_msg:	.asciiz "exception "
+save_a0:	.word 0
+save_v0:	.word 0
+save_ra:	.word 0
+
+	.ktext 0x80000000
+	sw	$a0, save_a0
+	sw	$v0, save_v0
+	sw	$ra, save_ra
+	la	$a0, refill_msg
+	li	$v0, 4
+	syscall
+	jal	report
+	lw	$a0, save_a0
+	lw	$v0, save_v0
+	lw	$ra, save_ra
+	eret
+
+	.ktext 0x80000180
+	sw	$a0, save_a0
+	sw	$v0, save_v0
+	sw	$ra, save_ra
+	la	$a0, exception_msg
+	li	$v0, 4
+	syscall
+	mfc0	$a0, $13
+	srl	$a0, $a0, 2
+	andi	$a0, $a0, 0x1F
+	li	$v0, 1
+	syscall				# ExcCode
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	jal	report
+	lw	$a0, save_a0
+	lw	$v0, save_v0
+	lw	$ra, save_ra
+	eret
+
+# prints BadVAddr, Context and EntryHi, and skips the instruction
+report:
+	mfc0	$a0, $8
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	mfc0	$a0, $4
+	li	$v0, 1
+	syscall
+	li	$a0, ' '
+	li	$v0, 11
+	syscall
+	mfc0	$a0, $10
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	mfc0	$k0, $14
+	addiu	$k0, $k0, 4
+	mtc0	$k0, $14
+	jr	$ra
+
+	.text
+main:
+	# the text's pages map to themselves
+	li	$t0, 0x00400000
+	mtc0	$t0, $10
+	li	$t0, 0x00010007		# PFN 0x400, D, V, G
+	mtc0	$t0, $2
+	li	$t0, 0x00010047		# PFN 0x401, D, V, G
+	mtc0	$t0, $3
+	mtc0	$zero, $0
+	tlbwi
+
+	# with ASID 5, 0x00800000 maps to physical 0x10030000, and 0x00801000 to 0x10031000 but isn't dirty
+	li	$t0, 0x00800005
+	mtc0	$t0, $10
+	li	$t0, 0x00400C06		# PFN 0x10030, D, V
+	mtc0	$t0, $2
+	li	$t0, 0x00400C42		# PFN 0x10031, V
+	mtc0	$t0, $3
+	li	$t0, 1
+	mtc0	$t0, $0
+	tlbwi
+
+	# and 0x00802000 and 0x00803000 are invalid
+	li	$t0, 0x00802005
+	mtc0	$t0, $10
+	mtc0	$zero, $2
+	mtc0	$zero, $3
+	li	$t0, 2
+	mtc0	$t0, $0
+	tlbwi
+
+	lui	$t0, 0xC000		# PTEBase
+	mtc0	$t0, $4
+
+	mfc0	$t0, $12
+	li	$t1, 0xFFFFFFFB
+	and	$t0, $t0, $t1
+	mtc0	$t0, $12		# ERL cleared
+
+	li	$t0, 0x00801000
+	lw	$a0, -0x1000($t0)	# fine, as the even page is valid
+	sw	$t0, -0x1000($t0)
+	lw	$a0, -0x1000($t0)
+	li	$v0, 1
+	syscall				# 8392704
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	sw	$t0, 0($t0)		# TLB Modified
+	lw	$a0, 0x1000($t0)	# TLBL
+	sw	$t0, 0x2000($t0)	# TLBS
+	lw	$a0, 0x3000($t0)	# refill
+	sw	$t0, 0x7FF0($t0)	# refill
+
+	li	$v0, 10
+	syscall
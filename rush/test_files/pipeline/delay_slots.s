@@ -0,0 +1,82 @@
+# With `delay_slots`, the instruction after a branch or jump runs before it takes effect, `jal` links past its delay slot,
+# and a branch likely that isn't taken skips its delay slot. Prints 101, 202, 303, 404, 5, 0 (`jal`'s link, less the
+# address after its delay slot), 606, 707 and 8.
+	.text
+main:
+	move	$s0, $ra
+
+	li	$a0, 1
+	b	taken
+	addiu	$a0, $a0, 100		# runs: 101
+	li	$a0, 0			# skipped by the branch
+taken:
+	jal	print_int		# 101
+	nop
+
+	li	$a0, 2
+	beq	$zero, $zero, after_beq
+	addiu	$a0, $a0, 200		# runs: 202
+after_beq:	jal	print_int		# 202
+	nop
+
+	li	$a0, 3
+	bne	$zero, $zero, after_bne
+	addiu	$a0, $a0, 300		# runs, though not taken: 303
+after_bne:	jal	print_int		# 303
+	nop
+
+	li	$a0, 4
+	beql	$zero, $zero, after_beql
+	addiu	$a0, $a0, 400		# taken, so runs: 404
+after_beql:	jal	print_int		# 404
+	nop
+
+	li	$a0, 5
+	bnel	$zero, $zero, after_bnel
+	addiu	$a0, $a0, 500		# not taken, so skipped: 5
+after_bnel:	jal	print_int		# 5
+	nop
+
+	# `jal` links to the instruction after its delay slot
+	jal	link
+	nop
+after:
+	la	$t0, after
+	subu	$a0, $v0, $t0		# 0
+	jal	print_int
+	nop
+
+	li	$a0, 6
+	la	$t0, print_int
+	jalr	$t0
+	addiu	$a0, $a0, 600		# runs: 606
+
+	li	$a0, 7
+	li	$t0, 1
+	bgezl	$t0, after_bgezl
+	addiu	$a0, $a0, 700		# taken, so runs: 707
+after_bgezl:	jal	print_int		# 707
+	nop
+
+	li	$a0, 8
+	bltzl	$t0, after_bltzl
+	addiu	$a0, $a0, 800		# not taken, so skipped: 8
+after_bltzl:	jal	print_int		# 8
+	nop
+
+	move	$ra, $s0
+	jr	$ra
+	nop
+
+link:
+	jr	$ra
+	move	$v0, $ra
+
+print_int:
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
+	nop
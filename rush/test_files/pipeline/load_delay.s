@@ -0,0 +1,38 @@
+# With `load_delay`, the instruction after a load still sees the register's old value, and a warning is printed when it
+# reads it. Prints the warning and 1 (the old value), then 42 after a `nop`, then 7 with no warning, as the delay slot
+# only writes the loaded register, a cycle after the load does, so that its value wins.
+	.data
+word:	.word 42
+
+	.text
+main:
+	la	$t0, word
+
+	li	$t1, 1
+	lw	$t1, 0($t0)
+	move	$a0, $t1		# reads the old value: 1
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lw	$t1, 0($t0)
+	nop
+	move	$a0, $t1		# 42
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	lw	$t1, 0($t0)
+	li	$t1, 7			# written after the load
+	move	$a0, $t1		# 7
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	jr	$ra
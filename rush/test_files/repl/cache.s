@@ -0,0 +1,47 @@
+# Fills 64 words (256 bytes), then sums them twice, through a 128-byte direct-mapped D-cache with 16-byte lines,
+# so that the first word of every line misses: 16 write misses filling, and 16 read misses on each pass.
+# The array evicts `sums` each pass, so both stores of a sum miss too (18 write misses in all), and the final
+# load of the second sum hits. The I-cache holds all of the code, which only misses the first time round.
+	.data
+array:	.space 256
+sums:	.word 0, 0
+
+	.text
+main:
+	la	$s0, array
+	li	$t0, 0
+fill:
+	sll	$t1, $t0, 2
+	addu	$t1, $t1, $s0
+	sw	$t0, 0($t1)
+	addiu	$t0, $t0, 1
+	li	$t2, 64
+	bne	$t0, $t2, fill
+
+	la	$s1, sums
+	li	$s2, 0			# the pass
+pass:
+	li	$t0, 0
+	li	$t3, 0
+sum:
+	sll	$t1, $t0, 2
+	addu	$t1, $t1, $s0
+	lw	$t2, 0($t1)
+	addu	$t3, $t3, $t2
+	addiu	$t0, $t0, 1
+	li	$t2, 64
+	bne	$t0, $t2, sum
+	sll	$t1, $s2, 2
+	addu	$t1, $t1, $s1
+	sw	$t3, 0($t1)
+	addiu	$s2, $s2, 1
+	li	$t2, 2
+	bne	$s2, $t2, pass
+
+	lw	$a0, 4($s1)
+	li	$v0, 1
+	syscall				# 2016
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	jr	$ra
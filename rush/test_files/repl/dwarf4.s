@@ -0,0 +1,16 @@
+# an executable with a DWARF 4 line table from llvm-mc, which rush's linker doesn't keep, so it was added back with:
+#   llvm-mc -triple=mipsel -g -dwarf-version=4 -fdebug-compilation-dir=. -filetype=obj test_files/repl/dwarf4.s -o dwarf4.o
+#   rush link -o test_files/repl/dwarf4.elf dwarf4.o
+#   llvm-objcopy --dump-section .debug_line=debug_line dwarf4.o
+#   python3 -c 'b = open("debug_line", "rb").read(); i = b.index(bytes([0, 5, 2])) + 3; open("debug_line", "wb").write(b[:i] + (0x00400000).to_bytes(4, "little") + b[i + 4:])'
+#   llvm-objcopy --add-section .debug_line=debug_line test_files/repl/dwarf4.elf
+# (the python relocates DW_LNE_set_address, as __start is linked first, at 0x00400000)
+	.text
+	.globl	__start
+__start:
+	li	$a0, 42
+	li	$v0, 1
+	syscall
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,16 @@
+# an executable with a DWARF 5 line table from llvm-mc, which rush's linker doesn't keep, so it was added back with:
+#   llvm-mc -triple=mipsel -g -dwarf-version=5 -fdebug-compilation-dir=. -filetype=obj test_files/repl/dwarf5.s -o dwarf5.o
+#   rush link -o test_files/repl/dwarf5.elf dwarf5.o
+#   llvm-objcopy --dump-section .debug_line=debug_line --dump-section .debug_line_str=debug_line_str dwarf5.o
+#   python3 -c 'b = open("debug_line", "rb").read(); i = b.index(bytes([0, 5, 2])) + 3; open("debug_line", "wb").write(b[:i] + (0x00400000).to_bytes(4, "little") + b[i + 4:])'
+#   llvm-objcopy --add-section .debug_line=debug_line --add-section .debug_line_str=debug_line_str test_files/repl/dwarf5.elf
+# (the python relocates DW_LNE_set_address, as __start is linked first, at 0x00400000)
+	.text
+	.globl	__start
+__start:
+	li	$a0, 42
+	li	$v0, 1
+	syscall
+
+	li	$v0, 10
+	syscall
@@ -0,0 +1,35 @@
+# Builds dwarf_overflow.elf: dwarf4.elf with a .debug_line whose first row is followed by an
+# advance_pc of 0xFFFFFFFF instructions, which overflows. Run from rush/ with:
+#   python3 test_files/repl/dwarf_overflow.py
+import subprocess, tempfile
+
+header = bytes([
+    4,                  # minimum_instruction_length
+    1,                  # maximum_operations_per_instruction
+    1,                  # default_is_stmt
+    0xFB,               # line_base (-5)
+    14,                 # line_range
+    13,                 # opcode_base
+    0, 1, 1, 1, 1, 0, 0, 0, 1, 0, 0, 1,
+    0,                  # no include_directories
+]) + b"overflow.s\0" + bytes([0, 0, 0]) + b"\0"
+
+program = bytes([
+    0, 5, 2, 0x00, 0x00, 0x40, 0x00,   # DW_LNE_set_address 0x00400000
+    1,                                  # DW_LNS_copy, a row for line 1
+    2, 0xFF, 0xFF, 0xFF, 0xFF, 0x0F,    # DW_LNS_advance_pc 0xFFFFFFFF
+    1,                                  # DW_LNS_copy
+    0, 1, 1,                            # DW_LNE_end_sequence
+])
+
+unit = (4).to_bytes(2, "little") + len(header).to_bytes(4, "little") + header + program
+debug_line = len(unit).to_bytes(4, "little") + unit
+
+with tempfile.NamedTemporaryFile() as section:
+    section.write(debug_line)
+    section.flush()
+
+    subprocess.run([
+        "llvm-objcopy", "--remove-section", ".debug_line", "--add-section", f".debug_line={section.name}",
+        "test_files/repl/dwarf4.elf", "test_files/repl/dwarf_overflow.elf",
+    ], check=True)
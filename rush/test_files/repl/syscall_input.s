@@ -0,0 +1,34 @@
+# Reads an int, a string and a char a line at a time, from the lines of the REPL script after `run`
+# (with MARS's keyboard there, so the REPL leaves keyboard mode for each of them)
+	.data
+buffer:	.space 16
+
+	.text
+main:
+	li	$v0, 5
+	syscall
+	sll	$a0, $v0, 1
+	li	$v0, 1
+	syscall				# twice the int
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	la	$a0, buffer
+	li	$a1, 16
+	li	$v0, 8
+	syscall
+	li	$v0, 4
+	syscall				# the string, as it was typed
+
+	li	$v0, 12
+	syscall
+	move	$a0, $v0
+	li	$v0, 1
+	syscall				# the char's code
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+
+	li	$v0, 10
+	syscall
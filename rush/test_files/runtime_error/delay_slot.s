@@ -0,0 +1,10 @@
+# With `delay_slots`, an unaligned load in the delay slot of a `jal` is reported at the `jal`'s address,
+# as EPC would be on hardware.
+	.data
+word:	.word 42
+
+	.text
+main:
+	la	$t0, word
+	jal	main
+	lw	$a0, 1($t0)
@@ -0,0 +1,16 @@
+# release 6 code, which rush can't assemble, run from r6_mfhi.yaml as a flat binary built with:
+#   llvm-mc -triple=mipsel -mcpu=mips32r6 -filetype=obj r6_mfhi.s -o r6_mfhi.o
+#   llvm-objcopy -O binary -j .text r6_mfhi.o r6_mfhi.bin
+
+	.set	noreorder
+	.text
+main:
+	li	$t0, 3
+	li	$t1, 4
+
+	# mfhi $t2, which release 6 removed along with HI and LO
+	# (and llvm-mc won't assemble for it)
+	.word	0x00005010
+
+	li	$v0, 10
+	syscall
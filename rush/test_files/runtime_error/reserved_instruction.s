@@ -0,0 +1,13 @@
+    .text
+main:
+    # opcode 0x3B isn't anything on a MIPS32 release 2 processor, and can only be
+    # put in the text segment by the program itself
+    la      $t0, patched
+    li      $t1, 0xEC221234
+    sw      $t1, 0($t0)
+
+patched:
+    nop
+
+    li      $v0, 0
+    jr      $ra
@@ -0,0 +1,10 @@
+    .text
+main:
+    li      $t0, 1
+    li      $t1, 2
+
+    # the assembler knows `dadd`, but only a 64-bit processor has it
+    dadd    $t2, $t0, $t1
+
+    li      $v0, 0
+    jr      $ra
@@ -0,0 +1,17 @@
+# Exits normally, through a D-cache, after printing: the summary of the cycles and the caches goes to stderr,
+# after the program's output, and none of it to stdout.
+	.data
+word:	.word 42
+
+	.text
+main:
+	la	$t0, word
+	lw	$a0, 0($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	li	$a0, 0
+	li	$v0, 17
+	syscall
@@ -0,0 +1,15 @@
+# Fails with a runtime error after printing: the summary goes to stderr just as it would on a normal exit,
+# and the error to stdout.
+	.data
+word:	.word 42
+
+	.text
+main:
+	la	$t0, word
+	lw	$a0, 0($t0)
+	li	$v0, 1
+	syscall
+	li	$a0, '\n'
+	li	$v0, 11
+	syscall
+	lw	$a0, 1($t0)